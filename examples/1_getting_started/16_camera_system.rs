@@ -1,295 +1,11 @@
-extern crate glfw;
-extern crate gl;
-
-use std::{ffi::{c_void, CString}, ptr, mem, path::Path};
-use glfw::{Action, Context, Key, GlfwReceiver};
-use gl::types::*;
-use learn_opengl_rust::shader::Shader;
-use image::{GenericImage, DynamicImage::{ImageRgba8, ImageRgb8}};
-use cgmath::{Matrix4, Vector3, Matrix, perspective, Deg, InnerSpace, Point3, Vector2};
-use learn_opengl_rust::camera::{Camera, CameraMovement};
+use learn_opengl_rust::scene;
+use learn_opengl_rust::scenes::getting_started::camera::CameraSystem;
 
 // Constants
 const WINDOW_WIDTH: u32 = 800;
 const WINDOW_HEIGHT: u32 = 600;
 
+// the scene lives in src/scenes/getting_started/camera.rs, so it can also be rendered headless
 fn main() {
-    // init
-    let mut glfw = initialize_glfw();
-    let (mut window, events) = create_window(&mut glfw);
-
-    // init camera
-    let mut camera = Camera::new(
-        Point3::new(0.0, 0.0, 3.0),
-        Vector3::new(0.0, 0.0, -1.0),
-        Vector3::new(0.0, 1.0, 0.0),
-        Vector2::new((WINDOW_WIDTH/2) as f32, (WINDOW_HEIGHT/2) as f32),
-    );
-
-    // gl: load all OpenGL function pointers
-    // ---------------------------------------
-    gl::load_with(|symbol| window.get_proc_address(symbol) as *const _);
-
-    let shader = Shader::new(
-        "./src/shaders/1_getting_started/coord_systems.vs", 
-        "./src/shaders/1_getting_started/multiple_tex.fs"
-    );
-
-    let (VAO, texture1, texture2) = unsafe {
-
-        // Vertices for a 3d cube
-        let vertices_3D: [f32; 180] = [
-            -0.5, -0.5, -0.5,  0.0, 0.0,
-             0.5, -0.5, -0.5,  1.0, 0.0,
-             0.5,  0.5, -0.5,  1.0, 1.0,
-             0.5,  0.5, -0.5,  1.0, 1.0,
-            -0.5,  0.5, -0.5,  0.0, 1.0,
-            -0.5, -0.5, -0.5,  0.0, 0.0,
-        
-            -0.5, -0.5,  0.5,  0.0, 0.0,
-             0.5, -0.5,  0.5,  1.0, 0.0,
-             0.5,  0.5,  0.5,  1.0, 1.0,
-             0.5,  0.5,  0.5,  1.0, 1.0,
-            -0.5,  0.5,  0.5,  0.0, 1.0,
-            -0.5, -0.5,  0.5,  0.0, 0.0,
-        
-            -0.5,  0.5,  0.5,  1.0, 0.0,
-            -0.5,  0.5, -0.5,  1.0, 1.0,
-            -0.5, -0.5, -0.5,  0.0, 1.0,
-            -0.5, -0.5, -0.5,  0.0, 1.0,
-            -0.5, -0.5,  0.5,  0.0, 0.0,
-            -0.5,  0.5,  0.5,  1.0, 0.0,
-        
-             0.5,  0.5,  0.5,  1.0, 0.0,
-             0.5,  0.5, -0.5,  1.0, 1.0,
-             0.5, -0.5, -0.5,  0.0, 1.0,
-             0.5, -0.5, -0.5,  0.0, 1.0,
-             0.5, -0.5,  0.5,  0.0, 0.0,
-             0.5,  0.5,  0.5,  1.0, 0.0,
-        
-            -0.5, -0.5, -0.5,  0.0, 1.0,
-             0.5, -0.5, -0.5,  1.0, 1.0,
-             0.5, -0.5,  0.5,  1.0, 0.0,
-             0.5, -0.5,  0.5,  1.0, 0.0,
-            -0.5, -0.5,  0.5,  0.0, 0.0,
-            -0.5, -0.5, -0.5,  0.0, 1.0,
-        
-            -0.5,  0.5, -0.5,  0.0, 1.0,
-             0.5,  0.5, -0.5,  1.0, 1.0,
-             0.5,  0.5,  0.5,  1.0, 0.0,
-             0.5,  0.5,  0.5,  1.0, 0.0,
-            -0.5,  0.5,  0.5,  0.0, 0.0,
-            -0.5,  0.5, -0.5,  0.0, 1.0
-        ];
-
-        let (mut VBO, mut VAO) = (0,0);
-
-        gl::GenVertexArrays(1, &mut VAO);
-        gl::GenBuffers(1, &mut VBO);
-        //gl::GenBuffers(1, &mut EBO);
-
-        gl::BindVertexArray(VAO);
-
-        // bind buffer will set the buffer as the current OpenGl State
-        // then storing the data inside this buffer
-        gl::BindBuffer(gl::ARRAY_BUFFER, VBO);
-        gl::BufferData(
-            gl::ARRAY_BUFFER, 
-            (vertices_3D.len() * mem::size_of::<GLfloat>()) as GLsizeiptr, 
-            &vertices_3D[0] as *const f32 as *const c_void, 
-            gl::STATIC_DRAW
-        );
-
-        let stride = 5 * mem::size_of::<GLfloat>() as GLsizei;
-
-        // configure position attribute aPos
-        gl::VertexAttribPointer(0, 3, gl::FLOAT, gl::FALSE, stride, ptr::null());
-        gl::EnableVertexAttribArray(0);
-
-        gl::VertexAttribPointer(1, 2, gl::FLOAT, gl::FALSE, stride, (3 * mem::size_of::<GLfloat>()) as *const c_void);
-        gl::EnableVertexAttribArray(1);
-
-        // textures
-        let texture1 = load_texture("resources/textures/container.jpg");
-        let texture2 = load_texture("resources/textures/awesomeface.png");
-        
-        // clean up? I think this is not mandatory
-        // unbind VBO
-        gl::BindBuffer(gl::ARRAY_BUFFER, 0);
-        // unbind VAO
-        gl::BindVertexArray(0);
-
-        // make sure to use the program befor setting uniforms
-        shader.use_program();
-
-        // set uniforms for textures
-        shader.set_int("texture1", 0);
-        shader.set_int("texture2", 1);
-
-        (VAO, texture1, texture2)
-    };
-
-    let projection = perspective(Deg(45.0), WINDOW_WIDTH as f32/WINDOW_HEIGHT as f32, 0.1, 100.0);
-
-    // checking matrices
-    println!("Projection Matrix: {:?}", projection);
-
-    let cube_positions: [Vector3<f32>; 10] = [
-        Vector3::new( 0.0, 0.0, 0.0),
-        Vector3::new( 2.0, 5.0, -15.0),
-        Vector3::new( -1.5, -2.2, -2.5),
-        Vector3::new( -3.8, -2.0, -12.3),
-        Vector3::new( 2.4, -0.4, -3.5),
-        Vector3::new( -1.7, 3.0, -7.5),
-        Vector3::new( 1.3, -2.0, -2.5),
-        Vector3::new( 1.5, 2.0, -2.5),
-        Vector3::new( 1.5, 0.2, -1.5),
-        Vector3::new( -1.3, 1.0, -1.5),
-    ];
-
-    // setting up uniforms
-    unsafe {
-        let proj_mat_location = gl::GetUniformLocation(shader.program, CString::new("projection").unwrap().as_ptr());
-        gl::UniformMatrix4fv(proj_mat_location, 1, gl::FALSE, projection.as_ptr());
-
-        gl::Enable(gl::DEPTH_TEST);
-    }
-
-    let mut last_frame = 0.0;
-    let mut delta_time: f32;
-
-    while !window.should_close() {
-
-        let current_frame = glfw.get_time() as f32;
-
-        delta_time = current_frame - last_frame;
-        last_frame = current_frame;
-
-        // processing events here
-        process_events(&events, &mut camera);
-        process_input_keyboard(&mut window, delta_time, &mut camera);
-
-        // render stuff here
-        unsafe {
-            gl::ClearColor(0.2, 0.3, 0.3, 1.0);
-            gl::Clear(gl::COLOR_BUFFER_BIT | gl::DEPTH_BUFFER_BIT);
-
-            shader.set_mat4("view", camera.calculate_view());
-
-            // set active texture group and bind the texture
-            gl::ActiveTexture(gl::TEXTURE0);
-            gl::BindTexture(gl::TEXTURE_2D, texture1);
-            gl::ActiveTexture(gl::TEXTURE1);
-            gl::BindTexture(gl::TEXTURE_2D, texture2);
-
-            shader.use_program();
-            gl::BindVertexArray(VAO);
-
-            for (index, vector) in cube_positions.iter().enumerate() {
-                
-                let angle = 20.0 * index as f32;
-
-                let model = Matrix4::<f32>::from_translation(*vector) * Matrix4::<f32>::from_axis_angle(Vector3::new(1.0, 0.3, 0.5).normalize(), Deg(angle));
-                let model_location = gl::GetUniformLocation(shader.program, CString::new("model").unwrap().as_ptr());
-                gl::UniformMatrix4fv(model_location, 1, gl::FALSE, model.as_ptr());
-
-                gl::DrawArrays(gl::TRIANGLES, 0, 36);
-            }
-        }
-    
-        // Swap front and back buffers
-        window.swap_buffers();
-        glfw.poll_events();
-    }
-}
-
-fn process_input_keyboard(window: &mut glfw::Window, delta_time: f32, camera: &mut Camera) {
-    // quit application
-    if window.get_key(Key::Escape) == Action::Press {
-        window.set_should_close(true)
-    }
-
-    if window.get_key(Key::W) == Action::Press {
-        camera.process_movement(CameraMovement::Forward, delta_time);
-    }
-    if window.get_key(Key::S) == Action::Press {
-        camera.process_movement(CameraMovement::Backward, delta_time);
-    }
-    if window.get_key(Key::A) == Action::Press {
-        camera.process_movement(CameraMovement::Left, delta_time);
-    }
-    if window.get_key(Key::D) == Action::Press {
-        camera.process_movement(CameraMovement::Right, delta_time);
-    }
-}
-
-fn process_events(events: &GlfwReceiver<(f64, glfw::WindowEvent)>, camera: &mut Camera) {
-    for (_, event) in glfw::flush_messages(events) {
-        println!("{:?}", event);
-        match event {
-            glfw::WindowEvent::CursorPos(x_pos, y_pos) => camera.process_cursor(x_pos as f32, y_pos as f32),
-            _ => ()
-        }
-    }
+    scene::run_windowed("Chapter 1: Camera system", WINDOW_WIDTH, WINDOW_HEIGHT, || Box::new(CameraSystem::new()));
 }
-
-fn initialize_glfw() -> glfw::Glfw {
-    use glfw::fail_on_errors;
-    let mut glfw: glfw::Glfw = glfw::init(glfw::fail_on_errors!()).expect("Failed to initialize GLFW");
-    glfw.window_hint(glfw::WindowHint::ContextVersion(3,3));
-    glfw.window_hint(glfw::WindowHint::OpenGlProfile(glfw::OpenGlProfileHint::Core));
-    glfw
-}
-
-fn create_window(glfw: &mut glfw::Glfw) -> (glfw::PWindow, glfw::GlfwReceiver<(f64, glfw::WindowEvent)>) {
-    // Create a windowed mode window and its OpenGL context
-    let (mut window, events) = glfw.create_window(WINDOW_WIDTH, WINDOW_HEIGHT, "Learning OpenGL the Rust Way...", glfw::WindowMode::Windowed)
-        .expect("Failed to create GLFW window.");
-
-    // Make the window's context current
-    window.make_current();
-    window.set_key_polling(true);
-    window.set_framebuffer_size_polling(true);
-    window.set_cursor_pos_polling(true);
-    window.set_scroll_polling(true);
-    window.set_cursor_mode(glfw::CursorMode::Disabled);
-    (window, events)
-}
-
-unsafe fn load_texture(path: &str) -> u32 {
-    let mut texture = 0;
-
-    let img = image::open(path).expect("Could not open file").flipv();
-    let format = match img {
-        ImageRgb8(_) => gl::RGB,
-        ImageRgba8(_) => gl::RGBA,
-        _ => panic!("Problem with the format")
-    };
-    let data = img.raw_pixels();
-
-    gl::GenTextures(1, &mut texture);
-    gl::BindTexture(gl::TEXTURE_2D, texture);
-
-    gl::TexImage2D(
-        gl::TEXTURE_2D,
-        0,
-        format as i32,
-        img.width() as i32,
-        img.height() as i32,
-        0,
-        format,
-        gl::UNSIGNED_BYTE,
-        &data[0] as *const u8 as *const c_void
-    );
-
-    gl::GenerateMipmap(gl::TEXTURE_2D);
-
-    //wrapping
-    gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_WRAP_S, gl::REPEAT as i32);
-    gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_WRAP_T, gl::REPEAT as i32);
-    //filtering
-    gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MIN_FILTER, gl::LINEAR as i32);
-    gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MAG_FILTER, gl::LINEAR as i32);
-
-    texture
-}
\ No newline at end of file
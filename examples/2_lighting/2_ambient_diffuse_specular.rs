@@ -1,310 +1,11 @@
-extern crate glfw;
-extern crate gl;
-
-use std::{ffi::c_void, ptr, mem};
-use glfw::{Action, Context, Key, GlfwReceiver};
-use gl::{types::*};
-use learn_opengl_rust::shader::Shader;
-use image::{GenericImage, DynamicImage::{ImageRgba8, ImageRgb8}};
-use cgmath::{Matrix4, Vector3, perspective, Deg, Point3, Vector2, SquareMatrix};
-use learn_opengl_rust::camera::{Camera, CameraMovement};
+use learn_opengl_rust::scene;
+use learn_opengl_rust::scenes::lighting::basic_lighting::PhongSplit;
 
 // Constants
 const WINDOW_WIDTH: u32 = 800;
 const WINDOW_HEIGHT: u32 = 600;
 
+// the scene lives in src/scenes/lighting/basic_lighting.rs, so it can also be rendered headless
 fn main() {
-    // init
-    let mut glfw = initialize_glfw();
-    let (mut window, events) = create_window(&mut glfw);
-
-    // init camera
-    let mut camera = Camera::new(
-        Point3::new(0.0, 0.5, 5.0),
-        Vector3::new(0.0, 0.0, -1.0),
-        Vector3::new(0.0, 1.0, 0.0),
-        Vector2::new((WINDOW_WIDTH/2) as f32, (WINDOW_HEIGHT/2) as f32),
-    );
-
-    // gl: load all OpenGL function pointers
-    // ---------------------------------------
-    gl::load_with(|symbol| window.get_proc_address(symbol) as *const _);
-
-    // enable depth perspective
-    unsafe { gl::Enable(gl::DEPTH_TEST); };
-
-    let ambient_shader = Shader::new(
-        "./src/shaders/2_lighting/phong_light.vs", 
-        "./src/shaders/2_lighting/ambient_light.fs"
-    );
-
-    let diffuse_shader = Shader::new(
-        "./src/shaders/2_lighting/phong_light.vs", 
-        "./src/shaders/2_lighting/diffuse_light.fs"
-    );
-
-    let specular_shader = Shader::new(
-        "./src/shaders/2_lighting/phong_light.vs", 
-        "./src/shaders/2_lighting/specular_light.fs"
-    );
-
-    let light_cube_shader = Shader::new(
-        "./src/shaders/2_lighting/basic_lighting.vs", 
-        "./src/shaders/2_lighting/light_source.fs"
-    );
-
-    // Vertices for a 3d cube
-    let vertex_cube: [f32; 216] = [
-        -0.5, -0.5, -0.5,  0.0,  0.0, -1.0,
-         0.5, -0.5, -0.5,  0.0,  0.0, -1.0, 
-         0.5,  0.5, -0.5,  0.0,  0.0, -1.0, 
-         0.5,  0.5, -0.5,  0.0,  0.0, -1.0, 
-        -0.5,  0.5, -0.5,  0.0,  0.0, -1.0, 
-        -0.5, -0.5, -0.5,  0.0,  0.0, -1.0, 
-    
-        -0.5, -0.5,  0.5,  0.0,  0.0, 1.0,
-         0.5, -0.5,  0.5,  0.0,  0.0, 1.0,
-         0.5,  0.5,  0.5,  0.0,  0.0, 1.0,
-         0.5,  0.5,  0.5,  0.0,  0.0, 1.0,
-        -0.5,  0.5,  0.5,  0.0,  0.0, 1.0,
-        -0.5, -0.5,  0.5,  0.0,  0.0, 1.0,
-    
-        -0.5,  0.5,  0.5, -1.0,  0.0,  0.0,
-        -0.5,  0.5, -0.5, -1.0,  0.0,  0.0,
-        -0.5, -0.5, -0.5, -1.0,  0.0,  0.0,
-        -0.5, -0.5, -0.5, -1.0,  0.0,  0.0,
-        -0.5, -0.5,  0.5, -1.0,  0.0,  0.0,
-        -0.5,  0.5,  0.5, -1.0,  0.0,  0.0,
-    
-         0.5,  0.5,  0.5,  1.0,  0.0,  0.0,
-         0.5,  0.5, -0.5,  1.0,  0.0,  0.0,
-         0.5, -0.5, -0.5,  1.0,  0.0,  0.0,
-         0.5, -0.5, -0.5,  1.0,  0.0,  0.0,
-         0.5, -0.5,  0.5,  1.0,  0.0,  0.0,
-         0.5,  0.5,  0.5,  1.0,  0.0,  0.0,
-    
-        -0.5, -0.5, -0.5,  0.0, -1.0,  0.0,
-         0.5, -0.5, -0.5,  0.0, -1.0,  0.0,
-         0.5, -0.5,  0.5,  0.0, -1.0,  0.0,
-         0.5, -0.5,  0.5,  0.0, -1.0,  0.0,
-        -0.5, -0.5,  0.5,  0.0, -1.0,  0.0,
-        -0.5, -0.5, -0.5,  0.0, -1.0,  0.0,
-    
-        -0.5,  0.5, -0.5,  0.0,  1.0,  0.0,
-         0.5,  0.5, -0.5,  0.0,  1.0,  0.0,
-         0.5,  0.5,  0.5,  0.0,  1.0,  0.0,
-         0.5,  0.5,  0.5,  0.0,  1.0,  0.0,
-        -0.5,  0.5,  0.5,  0.0,  1.0,  0.0,
-        -0.5,  0.5, -0.5,  0.0,  1.0,  0.0
-    ];
-
-        let vao = unsafe {
-            let (mut vao, mut vbo) = (0,0);
-
-            gl::GenBuffers(1, &mut vbo);
-            gl::GenVertexArrays(1, &mut vao);
-
-            gl::BindVertexArray(vao);
-            
-            gl::BindBuffer(gl::ARRAY_BUFFER, vbo);
-            gl::BufferData(
-                gl::ARRAY_BUFFER,
-                (vertex_cube.len() * mem::size_of::<GLfloat>()) as GLsizeiptr,
-                &vertex_cube[0] as *const f32 as *const c_void,
-                gl::STATIC_DRAW
-            );
-
-            let stride = 6 * mem::size_of::<GLfloat>() as GLsizei;
-            gl::VertexAttribPointer(0, 3, gl::FLOAT, gl::FALSE, stride, ptr::null());
-            gl::EnableVertexAttribArray(0);
-
-            gl::VertexAttribPointer(1, 3, gl::FLOAT, gl::FALSE, stride, (3 * mem::size_of::<GLfloat>()) as *const c_void);
-            gl::EnableVertexAttribArray(1);
-
-            vao
-        };
-    
-    let projection = perspective(Deg(45.0), WINDOW_WIDTH as f32/WINDOW_HEIGHT as f32, 0.1, 100.0);
-
-    let light_pos = Vector3::new(0.0, 1.5, 2.0);
-
-    ambient_shader.use_program();
-    ambient_shader.set_mat4("projection", projection);
-    ambient_shader.set_vector3("objectColor", 1.0, 0.5, 0.31);
-    ambient_shader.set_vector3("lightColor", 1.0, 1.0, 1.0);
-
-    diffuse_shader.use_program();
-    diffuse_shader.set_mat4("projection", projection);
-    diffuse_shader.set_vector3("objectColor", 1.0, 0.5, 0.31);
-    diffuse_shader.set_vector3("lightColor", 1.0, 1.0, 1.0);
-    diffuse_shader.set_vector3("lightPos", light_pos.x, light_pos.y, light_pos.z);
-
-    specular_shader.use_program();
-    specular_shader.set_mat4("projection", projection);
-    specular_shader.set_vector3("objectColor", 1.0, 0.5, 0.31);
-    specular_shader.set_vector3("lightColor", 1.0, 1.0, 1.0);
-    specular_shader.set_vector3("lightPos", light_pos.x, light_pos.y, light_pos.z);
-
-    let mut model;
-
-    let mut last_frame = 0.0;
-    let mut delta_time;
-
-    let cube_positions: [Vector3<f32>; 3] = [
-        Vector3::new(0.0, 0.0, 0.0),
-        Vector3::new(-1.5, 0.0, 0.0),
-        Vector3::new(1.5, 0.0, 0.0)
-    ];
-
-    while !window.should_close() {
-
-        let current_frame = glfw.get_time() as f32;
-
-        delta_time = current_frame - last_frame;
-        last_frame = current_frame;
-        
-        // processing events here
-        process_events(&events, &mut camera);
-        process_input_keyboard(&mut window, delta_time, &mut camera);
-
-        // render stuff here
-        unsafe {
-            gl::ClearColor(0.2, 0.3, 0.3, 1.0);
-            gl::Clear(gl::COLOR_BUFFER_BIT | gl::DEPTH_BUFFER_BIT);
-
-            gl::BindVertexArray(vao);
-
-            ambient_shader.use_program();
-            model = Matrix4::<f32>::from_translation(*cube_positions.get(1).unwrap());
-            ambient_shader.use_program();
-            ambient_shader.set_mat4("view", camera.calculate_view());
-            ambient_shader.set_mat4("model", model);
-            ambient_shader.set_vector3("cameraPos", camera.position.x, camera.position.y, camera.position.z);
-            
-            gl::DrawArrays(gl::TRIANGLES, 0, 36);
-
-            diffuse_shader.use_program();
-            // cube at 0,0,0
-            model = Matrix4::<f32>::identity();
-            diffuse_shader.use_program();
-            diffuse_shader.set_mat4("view", camera.calculate_view());
-            diffuse_shader.set_mat4("model", model);
-            diffuse_shader.set_vector3("cameraPos", camera.position.x, camera.position.y, camera.position.z);
-
-            gl::DrawArrays(gl::TRIANGLES, 0, 36);
-
-            specular_shader.use_program();
-            // cube at 0,0,0
-            model = Matrix4::<f32>::from_translation(*cube_positions.get(2).unwrap());
-            specular_shader.use_program();
-            specular_shader.set_mat4("view", camera.calculate_view());
-            specular_shader.set_mat4("model", model);
-            specular_shader.set_vector3("cameraPos", camera.position.x, camera.position.y, camera.position.z);
-
-            gl::DrawArrays(gl::TRIANGLES, 0, 36);
-
-            light_cube_shader.use_program();
-            model = Matrix4::<f32>::from_translation(light_pos) * Matrix4::<f32>::from_scale(0.2);
-            light_cube_shader.set_mat4("projection", projection);
-            light_cube_shader.set_mat4("view", camera.calculate_view());
-            light_cube_shader.set_mat4("model", model);
-
-            gl::DrawArrays(gl::TRIANGLES, 0, 36);
-        }
-    
-        // Swap front and back buffers
-        window.swap_buffers();
-        glfw.poll_events();
-    }
-}
-
-fn process_input_keyboard(window: &mut glfw::Window, delta_time: f32, camera: &mut Camera) {
-    // quit application
-    if window.get_key(Key::Escape) == Action::Press {
-        window.set_should_close(true)
-    }
-
-    if window.get_key(Key::W) == Action::Press {
-        camera.process_movement(CameraMovement::Forward, delta_time);
-    }
-    if window.get_key(Key::S) == Action::Press {
-        camera.process_movement(CameraMovement::Backward, delta_time);
-    }
-    if window.get_key(Key::A) == Action::Press {
-        camera.process_movement(CameraMovement::Left, delta_time);
-    }
-    if window.get_key(Key::D) == Action::Press {
-        camera.process_movement(CameraMovement::Right, delta_time);
-    }
-}
-
-fn process_events(events: &GlfwReceiver<(f64, glfw::WindowEvent)>, camera: &mut Camera) {
-    for (_, event) in glfw::flush_messages(events) {
-        println!("{:?}", event);
-        match event {
-            glfw::WindowEvent::CursorPos(x_pos, y_pos) => camera.process_cursor(x_pos as f32, y_pos as f32),
-            _ => ()
-        }
-    }
+    scene::run_windowed("Chapter 2: Ambient, diffuse and specular", WINDOW_WIDTH, WINDOW_HEIGHT, || Box::new(PhongSplit::new()));
 }
-
-fn initialize_glfw() -> glfw::Glfw {
-    use glfw::fail_on_errors;
-    let mut glfw: glfw::Glfw = glfw::init(glfw::fail_on_errors!()).expect("Failed to initialize GLFW");
-    glfw.window_hint(glfw::WindowHint::ContextVersion(3,3));
-    glfw.window_hint(glfw::WindowHint::OpenGlProfile(glfw::OpenGlProfileHint::Core));
-    glfw
-}
-
-fn create_window(glfw: &mut glfw::Glfw) -> (glfw::PWindow, glfw::GlfwReceiver<(f64, glfw::WindowEvent)>) {
-    // Create a windowed mode window and its OpenGL context
-    let (mut window, events) = glfw.create_window(WINDOW_WIDTH, WINDOW_HEIGHT, "Learning OpenGL the Rust Way...", glfw::WindowMode::Windowed)
-        .expect("Failed to create GLFW window.");
-
-    // Make the window's context current
-    window.make_current();
-    window.set_key_polling(true);
-    window.set_framebuffer_size_polling(true);
-    window.set_cursor_pos_polling(true);
-    window.set_scroll_polling(true);
-    window.set_cursor_mode(glfw::CursorMode::Disabled);
-    (window, events)
-}
-
-unsafe fn load_texture(path: &str) -> u32 {
-    let mut texture = 0;
-
-    let img = image::open(path).expect("Could not open file").flipv();
-    let format = match img {
-        ImageRgb8(_) => gl::RGB,
-        ImageRgba8(_) => gl::RGBA,
-        _ => panic!("Problem with the format")
-    };
-    let data = img.raw_pixels();
-
-    gl::GenTextures(1, &mut texture);
-    gl::BindTexture(gl::TEXTURE_2D, texture);
-
-    gl::TexImage2D(
-        gl::TEXTURE_2D,
-        0,
-        format as i32,
-        img.width() as i32,
-        img.height() as i32,
-        0,
-        format,
-        gl::UNSIGNED_BYTE,
-        &data[0] as *const u8 as *const c_void
-    );
-
-    gl::GenerateMipmap(gl::TEXTURE_2D);
-
-    //wrapping
-    gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_WRAP_S, gl::REPEAT as i32);
-    gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_WRAP_T, gl::REPEAT as i32);
-    //filtering
-    gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MIN_FILTER, gl::LINEAR as i32);
-    gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MAG_FILTER, gl::LINEAR as i32);
-
-    texture
-}
\ No newline at end of file
@@ -0,0 +1,12 @@
+use learn_opengl_rust::scene;
+use learn_opengl_rust::scenes::advanced_opengl::order_independent_transparency::OrderIndependentTransparency;
+
+// Constants
+const WINDOW_WIDTH: u32 = 800;
+const WINDOW_HEIGHT: u32 = 600;
+
+// the scene lives in src/scenes/advanced_opengl/order_independent_transparency.rs, so it can also be rendered headless
+// compare it with the sorted windows of the blending example
+fn main() {
+    scene::run_windowed("Chapter 4: Order-independent transparency", WINDOW_WIDTH, WINDOW_HEIGHT, || Box::new(OrderIndependentTransparency::new()));
+}
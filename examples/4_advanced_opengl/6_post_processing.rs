@@ -0,0 +1,11 @@
+use learn_opengl_rust::scene;
+use learn_opengl_rust::scenes::advanced_opengl::post_processing;
+
+// Constants
+const WINDOW_WIDTH: u32 = 800;
+const WINDOW_HEIGHT: u32 = 600;
+
+// the scene lives in src/scenes/advanced_opengl/post_processing.rs, so it can also be rendered headless
+fn main() {
+    scene::run_windowed("Chapter 4: Post-processing", WINDOW_WIDTH, WINDOW_HEIGHT, || Box::new(post_processing::post_processing()));
+}
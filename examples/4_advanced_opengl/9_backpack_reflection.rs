@@ -0,0 +1,11 @@
+use learn_opengl_rust::scene;
+use learn_opengl_rust::scenes::model_loading::backpack::BackpackReflection;
+
+// Constants
+const WINDOW_WIDTH: u32 = 800;
+const WINDOW_HEIGHT: u32 = 600;
+
+// the scene lives in src/scenes/model_loading/backpack.rs, so it can also be rendered headless
+fn main() {
+    scene::run_windowed("Chapter 4: Backpack reflection", WINDOW_WIDTH, WINDOW_HEIGHT, || Box::new(BackpackReflection::new()));
+}
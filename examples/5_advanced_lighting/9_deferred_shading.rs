@@ -0,0 +1,11 @@
+use learn_opengl_rust::scene;
+use learn_opengl_rust::scenes::advanced_lighting::deferred_shading::DeferredShading;
+
+// Constants
+const WINDOW_WIDTH: u32 = 800;
+const WINDOW_HEIGHT: u32 = 600;
+
+// the scene lives in src/scenes/advanced_lighting/deferred_shading.rs, so it can also be rendered headless
+fn main() {
+    scene::run_windowed("Chapter 5: Deferred Shading", WINDOW_WIDTH, WINDOW_HEIGHT, || Box::new(DeferredShading::new()));
+}
@@ -0,0 +1,60 @@
+#[cfg(unix)]
+use std::{env, path::Path};
+
+#[cfg(unix)]
+use learn_opengl_rust::{capture::Recorder, headless::HeadlessContext, scenes};
+
+// Constants
+#[cfg(unix)]
+const WIDTH: u32 = 800;
+#[cfg(unix)]
+const HEIGHT: u32 = 600;
+#[cfg(unix)]
+const DELTA_TIME: f32 = 1.0 / 60.0;
+
+// Renders a scene without a window, e.g. on CI:
+// cargo run --example headless -- <scene_name> [frames] [output]
+// output.png saves the last frame, output.gif records an animated gif
+// and an output without extension records a numbered png sequence into that directory
+#[cfg(unix)]
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let name = match args.first() {
+        Some(name) => name.as_str(),
+        None => {
+            println!("Usage: cargo run --example headless -- <scene_name> [frames] [output.png | output.gif | output_dir]");
+            let names: Vec<&str> = scenes::SCENES.iter().map(|entry| entry.name).collect();
+            println!("Available scenes: {}", names.join(", "));
+            return;
+        }
+    };
+    let frames: u32 = args.get(1).map(|f| f.parse().expect("frames has to be a number")).unwrap_or(1);
+    let output = args.get(2).cloned().unwrap_or(format!("{}.png", name));
+
+    let context = HeadlessContext::new(WIDTH, HEIGHT).unwrap_or_else(|e| panic!("{}", e));
+    println!("Rendering '{}' for {} frames with {}", name, frames, context.description());
+
+    let mut scene = scenes::create(name).unwrap_or_else(|| panic!("Unknown scene '{}'", name));
+    let output = Path::new(&output);
+    let recording = match output.extension().and_then(|e| e.to_str()) {
+        Some(extension) => extension.eq_ignore_ascii_case("gif"),
+        None => true,
+    };
+
+    if recording {
+        let mut recorder = Recorder::from_path(output, WIDTH, HEIGHT, DELTA_TIME).unwrap_or_else(|e| panic!("{}", e));
+        context.record_scene(scene.as_mut(), frames, &mut recorder).unwrap_or_else(|e| panic!("{}", e));
+        let frames = recorder.frame_count();
+        let path = recorder.finish().unwrap_or_else(|e| panic!("{}", e));
+        println!("Recorded {} frames to {}", frames, path.display());
+    } else {
+        context.render_scene_to_file(scene.as_mut(), frames, DELTA_TIME, output)
+            .unwrap_or_else(|e| panic!("{}", e));
+        println!("Saved {}", output.display());
+    }
+}
+
+#[cfg(not(unix))]
+fn main() {
+    println!("Headless rendering needs EGL or OSMesa and is only available on unix systems.");
+}
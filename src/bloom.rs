@@ -0,0 +1,150 @@
+//! Bloom: light bleeding around whatever is much brighter than white, like in a camera lens or the eye.
+//!
+//! The parts of an HDR frame above a threshold are blurred and added back before tone mapping. A
+//! single Gaussian blur only reaches a few texels, so the bright parts are downsampled into a chain
+//! of levels, each half the size of the one before, and the levels are upsampled again from the
+//! smallest one, each adding what it holds. The sum has a sharp core and a wide, soft glow.
+//!
+//! `Effect::with_bloom` adds it to the `tone_mapping` effect of a `PostProcess` chain.
+
+use crate::framebuffer::{ColorFormat, Framebuffer, FramebufferDesc, FullscreenQuad, FULLSCREEN_VERTEX_SHADER};
+use crate::scene::Frame;
+use crate::shader::Shader;
+
+pub const BLOOM_DOWNSAMPLE_SHADER: &str = "./src/shaders/5_advanced_lighting/bloom_downsample.fs";
+pub const BLOOM_UPSAMPLE_SHADER: &str = "./src/shaders/5_advanced_lighting/bloom_upsample.fs";
+
+/// The most levels below the frame, 1/512 of it is a few texels at most.
+pub const MAX_BLOOM_LEVELS: usize = 9;
+
+/// What blooms and how far.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct BloomSettings {
+    // colors brighter than this bloom, in linear HDR values before exposure
+    pub threshold: f32,
+    // the cut fades in over this fraction of the threshold below and above it, 0 for a hard cut
+    pub knee: f32,
+    // how much of the glow is added to the frame
+    pub intensity: f32,
+    // spread of the upsampling filter in texels, wider glows between 1 and 2
+    pub radius: f32,
+    // levels below the frame, each doubles how far the glow reaches
+    pub levels: usize,
+}
+
+impl Default for BloomSettings {
+    fn default() -> Self {
+        BloomSettings { threshold: 1.0, knee: 0.5, intensity: 1.0, radius: 1.0, levels: 6 }
+    }
+}
+
+/// The level chain and the shaders running it.
+pub struct Bloom {
+    settings: BloomSettings,
+    // the thresholded frame at half size, then smaller and smaller
+    down: Vec<Framebuffer>,
+    // down[i] plus everything upsampled from below, one less as the smallest level is its own sum
+    up: Vec<Framebuffer>,
+    downsample_shader: Shader,
+    upsample_shader: Shader,
+}
+
+impl Bloom {
+    pub fn new(settings: BloomSettings) -> Result<Self, String> {
+        let desc = FramebufferDesc { width: 1, height: 1, colors: vec![ColorFormat::Rgba16F], depth: None };
+        let mut down = Vec::with_capacity(MAX_BLOOM_LEVELS);
+        let mut up = Vec::with_capacity(MAX_BLOOM_LEVELS - 1);
+        for level in 0..MAX_BLOOM_LEVELS {
+            down.push(Framebuffer::new(desc.clone())?);
+            if level + 1 < MAX_BLOOM_LEVELS {
+                up.push(Framebuffer::new(desc.clone())?);
+            }
+        }
+
+        let downsample_shader = Shader::new(FULLSCREEN_VERTEX_SHADER, BLOOM_DOWNSAMPLE_SHADER);
+        downsample_shader.use_program();
+        downsample_shader.set_int("source", 0);
+        let upsample_shader = Shader::new(FULLSCREEN_VERTEX_SHADER, BLOOM_UPSAMPLE_SHADER);
+        upsample_shader.use_program();
+        upsample_shader.set_int("source", 0);
+        upsample_shader.set_int("current", 1);
+
+        let mut bloom = Bloom { settings: BloomSettings::default(), down, up, downsample_shader, upsample_shader };
+        bloom.set_settings(settings);
+        Ok(bloom)
+    }
+
+    pub fn settings(&self) -> BloomSettings {
+        self.settings
+    }
+
+    /// Changes the settings, `levels` is clamped to 1..=MAX_BLOOM_LEVELS.
+    pub fn set_settings(&mut self, settings: BloomSettings) {
+        self.settings = BloomSettings { levels: settings.levels.clamp(1, MAX_BLOOM_LEVELS), ..settings };
+    }
+
+    /// The glow of the last `render`, half the size of the frame.
+    pub fn texture(&self) -> u32 {
+        match self.settings.levels {
+            1 => self.down[0].color_texture(0),
+            _ => self.up[0].color_texture(0),
+        }
+    }
+
+    /// Sets `bloom` and `bloomIntensity` on `shader`, which has to be in use.
+    pub fn apply(&self, shader: &Shader) {
+        shader.set_bool("bloom", true);
+        // every level adds its share to the sum, the intensity should not depend on how many there are
+        shader.set_float("bloomIntensity", self.settings.intensity / self.settings.levels as f32);
+    }
+
+    /// Blurs the bright parts of `input`, a frame sized HDR texture. Returns `texture`.
+    /// The framebuffer and viewport of the caller are restored.
+    pub fn render(&mut self, quad: &FullscreenQuad, input: u32, frame: &Frame) -> u32 {
+        let (mut previous, mut viewport) = (0, [0; 4]);
+        unsafe {
+            gl::GetIntegerv(gl::FRAMEBUFFER_BINDING, &mut previous);
+            gl::GetIntegerv(gl::VIEWPORT, viewport.as_mut_ptr());
+        }
+
+        let levels = self.settings.levels;
+        let sizes: Vec<(u32, u32)> = (1..=levels).map(|level| ((frame.width >> level).max(1), (frame.height >> level).max(1))).collect();
+        for (level, &(width, height)) in sizes.iter().enumerate() {
+            self.down[level].resize(width, height).expect("Could not resize a bloom level");
+            if level + 1 < levels {
+                self.up[level].resize(width, height).expect("Could not resize a bloom level");
+            }
+        }
+
+        //down the chain, the first level keeps only what is bright enough
+        self.downsample_shader.use_program();
+        self.downsample_shader.set_float("threshold", self.settings.threshold);
+        self.downsample_shader.set_float("knee", self.settings.knee);
+        let (mut source, mut source_size) = (input, (frame.width, frame.height));
+        for (level, &size) in sizes.iter().enumerate() {
+            self.down[level].bind();
+            self.downsample_shader.set_bool("prefilter", level == 0);
+            self.downsample_shader.set_vector2("texelSize", 1.0 / source_size.0 as f32, 1.0 / source_size.1 as f32);
+            quad.pass(&self.downsample_shader, &[source]);
+            source = self.down[level].color_texture(0);
+            source_size = size;
+        }
+
+        //and up again, each level adds the blurred sum of the ones below
+        self.upsample_shader.use_program();
+        self.upsample_shader.set_float("radius", self.settings.radius);
+        for level in (0..levels - 1).rev() {
+            self.up[level].bind();
+            self.upsample_shader.set_vector2("texelSize", 1.0 / source_size.0 as f32, 1.0 / source_size.1 as f32);
+            quad.pass(&self.upsample_shader, &[source, self.down[level].color_texture(0)]);
+            source = self.up[level].color_texture(0);
+            source_size = sizes[level];
+        }
+
+        unsafe {
+            gl::BindFramebuffer(gl::FRAMEBUFFER, previous as u32);
+            gl::Viewport(viewport[0], viewport[1], viewport[2], viewport[3]);
+        }
+        self.texture()
+    }
+}
@@ -14,7 +14,13 @@ pub struct Camera {
     pub up: Vector3<f32>,
     pub cursor_pos: Vector2<f32>,
     pub pitch: f32,
-    pub yaw: f32
+    pub yaw: f32,
+    // the next cursor event only sets cursor_pos instead of turning the camera
+    pub first_mouse: bool,
+    // cursor movement only turns the camera while the window holds the cursor
+    pub cursor_captured: bool,
+    // all camera input is paused while the window is out of focus
+    pub focused: bool
 }
 
 // default camera settings
@@ -31,11 +37,29 @@ impl Camera {
             up: up_vector,
             cursor_pos: inital_cursor_pos,
             pitch: CAMERA_PITCH,
-            yaw: CAMERA_YAW
+            yaw: CAMERA_YAW,
+            first_mouse: true,
+            cursor_captured: true,
+            focused: true
         }
     }
 
+    /// Starts or stops turning the camera with the cursor, see `utils::set_cursor_captured`.
+    pub fn set_cursor_captured(&mut self, captured: bool) {
+        self.cursor_captured = captured;
+        self.first_mouse = true;
+    }
+
+    pub fn set_focused(&mut self, focused: bool) {
+        self.focused = focused;
+        // the cursor may have moved anywhere while the window was unfocused
+        self.first_mouse = true;
+    }
+
     pub fn process_movement(&mut self, movement: CameraMovement, delta_time: f32) {
+        if !self.focused {
+            return;
+        }
 
         if movement == CameraMovement::Forward {
             self.position += CAMERA_SPEED * delta_time * self.front;
@@ -52,6 +76,18 @@ impl Camera {
     }
 
     pub fn process_cursor(&mut self, x_new: f32, y_new: f32) {
+        if !self.focused || !self.cursor_captured {
+            return;
+        }
+
+        //the first position after capturing has no meaningful previous position
+        if self.first_mouse {
+            self.cursor_pos.x = x_new;
+            self.cursor_pos.y = y_new;
+            self.first_mouse = false;
+            return;
+        }
+
         //offset between old and new cursor position
         let mut x_off_set = x_new - self.cursor_pos.x;
         let mut y_off_set = y_new - self.cursor_pos.y;
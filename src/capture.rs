@@ -0,0 +1,198 @@
+//! Screenshots and recordings of rendered frames, used to regenerate the README media.
+
+use std::cell::RefCell;
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+
+use gif::SetParameter;
+use image::RgbaImage;
+
+use crate::utils;
+
+// default directory for screenshots and recordings taken from a window
+pub const CAPTURE_DIR: &str = "./screenshots";
+
+// gif quantization speed from 1 (best) to 30 (fastest), 10 is the suggested compromise
+const GIF_QUANTIZE_SPEED: i32 = 10;
+// gif delays are in 1/100 s, most viewers treat less than 2 as the default speed
+const GIF_MIN_DELAY: f32 = 2.0;
+
+/// Reads the color buffer of `framebuffer`, 0 is the default framebuffer.
+///
+/// The image is flipped so that the top row comes first. The read framebuffer binding is restored.
+pub unsafe fn read_framebuffer(framebuffer: u32, width: u32, height: u32) -> RgbaImage {
+    let mut previous = 0;
+    gl::GetIntegerv(gl::READ_FRAMEBUFFER_BINDING, &mut previous);
+    gl::BindFramebuffer(gl::READ_FRAMEBUFFER, framebuffer);
+    // the default framebuffer is double buffered, the frame is in the back buffer until the swap
+    if framebuffer == 0 {
+        gl::ReadBuffer(gl::BACK);
+    }
+
+    let image = utils::read_pixels(0, 0, width, height);
+    gl::BindFramebuffer(gl::READ_FRAMEBUFFER, previous as u32);
+    image
+}
+
+/// Saves an image as PNG, creating missing directories.
+pub fn save_png(image: &RgbaImage, path: &Path) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| format!("Could not create {}. {}", parent.display(), e))?;
+    }
+    image.save(path).map_err(|e| format!("Could not write {}. {}", path.display(), e))
+}
+
+/// The first `<dir>/<name>_<number>.<extension>` that does not exist yet, without extension for directories.
+pub fn next_free_path(dir: &Path, name: &str, extension: &str) -> PathBuf {
+    // window titles like "Chapter 4: Blending" become chapter_4_blending
+    let name: String = name.to_lowercase().chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect::<String>()
+        .split('_').filter(|part| !part.is_empty()).collect::<Vec<_>>().join("_");
+
+    (1..).map(|number| match extension {
+            "" => dir.join(format!("{}_{:03}", name, number)),
+            extension => dir.join(format!("{}_{:03}.{}", name, number, extension)),
+        })
+        .find(|path| !path.exists())
+        .unwrap()
+}
+
+// the gif encoder only writes its trailer when dropped, sharing the file lets `finish` flush it afterwards
+#[derive(Clone)]
+struct SharedFile(Rc<RefCell<BufWriter<File>>>);
+
+impl Write for SharedFile {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.borrow_mut().write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.0.borrow_mut().flush()
+    }
+}
+
+enum Output {
+    // numbered PNGs in a directory
+    Frames(PathBuf),
+    Gif {
+        path: PathBuf,
+        encoder: gif::Encoder<SharedFile>,
+        file: SharedFile,
+        // recorded time in 1/100 s that was not covered by frame delays yet
+        pending: f32,
+        written: u32,
+    },
+}
+
+/// Collects frames rendered at a fixed simulated time step into a frame sequence or a GIF.
+///
+/// The renderer has to advance the scene time by `delta_time` per added frame, so the result
+/// does not depend on how fast the frames were rendered.
+pub struct Recorder {
+    output: Output,
+    width: u32,
+    height: u32,
+    delta_time: f32,
+    frames: u32,
+}
+
+impl Recorder {
+    /// Writes `frame_00000.png`, `frame_00001.png`, ... into `dir`.
+    pub fn frame_sequence(dir: &Path, width: u32, height: u32, delta_time: f32) -> Result<Self, String> {
+        fs::create_dir_all(dir).map_err(|e| format!("Could not create {}. {}", dir.display(), e))?;
+        Ok(Recorder { output: Output::Frames(dir.to_path_buf()), width, height, delta_time, frames: 0 })
+    }
+
+    /// Encodes an endlessly looping animated GIF at `path`.
+    pub fn gif(path: &Path, width: u32, height: u32, delta_time: f32) -> Result<Self, String> {
+        if width > u16::MAX as u32 || height > u16::MAX as u32 {
+            return Err(format!("{}x{} is too large for a GIF", width, height));
+        }
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(|e| format!("Could not create {}. {}", parent.display(), e))?;
+        }
+
+        let file = File::create(path).map_err(|e| format!("Could not create {}. {}", path.display(), e))?;
+        let file = SharedFile(Rc::new(RefCell::new(BufWriter::new(file))));
+        let mut encoder = gif::Encoder::new(file.clone(), width as u16, height as u16, &[])
+            .map_err(|e| format!("Could not write {}. {}", path.display(), e))?;
+        encoder.set(gif::Repeat::Infinite)
+            .map_err(|e| format!("Could not write {}. {}", path.display(), e))?;
+
+        let output = Output::Gif { path: path.to_path_buf(), encoder, file, pending: 0.0, written: 0 };
+        Ok(Recorder { output, width, height, delta_time, frames: 0 })
+    }
+
+    /// A GIF if `path` ends with `.gif`, otherwise a frame sequence in the directory `path`.
+    pub fn from_path(path: &Path, width: u32, height: u32, delta_time: f32) -> Result<Self, String> {
+        match path.extension().and_then(|e| e.to_str()) {
+            Some(extension) if extension.eq_ignore_ascii_case("gif") => Recorder::gif(path, width, height, delta_time),
+            _ => Recorder::frame_sequence(path, width, height, delta_time),
+        }
+    }
+
+    /// Simulated time between two frames.
+    pub fn delta_time(&self) -> f32 {
+        self.delta_time
+    }
+
+    /// Frames added so far.
+    pub fn frame_count(&self) -> u32 {
+        self.frames
+    }
+
+    /// Frames written so far, a GIF skips frames that are shorter than its smallest delay.
+    pub fn written_frame_count(&self) -> u32 {
+        match self.output {
+            Output::Frames(_) => self.frames,
+            Output::Gif { written, .. } => written,
+        }
+    }
+
+    pub fn add_frame(&mut self, image: &RgbaImage) -> Result<(), String> {
+        if image.dimensions() != (self.width, self.height) {
+            return Err(format!(
+                "Frame is {}x{}, but the recording is {}x{}",
+                image.width(), image.height(), self.width, self.height
+            ));
+        }
+
+        match self.output {
+            Output::Frames(ref dir) => {
+                save_png(image, &dir.join(format!("frame_{:05}.png", self.frames)))?;
+            }
+            Output::Gif { ref path, ref mut encoder, ref mut pending, ref mut written, .. } => {
+                // the rounding error is carried to the next frame, so the total length stays right.
+                // steps shorter than the smallest delay skip frames instead of slowing down
+                *pending += self.delta_time * 100.0;
+                let delay = pending.round();
+                if delay >= GIF_MIN_DELAY {
+                    *pending -= delay;
+                    let mut pixels = image.clone().into_raw();
+                    let mut frame = gif::Frame::from_rgba_speed(self.width as u16, self.height as u16, &mut pixels, GIF_QUANTIZE_SPEED);
+                    frame.delay = delay as u16;
+                    encoder.write_frame(&frame).map_err(|e| format!("Could not write {}. {}", path.display(), e))?;
+                    *written += 1;
+                }
+            }
+        }
+        self.frames += 1;
+        Ok(())
+    }
+
+    /// Finishes the file and returns where the recording went.
+    pub fn finish(self) -> Result<PathBuf, String> {
+        match self.output {
+            Output::Frames(dir) => Ok(dir),
+            Output::Gif { path, encoder, mut file, .. } => {
+                // dropping the encoder writes the trailer
+                drop(encoder);
+                file.flush().map_err(|e| format!("Could not write {}. {}", path.display(), e))?;
+                Ok(path)
+            }
+        }
+    }
+}
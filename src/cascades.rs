@@ -0,0 +1,292 @@
+//! Cascaded shadow maps for a directional light over large scenes.
+//!
+//! One shadow map stretched over a whole landscape has texels the size of bricks near the camera.
+//! Cascades split the camera frustum by distance into slices and give each slice its own map,
+//! so the slices near the camera get small, sharp texels and the far ones cover a lot of ground.
+//! The lit pass picks the cascade by the fragment's view depth and blends into the next one over
+//! the last part of each slice, so the seams don't show.
+//!
+//! Each cascade's projection is fitted to a sphere around its slice instead of the slice itself:
+//! its size stays the same when the camera turns, and the light view is moved in whole texels
+//! only. Without both, shadow edges crawl as the camera moves.
+
+use std::ptr;
+
+use cgmath::{ortho, perspective, Deg, EuclideanSpace, InnerSpace, Matrix4, Point3, SquareMatrix, Transform, Vector3, Vector4};
+
+use crate::mesh::Geometry;
+use crate::shader::Shader;
+use crate::shadow::{ShadowSettings, CASCADE_UNIT, SHADOW_DEPTH_FRAGMENT_SHADER, SHADOW_DEPTH_VERTEX_SHADER};
+
+/// Cascades `multiple_lights.fs` has room for.
+pub const MAX_CASCADES: usize = 4;
+
+/// The perspective projection of the camera the cascades follow.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ViewFrustum {
+    pub fovy: Deg<f32>,
+    pub aspect: f32,
+    pub near: f32,
+    pub far: f32,
+}
+
+impl ViewFrustum {
+    /// World space corners of the slice from `near` to `far` of a camera with `view`.
+    pub fn corners(&self, view: Matrix4<f32>, near: f32, far: f32) -> [Point3<f32>; 8] {
+        let inverse = (perspective(self.fovy, self.aspect, near, far) * view).invert().expect("The camera matrix is not invertible");
+        let mut corners = [Point3::origin(); 8];
+        for (index, corner) in corners.iter_mut().enumerate() {
+            let ndc = Vector4::new(
+                if index & 1 == 0 { -1.0 } else { 1.0 },
+                if index & 2 == 0 { -1.0 } else { 1.0 },
+                if index & 4 == 0 { -1.0 } else { 1.0 },
+                1.0,
+            );
+            let world = inverse * ndc;
+            *corner = Point3::from_vec(world.truncate() / world.w);
+        }
+        corners
+    }
+}
+
+/// View depths where the cascades start and end, `count + 1` of them from `near` to `far`.
+///
+/// The practical split scheme: `lambda` 1 splits logarithmically, each cascade reaches the same
+/// factor further than the one before, which matches how perspective shrinks texels. 0 splits
+/// into equal lengths. Something in between keeps the first cascade from getting tiny.
+pub fn split_distances(near: f32, far: f32, count: usize, lambda: f32) -> Vec<f32> {
+    (0..=count).map(|index| {
+        let t = index as f32 / count as f32;
+        let logarithmic = near * (far / near).powf(t);
+        let uniform = near + (far - near) * t;
+        lambda * logarithmic + (1.0 - lambda) * uniform
+    }).collect()
+}
+
+/// Projection times view of a directional light shining along `direction` onto a frustum slice,
+/// stable as the camera moves and turns, for a map of `resolution` texels square.
+pub fn cascade_matrix(direction: Vector3<f32>, corners: &[Point3<f32>], resolution: u32) -> Matrix4<f32> {
+    let direction = direction.normalize();
+    let center = Point3::centroid(corners);
+    let radius = corners.iter().map(|&corner| (corner - center).magnitude()).fold(0.0, f32::max);
+    // rounded up, so float noise as the camera turns does not change the size
+    let radius = (radius * 16.0).ceil() / 16.0;
+
+    // look_at needs an up vector that is not parallel to the view direction
+    let up = if direction.y.abs() > 0.99 { Vector3::unit_z() } else { Vector3::unit_y() };
+    let view = Matrix4::look_at(center - direction * radius, center, up);
+    // casters in front of the near plane are clamped onto it, see `CascadedShadowMap::render`
+    let projection = ortho(-radius, radius, -radius, radius, 0.0, 2.0 * radius);
+    let matrix = projection * view;
+
+    // moves the map by less than a texel, so the world origin lands on a texel corner
+    let half = resolution as f32 / 2.0;
+    let origin = matrix.transform_point(Point3::origin());
+    let offset = Vector3::new((origin.x * half).round() - origin.x * half, (origin.y * half).round() - origin.y * half, 0.0) / half;
+    Matrix4::from_translation(offset) * matrix
+}
+
+/// How the cascades split the view and meet.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CascadeSettings {
+    pub count: usize,
+    // see `split_distances`
+    pub lambda: f32,
+    // the last fraction of each cascade blends into the next one
+    pub blend: f32,
+}
+
+impl Default for CascadeSettings {
+    fn default() -> Self {
+        CascadeSettings { count: MAX_CASCADES, lambda: 0.75, blend: 0.1 }
+    }
+}
+
+/// The cascades of a directional light, one layer of a depth texture array each.
+pub struct CascadedShadowMap {
+    settings: CascadeSettings,
+    shadow: ShadowSettings,
+    size: u32,
+    texture: u32,
+    framebuffer: u32,
+    depth_shader: Shader,
+    splits: Vec<f32>,
+    matrices: Vec<Matrix4<f32>>,
+    // colors the fragments by cascade
+    debug: bool,
+}
+
+impl CascadedShadowMap {
+    /// `MAX_CASCADES` layers of `size` by `size` texels, `settings.count` of them are used.
+    pub fn new(size: u32, settings: CascadeSettings) -> Result<Self, String> {
+        if size == 0 {
+            return Err("A cascaded shadow map needs at least one texel".to_string());
+        }
+        if settings.count == 0 || settings.count > MAX_CASCADES {
+            return Err(format!("{} cascades requested, between 1 and {} are supported", settings.count, MAX_CASCADES));
+        }
+        let mut map = CascadedShadowMap {
+            settings,
+            shadow: ShadowSettings::default(),
+            size,
+            texture: 0,
+            framebuffer: 0,
+            depth_shader: Shader::new(SHADOW_DEPTH_VERTEX_SHADER, SHADOW_DEPTH_FRAGMENT_SHADER),
+            splits: Vec::new(),
+            matrices: Vec::new(),
+            debug: false,
+        };
+        unsafe {
+            let mut previous = 0;
+            gl::GetIntegerv(gl::FRAMEBUFFER_BINDING, &mut previous);
+
+            gl::GenTextures(1, &mut map.texture);
+            gl::BindTexture(gl::TEXTURE_2D_ARRAY, map.texture);
+            gl::TexImage3D(gl::TEXTURE_2D_ARRAY, 0, gl::DEPTH_COMPONENT32F as i32, size as i32, size as i32, MAX_CASCADES as i32, 0, gl::DEPTH_COMPONENT, gl::FLOAT, ptr::null());
+            gl::TexParameteri(gl::TEXTURE_2D_ARRAY, gl::TEXTURE_MIN_FILTER, gl::NEAREST as i32);
+            gl::TexParameteri(gl::TEXTURE_2D_ARRAY, gl::TEXTURE_MAG_FILTER, gl::NEAREST as i32);
+            // like `ShadowMap`, past the edge nothing casts a shadow
+            gl::TexParameteri(gl::TEXTURE_2D_ARRAY, gl::TEXTURE_WRAP_S, gl::CLAMP_TO_BORDER as i32);
+            gl::TexParameteri(gl::TEXTURE_2D_ARRAY, gl::TEXTURE_WRAP_T, gl::CLAMP_TO_BORDER as i32);
+            let border = [1.0f32; 4];
+            gl::TexParameterfv(gl::TEXTURE_2D_ARRAY, gl::TEXTURE_BORDER_COLOR, border.as_ptr());
+            gl::BindTexture(gl::TEXTURE_2D_ARRAY, 0);
+
+            gl::GenFramebuffers(1, &mut map.framebuffer);
+            gl::BindFramebuffer(gl::FRAMEBUFFER, map.framebuffer);
+            gl::FramebufferTextureLayer(gl::FRAMEBUFFER, gl::DEPTH_ATTACHMENT, map.texture, 0, 0);
+            gl::DrawBuffer(gl::NONE);
+            gl::ReadBuffer(gl::NONE);
+            let status = gl::CheckFramebufferStatus(gl::FRAMEBUFFER);
+            gl::BindFramebuffer(gl::FRAMEBUFFER, previous as u32);
+            if status != gl::FRAMEBUFFER_COMPLETE {
+                return Err(format!("The cascade framebuffer is incomplete: {}", crate::framebuffer::status_message(status)));
+            }
+        }
+        Ok(map)
+    }
+
+    /// Bias and PCF kernel, the same for every cascade.
+    pub fn with_shadow_settings(mut self, shadow: ShadowSettings) -> Self {
+        self.shadow = shadow;
+        self
+    }
+
+    pub fn shadow_settings(&self) -> ShadowSettings {
+        self.shadow
+    }
+
+    pub fn set_shadow_settings(&mut self, shadow: ShadowSettings) {
+        self.shadow = shadow;
+    }
+
+    pub fn settings(&self) -> CascadeSettings {
+        self.settings
+    }
+
+    /// Takes effect on the next `render`, `count` is clamped to `MAX_CASCADES`.
+    pub fn set_settings(&mut self, settings: CascadeSettings) {
+        self.settings = CascadeSettings { count: settings.count.clamp(1, MAX_CASCADES), ..settings };
+    }
+
+    pub fn debug(&self) -> bool {
+        self.debug
+    }
+
+    pub fn set_debug(&mut self, debug: bool) {
+        self.debug = debug;
+    }
+
+    pub fn size(&self) -> u32 {
+        self.size
+    }
+
+    /// The texture array, cascade `i` in layer `i`.
+    pub fn texture(&self) -> u32 {
+        self.texture
+    }
+
+    /// View depths of the last `render`, cascade `i` covers `splits[i]` to `splits[i + 1]`.
+    pub fn splits(&self) -> &[f32] {
+        &self.splits
+    }
+
+    /// Light space matrices of the last `render`, one per cascade.
+    pub fn matrices(&self) -> &[Matrix4<f32>] {
+        &self.matrices
+    }
+
+    /// Splits the frustum of the camera with `view`, fits the cascades and renders `casters` into them.
+    /// The framebuffer, viewport and depth test of the caller are restored after.
+    pub fn render(&mut self, view: Matrix4<f32>, frustum: &ViewFrustum, direction: Vector3<f32>, casters: &[(&dyn Geometry, Matrix4<f32>)]) {
+        self.splits = split_distances(frustum.near, frustum.far, self.settings.count, self.settings.lambda);
+        self.matrices = self.splits.windows(2)
+            .map(|range| cascade_matrix(direction, &frustum.corners(view, range[0], range[1]), self.size))
+            .collect();
+
+        self.depth_shader.use_program();
+        unsafe {
+            let mut previous = 0;
+            let mut viewport = [0; 4];
+            gl::GetIntegerv(gl::FRAMEBUFFER_BINDING, &mut previous);
+            gl::GetIntegerv(gl::VIEWPORT, viewport.as_mut_ptr());
+            let depth_test = gl::IsEnabled(gl::DEPTH_TEST) == gl::TRUE;
+
+            gl::BindFramebuffer(gl::FRAMEBUFFER, self.framebuffer);
+            gl::Viewport(0, 0, self.size as i32, self.size as i32);
+            gl::Enable(gl::DEPTH_TEST);
+            // the cascades only reach as far towards the light as their sphere, casters in front of it
+            // are flattened onto the near plane instead of clipped away
+            gl::Enable(gl::DEPTH_CLAMP);
+            for (layer, &matrix) in self.matrices.iter().enumerate() {
+                gl::FramebufferTextureLayer(gl::FRAMEBUFFER, gl::DEPTH_ATTACHMENT, self.texture, 0, layer as i32);
+                gl::Clear(gl::DEPTH_BUFFER_BIT);
+                self.depth_shader.set_mat4("lightSpaceMatrix", matrix);
+                for &(geometry, model) in casters.iter() {
+                    self.depth_shader.set_mat4("model", model);
+                    geometry.draw_geometry();
+                }
+            }
+
+            gl::Disable(gl::DEPTH_CLAMP);
+            gl::BindVertexArray(0);
+            gl::BindFramebuffer(gl::FRAMEBUFFER, previous as u32);
+            gl::Viewport(viewport[0], viewport[1], viewport[2], viewport[3]);
+            if !depth_test {
+                gl::Disable(gl::DEPTH_TEST);
+            }
+        }
+    }
+
+    /// Binds the cascades to `CASCADE_UNIT` and sets the cascade uniforms of `shader`, which has to be in use.
+    /// The sampler has to be set up by `shadow::assign_shadow_units`, and `view` set like for the vertex shader.
+    pub fn apply(&self, shader: &Shader) {
+        unsafe {
+            gl::ActiveTexture(gl::TEXTURE0 + CASCADE_UNIT);
+            gl::BindTexture(gl::TEXTURE_2D_ARRAY, self.texture);
+            gl::ActiveTexture(gl::TEXTURE0);
+        }
+        shader.set_bool("cascaded", true);
+        shader.set_int("cascadeCount", self.matrices.len() as i32);
+        for (index, &matrix) in self.matrices.iter().enumerate() {
+            shader.set_mat4(&format!("cascadeMatrices[{}]", index), matrix);
+        }
+        for (index, &split) in self.splits.iter().enumerate() {
+            shader.set_float(&format!("cascadeSplits[{}]", index), split);
+        }
+        shader.set_float("cascadeBlend", self.settings.blend);
+        shader.set_bool("cascadeDebug", self.debug);
+        shader.set_float("shadowMinBias", self.shadow.min_bias);
+        shader.set_float("shadowSlopeBias", self.shadow.slope_bias);
+        shader.set_int("pcfRadius", self.shadow.pcf_radius as i32);
+    }
+}
+
+impl Drop for CascadedShadowMap {
+    fn drop(&mut self) {
+        unsafe {
+            gl::DeleteFramebuffers(1, &self.framebuffer);
+            gl::DeleteTextures(1, &self.texture);
+        }
+    }
+}
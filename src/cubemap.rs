@@ -0,0 +1,261 @@
+//! Cubemap textures from six images, a cross layout or an equirectangular panorama, and a skybox.
+//!
+//! Faces are always in GL's order: right (+X), left (-X), top (+Y), bottom (-Y), front (+Z) and
+//! back (-Z). Images are uploaded without flipping, their first row is the top of the face.
+//! Like in GL's lookup, the faces are seen from the inside of a left-handed cube, so e.g. the
+//! right edge of the -Z face lies towards -X.
+
+use std::f32::consts::PI;
+use std::ffi::c_void;
+
+use cgmath::{InnerSpace, Matrix3, Matrix4, Vector3};
+use image::{Rgba, RgbaImage};
+
+use crate::scenes::shapes;
+use crate::shader::Shader;
+
+/// The six faces in the order of `gl::TEXTURE_CUBE_MAP_POSITIVE_X + index`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum CubeFace {
+    PositiveX,
+    NegativeX,
+    PositiveY,
+    NegativeY,
+    PositiveZ,
+    NegativeZ,
+}
+
+pub const CUBE_FACES: [CubeFace; 6] = [
+    CubeFace::PositiveX,
+    CubeFace::NegativeX,
+    CubeFace::PositiveY,
+    CubeFace::NegativeY,
+    CubeFace::PositiveZ,
+    CubeFace::NegativeZ,
+];
+
+impl CubeFace {
+    pub fn gl_target(self) -> u32 {
+        gl::TEXTURE_CUBE_MAP_POSITIVE_X + self as u32
+    }
+
+    /// Direction through the point `(u, v)` of the face, `(0, 0)` is the top left of its image.
+    pub fn direction(self, u: f32, v: f32) -> Vector3<f32> {
+        // from the cubemap lookup in the GL spec, s and t run from -1 to 1
+        let (s, t) = (2.0 * u - 1.0, 2.0 * v - 1.0);
+        let direction = match self {
+            CubeFace::PositiveX => Vector3::new(1.0, -t, -s),
+            CubeFace::NegativeX => Vector3::new(-1.0, -t, s),
+            CubeFace::PositiveY => Vector3::new(s, 1.0, t),
+            CubeFace::NegativeY => Vector3::new(s, -1.0, -t),
+            CubeFace::PositiveZ => Vector3::new(s, -t, 1.0),
+            CubeFace::NegativeZ => Vector3::new(-s, -t, -1.0),
+        };
+        direction.normalize()
+    }
+
+    /// View matrix of a 90 degree camera at the origin that renders this face, e.g. for probes.
+    pub fn view(self) -> Matrix4<f32> {
+        let origin = cgmath::Point3::new(0.0, 0.0, 0.0);
+        let (front, up) = match self {
+            CubeFace::PositiveX => (Vector3::unit_x(), -Vector3::unit_y()),
+            CubeFace::NegativeX => (-Vector3::unit_x(), -Vector3::unit_y()),
+            CubeFace::PositiveY => (Vector3::unit_y(), Vector3::unit_z()),
+            CubeFace::NegativeY => (-Vector3::unit_y(), -Vector3::unit_z()),
+            CubeFace::PositiveZ => (Vector3::unit_z(), -Vector3::unit_y()),
+            CubeFace::NegativeZ => (-Vector3::unit_z(), -Vector3::unit_y()),
+        };
+        Matrix4::look_at(origin, origin + front, up)
+    }
+}
+
+/// Splits a cross into its faces, either 4x3 faces wide (horizontal) or 3x4 (vertical):
+///
+/// ```text
+///      +Y                +Y
+///  -X  +Z  +X  -Z    -X  +Z  +X
+///      -Y                -Y
+///                        -Z (upside down)
+/// ```
+pub fn faces_from_cross(image: &RgbaImage) -> Result<[RgbaImage; 6], String> {
+    let (width, height) = image.dimensions();
+    if width == 0 || height == 0 {
+        return Err("An empty image is no cubemap cross".to_string());
+    }
+    // cells of the faces in the grid, in face order
+    let (size, cells) = if width * 3 == height * 4 && width % 4 == 0 {
+        (width / 4, [(2, 1), (0, 1), (1, 0), (1, 2), (1, 1), (3, 1)])
+    } else if width * 4 == height * 3 && width % 3 == 0 {
+        (width / 3, [(2, 1), (0, 1), (1, 0), (1, 2), (1, 1), (1, 3)])
+    } else {
+        return Err(format!("A {}x{} image is no cubemap cross, expected a 4:3 or 3:4 image", width, height));
+    };
+    let vertical = height > width;
+
+    let face = |index: usize| {
+        let (cell_x, cell_y) = cells[index];
+        RgbaImage::from_fn(size, size, |x, y| {
+            // the back face of a vertical cross hangs below the bottom face, rotated by 180 degrees
+            let (x, y) = if vertical && index == 5 { (size - 1 - x, size - 1 - y) } else { (x, y) };
+            *image.get_pixel(cell_x * size + x, cell_y * size + y)
+        })
+    };
+    Ok([face(0), face(1), face(2), face(3), face(4), face(5)])
+}
+
+/// Resamples a panorama with longitude along x and latitude along y into faces of `size` pixels.
+///
+/// The center of the panorama ends up in the back face (-Z), where the default camera looks.
+pub fn faces_from_equirectangular(image: &RgbaImage, size: u32) -> [RgbaImage; 6] {
+    let face = |face: CubeFace| {
+        RgbaImage::from_fn(size, size, |x, y| {
+            let direction = face.direction((x as f32 + 0.5) / size as f32, (y as f32 + 0.5) / size as f32);
+            let u = 0.5 + direction.x.atan2(-direction.z) / (2.0 * PI);
+            let v = 0.5 - direction.y.asin() / PI;
+            sample_bilinear(image, u, v)
+        })
+    };
+    [
+        face(CubeFace::PositiveX),
+        face(CubeFace::NegativeX),
+        face(CubeFace::PositiveY),
+        face(CubeFace::NegativeY),
+        face(CubeFace::PositiveZ),
+        face(CubeFace::NegativeZ),
+    ]
+}
+
+// u wraps around, v is clamped at the poles
+fn sample_bilinear(image: &RgbaImage, u: f32, v: f32) -> Rgba<u8> {
+    let (width, height) = image.dimensions();
+    let x = u * width as f32 - 0.5;
+    let y = (v * height as f32 - 0.5).max(0.0).min(height as f32 - 1.0);
+    let (x0, y0) = (x.floor(), y.floor());
+    let (fx, fy) = (x - x0, y - y0);
+
+    let texel = |x: i64, y: i64| {
+        let x = x.rem_euclid(width as i64) as u32;
+        let y = y.max(0).min(height as i64 - 1) as u32;
+        image.get_pixel(x, y).data
+    };
+    let (x0, y0) = (x0 as i64, y0 as i64);
+    let corners = [texel(x0, y0), texel(x0 + 1, y0), texel(x0, y0 + 1), texel(x0 + 1, y0 + 1)];
+
+    let mut result = [0u8; 4];
+    for (channel, value) in result.iter_mut().enumerate() {
+        let top = corners[0][channel] as f32 * (1.0 - fx) + corners[1][channel] as f32 * fx;
+        let bottom = corners[2][channel] as f32 * (1.0 - fx) + corners[3][channel] as f32 * fx;
+        *value = (top * (1.0 - fy) + bottom * fy).round() as u8;
+    }
+    Rgba(result)
+}
+
+/// Uploads six square faces as a cubemap with mipmaps.
+///
+/// Filtering across face edges is global state, whoever samples the cubemap enables
+/// `gl::TEXTURE_CUBE_MAP_SEAMLESS` for that, see `Skybox::draw`.
+pub unsafe fn create_cubemap(faces: &[RgbaImage; 6]) -> Result<u32, String> {
+    let size = faces[0].width();
+    if faces.iter().any(|face| face.dimensions() != (size, size)) {
+        return Err("The cubemap faces have to be squares of the same size".to_string());
+    }
+
+    let mut texture = 0;
+    gl::GenTextures(1, &mut texture);
+    gl::BindTexture(gl::TEXTURE_CUBE_MAP, texture);
+    for (face, image) in CUBE_FACES.iter().zip(faces.iter()) {
+        gl::TexImage2D(
+            face.gl_target(), 0, gl::RGBA8 as i32, size as i32, size as i32, 0,
+            gl::RGBA, gl::UNSIGNED_BYTE, image.as_ptr() as *const c_void
+        );
+    }
+    gl::GenerateMipmap(gl::TEXTURE_CUBE_MAP);
+
+    gl::TexParameteri(gl::TEXTURE_CUBE_MAP, gl::TEXTURE_MIN_FILTER, gl::LINEAR_MIPMAP_LINEAR as i32);
+    gl::TexParameteri(gl::TEXTURE_CUBE_MAP, gl::TEXTURE_MAG_FILTER, gl::LINEAR as i32);
+    gl::TexParameteri(gl::TEXTURE_CUBE_MAP, gl::TEXTURE_WRAP_S, gl::CLAMP_TO_EDGE as i32);
+    gl::TexParameteri(gl::TEXTURE_CUBE_MAP, gl::TEXTURE_WRAP_T, gl::CLAMP_TO_EDGE as i32);
+    gl::TexParameteri(gl::TEXTURE_CUBE_MAP, gl::TEXTURE_WRAP_R, gl::CLAMP_TO_EDGE as i32);
+
+    gl::BindTexture(gl::TEXTURE_CUBE_MAP, 0);
+    Ok(texture)
+}
+
+fn load_image(path: &str) -> Result<RgbaImage, String> {
+    image::open(path).map(|image| image.to_rgba()).map_err(|e| format!("Could not load {}. {}", path, e))
+}
+
+/// Loads a cubemap from six images in face order: right, left, top, bottom, front, back.
+pub unsafe fn cubemap_from_files(paths: &[&str; 6]) -> Result<u32, String> {
+    let faces = [
+        load_image(paths[0])?, load_image(paths[1])?, load_image(paths[2])?,
+        load_image(paths[3])?, load_image(paths[4])?, load_image(paths[5])?,
+    ];
+    create_cubemap(&faces)
+}
+
+/// Loads a cubemap from a single image in a horizontal or vertical cross layout.
+pub unsafe fn cubemap_from_cross(path: &str) -> Result<u32, String> {
+    create_cubemap(&faces_from_cross(&load_image(path)?)?)
+}
+
+/// Loads a cubemap with faces of `size` pixels from an equirectangular panorama.
+pub unsafe fn cubemap_from_equirectangular(path: &str, size: u32) -> Result<u32, String> {
+    create_cubemap(&faces_from_equirectangular(&load_image(path)?, size))
+}
+
+/// Draws a cubemap around the camera, behind everything that was drawn before.
+pub struct Skybox {
+    shader: Shader,
+    vao: shapes::VertexArray,
+}
+
+impl Skybox {
+    pub fn new() -> Self {
+        let shader = Shader::new(
+            "./src/shaders/4_advanced_opengl/skybox.vs",
+            "./src/shaders/4_advanced_opengl/skybox.fs"
+        );
+        shader.use_program();
+        shader.set_int("skybox", 0);
+        // the tex coords of the cube are ignored, the position is the lookup direction
+        let vao = unsafe { shapes::create_vao(&shapes::CUBE_VERTICES, &[3, 2]) };
+        Skybox { shader, vao }
+    }
+
+    /// Draws after the opaque geometry, so only pixels that are still empty run the shader.
+    pub fn draw(&self, cubemap: u32, view: Matrix4<f32>, projection: Matrix4<f32>) {
+        // only the rotation of the view, the sky is infinitely far away
+        let view = Matrix4::from(Matrix3::from_cols(view.x.truncate(), view.y.truncate(), view.z.truncate()));
+
+        unsafe {
+            let mut depth_func = 0;
+            gl::GetIntegerv(gl::DEPTH_FUNC, &mut depth_func);
+            let cull_face = gl::IsEnabled(gl::CULL_FACE) == gl::TRUE;
+            let seamless = gl::IsEnabled(gl::TEXTURE_CUBE_MAP_SEAMLESS) == gl::TRUE;
+            // the shader puts the sky on the far plane at depth 1.0, which only passes with LEQUAL
+            gl::DepthFunc(gl::LEQUAL);
+            // the camera is inside the cube and sees its back faces
+            gl::Disable(gl::CULL_FACE);
+            // filter across face edges instead of clamping at them, otherwise the seams show
+            gl::Enable(gl::TEXTURE_CUBE_MAP_SEAMLESS);
+
+            self.shader.use_program();
+            self.shader.set_mat4("view", view);
+            self.shader.set_mat4("projection", projection);
+            gl::ActiveTexture(gl::TEXTURE0);
+            gl::BindTexture(gl::TEXTURE_CUBE_MAP, cubemap);
+            gl::BindVertexArray(self.vao.id);
+            gl::DrawArrays(gl::TRIANGLES, 0, 36);
+            gl::BindVertexArray(0);
+
+            gl::DepthFunc(depth_func as u32);
+            if cull_face {
+                gl::Enable(gl::CULL_FACE);
+            }
+            if !seamless {
+                gl::Disable(gl::TEXTURE_CUBE_MAP_SEAMLESS);
+            }
+        }
+    }
+}
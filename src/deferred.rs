@@ -0,0 +1,328 @@
+//! Deferred shading: draw the geometry once into a G-buffer, then light every pixel from it.
+//!
+//! Forward shading loops over all lights for every fragment of every object, so a shader has a
+//! fixed number of them, see `NR_POINT_LIGHTS` in `multiple_lights.fs`. Here the geometry pass only
+//! stores what lighting needs per pixel: the normal, the albedo and the specular intensity, and the
+//! depth, which gives back the world position. Each light then draws a volume around the space it
+//! reaches, a sphere for point lights and a cone for spot lights, and shades only the pixels inside,
+//! added on top of each other. A light costs the pixels it covers, so hundreds of them are cheap.
+//!
+//! Blending needs what is behind a surface, which the G-buffer does not keep. `shade` copies the
+//! depth of the G-buffer into the target, so transparent objects can be drawn forward afterwards,
+//! e.g. through a `RenderQueue`, and are hidden behind the opaque ones.
+
+use std::f32::consts::PI;
+
+use cgmath::{Angle, Deg, InnerSpace, Matrix4, Quaternion, SquareMatrix, Vector3};
+
+use crate::framebuffer::{ColorFormat, DepthFormat, Framebuffer, FramebufferDesc, FullscreenQuad, Storage, FULLSCREEN_VERTEX_SHADER};
+use crate::render_state::{BlendState, CullState, DepthState, RenderState, StateCache};
+use crate::scene::Frame;
+use crate::scenes::shapes::{self, VertexArray};
+use crate::shader::Shader;
+
+pub const GEOMETRY_FRAGMENT_SHADER: &str = "./src/shaders/5_advanced_lighting/gbuffer.fs";
+pub const LIGHT_VOLUME_VERTEX_SHADER: &str = "./src/shaders/5_advanced_lighting/light_volume.vs";
+pub const DEFERRED_LIGHT_SHADER: &str = "./src/shaders/5_advanced_lighting/deferred_light.fs";
+
+/// Light below this is not worth shading, it sets how far the volumes reach.
+pub const LIGHT_CUTOFF: f32 = 5.0 / 256.0;
+
+// triangles around the volumes, more fit them tighter
+const SPHERE_SEGMENTS: u32 = 16;
+const CONE_SEGMENTS: u32 = 16;
+
+/// A light shining in all directions, falling off with `1 / (1 + distance^2)`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PointLight {
+    pub position: Vector3<f32>,
+    pub color: Vector3<f32>,
+}
+
+impl PointLight {
+    /// How far the light reaches before it drops below `LIGHT_CUTOFF`.
+    pub fn radius(&self) -> f32 {
+        light_radius(self.color)
+    }
+}
+
+/// A point light limited to a cone, fading out from `cut_off` to `outer_cut_off` off its direction.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SpotLight {
+    pub position: Vector3<f32>,
+    pub direction: Vector3<f32>,
+    pub color: Vector3<f32>,
+    pub cut_off: Deg<f32>,
+    // below 90 degrees, wider cones do not fit a cone volume
+    pub outer_cut_off: Deg<f32>,
+}
+
+impl SpotLight {
+    pub fn radius(&self) -> f32 {
+        light_radius(self.color)
+    }
+}
+
+/// The distance at which the brightest channel of `color` falls off to `LIGHT_CUTOFF`, 0 for lights
+/// that never reach it.
+pub fn light_radius(color: Vector3<f32>) -> f32 {
+    let brightest = color.x.max(color.y).max(color.z);
+    (brightest / LIGHT_CUTOFF - 1.0).max(0.0).sqrt()
+}
+
+/// A unit sphere as a triangle list of positions, front faces outside. The corners lie a little
+/// outside the sphere so the flat faces still enclose it.
+pub fn sphere_vertices(segments: u32) -> Vec<f32> {
+    let rings = segments / 2;
+    // the middle of a face is closest to the center, on the equator cos(step / 2) squared of a corner
+    let scale = 1.0 / (PI / segments as f32).cos().powi(2);
+    let point = |ring: u32, segment: u32| {
+        let (theta, phi) = (PI * ring as f32 / rings as f32, 2.0 * PI * segment as f32 / segments as f32);
+        [theta.sin() * phi.cos() * scale, theta.cos() * scale, theta.sin() * phi.sin() * scale]
+    };
+    let mut vertices = Vec::new();
+    for ring in 0..rings {
+        for segment in 0..segments {
+            let (a, b, c, d) = (point(ring, segment), point(ring + 1, segment), point(ring + 1, segment + 1), point(ring, segment + 1));
+            // the triangles at the poles would be empty
+            if ring > 0 {
+                vertices.extend(a.iter().chain(d.iter()).chain(b.iter()));
+            }
+            if ring + 1 < rings {
+                vertices.extend(b.iter().chain(d.iter()).chain(c.iter()));
+            }
+        }
+    }
+    vertices
+}
+
+/// A cone as a triangle list of positions, front faces outside: the tip at the origin, opening
+/// towards -z with a base of radius 1 at z = -1. The base corners lie outside the circle.
+pub fn cone_vertices(segments: u32) -> Vec<f32> {
+    let scale = 1.0 / (PI / segments as f32).cos();
+    let corner = |segment: u32| {
+        let phi = 2.0 * PI * segment as f32 / segments as f32;
+        [phi.cos() * scale, phi.sin() * scale, -1.0]
+    };
+    let (tip, center) = ([0.0, 0.0, 0.0], [0.0, 0.0, -1.0]);
+    let mut vertices = Vec::new();
+    for segment in 0..segments {
+        let (a, b) = (corner(segment), corner(segment + 1));
+        vertices.extend(tip.iter().chain(a.iter()).chain(b.iter()));
+        vertices.extend(center.iter().chain(b.iter()).chain(a.iter()));
+    }
+    vertices
+}
+
+/// The G-buffer, the shaders filling and lighting it, and the light volumes.
+pub struct DeferredRenderer {
+    // world space normals, albedo with the specular intensity in alpha, and depth
+    gbuffer: Framebuffer,
+    geometry_shader: Shader,
+    // one fragment shader, run over the whole frame for the ambient light and over the volumes for the rest
+    ambient_shader: Shader,
+    light_shader: Shader,
+    quad: FullscreenQuad,
+    sphere: (VertexArray, i32),
+    cone: (VertexArray, i32),
+    // light every surface gets, times its albedo
+    pub ambient: Vector3<f32>,
+    pub shininess: f32,
+}
+
+impl DeferredRenderer {
+    pub fn new() -> Result<Self, String> {
+        let gbuffer = Framebuffer::new(FramebufferDesc {
+            width: 1,
+            height: 1,
+            colors: vec![ColorFormat::Rgba16F, ColorFormat::Rgba8],
+            // the same format as the targets of scenes, so it can be copied into them
+            depth: Some((DepthFormat::Depth24Stencil8, Storage::Texture)),
+        })?;
+
+        let geometry_shader = Shader::new("./src/shaders/2_lighting/lighting_maps.vs", GEOMETRY_FRAGMENT_SHADER);
+        geometry_shader.use_program();
+        geometry_shader.set_int("material.diffuseTex", 0);
+        geometry_shader.set_int("material.specularTex", 1);
+
+        let ambient_shader = Shader::new(FULLSCREEN_VERTEX_SHADER, DEFERRED_LIGHT_SHADER);
+        let light_shader = Shader::new(LIGHT_VOLUME_VERTEX_SHADER, DEFERRED_LIGHT_SHADER);
+        for shader in [&ambient_shader, &light_shader].iter() {
+            shader.use_program();
+            shader.set_int("gNormal", 0);
+            shader.set_int("gAlbedoSpecular", 1);
+            shader.set_int("gDepth", 2);
+        }
+
+        let (sphere, cone) = (sphere_vertices(SPHERE_SEGMENTS), cone_vertices(CONE_SEGMENTS));
+        Ok(DeferredRenderer {
+            gbuffer,
+            geometry_shader,
+            ambient_shader,
+            light_shader,
+            quad: FullscreenQuad::new(),
+            sphere: (unsafe { shapes::create_vao(&sphere, &[3]) }, sphere.len() as i32 / 3),
+            cone: (unsafe { shapes::create_vao(&cone, &[3]) }, cone.len() as i32 / 3),
+            ambient: Vector3::new(0.05, 0.05, 0.05),
+            shininess: 32.0,
+        })
+    }
+
+    pub fn gbuffer(&self) -> &Framebuffer {
+        &self.gbuffer
+    }
+
+    /// Binds and clears the G-buffer at the frame's size, returns the shader to draw the opaque
+    /// geometry with. It takes `model`, and the diffuse and specular map on units 0 and 1.
+    pub fn begin_geometry(&mut self, frame: &Frame, view: Matrix4<f32>, projection: Matrix4<f32>) -> &Shader {
+        self.gbuffer.resize(frame.width, frame.height).expect("Could not resize the G-buffer");
+        self.gbuffer.bind();
+        unsafe {
+            gl::Enable(gl::DEPTH_TEST);
+            gl::ClearColor(0.0, 0.0, 0.0, 0.0);
+            gl::Clear(gl::COLOR_BUFFER_BIT | gl::DEPTH_BUFFER_BIT | gl::STENCIL_BUFFER_BIT);
+        }
+        self.geometry_shader.use_program();
+        self.geometry_shader.set_mat4("view", view);
+        self.geometry_shader.set_mat4("projection", projection);
+        &self.geometry_shader
+    }
+
+    /// Lights the G-buffer into `frame.framebuffer`, cleared to `background` where nothing was drawn.
+    ///
+    /// The target needs a `Depth24Stencil8` depth attachment to take the depth of the G-buffer,
+    /// anything else is an error and nothing is drawn. The render state and the clear color are
+    /// given back as they were, the depth test on as `begin_geometry` left it. Leaves the target
+    /// bound with the depth of the G-buffer, ready for a forward pass, and the normal, albedo and
+    /// depth textures of the G-buffer bound on units 0 to 2, with unit 0 active.
+    pub fn shade(
+        &mut self,
+        frame: &Frame,
+        view: Matrix4<f32>,
+        projection: Matrix4<f32>,
+        point_lights: &[PointLight],
+        spot_lights: &[SpotLight],
+        background: Vector3<f32>,
+    ) -> Result<(), String> {
+        check_depth_stencil(frame.framebuffer)?;
+        let view_projection = projection * view;
+        let camera_position = view.invert().expect("The view matrix is invertible").w.truncate();
+
+        self.gbuffer.blit_to(frame.framebuffer, frame.width, frame.height, gl::DEPTH_BUFFER_BIT, gl::NEAREST);
+        let mut clear_color = [0.0; 4];
+        unsafe {
+            gl::BindFramebuffer(gl::FRAMEBUFFER, frame.framebuffer);
+            gl::Viewport(0, 0, frame.width as i32, frame.height as i32);
+            gl::GetFloatv(gl::COLOR_CLEAR_VALUE, clear_color.as_mut_ptr());
+            gl::ClearColor(background.x, background.y, background.z, 1.0);
+            gl::Clear(gl::COLOR_BUFFER_BIT);
+        }
+        let inputs = [self.gbuffer.color_texture(0), self.gbuffer.color_texture(1), self.gbuffer.depth_texture().expect("The G-buffer has a depth texture")];
+        for shader in [&self.ambient_shader, &self.light_shader].iter() {
+            shader.use_program();
+            shader.set_mat4("inverseViewProjection", view_projection.invert().expect("The view and projection are invertible"));
+            shader.set_vector2("screenSize", frame.width as f32, frame.height as f32);
+            shader.set_vector3v("cameraPosition", camera_position);
+            shader.set_float("shininess", self.shininess);
+        }
+
+        //the ambient light everywhere something was drawn
+        self.ambient_shader.use_program();
+        self.ambient_shader.set_int("lightType", 0);
+        self.ambient_shader.set_vector3v("ambient", self.ambient);
+        self.quad.pass(&self.ambient_shader, &inputs);
+
+        //then every light adds what it reaches, drawing the back faces of its volume behind the surfaces
+        // the caller's state is not known here, the scope puts it back afterwards
+        let mut cache = StateCache::new();
+        let _volumes = cache.scope(&RenderState {
+            depth: DepthState { test: true, func: gl::GEQUAL, write: false },
+            blend: BlendState::func(gl::ONE, gl::ONE),
+            cull: CullState::culling(gl::FRONT, gl::CCW),
+            ..RenderState::default()
+        });
+        self.light_shader.use_program();
+        self.light_shader.set_mat4("view", view);
+        self.light_shader.set_mat4("projection", projection);
+        unsafe {
+            // volumes reaching past the far plane keep their back faces, at the far depth
+            let depth_clamp = gl::IsEnabled(gl::DEPTH_CLAMP) == gl::TRUE;
+            gl::Enable(gl::DEPTH_CLAMP);
+            for (unit, &texture) in inputs.iter().enumerate() {
+                gl::ActiveTexture(gl::TEXTURE0 + unit as u32);
+                gl::BindTexture(gl::TEXTURE_2D, texture);
+            }
+            gl::ActiveTexture(gl::TEXTURE0);
+
+            self.light_shader.set_int("lightType", 1);
+            gl::BindVertexArray(self.sphere.0.id);
+            for light in point_lights {
+                let radius = light.radius();
+                self.light_shader.set_mat4("model", Matrix4::from_translation(light.position) * Matrix4::from_scale(radius));
+                self.light_shader.set_vector3v("lightPosition", light.position);
+                self.light_shader.set_vector3v("lightColor", light.color);
+                self.light_shader.set_float("lightRadius", radius);
+                gl::DrawArrays(gl::TRIANGLES, 0, self.sphere.1);
+            }
+
+            self.light_shader.set_int("lightType", 2);
+            gl::BindVertexArray(self.cone.0.id);
+            for light in spot_lights {
+                let (radius, direction) = (light.radius(), light.direction.normalize());
+                let width = radius * Deg(light.outer_cut_off.0.min(89.0)).tan();
+                let model = Matrix4::from_translation(light.position)
+                    * Matrix4::from(Quaternion::from_arc(-Vector3::unit_z(), direction, Some(Vector3::unit_y())))
+                    * Matrix4::from_nonuniform_scale(width, width, radius);
+                self.light_shader.set_mat4("model", model);
+                self.light_shader.set_vector3v("lightPosition", light.position);
+                self.light_shader.set_vector3v("lightDirection", direction);
+                self.light_shader.set_vector3v("lightColor", light.color);
+                self.light_shader.set_float("lightRadius", radius);
+                self.light_shader.set_float("cutOff", light.cut_off.cos());
+                self.light_shader.set_float("outerCutOff", light.outer_cut_off.cos());
+                gl::DrawArrays(gl::TRIANGLES, 0, self.cone.1);
+            }
+            gl::BindVertexArray(0);
+            if !depth_clamp {
+                gl::Disable(gl::DEPTH_CLAMP);
+            }
+            gl::ClearColor(clear_color[0], clear_color[1], clear_color[2], clear_color[3]);
+        }
+        Ok(())
+    }
+}
+
+// blitting depth needs the same format on both sides, a mismatch would only raise GL_INVALID_OPERATION
+fn check_depth_stencil(framebuffer: u32) -> Result<(), String> {
+    unsafe {
+        let mut previous = 0;
+        gl::GetIntegerv(gl::DRAW_FRAMEBUFFER_BINDING, &mut previous);
+        gl::BindFramebuffer(gl::DRAW_FRAMEBUFFER, framebuffer);
+        // the default framebuffer names its depth buffer differently
+        let attachment = if framebuffer == 0 { gl::DEPTH } else { gl::DEPTH_ATTACHMENT };
+        let parameter = |name| {
+            let mut value = 0;
+            gl::GetFramebufferAttachmentParameteriv(gl::DRAW_FRAMEBUFFER, attachment, name, &mut value);
+            value
+        };
+        let format = if parameter(gl::FRAMEBUFFER_ATTACHMENT_OBJECT_TYPE) == gl::NONE as i32 {
+            None
+        } else {
+            Some((
+                parameter(gl::FRAMEBUFFER_ATTACHMENT_DEPTH_SIZE),
+                parameter(gl::FRAMEBUFFER_ATTACHMENT_STENCIL_SIZE),
+                parameter(gl::FRAMEBUFFER_ATTACHMENT_COMPONENT_TYPE) as u32,
+            ))
+        };
+        gl::BindFramebuffer(gl::DRAW_FRAMEBUFFER, previous as u32);
+
+        match format {
+            Some((24, 8, gl::UNSIGNED_NORMALIZED)) => Ok(()),
+            Some((depth, stencil, _)) => Err(format!(
+                "Framebuffer {} has a {} bit depth and {} bit stencil attachment, shading needs Depth24Stencil8",
+                framebuffer, depth, stencil
+            )),
+            None => Err(format!("Framebuffer {} has no depth attachment, shading needs Depth24Stencil8", framebuffer)),
+        }
+    }
+}
@@ -0,0 +1,221 @@
+//! Materials that reflect or refract a cubemap, and probes that render the scene into one.
+//!
+//! The material works with anything that has positions at location 0 and normals at location 1,
+//! like the lit cube of `shapes` or the meshes of a `Model`. The cubemap is bound to
+//! `ENVIRONMENT_UNIT`, above the units a mesh binds its textures to. Scenes sampling a cubemap
+//! enable `gl::TEXTURE_CUBE_MAP_SEAMLESS`, otherwise the edges between the faces show.
+
+use std::ptr;
+
+use cgmath::{perspective, Deg, Matrix4, Vector3};
+
+use crate::cubemap::CUBE_FACES;
+use crate::shader::Shader;
+
+pub const ENVIRONMENT_VERTEX_SHADER: &str = "./src/shaders/4_advanced_opengl/environment_mapping.vs";
+pub const ENVIRONMENT_FRAGMENT_SHADER: &str = "./src/shaders/4_advanced_opengl/environment_mapping.fs";
+
+/// Texture unit of the environment cubemap, the last one every fragment shader has.
+pub const ENVIRONMENT_UNIT: u32 = 15;
+
+// indices of refraction
+pub const IOR_WATER: f32 = 1.33;
+pub const IOR_GLASS: f32 = 1.52;
+pub const IOR_DIAMOND: f32 = 2.42;
+
+/// How a surface shows its environment.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum EnvironmentMode {
+    // a perfect mirror
+    Reflect,
+    // the view bent at the surface, like looking into glass
+    Refract,
+    // both, mixed by the Schlick fresnel term, mostly reflecting at grazing angles
+    Fresnel,
+}
+
+/// Mode, index of refraction and a tint multiplied onto the looked up color.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct EnvironmentMaterial {
+    pub mode: EnvironmentMode,
+    pub ior: f32,
+    pub tint: Vector3<f32>,
+}
+
+impl EnvironmentMaterial {
+    pub fn reflective() -> Self {
+        EnvironmentMaterial { mode: EnvironmentMode::Reflect, ior: IOR_GLASS, tint: Vector3::new(1.0, 1.0, 1.0) }
+    }
+
+    pub fn refractive(ior: f32) -> Self {
+        EnvironmentMaterial { mode: EnvironmentMode::Refract, ior, tint: Vector3::new(1.0, 1.0, 1.0) }
+    }
+
+    pub fn fresnel(ior: f32) -> Self {
+        EnvironmentMaterial { mode: EnvironmentMode::Fresnel, ior, tint: Vector3::new(1.0, 1.0, 1.0) }
+    }
+
+    pub fn with_tint(self, tint: Vector3<f32>) -> Self {
+        EnvironmentMaterial { tint, ..self }
+    }
+
+    /// Creates the shader of the material, the matrices are set by the caller.
+    pub fn shader() -> Shader {
+        let shader = Shader::new(ENVIRONMENT_VERTEX_SHADER, ENVIRONMENT_FRAGMENT_SHADER);
+        shader.use_program();
+        shader.set_int("environment", ENVIRONMENT_UNIT as i32);
+        shader
+    }
+
+    /// Sets the uniforms of `shader` and binds `cubemap`, draw meshes or a `Model` afterwards.
+    pub fn apply(&self, shader: &Shader, cubemap: u32, camera_position: Vector3<f32>) {
+        shader.use_program();
+        let mode = match self.mode {
+            EnvironmentMode::Reflect => 0,
+            EnvironmentMode::Refract => 1,
+            EnvironmentMode::Fresnel => 2,
+        };
+        shader.set_int("mode", mode);
+        shader.set_float("ior", self.ior);
+        shader.set_vector3v("tint", self.tint);
+        shader.set_vector3v("cameraPos", camera_position);
+        unsafe {
+            gl::ActiveTexture(gl::TEXTURE0 + ENVIRONMENT_UNIT);
+            gl::BindTexture(gl::TEXTURE_CUBE_MAP, cubemap);
+            gl::ActiveTexture(gl::TEXTURE0);
+        }
+    }
+}
+
+/// The reflected fraction of light hitting a surface with index of refraction `ior` from air,
+/// `cos_theta` is the cosine between the view direction and the normal. Same as in the shader.
+pub fn schlick(cos_theta: f32, ior: f32) -> f32 {
+    let r0 = ((1.0 - ior) / (1.0 + ior)).powi(2);
+    r0 + (1.0 - r0) * (1.0 - cos_theta).powi(5)
+}
+
+/// Renders the scene around a point into a cubemap, a dynamic environment for reflections.
+///
+/// Rendering six faces is expensive, so the probe only renders every `interval` frames and the
+/// reflections lag a little behind. The object carrying the probe should not be drawn into it.
+pub struct EnvironmentProbe {
+    pub position: Vector3<f32>,
+    pub interval: u32,
+    size: u32,
+    texture: u32,
+    framebuffer: u32,
+    depth: u32,
+    frames_until_update: u32,
+}
+
+impl EnvironmentProbe {
+    /// Creates the cubemap with faces of `size` pixels, it is rendered on the first `update`.
+    pub fn new(position: Vector3<f32>, size: u32, interval: u32) -> Result<Self, String> {
+        if size == 0 {
+            return Err("An environment probe needs faces of at least one pixel".to_string());
+        }
+        let mut probe = EnvironmentProbe {
+            position, interval: interval.max(1), size,
+            texture: 0, framebuffer: 0, depth: 0, frames_until_update: 0,
+        };
+        unsafe {
+            let mut previous = 0;
+            gl::GetIntegerv(gl::FRAMEBUFFER_BINDING, &mut previous);
+
+            gl::GenTextures(1, &mut probe.texture);
+            gl::BindTexture(gl::TEXTURE_CUBE_MAP, probe.texture);
+            for face in CUBE_FACES.iter() {
+                gl::TexImage2D(face.gl_target(), 0, gl::RGBA8 as i32, size as i32, size as i32, 0, gl::RGBA, gl::UNSIGNED_BYTE, ptr::null());
+            }
+            // no mipmaps, they would have to be generated after every update
+            gl::TexParameteri(gl::TEXTURE_CUBE_MAP, gl::TEXTURE_MIN_FILTER, gl::LINEAR as i32);
+            gl::TexParameteri(gl::TEXTURE_CUBE_MAP, gl::TEXTURE_MAG_FILTER, gl::LINEAR as i32);
+            gl::TexParameteri(gl::TEXTURE_CUBE_MAP, gl::TEXTURE_WRAP_S, gl::CLAMP_TO_EDGE as i32);
+            gl::TexParameteri(gl::TEXTURE_CUBE_MAP, gl::TEXTURE_WRAP_T, gl::CLAMP_TO_EDGE as i32);
+            gl::TexParameteri(gl::TEXTURE_CUBE_MAP, gl::TEXTURE_WRAP_R, gl::CLAMP_TO_EDGE as i32);
+            gl::BindTexture(gl::TEXTURE_CUBE_MAP, 0);
+
+            // one depth buffer is shared by the faces, it is cleared for each of them
+            gl::GenRenderbuffers(1, &mut probe.depth);
+            gl::BindRenderbuffer(gl::RENDERBUFFER, probe.depth);
+            gl::RenderbufferStorage(gl::RENDERBUFFER, gl::DEPTH_COMPONENT24, size as i32, size as i32);
+            gl::BindRenderbuffer(gl::RENDERBUFFER, 0);
+
+            gl::GenFramebuffers(1, &mut probe.framebuffer);
+            gl::BindFramebuffer(gl::FRAMEBUFFER, probe.framebuffer);
+            gl::FramebufferRenderbuffer(gl::FRAMEBUFFER, gl::DEPTH_ATTACHMENT, gl::RENDERBUFFER, probe.depth);
+            gl::FramebufferTexture2D(gl::FRAMEBUFFER, gl::COLOR_ATTACHMENT0, CUBE_FACES[0].gl_target(), probe.texture, 0);
+            let status = gl::CheckFramebufferStatus(gl::FRAMEBUFFER);
+            gl::BindFramebuffer(gl::FRAMEBUFFER, previous as u32);
+            if status != gl::FRAMEBUFFER_COMPLETE {
+                return Err(format!("The probe framebuffer is incomplete: {}", crate::framebuffer::status_message(status)));
+            }
+        }
+        Ok(probe)
+    }
+
+    /// The cubemap, pass it to `EnvironmentMaterial::apply`.
+    pub fn texture(&self) -> u32 {
+        self.texture
+    }
+
+    pub fn size(&self) -> u32 {
+        self.size
+    }
+
+    /// 90 degrees wide and square, so the six faces cover every direction exactly once.
+    pub fn projection(&self) -> Matrix4<f32> {
+        perspective(Deg(90.0), 1.0, 0.1, 100.0)
+    }
+
+    /// View matrix of the face with index `face` in `CUBE_FACES`, seen from the probe's position.
+    pub fn face_view(&self, face: usize) -> Matrix4<f32> {
+        CUBE_FACES[face].view() * Matrix4::from_translation(-self.position)
+    }
+
+    /// Renders the faces if `interval` frames have passed since the last time, returns whether it did.
+    ///
+    /// `draw` gets the view and projection matrix of each face and draws the scene, the face
+    /// is bound and cleared before.
+    pub fn update<F: FnMut(Matrix4<f32>, Matrix4<f32>)>(&mut self, draw: F) -> bool {
+        if self.frames_until_update > 0 {
+            self.frames_until_update -= 1;
+            return false;
+        }
+        self.render(draw);
+        self.frames_until_update = self.interval - 1;
+        true
+    }
+
+    /// Renders all six faces now, the framebuffer and viewport of the caller are restored after.
+    pub fn render<F: FnMut(Matrix4<f32>, Matrix4<f32>)>(&mut self, mut draw: F) {
+        let projection = self.projection();
+        unsafe {
+            let mut previous = 0;
+            let mut viewport = [0; 4];
+            gl::GetIntegerv(gl::FRAMEBUFFER_BINDING, &mut previous);
+            gl::GetIntegerv(gl::VIEWPORT, viewport.as_mut_ptr());
+
+            gl::BindFramebuffer(gl::FRAMEBUFFER, self.framebuffer);
+            gl::Viewport(0, 0, self.size as i32, self.size as i32);
+            for (index, face) in CUBE_FACES.iter().enumerate() {
+                gl::FramebufferTexture2D(gl::FRAMEBUFFER, gl::COLOR_ATTACHMENT0, face.gl_target(), self.texture, 0);
+                gl::Clear(gl::COLOR_BUFFER_BIT | gl::DEPTH_BUFFER_BIT);
+                draw(self.face_view(index), projection);
+            }
+
+            gl::BindFramebuffer(gl::FRAMEBUFFER, previous as u32);
+            gl::Viewport(viewport[0], viewport[1], viewport[2], viewport[3]);
+        }
+    }
+}
+
+impl Drop for EnvironmentProbe {
+    fn drop(&mut self) {
+        unsafe {
+            gl::DeleteFramebuffers(1, &self.framebuffer);
+            gl::DeleteRenderbuffers(1, &self.depth);
+            gl::DeleteTextures(1, &self.texture);
+        }
+    }
+}
@@ -0,0 +1,383 @@
+//! Offscreen render targets built from attachment descriptions, and a fullscreen quad to shade them.
+//!
+//! A `Framebuffer` owns its attachments: color textures that later passes can sample, and an
+//! optional depth or depth-stencil attachment, either as a texture or as a renderbuffer when it
+//! is only needed for testing.
+
+use std::ptr;
+
+use crate::scenes::shapes;
+use crate::shader::Shader;
+
+/// Internal formats of color attachments.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ColorFormat {
+    Rgba8,
+    // stored in sRGB, reads return linear values
+    Srgb8Alpha8,
+    Rgb10A2,
+    // half floats, for HDR colors above 1.0
+    Rgba16F,
+    Rgba32F,
+    R8,
+    R16F,
+}
+
+impl ColorFormat {
+    // internal format, pixel format and pixel type for gl::TexImage2D
+    fn gl_formats(self) -> (u32, u32, u32) {
+        match self {
+            ColorFormat::Rgba8 => (gl::RGBA8, gl::RGBA, gl::UNSIGNED_BYTE),
+            ColorFormat::Srgb8Alpha8 => (gl::SRGB8_ALPHA8, gl::RGBA, gl::UNSIGNED_BYTE),
+            ColorFormat::Rgb10A2 => (gl::RGB10_A2, gl::RGBA, gl::UNSIGNED_INT_2_10_10_10_REV),
+            ColorFormat::Rgba16F => (gl::RGBA16F, gl::RGBA, gl::FLOAT),
+            ColorFormat::Rgba32F => (gl::RGBA32F, gl::RGBA, gl::FLOAT),
+            ColorFormat::R8 => (gl::R8, gl::RED, gl::UNSIGNED_BYTE),
+            ColorFormat::R16F => (gl::R16F, gl::RED, gl::FLOAT),
+        }
+    }
+}
+
+/// Internal formats of the depth attachment.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum DepthFormat {
+    Depth24,
+    Depth32F,
+    Depth24Stencil8,
+}
+
+impl DepthFormat {
+    // internal format, pixel format, pixel type and attachment point
+    fn gl_formats(self) -> (u32, u32, u32, u32) {
+        match self {
+            DepthFormat::Depth24 => (gl::DEPTH_COMPONENT24, gl::DEPTH_COMPONENT, gl::UNSIGNED_INT, gl::DEPTH_ATTACHMENT),
+            DepthFormat::Depth32F => (gl::DEPTH_COMPONENT32F, gl::DEPTH_COMPONENT, gl::FLOAT, gl::DEPTH_ATTACHMENT),
+            DepthFormat::Depth24Stencil8 => (gl::DEPTH24_STENCIL8, gl::DEPTH_STENCIL, gl::UNSIGNED_INT_24_8, gl::DEPTH_STENCIL_ATTACHMENT),
+        }
+    }
+}
+
+/// Where the depth attachment lives.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Storage {
+    // can be sampled, e.g. for shadow maps
+    Texture,
+    // write only, enough for the depth and stencil test
+    Renderbuffer,
+}
+
+/// Size and attachments of a framebuffer.
+#[derive(Clone, Debug, PartialEq)]
+pub struct FramebufferDesc {
+    pub width: u32,
+    pub height: u32,
+    // one texture per entry, attached to COLOR_ATTACHMENT0, 1, ... in order
+    pub colors: Vec<ColorFormat>,
+    pub depth: Option<(DepthFormat, Storage)>,
+}
+
+impl FramebufferDesc {
+    /// One RGBA8 color texture and a depth-stencil renderbuffer, the usual target of a scene.
+    pub fn color_depth(width: u32, height: u32) -> Self {
+        FramebufferDesc {
+            width,
+            height,
+            colors: vec![ColorFormat::Rgba8],
+            depth: Some((DepthFormat::Depth24Stencil8, Storage::Renderbuffer)),
+        }
+    }
+}
+
+/// A framebuffer object with the attachments of its `FramebufferDesc`.
+pub struct Framebuffer {
+    pub id: u32,
+    desc: FramebufferDesc,
+    colors: Vec<u32>,
+    depth: u32,
+}
+
+impl Framebuffer {
+    /// Creates the attachments, fails with a description if the framebuffer is incomplete.
+    pub fn new(desc: FramebufferDesc) -> Result<Self, String> {
+        if desc.width == 0 || desc.height == 0 {
+            return Err(format!("Framebuffer size {}x{} is empty", desc.width, desc.height));
+        }
+        let mut max_attachments = 0;
+        unsafe { gl::GetIntegerv(gl::MAX_COLOR_ATTACHMENTS, &mut max_attachments); }
+        if desc.colors.len() > max_attachments as usize {
+            return Err(format!("{} color attachments requested, the driver supports {}", desc.colors.len(), max_attachments));
+        }
+
+        let mut framebuffer = Framebuffer { id: 0, desc, colors: Vec::new(), depth: 0 };
+        unsafe {
+            gl::GenFramebuffers(1, &mut framebuffer.id);
+            framebuffer.attach()?;
+        }
+        Ok(framebuffer)
+    }
+
+    unsafe fn attach(&mut self) -> Result<(), String> {
+        let (width, height) = (self.desc.width as i32, self.desc.height as i32);
+        let mut previous = 0;
+        gl::GetIntegerv(gl::FRAMEBUFFER_BINDING, &mut previous);
+        gl::BindFramebuffer(gl::FRAMEBUFFER, self.id);
+
+        for (index, format) in self.desc.colors.iter().enumerate() {
+            let (internal, pixel_format, pixel_type) = format.gl_formats();
+            let mut texture = 0;
+            gl::GenTextures(1, &mut texture);
+            gl::BindTexture(gl::TEXTURE_2D, texture);
+            gl::TexImage2D(gl::TEXTURE_2D, 0, internal as i32, width, height, 0, pixel_format, pixel_type, ptr::null());
+            gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MIN_FILTER, gl::LINEAR as i32);
+            gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MAG_FILTER, gl::LINEAR as i32);
+            // filters and kernels sampling past the border repeat the edge instead of the other side
+            gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_WRAP_S, gl::CLAMP_TO_EDGE as i32);
+            gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_WRAP_T, gl::CLAMP_TO_EDGE as i32);
+            gl::FramebufferTexture2D(gl::FRAMEBUFFER, gl::COLOR_ATTACHMENT0 + index as u32, gl::TEXTURE_2D, texture, 0);
+            self.colors.push(texture);
+        }
+        gl::BindTexture(gl::TEXTURE_2D, 0);
+
+        // fragment shader outputs go to the attachments in order, none at all for depth only targets
+        let draw_buffers: Vec<u32> = (0..self.colors.len() as u32).map(|i| gl::COLOR_ATTACHMENT0 + i).collect();
+        if draw_buffers.is_empty() {
+            gl::DrawBuffer(gl::NONE);
+            gl::ReadBuffer(gl::NONE);
+        } else {
+            gl::DrawBuffers(draw_buffers.len() as i32, draw_buffers.as_ptr());
+            gl::ReadBuffer(gl::COLOR_ATTACHMENT0);
+        }
+
+        if let Some((format, storage)) = self.desc.depth {
+            let (internal, pixel_format, pixel_type, attachment) = format.gl_formats();
+            match storage {
+                Storage::Texture => {
+                    gl::GenTextures(1, &mut self.depth);
+                    gl::BindTexture(gl::TEXTURE_2D, self.depth);
+                    gl::TexImage2D(gl::TEXTURE_2D, 0, internal as i32, width, height, 0, pixel_format, pixel_type, ptr::null());
+                    gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MIN_FILTER, gl::NEAREST as i32);
+                    gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MAG_FILTER, gl::NEAREST as i32);
+                    gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_WRAP_S, gl::CLAMP_TO_EDGE as i32);
+                    gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_WRAP_T, gl::CLAMP_TO_EDGE as i32);
+                    gl::FramebufferTexture2D(gl::FRAMEBUFFER, attachment, gl::TEXTURE_2D, self.depth, 0);
+                    gl::BindTexture(gl::TEXTURE_2D, 0);
+                }
+                Storage::Renderbuffer => {
+                    gl::GenRenderbuffers(1, &mut self.depth);
+                    gl::BindRenderbuffer(gl::RENDERBUFFER, self.depth);
+                    gl::RenderbufferStorage(gl::RENDERBUFFER, internal, width, height);
+                    gl::FramebufferRenderbuffer(gl::FRAMEBUFFER, attachment, gl::RENDERBUFFER, self.depth);
+                    gl::BindRenderbuffer(gl::RENDERBUFFER, 0);
+                }
+            }
+        }
+
+        let status = gl::CheckFramebufferStatus(gl::FRAMEBUFFER);
+        gl::BindFramebuffer(gl::FRAMEBUFFER, previous as u32);
+        if status != gl::FRAMEBUFFER_COMPLETE {
+            return Err(format!("Framebuffer {:?} is incomplete: {}", self.desc, status_message(status)));
+        }
+        Ok(())
+    }
+
+    unsafe fn detach(&mut self) {
+        gl::DeleteTextures(self.colors.len() as i32, self.colors.as_ptr());
+        self.colors.clear();
+        match self.desc.depth {
+            Some((_, Storage::Texture)) => gl::DeleteTextures(1, &self.depth),
+            Some((_, Storage::Renderbuffer)) => gl::DeleteRenderbuffers(1, &self.depth),
+            None => (),
+        }
+        self.depth = 0;
+    }
+
+    pub fn desc(&self) -> &FramebufferDesc {
+        &self.desc
+    }
+
+    pub fn width(&self) -> u32 {
+        self.desc.width
+    }
+
+    pub fn height(&self) -> u32 {
+        self.desc.height
+    }
+
+    /// The texture of color attachment `index`.
+    pub fn color_texture(&self, index: usize) -> u32 {
+        self.colors[index]
+    }
+
+    /// The depth texture, `None` without depth attachment or if it is a renderbuffer.
+    pub fn depth_texture(&self) -> Option<u32> {
+        match self.desc.depth {
+            Some((_, Storage::Texture)) => Some(self.depth),
+            _ => None,
+        }
+    }
+
+    /// Recreates the attachments at a new size, their content is lost. Does nothing if the size is the same.
+    pub fn resize(&mut self, width: u32, height: u32) -> Result<(), String> {
+        if (width, height) == (self.desc.width, self.desc.height) {
+            return Ok(());
+        }
+        if width == 0 || height == 0 {
+            return Err(format!("Framebuffer size {}x{} is empty", width, height));
+        }
+        self.desc.width = width;
+        self.desc.height = height;
+        unsafe {
+            self.detach();
+            self.attach()
+        }
+    }
+
+    /// Binds the framebuffer for drawing and reading and sets the viewport to its size.
+    pub fn bind(&self) {
+        unsafe {
+            gl::BindFramebuffer(gl::FRAMEBUFFER, self.id);
+            gl::Viewport(0, 0, self.desc.width as i32, self.desc.height as i32);
+        }
+    }
+
+    /// Binds the framebuffer as draw target only and sets the viewport to its size.
+    pub fn bind_draw(&self) {
+        unsafe {
+            gl::BindFramebuffer(gl::DRAW_FRAMEBUFFER, self.id);
+            gl::Viewport(0, 0, self.desc.width as i32, self.desc.height as i32);
+        }
+    }
+
+    /// Binds the framebuffer as read source, reading from color attachment `index`.
+    pub fn bind_read(&self, index: usize) {
+        unsafe {
+            gl::BindFramebuffer(gl::READ_FRAMEBUFFER, self.id);
+            gl::ReadBuffer(gl::COLOR_ATTACHMENT0 + index as u32);
+        }
+    }
+
+    /// Copies color attachment 0 into the whole of `target`, 0 is the default framebuffer.
+    ///
+    /// `mask` selects the buffers like `gl::BlitFramebuffer`, depth and stencil need the same
+    /// format on both sides and `gl::NEAREST` as filter.
+    pub fn blit_to(&self, target: u32, target_width: u32, target_height: u32, mask: u32, filter: u32) {
+        self.blit_region(target, [0, 0, target_width as i32, target_height as i32], mask, filter);
+    }
+
+    /// Copies the whole framebuffer into the rectangle `[x0, y0, x1, y1]` of `target`.
+    pub fn blit_region(&self, target: u32, region: [i32; 4], mask: u32, filter: u32) {
+        unsafe {
+            let (mut previous_read, mut previous_draw) = (0, 0);
+            gl::GetIntegerv(gl::READ_FRAMEBUFFER_BINDING, &mut previous_read);
+            gl::GetIntegerv(gl::DRAW_FRAMEBUFFER_BINDING, &mut previous_draw);
+
+            self.bind_read(0);
+            gl::BindFramebuffer(gl::DRAW_FRAMEBUFFER, target);
+            gl::BlitFramebuffer(
+                0, 0, self.desc.width as i32, self.desc.height as i32,
+                region[0], region[1], region[2], region[3],
+                mask, filter
+            );
+
+            gl::BindFramebuffer(gl::READ_FRAMEBUFFER, previous_read as u32);
+            gl::BindFramebuffer(gl::DRAW_FRAMEBUFFER, previous_draw as u32);
+        }
+    }
+}
+
+impl Drop for Framebuffer {
+    fn drop(&mut self) {
+        unsafe {
+            self.detach();
+            gl::DeleteFramebuffers(1, &self.id);
+        }
+    }
+}
+
+/// Explains a `gl::CheckFramebufferStatus` result.
+pub fn status_message(status: u32) -> &'static str {
+    match status {
+        gl::FRAMEBUFFER_COMPLETE => "complete",
+        gl::FRAMEBUFFER_UNDEFINED => "the default framebuffer does not exist",
+        gl::FRAMEBUFFER_INCOMPLETE_ATTACHMENT => "an attachment is incomplete, e.g. it has no storage or an unrenderable format",
+        gl::FRAMEBUFFER_INCOMPLETE_MISSING_ATTACHMENT => "there is no attachment at all",
+        gl::FRAMEBUFFER_INCOMPLETE_DRAW_BUFFER => "a draw buffer points to a missing attachment",
+        gl::FRAMEBUFFER_INCOMPLETE_READ_BUFFER => "the read buffer points to a missing attachment",
+        gl::FRAMEBUFFER_UNSUPPORTED => "the driver does not support this combination of formats",
+        gl::FRAMEBUFFER_INCOMPLETE_MULTISAMPLE => "the attachments have different sample counts",
+        gl::FRAMEBUFFER_INCOMPLETE_LAYER_TARGETS => "layered and non-layered attachments are mixed",
+        _ => "unknown status",
+    }
+}
+
+// two triangles covering the screen, positions in NDC and texture coordinates
+const SCREEN_QUAD_VERTICES: [f32; 24] = [
+    -1.0,  1.0,  0.0, 1.0,
+    -1.0, -1.0,  0.0, 0.0,
+     1.0, -1.0,  1.0, 0.0,
+
+    -1.0,  1.0,  0.0, 1.0,
+     1.0, -1.0,  1.0, 0.0,
+     1.0,  1.0,  1.0, 1.0,
+];
+
+/// Vertex shader of every fullscreen pass, passes the texture coordinates on as `TexCoords`.
+pub const FULLSCREEN_VERTEX_SHADER: &str = "./src/shaders/4_advanced_opengl/fullscreen.vs";
+
+/// A quad covering the viewport, for passes that shade every pixel of a target once.
+///
+/// The vertices have a vec2 position at location 0 and vec2 texture coordinates at location 1,
+/// `FULLSCREEN_VERTEX_SHADER` forwards them.
+pub struct FullscreenQuad {
+    vao: shapes::VertexArray,
+}
+
+impl FullscreenQuad {
+    pub fn new() -> Self {
+        let vao = unsafe { shapes::create_vao(&SCREEN_QUAD_VERTICES, &[2, 2]) };
+        FullscreenQuad { vao }
+    }
+
+    /// Draws the quad with whatever program is in use.
+    pub fn draw(&self) {
+        unsafe {
+            gl::BindVertexArray(self.vao.id);
+            gl::DrawArrays(gl::TRIANGLES, 0, 6);
+            gl::BindVertexArray(0);
+        }
+    }
+
+    /// Shades the bound draw framebuffer with `shader`, `inputs` are bound to texture units 0, 1, ...
+    ///
+    /// Depth test, face culling, blending and the stencil test are off and polygons are filled
+    /// during the pass, so the quad always covers the target. The scene's state is restored afterwards.
+    pub fn pass(&self, shader: &Shader, inputs: &[u32]) {
+        unsafe {
+            // whatever the scene left enabled, e.g. culling with clockwise front faces would hide the quad
+            let capabilities = [gl::DEPTH_TEST, gl::CULL_FACE, gl::BLEND, gl::STENCIL_TEST];
+            let enabled: Vec<bool> = capabilities.iter().map(|&capability| gl::IsEnabled(capability) == gl::TRUE).collect();
+            for &capability in capabilities.iter() {
+                gl::Disable(capability);
+            }
+            // a wireframe quad would only show its diagonal
+            let mut polygon_mode = [0; 2];
+            gl::GetIntegerv(gl::POLYGON_MODE, polygon_mode.as_mut_ptr());
+            gl::PolygonMode(gl::FRONT_AND_BACK, gl::FILL);
+
+            shader.use_program();
+            for (unit, &texture) in inputs.iter().enumerate() {
+                gl::ActiveTexture(gl::TEXTURE0 + unit as u32);
+                gl::BindTexture(gl::TEXTURE_2D, texture);
+            }
+            self.draw();
+            gl::ActiveTexture(gl::TEXTURE0);
+
+            for (&capability, &enabled) in capabilities.iter().zip(enabled.iter()) {
+                if enabled {
+                    gl::Enable(capability);
+                }
+            }
+            gl::PolygonMode(gl::FRONT_AND_BACK, polygon_mode[0] as u32);
+        }
+    }
+}
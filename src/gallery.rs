@@ -0,0 +1,131 @@
+//! Every registered scene in one window, switched at runtime instead of through cargo.
+//!
+//! `next_scene` and `previous_scene` step through `scenes::SCENES`, `chapter_menu` opens a menu in
+//! the window title to jump to the first scene of a chapter.
+
+use crate::input::InputState;
+use crate::launcher;
+use crate::scene::{self, Control, Controller};
+use crate::scenes::Entry;
+
+/// Controller walking through a list of scenes, usually `scenes::SCENES`.
+pub struct Gallery {
+    entries: &'static [Entry],
+    current: usize,
+    // the highlighted chapter while the menu is open
+    menu: Option<usize>,
+}
+
+impl Gallery {
+    pub fn new(entries: &'static [Entry], start: usize) -> Self {
+        assert!(start < entries.len(), "There is no scene {} in the gallery", start);
+        Gallery { entries, current: start, menu: None }
+    }
+
+    pub fn current(&self) -> &'static Entry {
+        &self.entries[self.current]
+    }
+
+    pub fn menu(&self) -> Option<usize> {
+        self.menu
+    }
+
+    /// Chapters in the order of their first scene.
+    pub fn chapters(&self) -> Vec<&'static str> {
+        let mut chapters: Vec<&'static str> = Vec::new();
+        for entry in self.entries {
+            if !chapters.contains(&entry.chapter) {
+                chapters.push(entry.chapter);
+            }
+        }
+        chapters
+    }
+
+    fn step(&mut self, offset: isize) -> Control {
+        let count = self.entries.len() as isize;
+        self.current = (self.current as isize + offset).rem_euclid(count) as usize;
+        self.switch()
+    }
+
+    fn switch(&mut self) -> Control {
+        let entry = self.current();
+        println!("Switching to {}: {}", entry.name, entry.description);
+        Control::Switch(entry.create)
+    }
+
+    fn open_menu(&mut self) {
+        let chapters = self.chapters();
+        let selected = chapters.iter().position(|&c| c == self.current().chapter).unwrap_or(0);
+        println!("Chapters, choose with up and down and open with enter:");
+        for (index, chapter) in chapters.iter().enumerate() {
+            let marker = if index == selected { '>' } else { ' ' };
+            println!("{} {}", marker, launcher::chapter_title(chapter));
+        }
+        self.menu = Some(selected);
+    }
+
+    fn control_menu(&mut self, selected: usize, input: &InputState) -> Control {
+        let chapters = self.chapters();
+        if input.pressed("select") {
+            self.menu = None;
+            self.current = self.entries.iter().position(|e| e.chapter == chapters[selected]).unwrap_or(0);
+            return self.switch();
+        }
+        if input.pressed("chapter_menu") || input.pressed("quit") {
+            self.menu = None;
+            return Control::Capture;
+        }
+
+        let up = input.pressed("move_forward") || input.pressed("previous_scene");
+        let down = input.pressed("move_backward") || input.pressed("next_scene");
+        let count = chapters.len();
+        if up {
+            self.menu = Some((selected + count - 1) % count);
+        }
+        if down {
+            self.menu = Some((selected + 1) % count);
+        }
+        Control::Capture
+    }
+}
+
+impl Controller for Gallery {
+    fn title(&self) -> String {
+        match self.menu {
+            Some(selected) => format!(
+                "Chapter menu: < {} > (up/down to choose, enter to open)",
+                launcher::chapter_title(self.chapters()[selected])
+            ),
+            None => {
+                let entry = self.current();
+                format!(
+                    "{}: {} ({}/{})",
+                    launcher::chapter_title(entry.chapter), entry.name, self.current + 1, self.entries.len()
+                )
+            }
+        }
+    }
+
+    fn control(&mut self, input: &InputState) -> Control {
+        if let Some(selected) = self.menu {
+            return self.control_menu(selected, input);
+        }
+        if input.pressed("chapter_menu") {
+            self.open_menu();
+            Control::Capture
+        } else if input.pressed("next_scene") {
+            self.step(1)
+        } else if input.pressed("previous_scene") {
+            self.step(-1)
+        } else {
+            Control::Pass
+        }
+    }
+}
+
+/// Opens a window with the scene `start` of `entries`, the others are one key press away.
+pub fn run(entries: &'static [Entry], start: usize, width: u32, height: u32) {
+    let mut gallery = Gallery::new(entries, start);
+    let create = gallery.current().create;
+    scene::run_controlled(width, height, create, &mut gallery);
+}
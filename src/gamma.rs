@@ -0,0 +1,121 @@
+//! Gamma correction of a frame lit in linear space.
+//!
+//! Monitors show a pixel value `v` with a brightness of about `v^2.2`, and images are stored
+//! the same way, in sRGB. Lighting math has to happen on linear values: sRGB textures are
+//! decoded when sampled (see `model::ColorSpace`), and the linear result is encoded again on
+//! its way to the screen. A `GammaPass` does the encoding for everything drawn between `begin`
+//! and `end`, in one of two ways:
+//!
+//! - `GammaOutput::Shader` renders into a half float target and raises every pixel to `1/2.2`
+//!   in a fullscreen pass.
+//! - `GammaOutput::SrgbFramebuffer` renders into an sRGB target with `GL_FRAMEBUFFER_SRGB`
+//!   enabled, GL encodes every write, blending happens on the decoded values. The target is
+//!   copied to the screen as it is.
+//!
+//! Shaders drawn through the pass must not apply gamma themselves.
+
+use crate::framebuffer::{ColorFormat, DepthFormat, Framebuffer, FramebufferDesc, FullscreenQuad, Storage, FULLSCREEN_VERTEX_SHADER};
+use crate::shader::Shader;
+
+pub const GAMMA_SHADER: &str = "./src/shaders/5_advanced_lighting/gamma.fs";
+
+/// The gamma of the screen, `GammaOutput::Shader` encodes with it.
+pub const GAMMA: f32 = 2.2;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum GammaOutput {
+    // a fullscreen pass with pow(color, 1 / GAMMA)
+    Shader,
+    // GL writes sRGB into an sRGB target, with the exact sRGB curve instead of a plain power
+    SrgbFramebuffer,
+}
+
+impl GammaOutput {
+    fn color_format(self) -> ColorFormat {
+        match self {
+            // linear values need more than 8 bits in the dark, or the encoding shows bands
+            GammaOutput::Shader => ColorFormat::Rgba16F,
+            GammaOutput::SrgbFramebuffer => ColorFormat::Srgb8Alpha8,
+        }
+    }
+}
+
+/// An offscreen target sized like the viewport, encoded into the framebuffer bound at `begin` by `end`.
+pub struct GammaPass {
+    output: GammaOutput,
+    target: Framebuffer,
+    shader: Shader,
+    quad: FullscreenQuad,
+    // framebuffer and viewport to return to in `end`
+    previous: u32,
+    viewport: [i32; 4],
+}
+
+impl GammaPass {
+    pub fn new(output: GammaOutput) -> Result<Self, String> {
+        let target = Framebuffer::new(FramebufferDesc {
+            width: 1,
+            height: 1,
+            colors: vec![output.color_format()],
+            depth: Some((DepthFormat::Depth24Stencil8, Storage::Renderbuffer)),
+        })?;
+        let shader = Shader::new(FULLSCREEN_VERTEX_SHADER, GAMMA_SHADER);
+        shader.use_program();
+        shader.set_int("screenTexture", 0);
+        shader.set_float("gamma", GAMMA);
+
+        Ok(GammaPass { output, target, shader, quad: FullscreenQuad::new(), previous: 0, viewport: [0; 4] })
+    }
+
+    pub fn output(&self) -> GammaOutput {
+        self.output
+    }
+
+    /// Switches the way of encoding, the target is recreated in the new format.
+    pub fn set_output(&mut self, output: GammaOutput) -> Result<(), String> {
+        if output != self.output {
+            let desc = FramebufferDesc { colors: vec![output.color_format()], ..self.target.desc().clone() };
+            self.target = Framebuffer::new(desc)?;
+            self.output = output;
+        }
+        Ok(())
+    }
+
+    /// Binds the linear target, sized like the current viewport. Clearing is up to the scene,
+    /// the clear color counts as linear too.
+    pub fn begin(&mut self) -> Result<(), String> {
+        unsafe {
+            let mut previous = 0;
+            gl::GetIntegerv(gl::DRAW_FRAMEBUFFER_BINDING, &mut previous);
+            gl::GetIntegerv(gl::VIEWPORT, self.viewport.as_mut_ptr());
+            self.previous = previous as u32;
+        }
+        self.target.resize(self.viewport[2].max(1) as u32, self.viewport[3].max(1) as u32)?;
+        self.target.bind();
+        if self.output == GammaOutput::SrgbFramebuffer {
+            unsafe { gl::Enable(gl::FRAMEBUFFER_SRGB); }
+        }
+        Ok(())
+    }
+
+    /// Encodes the target into the framebuffer bound at `begin`, restoring it and the viewport.
+    pub fn end(&self) {
+        let [x, y, width, height] = self.viewport;
+        unsafe {
+            match self.output {
+                GammaOutput::Shader => {
+                    gl::BindFramebuffer(gl::FRAMEBUFFER, self.previous);
+                    gl::Viewport(x, y, width, height);
+                    self.quad.pass(&self.shader, &[self.target.color_texture(0)]);
+                }
+                GammaOutput::SrgbFramebuffer => {
+                    // disabled, the copy takes the encoded values as they are instead of decoding them again
+                    gl::Disable(gl::FRAMEBUFFER_SRGB);
+                    self.target.blit_region(self.previous, [x, y, x + width, y + height], gl::COLOR_BUFFER_BIT, gl::NEAREST);
+                    gl::BindFramebuffer(gl::FRAMEBUFFER, self.previous);
+                    gl::Viewport(x, y, width, height);
+                }
+            }
+        }
+    }
+}
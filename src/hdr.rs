@@ -0,0 +1,194 @@
+//! High dynamic range: lighting beyond 1.0, mapped back into what the screen can show.
+//!
+//! Scenes render into a half float target of a `PostProcess` chain, so a light 200 times brighter
+//! than white stays 200 times brighter instead of clipping. The `tone_mapping` effect of the chain
+//! squeezes the range into 0..1 with one of the `ToneMapping` curves and encodes the result for the
+//! screen, like a `GammaPass`.
+//!
+//! The exposure scales the colors before the curve. With an `AutoExposure` on the effect it follows
+//! the average luminance of the frame: the log luminance of every pixel is averaged down the mip
+//! chain to a single texel, and the exposure moves towards it a little every frame, like eyes
+//! adapting to a dark room.
+
+use crate::framebuffer::{ColorFormat, Framebuffer, FramebufferDesc, FullscreenQuad, FULLSCREEN_VERTEX_SHADER};
+use crate::scene::Frame;
+use crate::shader::Shader;
+
+pub const TONE_MAPPING_SHADER: &str = "./src/shaders/5_advanced_lighting/tone_mapping.fs";
+pub const LOG_LUMINANCE_SHADER: &str = "./src/shaders/5_advanced_lighting/log_luminance.fs";
+pub const ADAPTATION_SHADER: &str = "./src/shaders/5_advanced_lighting/adaptation.fs";
+
+/// The curve `tone_mapping.fs` maps HDR colors into 0..1 with, read from the effect's `operator` parameter.
+///
+/// Reinhard divides by one more than the color, it never quite reaches white and flattens bright
+/// areas. Exposure is `1 - e^-color`, with a softer shoulder. ACES filmic, the fit of the film curve
+/// by Krzysztof Narkowicz, also darkens the shadows a little for more contrast. Uncharted 2, by John
+/// Hable, has a toe and a shoulder, and is made for twice the exposure: it maps half the white point
+/// `UNCHARTED2_WHITE`, and everything brighter, to 1.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum ToneMapping {
+    Reinhard,
+    Exposure,
+    #[default]
+    AcesFilmic,
+    Uncharted2,
+}
+
+/// The linear value the Uncharted 2 curve maps to white, before doubling the exposure.
+pub const UNCHARTED2_WHITE: f32 = 11.2;
+
+impl ToneMapping {
+    /// The value of the `operator` parameter.
+    pub fn index(self) -> f32 {
+        self as i32 as f32
+    }
+
+    /// The following one, back to `Reinhard` after `Uncharted2`, for a key that cycles through them.
+    pub fn next(self) -> Self {
+        match self {
+            ToneMapping::Reinhard => ToneMapping::Exposure,
+            ToneMapping::Exposure => ToneMapping::AcesFilmic,
+            ToneMapping::AcesFilmic => ToneMapping::Uncharted2,
+            ToneMapping::Uncharted2 => ToneMapping::Reinhard,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            ToneMapping::Reinhard => "Reinhard",
+            ToneMapping::Exposure => "exposure",
+            ToneMapping::AcesFilmic => "ACES filmic",
+            ToneMapping::Uncharted2 => "Uncharted 2",
+        }
+    }
+
+    /// The curve of `tone_mapping.fs` for one channel, before gamma encoding.
+    pub fn map(self, value: f32) -> f32 {
+        match self {
+            ToneMapping::Reinhard => value / (1.0 + value),
+            ToneMapping::Exposure => 1.0 - (-value).exp(),
+            ToneMapping::AcesFilmic => {
+                let mapped = value * (2.51 * value + 0.03) / (value * (2.43 * value + 0.59) + 0.14);
+                mapped.clamp(0.0, 1.0)
+            }
+            // the curve is made for twice the exposure, and keeps rising past white
+            ToneMapping::Uncharted2 => (uncharted2(value * 2.0) / uncharted2(UNCHARTED2_WHITE)).min(1.0),
+        }
+    }
+}
+
+fn uncharted2(x: f32) -> f32 {
+    let (a, b, c, d, e, f) = (0.15, 0.50, 0.10, 0.20, 0.02, 0.30);
+    ((x * (a * x + c * b) + d * e) / (x * (a * x + b) + d * f)) - e / f
+}
+
+/// How the automatic exposure follows the frame.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ExposureSettings {
+    // how fast the eyes adapt, the remaining difference shrinks by e every 1 / speed seconds
+    pub adaptation_speed: f32,
+    // the average luminance is clamped into this range, so a black frame does not get blown up to gray
+    pub min_luminance: f32,
+    pub max_luminance: f32,
+}
+
+impl Default for ExposureSettings {
+    fn default() -> Self {
+        ExposureSettings { adaptation_speed: 1.5, min_luminance: 0.03, max_luminance: 8.0 }
+    }
+}
+
+/// Measures the average luminance of frames and adapts to it over time.
+pub struct AutoExposure {
+    settings: ExposureSettings,
+    // log luminance of every pixel, averaged down its mip chain
+    luminance: Framebuffer,
+    // the adapted luminance of the previous and the current frame, one texel each
+    adapted: [Framebuffer; 2],
+    current: usize,
+    // the next measurement jumps straight to the average instead of adapting
+    reset: bool,
+    luminance_shader: Shader,
+    adaptation_shader: Shader,
+}
+
+impl AutoExposure {
+    pub fn new(settings: ExposureSettings) -> Result<Self, String> {
+        let single = |format| FramebufferDesc { width: 1, height: 1, colors: vec![format], depth: None };
+        let luminance_shader = Shader::new(FULLSCREEN_VERTEX_SHADER, LOG_LUMINANCE_SHADER);
+        luminance_shader.use_program();
+        luminance_shader.set_int("screenTexture", 0);
+        let adaptation_shader = Shader::new(FULLSCREEN_VERTEX_SHADER, ADAPTATION_SHADER);
+        adaptation_shader.use_program();
+        adaptation_shader.set_int("logLuminance", 0);
+        adaptation_shader.set_int("previous", 1);
+        Ok(AutoExposure {
+            settings,
+            luminance: Framebuffer::new(single(ColorFormat::R16F))?,
+            adapted: [Framebuffer::new(single(ColorFormat::R16F))?, Framebuffer::new(single(ColorFormat::R16F))?],
+            current: 0,
+            reset: true,
+            luminance_shader,
+            adaptation_shader,
+        })
+    }
+
+    pub fn settings(&self) -> ExposureSettings {
+        self.settings
+    }
+
+    pub fn set_settings(&mut self, settings: ExposureSettings) {
+        self.settings = settings;
+    }
+
+    /// Forgets the adapted luminance, e.g. after switching scenes.
+    pub fn reset(&mut self) {
+        self.reset = true;
+    }
+
+    /// The texture holding the adapted luminance in its single red texel.
+    pub fn adapted_luminance(&self) -> u32 {
+        self.adapted[self.current].color_texture(0)
+    }
+
+    /// Averages the luminance of `input`, a frame sized texture, and adapts to it for `frame.delta_time`.
+    /// Returns `adapted_luminance`. The framebuffer and viewport of the caller are restored.
+    pub fn measure(&mut self, quad: &FullscreenQuad, input: u32, frame: &Frame) -> u32 {
+        let (mut previous, mut viewport) = (0, [0; 4]);
+        unsafe {
+            gl::GetIntegerv(gl::FRAMEBUFFER_BINDING, &mut previous);
+            gl::GetIntegerv(gl::VIEWPORT, viewport.as_mut_ptr());
+        }
+
+        self.luminance.resize(frame.width.max(1), frame.height.max(1)).expect("Could not resize the luminance target");
+        self.luminance.bind();
+        quad.pass(&self.luminance_shader, &[input]);
+        // the last level of the chain is a single texel, the average of them all
+        let levels = 32 - frame.width.max(frame.height).max(1).leading_zeros();
+        unsafe {
+            gl::BindTexture(gl::TEXTURE_2D, self.luminance.color_texture(0));
+            gl::GenerateMipmap(gl::TEXTURE_2D);
+            gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MIN_FILTER, gl::LINEAR_MIPMAP_NEAREST as i32);
+            gl::BindTexture(gl::TEXTURE_2D, 0);
+        }
+
+        let next = 1 - self.current;
+        self.adapted[next].bind();
+        self.adaptation_shader.use_program();
+        self.adaptation_shader.set_float("level", (levels - 1) as f32);
+        self.adaptation_shader.set_float("deltaTime", frame.delta_time);
+        self.adaptation_shader.set_float("speed", self.settings.adaptation_speed);
+        self.adaptation_shader.set_float("minLuminance", self.settings.min_luminance);
+        self.adaptation_shader.set_float("maxLuminance", self.settings.max_luminance);
+        self.adaptation_shader.set_bool("reset", self.reset);
+        quad.pass(&self.adaptation_shader, &[self.luminance.color_texture(0), self.adapted[self.current].color_texture(0)]);
+        self.current = next;
+        self.reset = false;
+
+        unsafe {
+            gl::BindFramebuffer(gl::FRAMEBUFFER, previous as u32);
+            gl::Viewport(viewport[0], viewport[1], viewport[2], viewport[3]);
+        }
+        self.adapted_luminance()
+    }
+}
@@ -0,0 +1,356 @@
+//! Offscreen OpenGL 3.3 contexts for machines without a display, e.g. CI.
+//!
+//! EGL (surfaceless platform) is tried first, then OSMesa. Both are loaded at runtime,
+//! so the crate still builds and runs windowed on machines that have neither.
+//! With Mesa, `LIBGL_ALWAYS_SOFTWARE=1` selects llvmpipe even when a GPU is present.
+
+use std::ffi::{c_void, CStr, CString};
+use std::os::raw::{c_char, c_int, c_uint};
+use std::path::Path;
+use std::ptr;
+use std::sync::{Mutex, MutexGuard};
+
+use image::RgbaImage;
+
+use crate::capture::{self, Recorder};
+use crate::scene::{Frame, Scene};
+
+// the gl crate keeps its function pointers in globals, so only one context may exist at a time
+static CONTEXT_LOCK: Mutex<()> = Mutex::new(());
+
+/// A current offscreen context rendering into its own framebuffer object.
+///
+/// Only one context exists per process at a time, `new` waits until the previous one is dropped.
+/// That also keeps tests, which run on parallel threads, from loading GL functions while another
+/// thread is rendering.
+pub struct HeadlessContext {
+    pub width: u32,
+    pub height: u32,
+    backend: Backend,
+    fbo: u32,
+    color_rbo: u32,
+    depth_rbo: u32,
+    // released last, after the backend destroyed the context
+    _lock: MutexGuard<'static, ()>,
+}
+
+impl HeadlessContext {
+    /// Creates the context, makes it current and loads the GL function pointers.
+    pub fn new(width: u32, height: u32) -> Result<Self, String> {
+        // a test that panicked while holding the lock leaves it poisoned, the next one may still go
+        let lock = CONTEXT_LOCK.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        let backend = match Backend::egl() {
+            Ok(backend) => backend,
+            Err(egl_error) => Backend::osmesa(width, height)
+                .map_err(|osmesa_error| format!("No headless context available. {} {}", egl_error, osmesa_error))?,
+        };
+        gl::load_with(|symbol| backend.get_proc_address(symbol));
+
+        let mut context = HeadlessContext { width, height, backend, fbo: 0, color_rbo: 0, depth_rbo: 0, _lock: lock };
+        unsafe { context.create_framebuffer()? };
+        Ok(context)
+    }
+
+    unsafe fn create_framebuffer(&mut self) -> Result<(), String> {
+        gl::GenFramebuffers(1, &mut self.fbo);
+        gl::BindFramebuffer(gl::FRAMEBUFFER, self.fbo);
+
+        gl::GenRenderbuffers(1, &mut self.color_rbo);
+        gl::BindRenderbuffer(gl::RENDERBUFFER, self.color_rbo);
+        gl::RenderbufferStorage(gl::RENDERBUFFER, gl::RGBA8, self.width as i32, self.height as i32);
+        gl::FramebufferRenderbuffer(gl::FRAMEBUFFER, gl::COLOR_ATTACHMENT0, gl::RENDERBUFFER, self.color_rbo);
+
+        gl::GenRenderbuffers(1, &mut self.depth_rbo);
+        gl::BindRenderbuffer(gl::RENDERBUFFER, self.depth_rbo);
+        gl::RenderbufferStorage(gl::RENDERBUFFER, gl::DEPTH24_STENCIL8, self.width as i32, self.height as i32);
+        gl::FramebufferRenderbuffer(gl::FRAMEBUFFER, gl::DEPTH_STENCIL_ATTACHMENT, gl::RENDERBUFFER, self.depth_rbo);
+
+        let status = gl::CheckFramebufferStatus(gl::FRAMEBUFFER);
+        if status != gl::FRAMEBUFFER_COMPLETE {
+            return Err(format!("Headless framebuffer is incomplete, status 0x{:X}", status));
+        }
+        gl::Viewport(0, 0, self.width as i32, self.height as i32);
+        Ok(())
+    }
+
+    /// Name of the backend and renderer, useful in test logs.
+    pub fn description(&self) -> String {
+        let renderer = unsafe {
+            let renderer = gl::GetString(gl::RENDERER);
+            if renderer.is_null() { "unknown".to_string() } else { CStr::from_ptr(renderer as *const c_char).to_string_lossy().into_owned() }
+        };
+        format!("{} ({})", self.backend.name(), renderer)
+    }
+
+    /// Binds the offscreen framebuffer as the target of all draw calls.
+    pub fn bind(&self) {
+        unsafe {
+            gl::BindFramebuffer(gl::FRAMEBUFFER, self.fbo);
+            gl::Viewport(0, 0, self.width as i32, self.height as i32);
+        }
+    }
+
+    /// Reads the offscreen framebuffer back, top row first.
+    pub fn read_pixels(&self) -> RgbaImage {
+        self.bind();
+        unsafe {
+            gl::Finish();
+            capture::read_framebuffer(self.fbo, self.width, self.height)
+        }
+    }
+
+    fn render_frame(&self, scene: &mut dyn Scene, index: u32, delta_time: f32) {
+        let frame = Frame {
+            width: self.width,
+            height: self.height,
+            time: index as f32 * delta_time,
+            delta_time,
+            framebuffer: self.fbo,
+        };
+        self.bind();
+        scene.update(&frame);
+        scene.render(&frame);
+    }
+
+    /// Renders `frames` frames with a fixed time step and returns the last one.
+    pub fn render_scene(&self, scene: &mut dyn Scene, frames: u32, delta_time: f32) -> RgbaImage {
+        for index in 0..frames.max(1) {
+            self.render_frame(scene, index, delta_time);
+        }
+        self.read_pixels()
+    }
+
+    /// Renders `frames` frames at the recorder's time step and adds every one of them.
+    pub fn record_scene(&self, scene: &mut dyn Scene, frames: u32, recorder: &mut Recorder) -> Result<(), String> {
+        for index in 0..frames.max(1) {
+            self.render_frame(scene, index, recorder.delta_time());
+            recorder.add_frame(&self.read_pixels())?;
+        }
+        Ok(())
+    }
+
+    /// Renders the scene and writes the last frame to `path`, the format follows the extension.
+    pub fn render_scene_to_file(&self, scene: &mut dyn Scene, frames: u32, delta_time: f32, path: &Path) -> Result<(), String> {
+        let image = self.render_scene(scene, frames, delta_time);
+        image.save(path).map_err(|e| format!("Could not write {}. {}", path.display(), e))
+    }
+}
+
+impl Drop for HeadlessContext {
+    fn drop(&mut self) {
+        unsafe {
+            gl::BindFramebuffer(gl::FRAMEBUFFER, 0);
+            gl::DeleteFramebuffers(1, &self.fbo);
+            gl::DeleteRenderbuffers(1, &self.color_rbo);
+            gl::DeleteRenderbuffers(1, &self.depth_rbo);
+        }
+    }
+}
+
+// EGL and OSMesa constants, from EGL/egl.h, EGL/eglext.h and GL/osmesa.h
+const EGL_NONE: c_int = 0x3038;
+const EGL_RENDERABLE_TYPE: c_int = 0x3040;
+const EGL_OPENGL_BIT: c_int = 0x0008;
+const EGL_RED_SIZE: c_int = 0x3024;
+const EGL_GREEN_SIZE: c_int = 0x3023;
+const EGL_BLUE_SIZE: c_int = 0x3022;
+const EGL_SURFACE_TYPE: c_int = 0x3033;
+const EGL_PBUFFER_BIT: c_int = 0x0001;
+const EGL_OPENGL_API: c_uint = 0x30A2;
+const EGL_CONTEXT_MAJOR_VERSION: c_int = 0x3098;
+const EGL_CONTEXT_MINOR_VERSION: c_int = 0x30FB;
+const EGL_CONTEXT_OPENGL_PROFILE_MASK: c_int = 0x30FD;
+const EGL_CONTEXT_OPENGL_CORE_PROFILE_BIT: c_int = 0x0001;
+const EGL_PLATFORM_SURFACELESS_MESA: c_uint = 0x31DD;
+
+const OSMESA_FORMAT: c_int = 0x22;
+const OSMESA_RGBA: c_int = gl::RGBA as c_int;
+const OSMESA_DEPTH_BITS: c_int = 0x30;
+const OSMESA_STENCIL_BITS: c_int = 0x31;
+const OSMESA_PROFILE: c_int = 0x33;
+const OSMESA_CORE_PROFILE: c_int = 0x34;
+const OSMESA_CONTEXT_MAJOR_VERSION: c_int = 0x36;
+const OSMESA_CONTEXT_MINOR_VERSION: c_int = 0x37;
+
+type EglGetProcAddress = unsafe extern "C" fn(*const c_char) -> *const c_void;
+type EglGetPlatformDisplay = unsafe extern "C" fn(c_uint, *mut c_void, *const c_int) -> *mut c_void;
+type EglInitialize = unsafe extern "C" fn(*mut c_void, *mut c_int, *mut c_int) -> c_uint;
+type EglBindApi = unsafe extern "C" fn(c_uint) -> c_uint;
+type EglChooseConfig = unsafe extern "C" fn(*mut c_void, *const c_int, *mut *mut c_void, c_int, *mut c_int) -> c_uint;
+type EglCreateContext = unsafe extern "C" fn(*mut c_void, *mut c_void, *mut c_void, *const c_int) -> *mut c_void;
+type EglMakeCurrent = unsafe extern "C" fn(*mut c_void, *mut c_void, *mut c_void, *mut c_void) -> c_uint;
+type EglDestroyContext = unsafe extern "C" fn(*mut c_void, *mut c_void) -> c_uint;
+type EglTerminate = unsafe extern "C" fn(*mut c_void) -> c_uint;
+
+type OsMesaCreateContextAttribs = unsafe extern "C" fn(*const c_int, *mut c_void) -> *mut c_void;
+type OsMesaMakeCurrent = unsafe extern "C" fn(*mut c_void, *mut c_void, c_uint, c_int, c_int) -> c_uint;
+type OsMesaGetProcAddress = unsafe extern "C" fn(*const c_char) -> *const c_void;
+type OsMesaDestroyContext = unsafe extern "C" fn(*mut c_void);
+
+enum Backend {
+    Egl {
+        display: *mut c_void,
+        context: *mut c_void,
+        get_proc_address: EglGetProcAddress,
+        destroy_context: EglDestroyContext,
+        terminate: EglTerminate,
+    },
+    OsMesa {
+        context: *mut c_void,
+        // OSMesa needs a default color buffer even though we render into the framebuffer object
+        _buffer: Vec<u8>,
+        get_proc_address: OsMesaGetProcAddress,
+        destroy_context: OsMesaDestroyContext,
+    },
+}
+
+impl Backend {
+    fn egl() -> Result<Self, String> {
+        unsafe {
+            let library = open_library(&["libEGL.so.1", "libEGL.so"])?;
+            let get_proc_address: EglGetProcAddress = load_symbol(library, "eglGetProcAddress")?;
+            let initialize: EglInitialize = load_symbol(library, "eglInitialize")?;
+            let bind_api: EglBindApi = load_symbol(library, "eglBindAPI")?;
+            let choose_config: EglChooseConfig = load_symbol(library, "eglChooseConfig")?;
+            let create_context: EglCreateContext = load_symbol(library, "eglCreateContext")?;
+            let make_current: EglMakeCurrent = load_symbol(library, "eglMakeCurrent")?;
+            let destroy_context: EglDestroyContext = load_symbol(library, "eglDestroyContext")?;
+            let terminate: EglTerminate = load_symbol(library, "eglTerminate")?;
+
+            let name = CString::new("eglGetPlatformDisplayEXT").unwrap();
+            let get_platform_display = get_proc_address(name.as_ptr());
+            if get_platform_display.is_null() {
+                return Err("EGL: eglGetPlatformDisplayEXT is not supported.".to_string());
+            }
+            let get_platform_display: EglGetPlatformDisplay = std::mem::transmute(get_platform_display);
+
+            let display = get_platform_display(EGL_PLATFORM_SURFACELESS_MESA, ptr::null_mut(), ptr::null());
+            let (mut major, mut minor) = (0, 0);
+            if display.is_null() || initialize(display, &mut major, &mut minor) == 0 {
+                return Err("EGL: could not initialize the surfaceless display.".to_string());
+            }
+            if bind_api(EGL_OPENGL_API) == 0 {
+                terminate(display);
+                return Err("EGL: desktop OpenGL is not supported.".to_string());
+            }
+
+            let config_attributes = [
+                EGL_RENDERABLE_TYPE, EGL_OPENGL_BIT,
+                // EGL_SURFACE_TYPE defaults to windows, which a surfaceless display has none of
+                EGL_SURFACE_TYPE, EGL_PBUFFER_BIT,
+                EGL_RED_SIZE, 8, EGL_GREEN_SIZE, 8, EGL_BLUE_SIZE, 8,
+                EGL_NONE
+            ];
+            let mut config = ptr::null_mut();
+            let mut config_count = 0;
+            if choose_config(display, config_attributes.as_ptr(), &mut config, 1, &mut config_count) == 0 || config_count == 0 {
+                terminate(display);
+                return Err("EGL: no matching config.".to_string());
+            }
+
+            let context_attributes = [
+                EGL_CONTEXT_MAJOR_VERSION, 3,
+                EGL_CONTEXT_MINOR_VERSION, 3,
+                EGL_CONTEXT_OPENGL_PROFILE_MASK, EGL_CONTEXT_OPENGL_CORE_PROFILE_BIT,
+                EGL_NONE
+            ];
+            let context = create_context(display, config, ptr::null_mut(), context_attributes.as_ptr());
+            if context.is_null() {
+                terminate(display);
+                return Err("EGL: could not create an OpenGL 3.3 core context.".to_string());
+            }
+            // surfaceless: no draw or read surface, everything goes into framebuffer objects
+            if make_current(display, ptr::null_mut(), ptr::null_mut(), context) == 0 {
+                destroy_context(display, context);
+                terminate(display);
+                return Err("EGL: could not make the context current.".to_string());
+            }
+
+            Ok(Backend::Egl { display, context, get_proc_address, destroy_context, terminate })
+        }
+    }
+
+    fn osmesa(width: u32, height: u32) -> Result<Self, String> {
+        unsafe {
+            let library = open_library(&["libOSMesa.so.8", "libOSMesa.so.6", "libOSMesa.so"])?;
+            let create_context: OsMesaCreateContextAttribs = load_symbol(library, "OSMesaCreateContextAttribs")?;
+            let make_current: OsMesaMakeCurrent = load_symbol(library, "OSMesaMakeCurrent")?;
+            let get_proc_address: OsMesaGetProcAddress = load_symbol(library, "OSMesaGetProcAddress")?;
+            let destroy_context: OsMesaDestroyContext = load_symbol(library, "OSMesaDestroyContext")?;
+
+            let attributes = [
+                OSMESA_FORMAT, OSMESA_RGBA,
+                OSMESA_DEPTH_BITS, 24,
+                OSMESA_STENCIL_BITS, 8,
+                OSMESA_PROFILE, OSMESA_CORE_PROFILE,
+                OSMESA_CONTEXT_MAJOR_VERSION, 3,
+                OSMESA_CONTEXT_MINOR_VERSION, 3,
+                0
+            ];
+            let context = create_context(attributes.as_ptr(), ptr::null_mut());
+            if context.is_null() {
+                return Err("OSMesa: could not create an OpenGL 3.3 core context.".to_string());
+            }
+
+            let mut buffer = vec![0u8; (width * height * 4) as usize];
+            if make_current(context, buffer.as_mut_ptr() as *mut c_void, gl::UNSIGNED_BYTE, width as c_int, height as c_int) == 0 {
+                destroy_context(context);
+                return Err("OSMesa: could not make the context current.".to_string());
+            }
+
+            Ok(Backend::OsMesa { context, _buffer: buffer, get_proc_address, destroy_context })
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            Backend::Egl { .. } => "EGL surfaceless",
+            Backend::OsMesa { .. } => "OSMesa",
+        }
+    }
+
+    fn get_proc_address(&self, symbol: &str) -> *const c_void {
+        let symbol = CString::new(symbol).unwrap();
+        unsafe {
+            match self {
+                Backend::Egl { get_proc_address, .. } => get_proc_address(symbol.as_ptr()),
+                Backend::OsMesa { get_proc_address, .. } => get_proc_address(symbol.as_ptr()),
+            }
+        }
+    }
+}
+
+impl Drop for Backend {
+    fn drop(&mut self) {
+        unsafe {
+            match *self {
+                Backend::Egl { display, context, destroy_context, terminate, .. } => {
+                    destroy_context(display, context);
+                    terminate(display);
+                }
+                Backend::OsMesa { context, destroy_context, .. } => destroy_context(context),
+            }
+        }
+    }
+}
+
+// the library handles are never closed, the function pointers stay valid for the whole run
+unsafe fn open_library(names: &[&str]) -> Result<*mut c_void, String> {
+    for name in names {
+        let c_name = CString::new(*name).unwrap();
+        let handle = libc::dlopen(c_name.as_ptr(), libc::RTLD_NOW | libc::RTLD_LOCAL);
+        if !handle.is_null() {
+            return Ok(handle);
+        }
+    }
+    Err(format!("Could not load any of {:?}.", names))
+}
+
+unsafe fn load_symbol<T: Copy>(library: *mut c_void, name: &str) -> Result<T, String> {
+    let c_name = CString::new(name).unwrap();
+    let symbol = libc::dlsym(library, c_name.as_ptr());
+    if symbol.is_null() {
+        return Err(format!("Missing symbol {}.", name));
+    }
+    Ok(std::mem::transmute_copy(&symbol))
+}
@@ -0,0 +1,367 @@
+use std::collections::{HashMap, HashSet};
+use std::fs;
+
+use glfw::{Action, GamepadAxis, GamepadButton, Key, MouseButton, WindowEvent};
+
+// default path of the binding file used by the examples
+pub const INPUT_CONFIG_PATH: &str = "./resources/config/input.cfg";
+
+// axis values below this are treated as a released stick/trigger
+const AXIS_DEAD_ZONE: f32 = 0.25;
+
+/// A single physical input an action can be bound to.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Binding {
+    Key(Key),
+    Mouse(MouseButton),
+    GamepadButton(GamepadButton),
+    // the sign selects which half of the axis drives the action
+    GamepadAxis(GamepadAxis, f32),
+}
+
+/// Named actions and the inputs bound to them.
+#[derive(Clone, Debug, Default)]
+pub struct InputMap {
+    pub actions: HashMap<String, Vec<Binding>>,
+}
+
+impl InputMap {
+    /// The hard-wired controls the examples used before bindings were configurable.
+    pub fn default_bindings() -> Self {
+        let mut map = InputMap::default();
+        map.bind("quit", Binding::Key(Key::Escape));
+        map.bind("move_forward", Binding::Key(Key::W));
+        map.bind("move_forward", Binding::GamepadAxis(GamepadAxis::AxisLeftY, -1.0));
+        map.bind("move_backward", Binding::Key(Key::S));
+        map.bind("move_backward", Binding::GamepadAxis(GamepadAxis::AxisLeftY, 1.0));
+        map.bind("move_left", Binding::Key(Key::A));
+        map.bind("move_left", Binding::GamepadAxis(GamepadAxis::AxisLeftX, -1.0));
+        map.bind("move_right", Binding::Key(Key::D));
+        map.bind("move_right", Binding::GamepadAxis(GamepadAxis::AxisLeftX, 1.0));
+        map.bind("sprint", Binding::Key(Key::LeftShift));
+        map.bind("sprint", Binding::GamepadButton(GamepadButton::ButtonLeftThumb));
+        map.bind("toggle_wireframe", Binding::Key(Key::F1));
+        map.bind("toggle_cursor", Binding::Key(Key::Tab));
+        map.bind("screenshot", Binding::Key(Key::F12));
+        map.bind("record_gif", Binding::Key(Key::F10));
+        map.bind("record_frames", Binding::Key(Key::F9));
+        map.bind("next_scene", Binding::Key(Key::PageDown));
+        map.bind("next_scene", Binding::GamepadButton(GamepadButton::ButtonRightBumper));
+        map.bind("previous_scene", Binding::Key(Key::PageUp));
+        map.bind("previous_scene", Binding::GamepadButton(GamepadButton::ButtonLeftBumper));
+        map.bind("chapter_menu", Binding::Key(Key::M));
+        map.bind("chapter_menu", Binding::GamepadButton(GamepadButton::ButtonStart));
+        map.bind("select", Binding::Key(Key::Enter));
+        map.bind("select", Binding::GamepadButton(GamepadButton::ButtonA));
+        map.bind("postprocess", Binding::Key(Key::P));
+        map.bind("toggle_lighting_model", Binding::Key(Key::B));
+        map.bind("toggle_gamma", Binding::Key(Key::G));
+        map.bind("shadow_debug", Binding::Key(Key::V));
+        map.bind("pcf_kernel", Binding::Key(Key::K));
+        map.bind("toggle_shadows", Binding::Key(Key::H));
+        map.bind("toggle_normal_map", Binding::Key(Key::N));
+        map.bind("parallax_mode", Binding::Key(Key::L));
+        map.bind("tone_mapping", Binding::Key(Key::T));
+        map.bind("auto_exposure", Binding::Key(Key::E));
+        map.bind("exposure_up", Binding::Key(Key::Equal));
+        map.bind("exposure_down", Binding::Key(Key::Minus));
+        map.bind("toggle_bloom", Binding::Key(Key::O));
+        map.bind("bloom_intensity_up", Binding::Key(Key::RightBracket));
+        map.bind("bloom_intensity_down", Binding::Key(Key::LeftBracket));
+        map.bind("more_lights", Binding::Key(Key::Period));
+        map.bind("fewer_lights", Binding::Key(Key::Comma));
+        for (index, &key) in [Key::Num1, Key::Num2, Key::Num3, Key::Num4, Key::Num5, Key::Num6, Key::Num7, Key::Num8, Key::Num9].iter().enumerate() {
+            map.bind(&format!("effect_{}", index + 1), Binding::Key(key));
+        }
+        map
+    }
+
+    pub fn bind(&mut self, action: &str, binding: Binding) {
+        self.actions.entry(action.to_string()).or_default().push(binding);
+    }
+
+    /// Loads bindings from a file, see `parse` for the format.
+    pub fn from_file(path: &str) -> Result<Self, String> {
+        let source = fs::read_to_string(path)
+            .map_err(|e| format!("Could not read the input config {}. {}", path, e))?;
+        Self::parse(&source)
+    }
+
+    /// Parses lines of the form `action = kind:name, kind:name`.
+    ///
+    /// Kinds are `key`, `mouse`, `button` (gamepad) and `axis` (gamepad). Axis names take a
+    /// trailing `+` or `-` for the half of the axis to use. `#` starts a comment.
+    pub fn parse(source: &str) -> Result<Self, String> {
+        let mut map = InputMap::default();
+        for (number, line) in source.lines().enumerate() {
+            let line = line.split('#').next().unwrap_or("").trim();
+            if line.is_empty() {
+                continue;
+            }
+            let (action, bindings) = line.split_once('=')
+                .ok_or_else(|| format!("line {}: expected 'action = bindings'", number + 1))?;
+            let action = action.trim();
+            if action.is_empty() {
+                return Err(format!("line {}: missing action name", number + 1));
+            }
+            for binding in bindings.split(',').map(str::trim).filter(|b| !b.is_empty()) {
+                let binding = parse_binding(binding).map_err(|e| format!("line {}: {}", number + 1, e))?;
+                map.bind(action, binding);
+            }
+        }
+        Ok(map)
+    }
+}
+
+fn parse_binding(source: &str) -> Result<Binding, String> {
+    let (kind, name) = source.split_once(':')
+        .ok_or_else(|| format!("binding '{}' is missing a kind, e.g. 'key:W'", source))?;
+    let (kind, name) = (kind.trim().to_lowercase(), name.trim());
+    let binding = match kind.as_str() {
+        "key" => find_named(KEYS, name).map(Binding::Key),
+        "mouse" => find_named(MOUSE_BUTTONS, name).map(Binding::Mouse),
+        "button" => find_named(GAMEPAD_BUTTONS, name).map(Binding::GamepadButton),
+        "axis" => {
+            let (name, direction) = match name.strip_suffix('-') {
+                Some(name) => (name, -1.0),
+                None => (name.strip_suffix('+').unwrap_or(name), 1.0),
+            };
+            find_named(GAMEPAD_AXES, name).map(|axis| Binding::GamepadAxis(axis, direction))
+        }
+        _ => return Err(format!("unknown binding kind '{}'", kind)),
+    };
+    binding.ok_or_else(|| format!("unknown {} '{}'", kind, name))
+}
+
+// matches either glfw's variant name or the short alias, ignoring case
+fn find_named<T: Copy + std::fmt::Debug>(table: &[(T, &str)], name: &str) -> Option<T> {
+    let name = name.to_lowercase();
+    table.iter()
+        .find(|(value, alias)| {
+            let debug = format!("{:?}", value).to_lowercase();
+            debug == name || alias.to_lowercase() == name
+        })
+        .map(|(value, _)| *value)
+}
+
+/// Current and previous action states, fed by window events and gamepad polling.
+///
+/// Call `update` once per frame after all events were handled, then query the actions.
+/// Nothing in here needs a window, so synthetic events can drive it as well.
+#[derive(Default)]
+pub struct InputState {
+    pub map: InputMap,
+    keys: HashSet<Key>,
+    mouse_buttons: HashSet<MouseButton>,
+    gamepad_buttons: HashSet<GamepadButton>,
+    gamepad_axes: HashMap<GamepadAxis, f32>,
+    // inputs pressed since the last update, so a press and release within one frame still counts
+    tapped: Vec<Binding>,
+    current: HashMap<String, f32>,
+    previous: HashMap<String, f32>,
+}
+
+impl InputState {
+    pub fn new(map: InputMap) -> Self {
+        InputState { map, ..InputState::default() }
+    }
+
+    /// Bindings from `INPUT_CONFIG_PATH`, falling back to the default bindings if it is missing.
+    pub fn from_config() -> Self {
+        match InputMap::from_file(INPUT_CONFIG_PATH) {
+            Ok(map) => InputState::new(map),
+            Err(e) => {
+                println!("{} Using default bindings.", e);
+                InputState::new(InputMap::default_bindings())
+            }
+        }
+    }
+
+    pub fn handle_event(&mut self, event: &WindowEvent) {
+        match *event {
+            WindowEvent::Key(key, _, Action::Press, _) => {
+                self.keys.insert(key);
+                self.tapped.push(Binding::Key(key));
+            }
+            WindowEvent::Key(key, _, Action::Release, _) => {
+                self.keys.remove(&key);
+            }
+            WindowEvent::MouseButton(button, Action::Press, _) => {
+                self.mouse_buttons.insert(button);
+                self.tapped.push(Binding::Mouse(button));
+            }
+            WindowEvent::MouseButton(button, Action::Release, _) => {
+                self.mouse_buttons.remove(&button);
+            }
+            // all inputs are lost together with the focus, otherwise keys stay stuck
+            WindowEvent::Focus(false) => self.clear(),
+            _ => ()
+        }
+    }
+
+    pub fn set_gamepad_button(&mut self, button: GamepadButton, pressed: bool) {
+        if pressed {
+            if self.gamepad_buttons.insert(button) {
+                self.tapped.push(Binding::GamepadButton(button));
+            }
+        } else {
+            self.gamepad_buttons.remove(&button);
+        }
+    }
+
+    pub fn set_gamepad_axis(&mut self, axis: GamepadAxis, value: f32) {
+        self.gamepad_axes.insert(axis, value);
+    }
+
+    /// Reads the first connected gamepad, glfw has no events for gamepads.
+    pub fn poll_gamepad(&mut self, glfw: &glfw::Glfw) {
+        let state = (0..16)
+            .filter_map(glfw::JoystickId::from_i32)
+            .map(|id| glfw.get_joystick(id))
+            .filter(|joystick| joystick.is_gamepad())
+            .find_map(|joystick| joystick.get_gamepad_state());
+
+        match state {
+            Some(state) => {
+                for &(button, _) in GAMEPAD_BUTTONS {
+                    self.set_gamepad_button(button, state.get_button_state(button) == Action::Press);
+                }
+                for &(axis, _) in GAMEPAD_AXES {
+                    self.set_gamepad_axis(axis, state.get_axis(axis));
+                }
+            }
+            None => {
+                self.gamepad_buttons.clear();
+                self.gamepad_axes.clear();
+            }
+        }
+    }
+
+    /// Releases every input, e.g. when the window loses focus.
+    pub fn clear(&mut self) {
+        self.keys.clear();
+        self.mouse_buttons.clear();
+        self.gamepad_buttons.clear();
+        self.gamepad_axes.clear();
+        self.tapped.clear();
+    }
+
+    /// Advances to the next frame and evaluates all actions.
+    pub fn update(&mut self) {
+        let current: HashMap<String, f32> = self.map.actions.iter()
+            .map(|(action, bindings)| {
+                let value = bindings.iter()
+                    .map(|binding| self.binding_value(binding))
+                    .fold(0.0, f32::max);
+                (action.clone(), value)
+            })
+            .collect();
+        self.previous = std::mem::replace(&mut self.current, current);
+        self.tapped.clear();
+    }
+
+    fn binding_value(&self, binding: &Binding) -> f32 {
+        if self.tapped.contains(binding) {
+            return 1.0;
+        }
+        let down = match binding {
+            Binding::Key(key) => self.keys.contains(key),
+            Binding::Mouse(button) => self.mouse_buttons.contains(button),
+            Binding::GamepadButton(button) => self.gamepad_buttons.contains(button),
+            Binding::GamepadAxis(axis, direction) => {
+                let value = self.gamepad_axes.get(axis).copied().unwrap_or(0.0) * direction;
+                return if value > AXIS_DEAD_ZONE { value.min(1.0) } else { 0.0 };
+            }
+        };
+        if down { 1.0 } else { 0.0 }
+    }
+
+    /// Value of the action this frame, 0.0 to 1.0. Analog bindings give values in between.
+    pub fn axis(&self, action: &str) -> f32 {
+        self.current.get(action).copied().unwrap_or(0.0)
+    }
+
+    /// Difference of two actions, e.g. `axis_pair("move_left", "move_right")` gives -1.0 to 1.0.
+    pub fn axis_pair(&self, negative: &str, positive: &str) -> f32 {
+        self.axis(positive) - self.axis(negative)
+    }
+
+    pub fn held(&self, action: &str) -> bool {
+        self.axis(action) > 0.0
+    }
+
+    /// True only in the frame the action went down.
+    pub fn pressed(&self, action: &str) -> bool {
+        self.held(action) && self.previous.get(action).copied().unwrap_or(0.0) <= 0.0
+    }
+
+    /// True only in the frame the action went up.
+    pub fn released(&self, action: &str) -> bool {
+        !self.held(action) && self.previous.get(action).copied().unwrap_or(0.0) > 0.0
+    }
+}
+
+// names accepted in the config file next to glfw's own variant names
+const MOUSE_BUTTONS: &[(MouseButton, &str)] = &[
+    (MouseButton::Button1, "Left"),
+    (MouseButton::Button2, "Right"),
+    (MouseButton::Button3, "Middle"),
+    (MouseButton::Button4, "4"),
+    (MouseButton::Button5, "5"),
+    (MouseButton::Button6, "6"),
+    (MouseButton::Button7, "7"),
+    (MouseButton::Button8, "8"),
+];
+
+const GAMEPAD_BUTTONS: &[(GamepadButton, &str)] = &[
+    (GamepadButton::ButtonA, "A"),
+    (GamepadButton::ButtonB, "B"),
+    (GamepadButton::ButtonX, "X"),
+    (GamepadButton::ButtonY, "Y"),
+    (GamepadButton::ButtonLeftBumper, "LeftBumper"),
+    (GamepadButton::ButtonRightBumper, "RightBumper"),
+    (GamepadButton::ButtonBack, "Back"),
+    (GamepadButton::ButtonStart, "Start"),
+    (GamepadButton::ButtonGuide, "Guide"),
+    (GamepadButton::ButtonLeftThumb, "LeftThumb"),
+    (GamepadButton::ButtonRightThumb, "RightThumb"),
+    (GamepadButton::ButtonDpadUp, "DpadUp"),
+    (GamepadButton::ButtonDpadRight, "DpadRight"),
+    (GamepadButton::ButtonDpadDown, "DpadDown"),
+    (GamepadButton::ButtonDpadLeft, "DpadLeft"),
+];
+
+const GAMEPAD_AXES: &[(GamepadAxis, &str)] = &[
+    (GamepadAxis::AxisLeftX, "LeftX"),
+    (GamepadAxis::AxisLeftY, "LeftY"),
+    (GamepadAxis::AxisRightX, "RightX"),
+    (GamepadAxis::AxisRightY, "RightY"),
+    (GamepadAxis::AxisLeftTrigger, "LeftTrigger"),
+    (GamepadAxis::AxisRightTrigger, "RightTrigger"),
+];
+
+const KEYS: &[(Key, &str)] = &[
+    (Key::Space, "Space"), (Key::Apostrophe, "'"), (Key::Comma, "Comma"), (Key::Minus, "-"),
+    (Key::Period, "."), (Key::Slash, "/"), (Key::Semicolon, ";"), (Key::Equal, "="),
+    (Key::Num0, "0"), (Key::Num1, "1"), (Key::Num2, "2"), (Key::Num3, "3"), (Key::Num4, "4"),
+    (Key::Num5, "5"), (Key::Num6, "6"), (Key::Num7, "7"), (Key::Num8, "8"), (Key::Num9, "9"),
+    (Key::A, "A"), (Key::B, "B"), (Key::C, "C"), (Key::D, "D"), (Key::E, "E"), (Key::F, "F"),
+    (Key::G, "G"), (Key::H, "H"), (Key::I, "I"), (Key::J, "J"), (Key::K, "K"), (Key::L, "L"),
+    (Key::M, "M"), (Key::N, "N"), (Key::O, "O"), (Key::P, "P"), (Key::Q, "Q"), (Key::R, "R"),
+    (Key::S, "S"), (Key::T, "T"), (Key::U, "U"), (Key::V, "V"), (Key::W, "W"), (Key::X, "X"),
+    (Key::Y, "Y"), (Key::Z, "Z"),
+    (Key::LeftBracket, "["), (Key::Backslash, "\\"), (Key::RightBracket, "]"), (Key::GraveAccent, "`"),
+    (Key::Escape, "Esc"), (Key::Enter, "Return"), (Key::Tab, "Tab"), (Key::Backspace, "Backspace"),
+    (Key::Insert, "Ins"), (Key::Delete, "Del"), (Key::Right, "Right"), (Key::Left, "Left"),
+    (Key::Down, "Down"), (Key::Up, "Up"), (Key::PageUp, "PageUp"), (Key::PageDown, "PageDown"),
+    (Key::Home, "Home"), (Key::End, "End"), (Key::CapsLock, "CapsLock"), (Key::Pause, "Pause"),
+    (Key::F1, "F1"), (Key::F2, "F2"), (Key::F3, "F3"), (Key::F4, "F4"), (Key::F5, "F5"),
+    (Key::F6, "F6"), (Key::F7, "F7"), (Key::F8, "F8"), (Key::F9, "F9"), (Key::F10, "F10"),
+    (Key::F11, "F11"), (Key::F12, "F12"),
+    (Key::Kp0, "Numpad0"), (Key::Kp1, "Numpad1"), (Key::Kp2, "Numpad2"), (Key::Kp3, "Numpad3"),
+    (Key::Kp4, "Numpad4"), (Key::Kp5, "Numpad5"), (Key::Kp6, "Numpad6"), (Key::Kp7, "Numpad7"),
+    (Key::Kp8, "Numpad8"), (Key::Kp9, "Numpad9"), (Key::KpAdd, "NumpadPlus"), (Key::KpSubtract, "NumpadMinus"),
+    (Key::LeftShift, "Shift"), (Key::LeftControl, "Ctrl"), (Key::LeftAlt, "Alt"), (Key::LeftSuper, "Super"),
+    (Key::RightShift, "RightShift"), (Key::RightControl, "RightCtrl"), (Key::RightAlt, "RightAlt"),
+    (Key::RightSuper, "RightSuper"),
+];
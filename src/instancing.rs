@@ -0,0 +1,114 @@
+//! Per-instance attributes for drawing many copies of a mesh in one call.
+//!
+//! An `InstanceBuffer` holds one `Instance` per copy. Attached to a vertex array, its attributes
+//! advance once per instance instead of once per vertex:
+//!
+//! ```glsl
+//! layout (location = 5) in mat4 aInstanceModel; // takes the locations 5 to 8
+//! layout (location = 9) in vec4 aInstanceColor;
+//! layout (location = 10) in vec4 aInstanceData;
+//! ```
+//!
+//! The locations start after the ones a `Mesh` uses, so the same buffer works with meshes,
+//! models and the vertex arrays of `shapes`.
+
+use std::mem::{size_of, size_of_val};
+use std::os::raw::c_void;
+
+use cgmath::{Matrix4, SquareMatrix, Vector4};
+
+/// First attribute location of the instance attributes.
+pub const INSTANCE_LOCATION: u32 = 5;
+
+/// The attributes of one instance.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Instance {
+    pub model: Matrix4<f32>,
+    pub color: Vector4<f32>,
+    // whatever the shader wants, e.g. an animation phase
+    pub data: Vector4<f32>,
+}
+
+impl Instance {
+    /// An instance placed by `model`, white and without data.
+    pub fn new(model: Matrix4<f32>) -> Self {
+        Instance { model, color: Vector4::new(1.0, 1.0, 1.0, 1.0), data: Vector4::new(0.0, 0.0, 0.0, 0.0) }
+    }
+}
+
+impl Default for Instance {
+    fn default() -> Self {
+        Instance::new(Matrix4::identity())
+    }
+}
+
+/// A vertex buffer of `Instance`s, attach it to the vertex arrays that draw them.
+///
+/// The vertex arrays only reference the buffer, it has to live as long as they draw with it.
+pub struct InstanceBuffer {
+    pub id: u32,
+    len: usize,
+    capacity: usize,
+}
+
+impl InstanceBuffer {
+    pub fn new(instances: &[Instance]) -> Self {
+        let mut buffer = InstanceBuffer { id: 0, len: 0, capacity: 0 };
+        unsafe { gl::GenBuffers(1, &mut buffer.id); }
+        buffer.update(instances);
+        buffer
+    }
+
+    /// Replaces the instances, the buffer only grows when there are more than ever before.
+    pub fn update(&mut self, instances: &[Instance]) {
+        let size = size_of_val(instances) as isize;
+        unsafe {
+            gl::BindBuffer(gl::ARRAY_BUFFER, self.id);
+            if instances.len() > self.capacity {
+                // the instances change every frame in most scenes
+                gl::BufferData(gl::ARRAY_BUFFER, size, instances.as_ptr() as *const c_void, gl::DYNAMIC_DRAW);
+                self.capacity = instances.len();
+            } else if !instances.is_empty() {
+                gl::BufferSubData(gl::ARRAY_BUFFER, 0, size, instances.as_ptr() as *const c_void);
+            }
+            gl::BindBuffer(gl::ARRAY_BUFFER, 0);
+        }
+        self.len = instances.len();
+    }
+
+    /// Number of instances, the count to draw.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Sets up the instance attributes of the vertex array `vao`.
+    pub fn attach(&self, vao: u32) {
+        let stride = size_of::<Instance>() as i32;
+        let vec4 = 4 * size_of::<f32>();
+        unsafe {
+            gl::BindVertexArray(vao);
+            gl::BindBuffer(gl::ARRAY_BUFFER, self.id);
+            // a mat4 attribute is four vec4 columns in consecutive locations, then color and data
+            for column in 0..6 {
+                let location = INSTANCE_LOCATION + column as u32;
+                gl::EnableVertexAttribArray(location);
+                gl::VertexAttribPointer(location, 4, gl::FLOAT, gl::FALSE, stride, (column * vec4) as *const c_void);
+                // advance once per instance instead of once per vertex
+                gl::VertexAttribDivisor(location, 1);
+            }
+            gl::BindVertexArray(0);
+            gl::BindBuffer(gl::ARRAY_BUFFER, 0);
+        }
+    }
+}
+
+impl Drop for InstanceBuffer {
+    fn drop(&mut self) {
+        unsafe { gl::DeleteBuffers(1, &self.id); }
+    }
+}
@@ -0,0 +1,199 @@
+//! Finds the cargo examples and picks one by name, number or a fuzzy match, used by `main.rs`.
+
+use std::fs;
+use std::path::Path;
+
+use crate::scenes;
+
+/// A cargo example as listed in `Cargo.toml`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Example {
+    pub name: String,
+    // e.g. "1_getting_started", taken from the directory under examples/
+    pub chapter: String,
+    pub description: String,
+}
+
+/// Result of looking up what the user typed.
+#[derive(Debug, PartialEq)]
+pub enum Match<'a> {
+    Found(&'a Example),
+    // the input was not an exact name, but close to exactly one example
+    Fuzzy(&'a Example),
+    Ambiguous(Vec<&'a Example>),
+    NotFound,
+}
+
+/// Examples from the manifest next to the crate, or from the scene registry if it can't be read.
+pub fn discover() -> Vec<Example> {
+    let manifest = Path::new(env!("CARGO_MANIFEST_DIR")).join("Cargo.toml");
+    match fs::read_to_string(&manifest) {
+        Ok(source) => {
+            let examples = parse_manifest(&source);
+            if examples.is_empty() { from_registry() } else { examples }
+        }
+        Err(_) => from_registry(),
+    }
+}
+
+/// Every scene of `scenes::SCENES`, these all have an example of the same name.
+pub fn from_registry() -> Vec<Example> {
+    scenes::SCENES.iter()
+        .map(|entry| Example {
+            name: entry.name.to_string(),
+            chapter: entry.chapter.to_string(),
+            description: entry.description.to_string(),
+        })
+        .collect()
+}
+
+/// Reads the `[[example]]` tables of a Cargo.toml, in file order.
+///
+/// Only `name` and `path` are used, descriptions come from the scene registry or from the first
+/// comment in the example's source.
+pub fn parse_manifest(source: &str) -> Vec<Example> {
+    let mut tables: Vec<(Option<String>, Option<String>)> = Vec::new();
+    let mut in_example = false;
+    for line in source.lines().map(str::trim) {
+        if line.starts_with('[') {
+            in_example = line == "[[example]]";
+            if in_example {
+                tables.push((None, None));
+            }
+            continue;
+        }
+        if !in_example {
+            continue;
+        }
+        if let Some((key, value)) = line.split_once('=') {
+            let value = value.trim().trim_matches('"').to_string();
+            let table = tables.last_mut().unwrap();
+            match key.trim() {
+                "name" => table.0 = Some(value),
+                "path" => table.1 = Some(value),
+                _ => ()
+            }
+        }
+    }
+
+    tables.into_iter()
+        .filter_map(|(name, path)| {
+            let name = name?;
+            let path = path.unwrap_or_else(|| format!("examples/{}.rs", name));
+            let chapter = Path::new(&path).parent()
+                .and_then(|dir| dir.file_name())
+                .map(|dir| dir.to_string_lossy().into_owned())
+                .filter(|dir| dir != "examples")
+                .unwrap_or_default();
+            let description = describe(&name, &path);
+            Some(Example { name, chapter, description })
+        })
+        .collect()
+}
+
+fn describe(name: &str, path: &str) -> String {
+    if let Some(entry) = scenes::find(name) {
+        return entry.description.to_string();
+    }
+    // tools have no scene, their first comment explains them instead
+    let source = fs::read_to_string(Path::new(env!("CARGO_MANIFEST_DIR")).join(path)).unwrap_or_default();
+    source.lines()
+        .map(str::trim)
+        .filter(|line| line.starts_with("//"))
+        .map(|line| line.trim_start_matches('/').trim())
+        .find(|line| !line.is_empty() && !line.starts_with("Constants"))
+        .unwrap_or("")
+        .to_string()
+}
+
+/// "1_getting_started" becomes "1. Getting started".
+pub fn chapter_title(chapter: &str) -> String {
+    if chapter.is_empty() {
+        return "Other".to_string();
+    }
+    let (number, name) = match chapter.split_once('_') {
+        Some((number, name)) if number.chars().all(|c| c.is_ascii_digit()) => (Some(number), name),
+        _ => (None, chapter),
+    };
+    let mut name = name.replace('_', " ");
+    if let Some(first) = name.get(0..1) {
+        name = first.to_uppercase() + &name[1..];
+    }
+    match number {
+        Some(number) => format!("{}. {}", number, name),
+        None => name,
+    }
+}
+
+/// The list grouped by chapter, numbered from 1 in the order of `examples`.
+pub fn format_list(examples: &[Example]) -> String {
+    let mut list = String::new();
+    let mut chapter = None;
+    for (index, example) in examples.iter().enumerate() {
+        if chapter != Some(&example.chapter) {
+            chapter = Some(&example.chapter);
+            list += &format!("\n{}\n", chapter_title(&example.chapter));
+        }
+        list += &format!("{:>4}  {:<20} {}\n", index + 1, example.name, example.description);
+    }
+    list
+}
+
+/// Looks up a number from the list, an exact name, a unique part of a name or a name with typos.
+pub fn find<'a>(examples: &'a [Example], input: &str) -> Match<'a> {
+    let input = input.trim().to_lowercase();
+    if input.is_empty() {
+        return Match::NotFound;
+    }
+
+    if let Ok(number) = input.parse::<usize>() {
+        return match examples.get(number.wrapping_sub(1)) {
+            Some(example) => Match::Found(example),
+            None => Match::NotFound,
+        };
+    }
+
+    if let Some(example) = examples.iter().find(|example| example.name.to_lowercase() == input) {
+        return Match::Found(example);
+    }
+
+    let containing: Vec<&Example> = examples.iter()
+        .filter(|example| example.name.to_lowercase().contains(&input))
+        .collect();
+    match containing.len() {
+        1 => return Match::Fuzzy(containing[0]),
+        0 => (),
+        _ => return Match::Ambiguous(containing),
+    }
+
+    // typos: the closest names within a few edits
+    let max_distance = (input.len() / 4).max(1);
+    let distances: Vec<(usize, &Example)> = examples.iter()
+        .map(|example| (edit_distance(&input, &example.name.to_lowercase()), example))
+        .filter(|&(distance, _)| distance <= max_distance)
+        .collect();
+    let best = match distances.iter().map(|&(distance, _)| distance).min() {
+        Some(best) => best,
+        None => return Match::NotFound,
+    };
+    let closest: Vec<&Example> = distances.into_iter()
+        .filter(|&(distance, _)| distance == best)
+        .map(|(_, example)| example)
+        .collect();
+    if closest.len() == 1 { Match::Fuzzy(closest[0]) } else { Match::Ambiguous(closest) }
+}
+
+// Levenshtein distance
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut current = vec![i + 1; b.len() + 1];
+        for (j, &cb) in b.iter().enumerate() {
+            let substitution = previous[j] + if ca == cb { 0 } else { 1 };
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
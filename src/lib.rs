@@ -1,5 +1,31 @@
 pub mod shader;
 pub mod camera;
 pub mod mesh;
+pub mod instancing;
 pub mod model;
-pub mod utils;
\ No newline at end of file
+pub mod utils;
+pub mod input;
+pub mod scene;
+pub mod scenes;
+pub mod capture;
+pub mod framebuffer;
+pub mod render_state;
+pub mod render_queue;
+pub mod shading;
+pub mod gamma;
+pub mod hdr;
+pub mod bloom;
+pub mod deferred;
+pub mod shadow;
+pub mod cascades;
+pub mod relief;
+pub mod oit;
+pub mod outline;
+pub mod cubemap;
+pub mod environment;
+pub mod postprocess;
+pub mod rasterizer;
+pub mod launcher;
+pub mod gallery;
+#[cfg(unix)]
+pub mod headless;
\ No newline at end of file
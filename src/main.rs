@@ -1,25 +1,120 @@
-use std::process::Command;
+use std::env;
+use std::io::{self, Write};
+use std::process::{self, Command};
+
+use learn_opengl_rust::gallery;
+use learn_opengl_rust::launcher::{self, Example, Match};
+use learn_opengl_rust::scenes;
+
+// Constants
+const WINDOW_WIDTH: u32 = 800;
+const WINDOW_HEIGHT: u32 = 600;
+
+const USAGE: &str = "\
+Usage: cargo run -- [example] [arguments for the example]
+       cargo run -- --list
+
+The example can be its name, its number in the list or a part of the name.
+Without an example the launcher asks for one.
+
+Scenes open in one window, PageDown and PageUp switch to the next and previous
+scene, M opens the chapter menu. Tools like headless are run through cargo.";
 
 fn main() {
+    let examples = launcher::discover();
+    let mut args = env::args().skip(1);
+
+    match args.next().as_deref() {
+        Some("-h") | Some("--help") => println!("{}", USAGE),
+        Some("-l") | Some("--list") => print!("{}", launcher::format_list(&examples)),
+        Some(input) => {
+            // the remaining arguments go to the example, an optional `--` separates them
+            let example_args: Vec<String> = args.skip_while(|arg| arg == "--").collect();
+            match select(&examples, input) {
+                Some(example) => run_example(example, &example_args),
+                None => process::exit(2),
+            }
+        }
+        None => interactive(&examples),
+    }
+}
+
+fn interactive(examples: &[Example]) {
     println!("Hey, you are running the Learn-OpenGL-Rust Project by Tobyeus.");
-    println!("All examples can be run with the command 'cargo run --example [example_name]'.");
-    println!("You can find all the example names in the 'Cargo.toml' file");
-    println!("Which example would you like to run? Enter the name of the example:");
-    let mut example: String = String::new();
-    std::io::stdin().read_line(&mut example).unwrap();
-    println!("You entered: {}", example);
-    println!("Loading the example...");
-    run_example(example.trim());
-    //output
-    //println!("Status: {:?}", result.status);
-    //println!("Error: {:?}", result.stderr);
-    //println!("Out:: {:?}", result.stdout);
+    println!("Scenes open in one window, PageDown and PageUp switch between them and M opens the chapter menu.");
+    loop {
+        print!("Which example would you like to run? Enter a name or number, nothing for the list, q to quit: ");
+        io::stdout().flush().unwrap();
+
+        let mut input = String::new();
+        if io::stdin().read_line(&mut input).unwrap_or(0) == 0 {
+            return;
+        }
+        match input.trim() {
+            "" => print!("{}", launcher::format_list(examples)),
+            "q" | "quit" => return,
+            input => {
+                if let Some(example) = select(examples, input) {
+                    run_example(example, &[]);
+                    return;
+                }
+            }
+        }
+    }
+}
+
+// prints why nothing was selected
+fn select<'a>(examples: &'a [Example], input: &str) -> Option<&'a Example> {
+    match launcher::find(examples, input) {
+        Match::Found(example) => Some(example),
+        Match::Fuzzy(example) => {
+            println!("'{}' matched {}", input, example.name);
+            Some(example)
+        }
+        Match::Ambiguous(candidates) => {
+            println!("'{}' could be any of:", input);
+            for example in candidates {
+                println!("  {:<20} {}", example.name, example.description);
+            }
+            None
+        }
+        Match::NotFound => {
+            println!("There is no example '{}', run with --list to see all of them.", input);
+            None
+        }
+    }
 }
 
-fn run_example(example_name: &str) {
-    let mut example_command = Command::new("cargo");
-    example_command.arg("run");
-    example_command.arg("--example");
-    example_command.arg(example_name);
-    example_command.status();
-}
\ No newline at end of file
+fn run_example(example: &Example, args: &[String]) {
+    // scenes are compiled into the launcher, only tools still need their own binary
+    if let Some(index) = scenes::SCENES.iter().position(|entry| entry.name == example.name) {
+        if !args.is_empty() {
+            println!("Scenes take no arguments, ignoring {:?}", args);
+        }
+        println!("Loading {}: {}", example.name, example.description);
+        gallery::run(scenes::SCENES, index, WINDOW_WIDTH, WINDOW_HEIGHT);
+        return;
+    }
+
+    println!("Running {} through cargo: {}", example.name, example.description);
+    let status = Command::new("cargo")
+        .args(["run", "--example", &example.name])
+        .args(if args.is_empty() { &[][..] } else { &["--"][..] })
+        .args(args)
+        .status();
+
+    match status {
+        Ok(status) if status.success() => (),
+        Ok(status) => {
+            match status.code() {
+                Some(code) => eprintln!("Example '{}' failed with exit code {}", example.name, code),
+                None => eprintln!("Example '{}' was terminated by a signal", example.name),
+            }
+            process::exit(status.code().unwrap_or(1));
+        }
+        Err(e) => {
+            eprintln!("Could not run cargo: {}", e);
+            process::exit(1);
+        }
+    }
+}
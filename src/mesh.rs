@@ -12,6 +12,7 @@ use gl;
 
 use shader::Shader;
 
+use crate::instancing::InstanceBuffer;
 use crate::shader;
 
 #[repr(C)]
@@ -73,11 +74,40 @@ impl Mesh {
 
     /// render the mesh
     pub unsafe fn Draw(&self, shader: &Shader) {
+        self.bindTextures(shader);
+
+        // draw mesh
+        gl::BindVertexArray(self.VAO);
+        gl::DrawElements(gl::TRIANGLES, self.indices.len() as i32, gl::UNSIGNED_INT, ptr::null());
+        gl::BindVertexArray(0);
+
+        // always good practice to set everything back to defaults once configured.
+        gl::ActiveTexture(gl::TEXTURE0);
+    }
+
+    /// render `count` instances of the mesh in one call, attach an `InstanceBuffer` with at least as many first
+    pub unsafe fn DrawInstanced(&self, shader: &Shader, count: usize) {
+        self.bindTextures(shader);
+
+        gl::BindVertexArray(self.VAO);
+        gl::DrawElementsInstanced(gl::TRIANGLES, self.indices.len() as i32, gl::UNSIGNED_INT, ptr::null(), count as i32);
+        gl::BindVertexArray(0);
+
+        gl::ActiveTexture(gl::TEXTURE0);
+    }
+
+    /// the per-instance attributes of `instances` follow the vertex attributes, see `instancing`
+    pub fn attach_instances(&self, instances: &InstanceBuffer) {
+        instances.attach(self.VAO);
+    }
+
+    unsafe fn bindTextures(&self, shader: &Shader) {
         // bind appropriate textures
         let mut diffuseNr  = 0;
         let mut specularNr = 0;
         let mut normalNr = 0;
         let mut heightNr = 0;
+        let mut emissiveNr = 0;
         for (i, texture) in self.textures.iter().enumerate() {
             gl::ActiveTexture(gl::TEXTURE0 + i as u32); // active proper texture unit before binding
             // retrieve texture number (the N in diffuse_textureN)
@@ -99,6 +129,10 @@ impl Mesh {
                     heightNr += 1;
                     heightNr
                 }
+                "texture_emissive" => {
+                    emissiveNr += 1;
+                    emissiveNr
+                }
                 _ => panic!("unknown texture type")
             };
             // now set the sampler to the correct texture unit
@@ -107,14 +141,6 @@ impl Mesh {
             // and finally bind the texture
             gl::BindTexture(gl::TEXTURE_2D, texture.id);
         }
-
-        // draw mesh
-        gl::BindVertexArray(self.VAO);
-        gl::DrawElements(gl::TRIANGLES, self.indices.len() as i32, gl::UNSIGNED_INT, ptr::null());
-        gl::BindVertexArray(0);
-
-        // always good practice to set everything back to defaults once configured.
-        gl::ActiveTexture(gl::TEXTURE0);
     }
 
     unsafe fn setupMesh(&mut self) {
@@ -158,4 +184,65 @@ impl Mesh {
 
         gl::BindVertexArray(0);
     }
-}
\ No newline at end of file
+}
+
+/// Fills in `tangent` and `bitangent` of every vertex: the directions the texture coordinates u and v
+/// grow in across its triangles, averaged. Normal maps store their normals relative to these.
+/// Triangles without distinct texture coordinates are skipped, their vertices keep zero tangents.
+pub fn calculate_tangents(vertices: &mut [Vertex], indices: &[u32]) {
+    let mut tangents = vec![Vector3::zero(); vertices.len()];
+    let mut bitangents = vec![Vector3::zero(); vertices.len()];
+    for triangle in indices.chunks(3).filter(|triangle| triangle.len() == 3) {
+        let (a, b, c) = (triangle[0] as usize, triangle[1] as usize, triangle[2] as usize);
+        let edge1 = vertices[b].position - vertices[a].position;
+        let edge2 = vertices[c].position - vertices[a].position;
+        let delta1 = vertices[b].tex_coords - vertices[a].tex_coords;
+        let delta2 = vertices[c].tex_coords - vertices[a].tex_coords;
+        // solves edge = delta.x * tangent + delta.y * bitangent for both edges
+        let determinant = delta1.x * delta2.y - delta2.x * delta1.y;
+        if determinant.abs() < 1e-12 {
+            continue;
+        }
+        let tangent = (edge1 * delta2.y - edge2 * delta1.y) / determinant;
+        let bitangent = (edge2 * delta1.x - edge1 * delta2.x) / determinant;
+        for &index in [a, b, c].iter() {
+            tangents[index] += tangent;
+            bitangents[index] += bitangent;
+        }
+    }
+    for (vertex, (tangent, bitangent)) in vertices.iter_mut().zip(tangents.into_iter().zip(bitangents)) {
+        if tangent.magnitude2() > 0.0 {
+            vertex.tangent = tangent.normalize();
+        }
+        if bitangent.magnitude2() > 0.0 {
+            vertex.bitangent = bitangent.normalize();
+        }
+    }
+}
+
+/// Geometry drawn without its materials, by passes that only need the shape: outlines, shadow maps.
+/// Positions are at location 0 and normals at location 1.
+pub trait Geometry {
+    /// Draws the triangles with the program in use, without binding textures.
+    fn draw_geometry(&self);
+}
+
+impl Geometry for Mesh {
+    fn draw_geometry(&self) {
+        unsafe {
+            gl::BindVertexArray(self.VAO);
+            gl::DrawElements(gl::TRIANGLES, self.indices.len() as i32, gl::UNSIGNED_INT, ptr::null());
+        }
+    }
+}
+
+// the textures belong to the model, they may be shared between meshes
+impl Drop for Mesh {
+    fn drop(&mut self) {
+        unsafe {
+            gl::DeleteVertexArrays(1, &self.VAO);
+            gl::DeleteBuffers(1, &self.VBO);
+            gl::DeleteBuffers(1, &self.EBO);
+        }
+    }
+}
@@ -12,10 +12,11 @@ use image::DynamicImage::*;
 use image::GenericImage;
 use tobj;
 
-use mesh::{ Mesh, Texture, Vertex };
+use mesh::{ Geometry, Mesh, Texture, Vertex };
 use shader::Shader;
 use tobj::Material;
 
+use crate::instancing::InstanceBuffer;
 use crate::mesh;
 use crate::shader;
 
@@ -25,13 +26,22 @@ pub struct Model {
     pub meshes: Vec<Mesh>,
     pub textures_loaded: Vec<Texture>,   // stores all the textures loaded so far, optimization to make sure textures aren't loaded more than once.
     directory: String,
+    // of the diffuse and emissive maps, the others hold data and are always linear
+    color_space: ColorSpace,
 }
 
 impl Model {
     pub fn new(path: &str, file: &str) -> Model {
+        Model::with_color_space(path, file, ColorSpace::Linear)
+    }
+
+    /// loads the diffuse and emissive maps as `color_space`, `ColorSpace::Srgb` for shaders that light in linear space
+    pub fn with_color_space(path: &str, file: &str, color_space: ColorSpace) -> Model {
         let mut model = Model {
             directory: path.into(),
-            ..Model::default()
+            meshes: Vec::new(),
+            textures_loaded: Vec::new(),
+            color_space,
         };
         model.load_model(file);
         model
@@ -42,6 +52,20 @@ impl Model {
             unsafe { mesh.Draw(shader); }
         }
     }
+
+    /// draws `count` instances of every mesh, see `Mesh::DrawInstanced`
+    pub fn DrawInstanced(&self, shader: &Shader, count: usize) {
+        for mesh in &self.meshes {
+            unsafe { mesh.DrawInstanced(shader, count); }
+        }
+    }
+
+    /// attaches the instance buffer to every mesh
+    pub fn attach_instances(&self, instances: &InstanceBuffer) {
+        for mesh in &self.meshes {
+            mesh.attach_instances(instances);
+        }
+    }
     
     pub fn load_model(&mut self, file: &str) {
         let full_dir = &format!("{}/{}", self.directory, file);
@@ -68,6 +92,7 @@ impl Model {
                     ..Vertex::default()
                 })
             }
+            mesh::calculate_tangents(&mut vertices, &indices);
             
             let textures = match mesh.material_id {
                 Some(material_id) => self.process_materials(materials.get(material_id).expect("Could not load material from mesh")),
@@ -80,31 +105,44 @@ impl Model {
     
     fn process_materials(&mut self, material: &Material) -> Vec<Texture> {
         let mut textures = Vec::new();
+        let color_space = self.color_space;
         // 1. diffuse map
         if !material.diffuse_texture.is_empty() {
-            let texture = self.load_material_texture(&format!("{}/{}", self.directory, material.diffuse_texture), "texture_diffuse");
+            let texture = self.load_material_texture(&material.diffuse_texture, "texture_diffuse", color_space);
             textures.push(texture);
         }
         // 2. specular map
         if !material.specular_texture.is_empty() {
-            let texture = self.load_material_texture(&format!("{}/{}", self.directory, material.diffuse_texture), "texture_specular");
+            let texture = self.load_material_texture(&material.specular_texture, "texture_specular", ColorSpace::Linear);
+            textures.push(texture);
+        }
+        // 3. normal map, tobj does not know map_Bump, bump or norm. its normal_texture holds map_Ns,
+        // the shininess map, which no shader here uses
+        if let Some(file) = ["map_Bump", "bump", "norm"].iter().find_map(|key| material.unknown_param.get(*key)) {
+            let texture = self.load_material_texture(file, "texture_normal", ColorSpace::Linear);
             textures.push(texture);
         }
-        // 3. normal map
-        if !material.normal_texture.is_empty() {
-            let texture = self.load_material_texture(&format!("{}/{}", self.directory, material.diffuse_texture), "texture_normal");
+        // 4. height map for parallax mapping, disp
+        if let Some(file) = ["map_Disp", "disp"].iter().find_map(|key| material.unknown_param.get(*key)) {
+            let texture = self.load_material_texture(file, "texture_height", ColorSpace::Linear);
+            textures.push(texture);
+        }
+        // 5. emissive map, tobj does not know map_Ke
+        if let Some(file) = material.unknown_param.get("map_Ke") {
+            let texture = self.load_material_texture(file, "texture_emissive", color_space);
             textures.push(texture);
         }
         textures
     }
     
-    fn load_material_texture(&mut self, path: &str, typeName: &str) -> Texture {
-        let texture = self.textures_loaded.iter().find(|t| t.path == path);
+    fn load_material_texture(&mut self, file: &str, typeName: &str, color_space: ColorSpace) -> Texture {
+        let path = &format!("{}/{}", self.directory, file);
+        let texture = self.textures_loaded.iter().find(|t| &t.path == path);
         if let Some(texture) = texture {
             return texture.clone();
         }
         let texture = Texture {
-            id: unsafe { texture_from_file(path) },
+            id: unsafe { texture_from_file_with(path, color_space) },
             type_: typeName.into(),
             path: path.into()
         };
@@ -113,7 +151,40 @@ impl Model {
     }
 }
 
+impl Geometry for Model {
+    fn draw_geometry(&self) {
+        for mesh in &self.meshes {
+            mesh.draw_geometry();
+        }
+    }
+}
+
+impl Drop for Model {
+    fn drop(&mut self) {
+        for texture in &self.textures_loaded {
+            unsafe { gl::DeleteTextures(1, &texture.id); }
+        }
+    }
+}
+
+/// How the texels of an image are encoded.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum ColorSpace {
+    // colors as painted or photographed, e.g. diffuse and emissive maps, decoded to linear when sampled
+    Srgb,
+    // data like specular, normal and height maps, sampled as stored
+    #[default]
+    Linear,
+}
+
+/// loads a texture as stored, see `texture_from_file_with`
 pub unsafe fn texture_from_file(path: &str) -> u32 {
+    texture_from_file_with(path, ColorSpace::Linear)
+}
+
+/// loads a texture with mipmaps and repeat wrapping, color textures in `ColorSpace::Srgb` get an sRGB internal format
+/// so sampling them returns linear values, grayscale images are data and stay linear
+pub unsafe fn texture_from_file_with(path: &str, color_space: ColorSpace) -> u32 {
     let mut textureID = 0;
     gl::GenTextures(1, &mut textureID);
     
@@ -126,11 +197,16 @@ pub unsafe fn texture_from_file(path: &str) -> u32 {
         ImageRgb8(_) => gl::RGB,
         ImageRgba8(_) => gl::RGBA,
     };
+    let internal_format = match (format, color_space) {
+        (gl::RGB, ColorSpace::Srgb) => gl::SRGB8,
+        (gl::RGBA, ColorSpace::Srgb) => gl::SRGB8_ALPHA8,
+        _ => format,
+    };
     
     let data = img.raw_pixels();
     
     gl::BindTexture(gl::TEXTURE_2D, textureID);
-    gl::TexImage2D(gl::TEXTURE_2D, 0, format as i32, img.width() as i32, img.height() as i32,
+    gl::TexImage2D(gl::TEXTURE_2D, 0, internal_format as i32, img.width() as i32, img.height() as i32,
     0, format, gl::UNSIGNED_BYTE, &data[0] as *const u8 as *const c_void);
     gl::GenerateMipmap(gl::TEXTURE_2D);
     
@@ -0,0 +1,170 @@
+//! Weighted blended order-independent transparency (McGuire and Bavoil 2013).
+//!
+//! Instead of blending each transparent fragment over the last, all of them are summed up in
+//! any order: their colors weighted by alpha and a depth based weight into an accumulation
+//! target, and the product of their `1 - alpha`, the revealage, which is how much of the opaque
+//! scene still shows through. A composite pass divides the sum by the total weight and blends
+//! the average over the opaque scene. Intersecting glass or particle clouds, which no sorting by
+//! object can get right, come out close to correct.
+//!
+//! GL 3.3 has one blend function for all attachments, so the targets are laid out to make do
+//! with `BlendFuncSeparate(ONE, ONE, ZERO, ONE_MINUS_SRC_ALPHA)`: additive color, multiplied alpha.
+//!
+//! | target | rgb                       | alpha                  |
+//! |--------|---------------------------|------------------------|
+//! | 0      | sum of color * alpha * w  | revealage, prod(1 - a) |
+//! | 1      | sum of alpha * w (red)    |                        |
+//!
+//! A fragment shader writing into the pass, see `oit_accumulate.fs`:
+//!
+//! ```glsl
+//! layout (location = 0) out vec4 accumulation;
+//! layout (location = 1) out float weight;
+//! // ...
+//! float w = clamp(pow(min(1.0, color.a * 10.0) + 0.01, 3.0) * 1e8 * pow(1.0 - gl_FragCoord.z * 0.9, 3.0), 1e-2, 3e3);
+//! accumulation = vec4(color.rgb * color.a * w, color.a);
+//! weight = color.a * w;
+//! ```
+
+use crate::framebuffer::{ColorFormat, DepthFormat, Framebuffer, FramebufferDesc, FullscreenQuad, Storage, FULLSCREEN_VERTEX_SHADER};
+use crate::shader::Shader;
+
+pub const OIT_COMPOSITE_SHADER: &str = "./src/shaders/4_advanced_opengl/oit_composite.fs";
+
+/// The targets of the pass: the opaque scene, and accumulation and weights sharing its depth.
+///
+/// A frame goes `begin_opaque`, opaque draws, `begin_accumulation`, transparent draws and
+/// `resolve`, which copies the result into the framebuffer that was bound before. Only the color
+/// is copied, so everything of the frame has to be drawn through the pass. A `RenderQueue`
+/// flushed with `flush_with` does all of this.
+pub struct OitPass {
+    scene: Framebuffer,
+    accumulation: Framebuffer,
+    composite: Shader,
+    quad: FullscreenQuad,
+    // framebuffer and viewport to return to in `resolve`
+    previous: u32,
+    viewport: [i32; 4],
+}
+
+impl OitPass {
+    pub fn new(width: u32, height: u32) -> Result<Self, String> {
+        let scene = Framebuffer::new(FramebufferDesc {
+            width,
+            height,
+            colors: vec![ColorFormat::Rgba8],
+            // a texture, so the accumulation framebuffer can test against it too
+            depth: Some((DepthFormat::Depth24Stencil8, Storage::Texture)),
+        })?;
+        let accumulation = Framebuffer::new(FramebufferDesc {
+            width,
+            height,
+            // half floats, the weighted colors go up to a few thousand
+            colors: vec![ColorFormat::Rgba16F, ColorFormat::R16F],
+            depth: None,
+        })?;
+
+        let composite = Shader::new(FULLSCREEN_VERTEX_SHADER, OIT_COMPOSITE_SHADER);
+        composite.use_program();
+        composite.set_int("accumulation", 0);
+        composite.set_int("weights", 1);
+
+        let pass = OitPass { scene, accumulation, composite, quad: FullscreenQuad::new(), previous: 0, viewport: [0; 4] };
+        pass.share_depth()?;
+        Ok(pass)
+    }
+
+    // attaches the scene's depth texture to the accumulation framebuffer
+    fn share_depth(&self) -> Result<(), String> {
+        unsafe {
+            let mut previous = 0;
+            gl::GetIntegerv(gl::FRAMEBUFFER_BINDING, &mut previous);
+            gl::BindFramebuffer(gl::FRAMEBUFFER, self.accumulation.id);
+            let depth = self.scene.depth_texture().unwrap();
+            gl::FramebufferTexture2D(gl::FRAMEBUFFER, gl::DEPTH_STENCIL_ATTACHMENT, gl::TEXTURE_2D, depth, 0);
+            let status = gl::CheckFramebufferStatus(gl::FRAMEBUFFER);
+            gl::BindFramebuffer(gl::FRAMEBUFFER, previous as u32);
+            if status != gl::FRAMEBUFFER_COMPLETE {
+                return Err(format!("The accumulation framebuffer is incomplete: {}", crate::framebuffer::status_message(status)));
+            }
+        }
+        Ok(())
+    }
+
+    pub fn width(&self) -> u32 {
+        self.scene.width()
+    }
+
+    pub fn height(&self) -> u32 {
+        self.scene.height()
+    }
+
+    /// Binds the opaque target, sized like the current viewport, and clears it with the current clear color.
+    pub fn begin_opaque(&mut self) -> Result<(), String> {
+        unsafe {
+            let mut previous = 0;
+            gl::GetIntegerv(gl::DRAW_FRAMEBUFFER_BINDING, &mut previous);
+            gl::GetIntegerv(gl::VIEWPORT, self.viewport.as_mut_ptr());
+            self.previous = previous as u32;
+        }
+        let (width, height) = (self.viewport[2].max(1) as u32, self.viewport[3].max(1) as u32);
+        if (width, height) != (self.width(), self.height()) {
+            self.scene.resize(width, height)?;
+            self.accumulation.resize(width, height)?;
+            // the old depth texture is gone
+            self.share_depth()?;
+        }
+
+        self.scene.bind();
+        unsafe { gl::Clear(gl::COLOR_BUFFER_BIT | gl::DEPTH_BUFFER_BIT | gl::STENCIL_BUFFER_BIT); }
+        Ok(())
+    }
+
+    /// Binds and clears the accumulation targets and sets the blending for transparent draws.
+    ///
+    /// The depth test stays as the scene set it, depth writes are off.
+    pub fn begin_accumulation(&self) {
+        self.accumulation.bind();
+        unsafe {
+            // nothing accumulated and everything revealed
+            gl::ClearBufferfv(gl::COLOR, 0, [0.0f32, 0.0, 0.0, 1.0].as_ptr());
+            gl::ClearBufferfv(gl::COLOR, 1, [0.0f32; 4].as_ptr());
+
+            gl::Enable(gl::BLEND);
+            gl::BlendEquation(gl::FUNC_ADD);
+            gl::BlendFuncSeparate(gl::ONE, gl::ONE, gl::ZERO, gl::ONE_MINUS_SRC_ALPHA);
+            gl::DepthMask(gl::FALSE);
+        }
+    }
+
+    /// Blends the average transparent color over the opaque scene and copies it to the framebuffer
+    /// that was bound at `begin_opaque`, restoring it and the viewport.
+    pub fn resolve(&self) {
+        unsafe {
+            gl::DepthMask(gl::TRUE);
+            let depth_test = gl::IsEnabled(gl::DEPTH_TEST) == gl::TRUE;
+            gl::Disable(gl::DEPTH_TEST);
+            gl::Enable(gl::BLEND);
+            gl::BlendFunc(gl::SRC_ALPHA, gl::ONE_MINUS_SRC_ALPHA);
+
+            self.scene.bind();
+            self.composite.use_program();
+            gl::ActiveTexture(gl::TEXTURE0);
+            gl::BindTexture(gl::TEXTURE_2D, self.accumulation.color_texture(0));
+            gl::ActiveTexture(gl::TEXTURE1);
+            gl::BindTexture(gl::TEXTURE_2D, self.accumulation.color_texture(1));
+            self.quad.draw();
+            gl::ActiveTexture(gl::TEXTURE0);
+
+            gl::Disable(gl::BLEND);
+            if depth_test {
+                gl::Enable(gl::DEPTH_TEST);
+            }
+
+            let [x, y, width, height] = self.viewport;
+            self.scene.blit_region(self.previous, [x, y, x + width, y + height], gl::COLOR_BUFFER_BIT, gl::NEAREST);
+            gl::BindFramebuffer(gl::FRAMEBUFFER, self.previous);
+            gl::Viewport(x, y, width, height);
+        }
+    }
+}
@@ -0,0 +1,260 @@
+//! Outlines around meshes and models, drawn after the object itself.
+//!
+//! Two ways to find the pixels around an object:
+//!
+//! - `OutlineStyle::Extrude` marks the object's pixels in the stencil buffer, then draws it again
+//!   with every vertex moved along its normal and keeps only what lies outside the marked pixels.
+//!   The thickness is in world units, so the outline gets thinner with distance. Hard edges, e.g.
+//!   the corners of a cube, have one normal per face and open small gaps in the outline.
+//! - `OutlineStyle::Dilate` renders the object into a mask texture and colors every pixel within
+//!   `thickness` pixels of the mask that is not covered by it. Works for any shape, concave models
+//!   and hard edges included, at the cost of a fullscreen pass.
+//!
+//! Both ignore depth, the outline shows through whatever is in front of the object, and both put
+//! the stencil, depth and blend state, the bound program and vertex array back as they found them.
+
+use cgmath::{Matrix4, Vector4};
+
+use crate::framebuffer::{ColorFormat, Framebuffer, FramebufferDesc, FullscreenQuad, FULLSCREEN_VERTEX_SHADER};
+use crate::mesh::Geometry;
+use crate::shader::Shader;
+
+pub const OUTLINE_VERTEX_SHADER: &str = "./src/shaders/4_advanced_opengl/outline.vs";
+pub const OUTLINE_FRAGMENT_SHADER: &str = "./src/shaders/4_advanced_opengl/outline.fs";
+pub const OUTLINE_DILATE_SHADER: &str = "./src/shaders/4_advanced_opengl/outline_dilate.fs";
+
+/// The stencil bit `Extrude` marks the object with, cleared again under the object before
+/// `Outline::draw` returns. The other bits are left to the scene.
+pub const OUTLINE_STENCIL_BIT: u32 = 0x80;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum OutlineStyle {
+    // normal extrusion through the stencil buffer, thickness in world units
+    Extrude,
+    // screen-space dilation of a mask, thickness in pixels
+    Dilate,
+}
+
+/// Draws outlines of one style, color and thickness.
+pub struct Outline {
+    style: OutlineStyle,
+    color: Vector4<f32>,
+    thickness: f32,
+    shader: Shader,
+    dilate_shader: Shader,
+    // the object's coverage for `Dilate`, sized like the viewport
+    mask: Framebuffer,
+    quad: FullscreenQuad,
+}
+
+impl Outline {
+    /// An outline in the border color of the stencil testing chapter, 0.05 units or 3 pixels thick.
+    pub fn new(style: OutlineStyle) -> Result<Self, String> {
+        let mask = Framebuffer::new(FramebufferDesc { width: 1, height: 1, colors: vec![ColorFormat::R8], depth: None })?;
+        let dilate_shader = Shader::new(FULLSCREEN_VERTEX_SHADER, OUTLINE_DILATE_SHADER);
+        dilate_shader.use_program();
+        dilate_shader.set_int("mask", 0);
+
+        Ok(Outline {
+            style,
+            color: Vector4::new(0.04, 0.28, 0.26, 1.0),
+            thickness: match style { OutlineStyle::Extrude => 0.05, OutlineStyle::Dilate => 3.0 },
+            shader: Shader::new(OUTLINE_VERTEX_SHADER, OUTLINE_FRAGMENT_SHADER),
+            dilate_shader,
+            mask,
+            quad: FullscreenQuad::new(),
+        })
+    }
+
+    pub fn with_color(mut self, color: Vector4<f32>) -> Self {
+        self.color = color;
+        self
+    }
+
+    /// World units for `Extrude`, pixels for `Dilate`.
+    pub fn with_thickness(mut self, thickness: f32) -> Self {
+        self.thickness = thickness;
+        self
+    }
+
+    pub fn style(&self) -> OutlineStyle {
+        self.style
+    }
+
+    /// Outlines `geometry` placed with `model`, into the bound framebuffer and viewport.
+    ///
+    /// `Extrude` needs a stencil buffer, `Dilate` fails if its mask cannot be resized to the viewport.
+    pub fn draw<G: Geometry + ?Sized>(&mut self, geometry: &G, model: Matrix4<f32>, view: Matrix4<f32>, projection: Matrix4<f32>) -> Result<(), String> {
+        let saved = unsafe { SavedState::capture() };
+
+        self.shader.use_program();
+        self.shader.set_mat4("projection", projection);
+        self.shader.set_mat4("view", view);
+        self.shader.set_mat4("model", model);
+        unsafe {
+            gl::Disable(gl::DEPTH_TEST);
+            gl::DepthMask(gl::FALSE);
+            gl::Disable(gl::BLEND);
+            // extruded back faces fill the outline too
+            gl::Disable(gl::CULL_FACE);
+        }
+
+        let result = match self.style {
+            OutlineStyle::Extrude => {
+                unsafe { self.extrude(geometry); }
+                Ok(())
+            }
+            OutlineStyle::Dilate => unsafe { self.dilate(geometry, &saved) },
+        };
+
+        unsafe { saved.restore(); }
+        result
+    }
+
+    unsafe fn extrude<G: Geometry + ?Sized>(&self, geometry: &G) {
+        // mark the object's pixels, without drawing it again
+        gl::Enable(gl::STENCIL_TEST);
+        gl::StencilMask(OUTLINE_STENCIL_BIT);
+        gl::StencilFunc(gl::ALWAYS, OUTLINE_STENCIL_BIT as i32, OUTLINE_STENCIL_BIT);
+        gl::StencilOp(gl::REPLACE, gl::REPLACE, gl::REPLACE);
+        gl::ColorMask(gl::FALSE, gl::FALSE, gl::FALSE, gl::FALSE);
+        self.shader.set_float("thickness", 0.0);
+        geometry.draw_geometry();
+
+        // the grown object, wherever it is not covered by the object
+        gl::ColorMask(gl::TRUE, gl::TRUE, gl::TRUE, gl::TRUE);
+        gl::StencilMask(0x00);
+        gl::StencilFunc(gl::NOTEQUAL, OUTLINE_STENCIL_BIT as i32, OUTLINE_STENCIL_BIT);
+        self.shader.set_float("thickness", self.thickness);
+        self.shader.set_vector4v("color", self.color);
+        geometry.draw_geometry();
+
+        // unmark the object's pixels again, the bit elsewhere is left as it was
+        gl::ColorMask(gl::FALSE, gl::FALSE, gl::FALSE, gl::FALSE);
+        gl::StencilMask(OUTLINE_STENCIL_BIT);
+        gl::StencilFunc(gl::ALWAYS, 0, OUTLINE_STENCIL_BIT);
+        gl::StencilOp(gl::ZERO, gl::ZERO, gl::ZERO);
+        self.shader.set_float("thickness", 0.0);
+        geometry.draw_geometry();
+    }
+
+    unsafe fn dilate<G: Geometry + ?Sized>(&mut self, geometry: &G, saved: &SavedState) -> Result<(), String> {
+        let [x, y, width, height] = saved.viewport;
+        self.mask.resize(width.max(1) as u32, height.max(1) as u32)?;
+
+        // coverage of the object
+        self.mask.bind();
+        gl::ClearBufferfv(gl::COLOR, 0, [0.0f32; 4].as_ptr());
+        gl::Disable(gl::STENCIL_TEST);
+        self.shader.set_float("thickness", 0.0);
+        self.shader.set_vector4v("color", Vector4::new(1.0, 1.0, 1.0, 1.0));
+        geometry.draw_geometry();
+
+        // every uncovered pixel near a covered one
+        gl::BindFramebuffer(gl::FRAMEBUFFER, saved.draw_framebuffer);
+        gl::Viewport(x, y, width, height);
+        self.dilate_shader.use_program();
+        self.dilate_shader.set_int("radius", self.thickness.round() as i32);
+        self.dilate_shader.set_vector4v("color", self.color);
+        self.quad.pass(&self.dilate_shader, &[self.mask.color_texture(0)]);
+        Ok(())
+    }
+}
+
+// everything `Outline::draw` changes
+struct SavedState {
+    capabilities: [(u32, bool); 4],
+    depth_mask: u8,
+    color_mask: [u8; 4],
+    // func, ref, value mask, fail, depth fail and pass op and write mask, for the front and the back faces
+    stencil: [[i32; 7]; 2],
+    program: i32,
+    vertex_array: i32,
+    read_framebuffer: u32,
+    draw_framebuffer: u32,
+    viewport: [i32; 4],
+    active_texture: i32,
+    texture: i32,
+}
+
+const STENCIL_PARAMETERS: [[u32; 7]; 2] = [
+    [
+        gl::STENCIL_FUNC, gl::STENCIL_REF, gl::STENCIL_VALUE_MASK,
+        gl::STENCIL_FAIL, gl::STENCIL_PASS_DEPTH_FAIL, gl::STENCIL_PASS_DEPTH_PASS, gl::STENCIL_WRITEMASK,
+    ],
+    [
+        gl::STENCIL_BACK_FUNC, gl::STENCIL_BACK_REF, gl::STENCIL_BACK_VALUE_MASK,
+        gl::STENCIL_BACK_FAIL, gl::STENCIL_BACK_PASS_DEPTH_FAIL, gl::STENCIL_BACK_PASS_DEPTH_PASS, gl::STENCIL_BACK_WRITEMASK,
+    ],
+];
+
+impl SavedState {
+    unsafe fn capture() -> Self {
+        let get = |parameter: u32| {
+            let mut value = 0;
+            gl::GetIntegerv(parameter, &mut value);
+            value
+        };
+        let mut capabilities = [(gl::DEPTH_TEST, false), (gl::STENCIL_TEST, false), (gl::BLEND, false), (gl::CULL_FACE, false)];
+        for capability in capabilities.iter_mut() {
+            capability.1 = gl::IsEnabled(capability.0) == gl::TRUE;
+        }
+        let mut depth_mask = 0;
+        gl::GetBooleanv(gl::DEPTH_WRITEMASK, &mut depth_mask);
+        let mut color_mask = [0; 4];
+        gl::GetBooleanv(gl::COLOR_WRITEMASK, color_mask.as_mut_ptr());
+        let mut stencil = [[0; 7]; 2];
+        for (face, parameters) in stencil.iter_mut().zip(STENCIL_PARAMETERS.iter()) {
+            for (value, &parameter) in face.iter_mut().zip(parameters.iter()) {
+                *value = get(parameter);
+            }
+        }
+        let mut viewport = [0; 4];
+        gl::GetIntegerv(gl::VIEWPORT, viewport.as_mut_ptr());
+        let active_texture = get(gl::ACTIVE_TEXTURE);
+        gl::ActiveTexture(gl::TEXTURE0);
+        let texture = get(gl::TEXTURE_BINDING_2D);
+        gl::ActiveTexture(active_texture as u32);
+
+        SavedState {
+            capabilities,
+            depth_mask,
+            color_mask,
+            stencil,
+            program: get(gl::CURRENT_PROGRAM),
+            vertex_array: get(gl::VERTEX_ARRAY_BINDING),
+            read_framebuffer: get(gl::READ_FRAMEBUFFER_BINDING) as u32,
+            draw_framebuffer: get(gl::DRAW_FRAMEBUFFER_BINDING) as u32,
+            viewport,
+            active_texture,
+            texture,
+        }
+    }
+
+    unsafe fn restore(&self) {
+        for &(capability, enabled) in self.capabilities.iter() {
+            if enabled {
+                gl::Enable(capability);
+            } else {
+                gl::Disable(capability);
+            }
+        }
+        gl::DepthMask(self.depth_mask);
+        let [r, g, b, a] = self.color_mask;
+        gl::ColorMask(r, g, b, a);
+        for (&face, s) in [gl::FRONT, gl::BACK].iter().zip(self.stencil.iter()) {
+            gl::StencilFuncSeparate(face, s[0] as u32, s[1], s[2] as u32);
+            gl::StencilOpSeparate(face, s[3] as u32, s[4] as u32, s[5] as u32);
+            gl::StencilMaskSeparate(face, s[6] as u32);
+        }
+        gl::UseProgram(self.program as u32);
+        gl::BindVertexArray(self.vertex_array as u32);
+        gl::BindFramebuffer(gl::READ_FRAMEBUFFER, self.read_framebuffer);
+        gl::BindFramebuffer(gl::DRAW_FRAMEBUFFER, self.draw_framebuffer);
+        let [x, y, width, height] = self.viewport;
+        gl::Viewport(x, y, width, height);
+        gl::ActiveTexture(gl::TEXTURE0);
+        gl::BindTexture(gl::TEXTURE_2D, self.texture as u32);
+        gl::ActiveTexture(self.active_texture as u32);
+    }
+}
@@ -0,0 +1,297 @@
+//! A chain of fullscreen effects applied to a rendered frame.
+//!
+//! The scene renders into an offscreen target, then every enabled effect reads the result of the
+//! previous one. Two targets are used in turn (ping-pong), the last effect writes straight into
+//! the frame's framebuffer.
+
+use crate::bloom::Bloom;
+use crate::camera::Camera;
+use crate::framebuffer::{ColorFormat, Framebuffer, FramebufferDesc, FullscreenQuad, FULLSCREEN_VERTEX_SHADER};
+use crate::gamma::GAMMA;
+use crate::hdr::{self, AutoExposure, ToneMapping};
+use crate::input::InputState;
+use crate::scene::{Frame, Scene};
+use crate::shader::Shader;
+
+const SHADER_DIR: &str = "./src/shaders/4_advanced_opengl";
+
+// the kernel weights are uniforms like every other parameter
+const KERNEL_UNIFORMS: [&str; 9] = [
+    "kernel[0]", "kernel[1]", "kernel[2]",
+    "kernel[3]", "kernel[4]", "kernel[5]",
+    "kernel[6]", "kernel[7]", "kernel[8]",
+];
+
+/// One fullscreen pass with its float parameters.
+pub struct Effect {
+    pub name: &'static str,
+    pub enabled: bool,
+    // uniform names and values, set before every pass
+    pub params: Vec<(&'static str, f32)>,
+    // measures the input before every pass, see `with_auto_exposure`
+    pub auto_exposure: Option<AutoExposure>,
+    // blurs the bright parts of the input before every pass, see `with_bloom`
+    pub bloom: Option<Bloom>,
+    shader: Shader,
+}
+
+impl Effect {
+    /// An effect from a fragment shader in the shader directory, disabled until toggled.
+    ///
+    /// Besides its parameters the shader gets `screenTexture` (unit 0), `texelSize` and `time`.
+    pub fn new(name: &'static str, fragment_shader: &str, params: Vec<(&'static str, f32)>) -> Self {
+        let shader = Shader::new(FULLSCREEN_VERTEX_SHADER, &format!("{}/{}", SHADER_DIR, fragment_shader));
+        Effect { name, enabled: false, params, auto_exposure: None, bloom: None, shader }
+    }
+
+    /// Maps HDR colors into 0..1 with `operator` and encodes them with the gamma of the screen, see `hdr`.
+    /// The chain has to store `Rgba16F`, and the effects after this one get LDR colors.
+    pub fn tone_mapping(operator: ToneMapping) -> Self {
+        let shader = Shader::new(FULLSCREEN_VERTEX_SHADER, hdr::TONE_MAPPING_SHADER);
+        let params = vec![("exposure", 1.0), ("operator", operator.index()), ("key", 0.18), ("gamma", GAMMA)];
+        Effect { name: "tone_mapping", enabled: false, params, auto_exposure: None, bloom: None, shader }
+    }
+
+    /// Measures the average luminance of the input before every pass, the shader gets the adapted
+    /// luminance as `luminanceTexture` (unit 1) and `autoExposure` set.
+    pub fn with_auto_exposure(mut self, auto_exposure: AutoExposure) -> Self {
+        self.auto_exposure = Some(auto_exposure);
+        self
+    }
+
+    /// Blurs the bright parts of the input before every pass, the shader gets the glow as `bloomTexture`
+    /// (unit 2), `bloom` set and `bloomIntensity`.
+    pub fn with_bloom(mut self, bloom: Bloom) -> Self {
+        self.bloom = Some(bloom);
+        self
+    }
+
+    /// A 3x3 convolution, the weights are given row by row from the top left.
+    pub fn kernel(name: &'static str, weights: [f32; 9]) -> Self {
+        Effect::new(name, "post_kernel.fs", KERNEL_UNIFORMS.iter().copied().zip(weights.iter().copied()).collect())
+    }
+
+    pub fn inversion() -> Self {
+        Effect::new("inversion", "post_inversion.fs", vec![])
+    }
+
+    pub fn grayscale() -> Self {
+        Effect::new("grayscale", "post_grayscale.fs", vec![])
+    }
+
+    pub fn sharpen() -> Self {
+        Effect::kernel("sharpen", [
+            -1.0, -1.0, -1.0,
+            -1.0,  9.0, -1.0,
+            -1.0, -1.0, -1.0,
+        ])
+    }
+
+    pub fn blur() -> Self {
+        Effect::kernel("blur", [
+            1.0 / 16.0, 2.0 / 16.0, 1.0 / 16.0,
+            2.0 / 16.0, 4.0 / 16.0, 2.0 / 16.0,
+            1.0 / 16.0, 2.0 / 16.0, 1.0 / 16.0,
+        ])
+    }
+
+    pub fn edge_detection() -> Self {
+        Effect::kernel("edge_detection", [
+            1.0,  1.0, 1.0,
+            1.0, -8.0, 1.0,
+            1.0,  1.0, 1.0,
+        ])
+    }
+
+    pub fn vignette() -> Self {
+        Effect::new("vignette", "post_vignette.fs", vec![("radius", 0.5), ("strength", 0.7)])
+    }
+
+    pub fn chromatic_aberration() -> Self {
+        Effect::new("chromatic_aberration", "post_chromatic_aberration.fs", vec![("strength", 0.01)])
+    }
+
+    pub fn film_grain() -> Self {
+        Effect::new("film_grain", "post_film_grain.fs", vec![("amount", 0.08)])
+    }
+
+    pub fn param(&self, name: &str) -> Option<f32> {
+        self.params.iter().find(|(param, _)| *param == name).map(|&(_, value)| value)
+    }
+
+    pub fn set_param(&mut self, name: &str, value: f32) -> Result<(), String> {
+        match self.params.iter_mut().find(|(param, _)| *param == name) {
+            Some(param) => {
+                param.1 = value;
+                Ok(())
+            }
+            None => Err(format!("Effect {} has no parameter {}", self.name, name)),
+        }
+    }
+
+    fn apply(&mut self, quad: &FullscreenQuad, input: u32, frame: &Frame) {
+        let luminance = self.auto_exposure.as_mut().map(|auto_exposure| auto_exposure.measure(quad, input, frame));
+        let glow = self.bloom.as_mut().map(|bloom| bloom.render(quad, input, frame));
+        self.shader.use_program();
+        self.shader.set_int("screenTexture", 0);
+        self.shader.set_vector2("texelSize", 1.0 / frame.width as f32, 1.0 / frame.height as f32);
+        self.shader.set_float("time", frame.time);
+        for &(name, value) in &self.params {
+            self.shader.set_float(name, value);
+        }
+        self.shader.set_int("luminanceTexture", 1);
+        self.shader.set_bool("autoExposure", luminance.is_some());
+        self.shader.set_int("bloomTexture", 2);
+        match self.bloom.as_ref() {
+            Some(bloom) => bloom.apply(&self.shader),
+            None => self.shader.set_bool("bloom", false),
+        }
+        quad.pass(&self.shader, &[input, luminance.unwrap_or(0), glow.unwrap_or(0)]);
+    }
+}
+
+/// Every built-in effect in a sensible order, all disabled.
+pub fn default_effects() -> Vec<Effect> {
+    vec![
+        // first, the others work on LDR colors
+        Effect::tone_mapping(ToneMapping::default()),
+        Effect::inversion(),
+        Effect::grayscale(),
+        Effect::sharpen(),
+        Effect::blur(),
+        Effect::edge_detection(),
+        Effect::vignette(),
+        Effect::chromatic_aberration(),
+        Effect::film_grain(),
+    ]
+}
+
+/// The ordered effects and the render targets they ping-pong between.
+pub struct PostProcess {
+    pub effects: Vec<Effect>,
+    quad: FullscreenQuad,
+    // the scene renders here, with depth and stencil
+    scene_target: Framebuffer,
+    ping_pong: [Framebuffer; 2],
+}
+
+impl PostProcess {
+    /// An empty chain whose targets store `format`, e.g. `Rgba16F` to keep HDR colors between effects.
+    pub fn new(format: ColorFormat, effects: Vec<Effect>) -> Result<Self, String> {
+        let mut scene_desc = FramebufferDesc::color_depth(1, 1);
+        scene_desc.colors = vec![format];
+        let color_desc = FramebufferDesc { width: 1, height: 1, colors: vec![format], depth: None };
+        Ok(PostProcess {
+            effects,
+            quad: FullscreenQuad::new(),
+            scene_target: Framebuffer::new(scene_desc)?,
+            ping_pong: [Framebuffer::new(color_desc.clone())?, Framebuffer::new(color_desc)?],
+        })
+    }
+
+    /// The effect called `name`.
+    pub fn effect_mut(&mut self, name: &str) -> Option<&mut Effect> {
+        self.effects.iter_mut().find(|effect| effect.name == name)
+    }
+
+    /// Turns an effect on or off, returns its new state.
+    pub fn toggle(&mut self, name: &str) -> Option<bool> {
+        self.effect_mut(name).map(|effect| {
+            effect.enabled = !effect.enabled;
+            effect.enabled
+        })
+    }
+
+    /// The actions `effect_1` to `effect_9` toggle the effects in order.
+    pub fn handle_input(&mut self, input: &InputState) {
+        for (index, effect) in self.effects.iter_mut().enumerate().take(9) {
+            if input.pressed(&format!("effect_{}", index + 1)) {
+                effect.enabled = !effect.enabled;
+                println!("{} {}", effect.name, if effect.enabled { "on" } else { "off" });
+            }
+        }
+    }
+
+    /// Binds the offscreen target at the frame's size, returns the frame the scene should render.
+    pub fn begin(&mut self, frame: &Frame) -> Frame {
+        self.scene_target.resize(frame.width, frame.height).expect("Could not resize the post-processing target");
+        self.scene_target.bind();
+        Frame { framebuffer: self.scene_target.id, ..*frame }
+    }
+
+    /// Runs the enabled effects on what was rendered since `begin`, the result goes to `frame.framebuffer`.
+    pub fn end(&mut self, frame: &Frame) {
+        let enabled = self.effects.iter().filter(|effect| effect.enabled).count();
+        if enabled == 0 {
+            self.scene_target.blit_to(frame.framebuffer, frame.width, frame.height, gl::COLOR_BUFFER_BIT, gl::NEAREST);
+            unsafe { gl::BindFramebuffer(gl::FRAMEBUFFER, frame.framebuffer); }
+            return;
+        }
+
+        for target in self.ping_pong.iter_mut() {
+            target.resize(frame.width, frame.height).expect("Could not resize the post-processing target");
+        }
+        let mut input = self.scene_target.color_texture(0);
+        for (index, effect) in self.effects.iter_mut().filter(|effect| effect.enabled).enumerate() {
+            if index + 1 == enabled {
+                unsafe {
+                    gl::BindFramebuffer(gl::FRAMEBUFFER, frame.framebuffer);
+                    gl::Viewport(0, 0, frame.width as i32, frame.height as i32);
+                }
+            } else {
+                self.ping_pong[index % 2].bind();
+            }
+            effect.apply(&self.quad, input, frame);
+            input = self.ping_pong[index % 2].color_texture(0);
+        }
+    }
+}
+
+/// Any scene with a post-processing chain behind it.
+pub struct PostProcessed {
+    pub scene: Box<dyn Scene>,
+    pub chain: PostProcess,
+}
+
+impl PostProcessed {
+    /// Wraps `scene` with all default effects, `enabled` names the ones that start turned on.
+    pub fn new(scene: Box<dyn Scene>, enabled: &[&str]) -> Self {
+        PostProcessed::with_format(scene, ColorFormat::Rgba8, enabled)
+    }
+
+    /// Like `new`, but the scene renders into half floats, for HDR scenes mapped by `tone_mapping`.
+    pub fn hdr(scene: Box<dyn Scene>, enabled: &[&str]) -> Self {
+        PostProcessed::with_format(scene, ColorFormat::Rgba16F, enabled)
+    }
+
+    fn with_format(scene: Box<dyn Scene>, format: ColorFormat, enabled: &[&str]) -> Self {
+        let mut chain = PostProcess::new(format, default_effects()).expect("Could not create the post-processing chain");
+        for name in enabled {
+            if let Some(effect) = chain.effect_mut(name) {
+                effect.enabled = true;
+            }
+        }
+        PostProcessed { scene, chain }
+    }
+}
+
+impl Scene for PostProcessed {
+    fn camera(&mut self) -> Option<&mut Camera> {
+        self.scene.camera()
+    }
+
+    fn handle_input(&mut self, input: &InputState) {
+        self.chain.handle_input(input);
+        self.scene.handle_input(input);
+    }
+
+    fn update(&mut self, frame: &Frame) {
+        self.scene.update(frame);
+    }
+
+    fn render(&mut self, frame: &Frame) {
+        let inner = self.chain.begin(frame);
+        self.scene.render(&inner);
+        self.chain.end(frame);
+    }
+}
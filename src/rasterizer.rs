@@ -0,0 +1,300 @@
+//! A small CPU rasterizer following OpenGL's rules, the reference for tests without a GL driver.
+//!
+//! It draws the same vertex and index data as `mesh::Mesh` with a model-view-projection matrix,
+//! e.g. `projection * camera.calculate_view() * model`. Triangles are clipped in clip space,
+//! attributes are interpolated perspective-correct and fragments are colored by a closure.
+
+use cgmath::{Vector2, Vector3, Vector4, Matrix4};
+use image::{Rgba, RgbaImage};
+
+use crate::mesh::{Mesh, Vertex};
+
+/// Winding of front faces as seen on screen, like `gl::FrontFace`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum FrontFace {
+    Ccw,
+    Cw,
+}
+
+/// Faces that are discarded, like `gl::CullFace`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum CullFace {
+    Front,
+    Back,
+    FrontAndBack,
+}
+
+/// Input of the fragment closure, the software version of the GLSL built-ins and varyings.
+pub struct Fragment {
+    // window coordinates of the pixel, (0, 0) is the bottom left like gl_FragCoord
+    pub x: u32,
+    pub y: u32,
+    // window depth from 0.0 (near) to 1.0 (far)
+    pub depth: f32,
+    pub front_facing: bool,
+    // the vertex attributes interpolated perspective-correct
+    pub vertex: Vertex,
+}
+
+// position, normal, tex coords, tangent and bitangent as flat floats
+const ATTRIBUTES: usize = 14;
+
+#[derive(Clone, Copy)]
+struct ClipVertex {
+    position: Vector4<f32>,
+    attributes: [f32; ATTRIBUTES],
+}
+
+impl ClipVertex {
+    fn new(vertex: &Vertex, transform: &Matrix4<f32>) -> Self {
+        let (p, n, t) = (vertex.position, vertex.normal, vertex.tex_coords);
+        let (tangent, bitangent) = (vertex.tangent, vertex.bitangent);
+        let attributes = [
+            p.x, p.y, p.z, n.x, n.y, n.z, t.x, t.y,
+            tangent.x, tangent.y, tangent.z, bitangent.x, bitangent.y, bitangent.z,
+        ];
+        ClipVertex { position: transform * vertex.position.extend(1.0), attributes }
+    }
+
+    fn lerp(&self, other: &ClipVertex, t: f32) -> Self {
+        let mut attributes = [0.0; ATTRIBUTES];
+        for (i, slot) in attributes.iter_mut().enumerate() {
+            *slot = self.attributes[i] + (other.attributes[i] - self.attributes[i]) * t;
+        }
+        ClipVertex { position: self.position + (other.position - self.position) * t, attributes }
+    }
+}
+
+fn to_vertex(a: &[f32; ATTRIBUTES]) -> Vertex {
+    Vertex {
+        position: Vector3::new(a[0], a[1], a[2]),
+        normal: Vector3::new(a[3], a[4], a[5]),
+        tex_coords: Vector2::new(a[6], a[7]),
+        tangent: Vector3::new(a[8], a[9], a[10]),
+        bitangent: Vector3::new(a[11], a[12], a[13]),
+    }
+}
+
+// distances to the six clip planes, inside is -w <= x, y, z <= w
+fn plane_distance(position: &Vector4<f32>, plane: usize) -> f32 {
+    let w = position.w;
+    match plane {
+        0 => w + position.x,
+        1 => w - position.x,
+        2 => w + position.y,
+        3 => w - position.y,
+        4 => w + position.z,
+        _ => w - position.z,
+    }
+}
+
+// Sutherland-Hodgman against every clip plane, the result is a convex polygon
+fn clip_polygon(triangle: [ClipVertex; 3]) -> Vec<ClipVertex> {
+    let mut polygon = triangle.to_vec();
+    for plane in 0..6 {
+        if polygon.is_empty() {
+            break;
+        }
+        let mut clipped = Vec::with_capacity(polygon.len() + 1);
+        for (i, current) in polygon.iter().enumerate() {
+            let next = &polygon[(i + 1) % polygon.len()];
+            let (d_current, d_next) = (plane_distance(&current.position, plane), plane_distance(&next.position, plane));
+            if d_current >= 0.0 {
+                clipped.push(*current);
+            }
+            if (d_current >= 0.0) != (d_next >= 0.0) {
+                clipped.push(current.lerp(next, d_current / (d_current - d_next)));
+            }
+        }
+        polygon = clipped;
+    }
+    polygon
+}
+
+// a vertex after the perspective divide and viewport transform
+struct WindowVertex {
+    x: f32,
+    y: f32,
+    z: f32,
+    // 1/w, interpolated linearly on screen for perspective-correct attributes
+    inv_w: f32,
+    attributes: [f32; ATTRIBUTES],
+}
+
+// edge function, positive if p lies left of a -> b (window y points up)
+fn edge(a: &WindowVertex, b: &WindowVertex, x: f32, y: f32) -> f32 {
+    (b.x - a.x) * (y - a.y) - (b.y - a.y) * (x - a.x)
+}
+
+// top-left fill rule for counter-clockwise triangles, shared edges are drawn exactly once
+fn is_top_left(a: &WindowVertex, b: &WindowVertex) -> bool {
+    (a.y == b.y && b.x < a.x) || b.y < a.y
+}
+
+/// Color and depth buffer with the fixed function state that matters for triangles.
+pub struct Rasterizer {
+    pub width: u32,
+    pub height: u32,
+    // rows from the bottom up, like the GL default framebuffer
+    pub color: Vec<Vector4<f32>>,
+    pub depth: Vec<f32>,
+    pub front_face: FrontFace,
+    // None disables culling, like gl::Disable(gl::CULL_FACE)
+    pub cull_face: Option<CullFace>,
+    // depth test with gl::LESS
+    pub depth_test: bool,
+    // gl::DepthMask
+    pub depth_write: bool,
+}
+
+impl Rasterizer {
+    /// A black buffer with GL's default state: CCW front faces, no culling and no depth test.
+    pub fn new(width: u32, height: u32) -> Self {
+        let pixels = (width * height) as usize;
+        Rasterizer {
+            width,
+            height,
+            color: vec![Vector4::new(0.0, 0.0, 0.0, 1.0); pixels],
+            depth: vec![1.0; pixels],
+            front_face: FrontFace::Ccw,
+            cull_face: None,
+            depth_test: false,
+            depth_write: true,
+        }
+    }
+
+    pub fn clear_color(&mut self, color: Vector4<f32>) {
+        self.color.iter_mut().for_each(|pixel| *pixel = color);
+    }
+
+    pub fn clear_depth(&mut self) {
+        self.depth.iter_mut().for_each(|depth| *depth = 1.0);
+    }
+
+    /// Color at window coordinates, (0, 0) is the bottom left.
+    pub fn pixel(&self, x: u32, y: u32) -> Vector4<f32> {
+        self.color[(y * self.width + x) as usize]
+    }
+
+    pub fn depth_at(&self, x: u32, y: u32) -> f32 {
+        self.depth[(y * self.width + x) as usize]
+    }
+
+    /// The color buffer with the top row first, comparable to `utils::read_pixels`.
+    pub fn to_image(&self) -> RgbaImage {
+        RgbaImage::from_fn(self.width, self.height, |x, y| {
+            let color = self.pixel(x, self.height - 1 - y);
+            let channel = |value: f32| (value.max(0.0).min(1.0) * 255.0).round() as u8;
+            Rgba([channel(color.x), channel(color.y), channel(color.z), channel(color.w)])
+        })
+    }
+
+    /// Draws a mesh's triangles, see `draw`.
+    pub fn draw_mesh<F>(&mut self, mesh: &Mesh, transform: Matrix4<f32>, fragment_shader: F)
+    where F: FnMut(&Fragment) -> Option<Vector4<f32>> {
+        self.draw(&mesh.vertices, &mesh.indices, transform, fragment_shader);
+    }
+
+    /// Draws indexed triangles like `gl::DrawElements(gl::TRIANGLES, ...)`.
+    ///
+    /// `transform` takes the vertex positions to clip space. The fragment shader returns the
+    /// color of a fragment or `None` to discard it.
+    pub fn draw<F>(&mut self, vertices: &[Vertex], indices: &[u32], transform: Matrix4<f32>, mut fragment_shader: F)
+    where F: FnMut(&Fragment) -> Option<Vector4<f32>> {
+        for triangle in indices.chunks(3).filter(|triangle| triangle.len() == 3) {
+            let corners = [
+                ClipVertex::new(&vertices[triangle[0] as usize], &transform),
+                ClipVertex::new(&vertices[triangle[1] as usize], &transform),
+                ClipVertex::new(&vertices[triangle[2] as usize], &transform),
+            ];
+            let polygon: Vec<WindowVertex> = clip_polygon(corners).iter().map(|v| self.to_window(v)).collect();
+
+            // the clipped polygon is convex, so a fan keeps the winding of the triangle
+            for i in 1..polygon.len().saturating_sub(1) {
+                self.rasterize([&polygon[0], &polygon[i], &polygon[i + 1]], &mut fragment_shader);
+            }
+        }
+    }
+
+    fn to_window(&self, vertex: &ClipVertex) -> WindowVertex {
+        let inv_w = 1.0 / vertex.position.w;
+        WindowVertex {
+            x: (vertex.position.x * inv_w + 1.0) * 0.5 * self.width as f32,
+            y: (vertex.position.y * inv_w + 1.0) * 0.5 * self.height as f32,
+            z: (vertex.position.z * inv_w + 1.0) * 0.5,
+            inv_w,
+            attributes: vertex.attributes,
+        }
+    }
+
+    fn rasterize<F>(&mut self, triangle: [&WindowVertex; 3], fragment_shader: &mut F)
+    where F: FnMut(&Fragment) -> Option<Vector4<f32>> {
+        let area = edge(triangle[0], triangle[1], triangle[2].x, triangle[2].y);
+        if area == 0.0 {
+            return;
+        }
+
+        let counter_clockwise = area > 0.0;
+        let front_facing = counter_clockwise == (self.front_face == FrontFace::Ccw);
+        let culled = match self.cull_face {
+            Some(CullFace::Front) => front_facing,
+            Some(CullFace::Back) => !front_facing,
+            Some(CullFace::FrontAndBack) => true,
+            None => false,
+        };
+        if culled {
+            return;
+        }
+
+        // walk the triangle counter-clockwise, so the inside is where all edge functions are positive
+        let [a, b, c] = if counter_clockwise { triangle } else { [triangle[0], triangle[2], triangle[1]] };
+        let area = area.abs();
+        let edges = [(b, c), (c, a), (a, b)];
+        let top_left = [is_top_left(b, c), is_top_left(c, a), is_top_left(a, b)];
+
+        let min_x = a.x.min(b.x).min(c.x).floor().max(0.0) as u32;
+        let min_y = a.y.min(b.y).min(c.y).floor().max(0.0) as u32;
+        let max_x = (a.x.max(b.x).max(c.x).ceil() as u32).min(self.width);
+        let max_y = (a.y.max(b.y).max(c.y).ceil() as u32).min(self.height);
+
+        for y in min_y..max_y {
+            for x in min_x..max_x {
+                // sample at the pixel center
+                let (px, py) = (x as f32 + 0.5, y as f32 + 0.5);
+                let mut weights = [0.0; 3];
+                let mut inside = true;
+                for (i, &(from, to)) in edges.iter().enumerate() {
+                    let distance = edge(from, to, px, py);
+                    inside &= distance > 0.0 || (distance == 0.0 && top_left[i]);
+                    weights[i] = distance / area;
+                }
+                if !inside {
+                    continue;
+                }
+
+                // depth is linear in window space, attributes are linear in window space after dividing by w
+                let depth = weights[0] * a.z + weights[1] * b.z + weights[2] * c.z;
+                let index = (y * self.width + x) as usize;
+                if self.depth_test && depth >= self.depth[index] {
+                    continue;
+                }
+
+                let perspective = [weights[0] * a.inv_w, weights[1] * b.inv_w, weights[2] * c.inv_w];
+                let sum = perspective[0] + perspective[1] + perspective[2];
+                let mut attributes = [0.0; ATTRIBUTES];
+                for (i, slot) in attributes.iter_mut().enumerate() {
+                    *slot = (perspective[0] * a.attributes[i] + perspective[1] * b.attributes[i] + perspective[2] * c.attributes[i]) / sum;
+                }
+
+                let fragment = Fragment { x, y, depth, front_facing, vertex: to_vertex(&attributes) };
+                if let Some(color) = fragment_shader(&fragment) {
+                    self.color[index] = color;
+                    // like GL, a disabled depth test also disables depth writes
+                    if self.depth_test && self.depth_write {
+                        self.depth[index] = depth;
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,123 @@
+//! Normal and parallax mapping: surface detail from textures instead of triangles.
+//!
+//! A normal map stores a normal per texel relative to the surface, in tangent space: x along the
+//! direction u grows, y along v, z out of the surface. `normal_mapping.vs` builds the matrix into
+//! that space from the vertex tangent, bitangent and normal, and moves the light and camera into it,
+//! so the fragment shader can light with the sampled normals directly.
+//!
+//! Normals alone leave the surface flat at grazing angles. Parallax mapping also shifts the texture
+//! coordinates along the view direction by how deep the height map says the surface is there, so
+//! bumps hide what lies behind them.
+
+use std::os::raw::c_void;
+
+use cgmath::{InnerSpace, Vector3};
+
+use crate::shader::Shader;
+
+pub const NORMAL_MAPPING_VERTEX_SHADER: &str = "./src/shaders/5_advanced_lighting/normal_mapping.vs";
+pub const NORMAL_MAPPING_FRAGMENT_SHADER: &str = "./src/shaders/5_advanced_lighting/normal_mapping.fs";
+
+/// How `normal_mapping.fs` offsets the texture coordinates, read from `uniform int parallaxMode`.
+///
+/// Simple shifts once by the depth at the fragment, which works for shallow bumps seen from the
+/// front. Steep walks the view ray through layers of depth until it is below the surface, which
+/// holds up at grazing angles but shows the layers as steps. Occlusion interpolates between the
+/// last two layers to smooth the steps away.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum ParallaxMode {
+    #[default]
+    Off,
+    Simple,
+    Steep,
+    Occlusion,
+}
+
+impl ParallaxMode {
+    /// Sets `parallaxMode` on `shader`, which has to be in use.
+    pub fn apply(self, shader: &Shader) {
+        shader.set_int("parallaxMode", self as i32);
+    }
+
+    /// The following one, back to `Off` after `Occlusion`, for a key that cycles through them.
+    pub fn next(self) -> Self {
+        match self {
+            ParallaxMode::Off => ParallaxMode::Simple,
+            ParallaxMode::Simple => ParallaxMode::Steep,
+            ParallaxMode::Steep => ParallaxMode::Occlusion,
+            ParallaxMode::Occlusion => ParallaxMode::Off,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            ParallaxMode::Off => "off",
+            ParallaxMode::Simple => "simple",
+            ParallaxMode::Steep => "steep",
+            ParallaxMode::Occlusion => "parallax occlusion",
+        }
+    }
+}
+
+/// Heights from 0 to 1 of `tiles` by `tiles` raised tiles, `size` texels square, 0 in the grout
+/// between them and rising to 1 over `bevel` of a tile's width. Repeats without a seam.
+pub fn tile_heights(size: usize, tiles: usize, bevel: f32) -> Vec<f32> {
+    let mut heights = Vec::with_capacity(size * size);
+    for y in 0..size {
+        for x in 0..size {
+            // distance to the nearest tile edge, in tile widths
+            let edge = |texel: usize| {
+                let position = ((texel as f32 + 0.5) / size as f32 * tiles as f32).fract();
+                position.min(1.0 - position)
+            };
+            let distance = edge(x).min(edge(y));
+            heights.push((distance / bevel).min(1.0));
+        }
+    }
+    heights
+}
+
+/// Tangent space normals of the `size` by `size` heights, as RGB bytes mapping -1..1 to 0..255.
+/// `strength` scales the slopes, the heights wrap around at the edges like a repeating texture.
+pub fn normals_from_heights(heights: &[f32], size: usize, strength: f32) -> Vec<u8> {
+    let height = |x: usize, y: usize| heights[(y % size) * size + x % size];
+    let mut normals = Vec::with_capacity(size * size * 3);
+    for y in 0..size {
+        for x in 0..size {
+            // central differences, per texel
+            let dx = (height(x + 1, y) - height(x + size - 1, y)) / 2.0;
+            let dy = (height(x, y + 1) - height(x, y + size - 1)) / 2.0;
+            let normal = Vector3::new(-dx * strength, -dy * strength, 1.0).normalize();
+            for &component in [normal.x, normal.y, normal.z].iter() {
+                normals.push(((component * 0.5 + 0.5) * 255.0).round() as u8);
+            }
+        }
+    }
+    normals
+}
+
+/// Uploads the heights as a single channel texture and the normals derived from them,
+/// with mipmaps and repeat wrapping. Returns the height map and the normal map.
+pub unsafe fn relief_textures(heights: &[f32], size: usize, strength: f32) -> (u32, u32) {
+    let bytes: Vec<u8> = heights.iter().map(|&height| (height.clamp(0.0, 1.0) * 255.0).round() as u8).collect();
+    let height_map = upload(&bytes, size, gl::RED);
+    let normal_map = upload(&normals_from_heights(heights, size, strength), size, gl::RGB);
+    (height_map, normal_map)
+}
+
+unsafe fn upload(data: &[u8], size: usize, format: u32) -> u32 {
+    let mut texture = 0;
+    gl::GenTextures(1, &mut texture);
+    gl::BindTexture(gl::TEXTURE_2D, texture);
+    // rows of one channel are not 4 byte aligned
+    gl::PixelStorei(gl::UNPACK_ALIGNMENT, 1);
+    gl::TexImage2D(gl::TEXTURE_2D, 0, format as i32, size as i32, size as i32, 0, format, gl::UNSIGNED_BYTE, data.as_ptr() as *const c_void);
+    gl::PixelStorei(gl::UNPACK_ALIGNMENT, 4);
+    gl::GenerateMipmap(gl::TEXTURE_2D);
+    gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_WRAP_S, gl::REPEAT as i32);
+    gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_WRAP_T, gl::REPEAT as i32);
+    gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MIN_FILTER, gl::LINEAR_MIPMAP_LINEAR as i32);
+    gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MAG_FILTER, gl::LINEAR as i32);
+    gl::BindTexture(gl::TEXTURE_2D, 0);
+    texture
+}
@@ -0,0 +1,151 @@
+//! Collects the draws of a frame and runs them in an order that makes transparency work.
+//!
+//! Opaque draws go first, front to back, so the depth test rejects hidden fragments before they
+//! are shaded. Alpha-tested draws, e.g. grass that discards its transparent texels, write depth
+//! like opaque ones and follow them. Blended draws come last and back to front: each one is
+//! blended over everything behind it, which has to be in the framebuffer already. Where
+//! sorting by object is not enough, e.g. for intersecting glass, draws can go into the
+//! order-independent bucket instead, see `oit`.
+
+use cgmath::{InnerSpace, Vector3};
+
+use crate::oit::OitPass;
+
+/// How a draw treats alpha, the queue sets blending and depth writes for each bucket.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Bucket {
+    Opaque,
+    // discards texels below an alpha threshold, everything else is opaque
+    AlphaTested,
+    // blended over what is behind, does not write depth
+    Transparent,
+    // accumulated by an `OitPass` in any order, sorted like `Transparent` without one
+    OrderIndependent,
+}
+
+struct Item<'a> {
+    bucket: Bucket,
+    position: Vector3<f32>,
+    draw: Box<dyn FnOnce() + 'a>,
+}
+
+/// The draws of one frame, run and emptied by `flush`.
+#[derive(Default)]
+pub struct RenderQueue<'a> {
+    items: Vec<Item<'a>>,
+}
+
+impl<'a> RenderQueue<'a> {
+    pub fn new() -> Self {
+        RenderQueue { items: Vec::new() }
+    }
+
+    /// Adds a draw, `position` is the world position its distance to the camera is measured from.
+    ///
+    /// `draw` binds its program, vertex array and textures itself and issues the draw call.
+    pub fn push<F: FnOnce() + 'a>(&mut self, bucket: Bucket, position: Vector3<f32>, draw: F) {
+        self.items.push(Item { bucket, position, draw: Box::new(draw) });
+    }
+
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    /// Indices of the queued draws in the order `flush` runs them, draws at the same distance
+    /// keep the order they were pushed in.
+    pub fn order(&self, camera_position: Vector3<f32>) -> Vec<usize> {
+        let distance = |index: usize| (self.items[index].position - camera_position).magnitude2();
+        let mut order: Vec<usize> = (0..self.items.len()).collect();
+        order.sort_by(|&a, &b| {
+            let (bucket_a, bucket_b) = (self.items[a].bucket, self.items[b].bucket);
+            let by_distance = if bucket_a >= Bucket::Transparent {
+                // back to front
+                distance(b).partial_cmp(&distance(a))
+            } else {
+                // front to back
+                distance(a).partial_cmp(&distance(b))
+            };
+            bucket_a.cmp(&bucket_b).then(by_distance.unwrap_or(std::cmp::Ordering::Equal))
+        });
+        order
+    }
+
+    /// Runs the draws sorted for a camera at `camera_position` and empties the queue.
+    ///
+    /// The depth test is left as it is. Blending and depth writes are set per bucket and
+    /// restored afterwards.
+    pub fn flush(&mut self, camera_position: Vector3<f32>) {
+        self.flush_with(camera_position, None)
+            .expect("Without an OIT pass there are no framebuffers to fail");
+    }
+
+    /// Like `flush`, but the order-independent bucket is accumulated by `oit`.
+    ///
+    /// With a pass all draws go into its targets, the result is copied into the framebuffer
+    /// bound before, which is cleared by the pass with the current clear color.
+    pub fn flush_with(&mut self, camera_position: Vector3<f32>, mut oit: Option<&mut OitPass>) -> Result<(), String> {
+        let order = self.order(camera_position);
+        let mut items: Vec<Option<Item<'a>>> = self.items.drain(..).map(Some).collect();
+
+        unsafe {
+            let blend = gl::IsEnabled(gl::BLEND) == gl::TRUE;
+            let mut depth_mask = 0;
+            gl::GetBooleanv(gl::DEPTH_WRITEMASK, &mut depth_mask);
+            let (mut source, mut destination) = (0, 0);
+            gl::GetIntegerv(gl::BLEND_SRC_RGB, &mut source);
+            gl::GetIntegerv(gl::BLEND_DST_RGB, &mut destination);
+
+            if let Some(oit) = oit.as_mut() {
+                oit.begin_opaque()?;
+            }
+
+            let mut current = None;
+            for index in order {
+                let item = items[index].take().unwrap();
+                if current != Some(item.bucket) {
+                    match (item.bucket, oit.as_ref()) {
+                        (Bucket::OrderIndependent, Some(oit)) => oit.begin_accumulation(),
+                        (bucket, _) => set_bucket_state(bucket),
+                    }
+                    current = Some(item.bucket);
+                }
+                (item.draw)();
+            }
+
+            if let Some(oit) = oit {
+                oit.resolve();
+            }
+
+            if blend {
+                gl::Enable(gl::BLEND);
+            } else {
+                gl::Disable(gl::BLEND);
+            }
+            gl::BlendFunc(source as u32, destination as u32);
+            gl::DepthMask(depth_mask);
+        }
+        Ok(())
+    }
+}
+
+unsafe fn set_bucket_state(bucket: Bucket) {
+    match bucket {
+        Bucket::Opaque | Bucket::AlphaTested => {
+            gl::Disable(gl::BLEND);
+            gl::DepthMask(gl::TRUE);
+        }
+        Bucket::Transparent | Bucket::OrderIndependent => {
+            //how blending works:
+            //result = source color vector * source factor value + destination color vector * destination factor value
+            //with the source alpha as factor, a window with alpha 0.3 keeps 70% of what is behind it
+            gl::Enable(gl::BLEND);
+            gl::BlendFunc(gl::SRC_ALPHA, gl::ONE_MINUS_SRC_ALPHA);
+            // still tested against the opaque depth, but a pane must not hide the panes behind it
+            gl::DepthMask(gl::FALSE);
+        }
+    }
+}
@@ -0,0 +1,326 @@
+//! A description of the fixed-function state a group of draws needs, and a cache that only sends
+//! GL what changed.
+//!
+//! Instead of toggling depth test, blending, culling and the stencil test by hand and switching
+//! them back afterwards, a scene describes the whole state in a `RenderState` and opens a scope
+//! on a `StateCache`: the cache applies only the differences to the current state, and the scope
+//! puts the previous state back when it is dropped.
+//!
+//! ```ignore
+//! let mut cache = StateCache::new();
+//! {
+//!     let _culled = cache.scope(&RenderState { cull: CullState::culling(gl::BACK, gl::CCW), ..RenderState::opaque() });
+//!     // draws with back faces culled
+//! }
+//! // culling is off again
+//! ```
+//!
+//! The cache assumes nobody else changes these states while it is alive. Code that saves and
+//! restores what it changes, like `FullscreenQuad::pass`, is fine, after anything else call `invalidate`.
+
+use std::ops::{Deref, DerefMut};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct DepthState {
+    pub test: bool,
+    pub func: u32,
+    pub write: bool,
+}
+
+impl Default for DepthState {
+    fn default() -> Self {
+        DepthState { test: false, func: gl::LESS, write: true }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct BlendState {
+    pub enabled: bool,
+    pub source_rgb: u32,
+    pub destination_rgb: u32,
+    pub source_alpha: u32,
+    pub destination_alpha: u32,
+    // for rgb and alpha alike
+    pub equation: u32,
+}
+
+impl BlendState {
+    /// Blending with the same factors for color and alpha.
+    pub fn func(source: u32, destination: u32) -> Self {
+        BlendState {
+            enabled: true,
+            source_rgb: source,
+            destination_rgb: destination,
+            source_alpha: source,
+            destination_alpha: destination,
+            equation: gl::FUNC_ADD,
+        }
+    }
+
+    /// The usual blending of transparent surfaces over what is behind them.
+    pub fn alpha() -> Self {
+        BlendState::func(gl::SRC_ALPHA, gl::ONE_MINUS_SRC_ALPHA)
+    }
+}
+
+impl Default for BlendState {
+    fn default() -> Self {
+        BlendState {
+            enabled: false,
+            source_rgb: gl::ONE,
+            destination_rgb: gl::ZERO,
+            source_alpha: gl::ONE,
+            destination_alpha: gl::ZERO,
+            equation: gl::FUNC_ADD,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CullState {
+    pub enabled: bool,
+    // gl::BACK, gl::FRONT or gl::FRONT_AND_BACK
+    pub face: u32,
+    // winding of front faces, gl::CCW or gl::CW
+    pub front_face: u32,
+}
+
+impl CullState {
+    pub fn culling(face: u32, front_face: u32) -> Self {
+        CullState { enabled: true, face, front_face }
+    }
+}
+
+impl Default for CullState {
+    fn default() -> Self {
+        CullState { enabled: false, face: gl::BACK, front_face: gl::CCW }
+    }
+}
+
+/// Stencil test and operations, for front and back faces alike.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct StencilState {
+    pub test: bool,
+    pub func: u32,
+    pub reference: i32,
+    pub value_mask: u32,
+    // stencil test fails, stencil passes and depth test fails, both pass
+    pub fail: u32,
+    pub depth_fail: u32,
+    pub pass: u32,
+    pub write_mask: u32,
+}
+
+impl Default for StencilState {
+    fn default() -> Self {
+        StencilState {
+            test: false,
+            func: gl::ALWAYS,
+            reference: 0,
+            value_mask: 0xFF,
+            fail: gl::KEEP,
+            depth_fail: gl::KEEP,
+            pass: gl::KEEP,
+            write_mask: 0xFF,
+        }
+    }
+}
+
+/// Everything a draw needs besides program, buffers and textures. The default is GL's initial state.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct RenderState {
+    pub depth: DepthState,
+    pub blend: BlendState,
+    pub cull: CullState,
+    pub stencil: StencilState,
+    pub color_mask: [bool; 4],
+    // for front and back faces, gl::FILL, gl::LINE or gl::POINT
+    pub polygon_mode: u32,
+}
+
+impl Default for RenderState {
+    fn default() -> Self {
+        RenderState {
+            depth: DepthState::default(),
+            blend: BlendState::default(),
+            cull: CullState::default(),
+            stencil: StencilState::default(),
+            color_mask: [true; 4],
+            polygon_mode: gl::FILL,
+        }
+    }
+}
+
+impl RenderState {
+    /// Depth tested and written, nothing else.
+    pub fn opaque() -> Self {
+        RenderState { depth: DepthState { test: true, ..DepthState::default() }, ..RenderState::default() }
+    }
+
+    /// Depth tested but not written, blended by alpha.
+    pub fn transparent() -> Self {
+        RenderState {
+            depth: DepthState { test: true, write: false, ..DepthState::default() },
+            blend: BlendState::alpha(),
+            ..RenderState::default()
+        }
+    }
+
+    /// Reads the state GL is in, the stencil state of the front faces stands for both.
+    pub fn query() -> Self {
+        unsafe {
+            let get = |parameter: u32| {
+                let mut value = 0;
+                gl::GetIntegerv(parameter, &mut value);
+                value
+            };
+            let enabled = |capability: u32| gl::IsEnabled(capability) == gl::TRUE;
+            let mut depth_write = 0;
+            gl::GetBooleanv(gl::DEPTH_WRITEMASK, &mut depth_write);
+            let mut color_mask = [0; 4];
+            gl::GetBooleanv(gl::COLOR_WRITEMASK, color_mask.as_mut_ptr());
+            let mut polygon_mode = [0; 2];
+            gl::GetIntegerv(gl::POLYGON_MODE, polygon_mode.as_mut_ptr());
+
+            RenderState {
+                depth: DepthState { test: enabled(gl::DEPTH_TEST), func: get(gl::DEPTH_FUNC) as u32, write: depth_write == gl::TRUE },
+                blend: BlendState {
+                    enabled: enabled(gl::BLEND),
+                    source_rgb: get(gl::BLEND_SRC_RGB) as u32,
+                    destination_rgb: get(gl::BLEND_DST_RGB) as u32,
+                    source_alpha: get(gl::BLEND_SRC_ALPHA) as u32,
+                    destination_alpha: get(gl::BLEND_DST_ALPHA) as u32,
+                    equation: get(gl::BLEND_EQUATION_RGB) as u32,
+                },
+                cull: CullState { enabled: enabled(gl::CULL_FACE), face: get(gl::CULL_FACE_MODE) as u32, front_face: get(gl::FRONT_FACE) as u32 },
+                stencil: StencilState {
+                    test: enabled(gl::STENCIL_TEST),
+                    func: get(gl::STENCIL_FUNC) as u32,
+                    reference: get(gl::STENCIL_REF),
+                    value_mask: get(gl::STENCIL_VALUE_MASK) as u32,
+                    fail: get(gl::STENCIL_FAIL) as u32,
+                    depth_fail: get(gl::STENCIL_PASS_DEPTH_FAIL) as u32,
+                    pass: get(gl::STENCIL_PASS_DEPTH_PASS) as u32,
+                    write_mask: get(gl::STENCIL_WRITEMASK) as u32,
+                },
+                color_mask: [color_mask[0] == gl::TRUE, color_mask[1] == gl::TRUE, color_mask[2] == gl::TRUE, color_mask[3] == gl::TRUE],
+                polygon_mode: polygon_mode[0] as u32,
+            }
+        }
+    }
+}
+
+/// The state GL is in, as far as `RenderState` describes it.
+pub struct StateCache {
+    current: RenderState,
+}
+
+impl StateCache {
+    /// Starts from the state GL is in right now.
+    pub fn new() -> Self {
+        StateCache { current: RenderState::query() }
+    }
+
+    pub fn current(&self) -> &RenderState {
+        &self.current
+    }
+
+    /// Reads the state from GL again, after code that changed it behind the cache's back.
+    pub fn invalidate(&mut self) {
+        self.current = RenderState::query();
+    }
+
+    /// Switches to `state`, returns the number of GL calls that took, 0 if nothing changed.
+    pub fn apply(&mut self, state: &RenderState) -> usize {
+        let (current, mut calls) = (self.current, 0);
+        let mut call = |changed: bool, set: &dyn Fn()| {
+            if changed {
+                set();
+                calls += 1;
+            }
+        };
+
+        unsafe {
+            call(current.depth.test != state.depth.test, &|| capability(gl::DEPTH_TEST, state.depth.test));
+            call(current.depth.func != state.depth.func, &|| gl::DepthFunc(state.depth.func));
+            call(current.depth.write != state.depth.write, &|| gl::DepthMask(state.depth.write as u8));
+
+            let (blend, old_blend) = (&state.blend, &current.blend);
+            call(old_blend.enabled != blend.enabled, &|| capability(gl::BLEND, blend.enabled));
+            let factors = |b: &BlendState| (b.source_rgb, b.destination_rgb, b.source_alpha, b.destination_alpha);
+            call(factors(old_blend) != factors(blend), &|| {
+                gl::BlendFuncSeparate(blend.source_rgb, blend.destination_rgb, blend.source_alpha, blend.destination_alpha)
+            });
+            call(old_blend.equation != blend.equation, &|| gl::BlendEquation(blend.equation));
+
+            call(current.cull.enabled != state.cull.enabled, &|| capability(gl::CULL_FACE, state.cull.enabled));
+            call(current.cull.face != state.cull.face, &|| gl::CullFace(state.cull.face));
+            call(current.cull.front_face != state.cull.front_face, &|| gl::FrontFace(state.cull.front_face));
+
+            let (stencil, old_stencil) = (&state.stencil, &current.stencil);
+            call(old_stencil.test != stencil.test, &|| capability(gl::STENCIL_TEST, stencil.test));
+            let func = |s: &StencilState| (s.func, s.reference, s.value_mask);
+            call(func(old_stencil) != func(stencil), &|| gl::StencilFunc(stencil.func, stencil.reference, stencil.value_mask));
+            let op = |s: &StencilState| (s.fail, s.depth_fail, s.pass);
+            call(op(old_stencil) != op(stencil), &|| gl::StencilOp(stencil.fail, stencil.depth_fail, stencil.pass));
+            call(old_stencil.write_mask != stencil.write_mask, &|| gl::StencilMask(stencil.write_mask));
+
+            let [r, g, b, a] = state.color_mask;
+            call(current.color_mask != state.color_mask, &|| gl::ColorMask(r as u8, g as u8, b as u8, a as u8));
+            call(current.polygon_mode != state.polygon_mode, &|| gl::PolygonMode(gl::FRONT_AND_BACK, state.polygon_mode));
+        }
+
+        self.current = *state;
+        calls
+    }
+
+    /// Applies `state` until the returned scope is dropped, then goes back to the state before.
+    ///
+    /// Scopes nest, a scope can open another one on the same cache.
+    pub fn scope(&mut self, state: &RenderState) -> StateScope<'_> {
+        let previous = self.current;
+        self.apply(state);
+        StateScope { cache: self, previous }
+    }
+}
+
+impl Default for StateCache {
+    fn default() -> Self {
+        StateCache::new()
+    }
+}
+
+unsafe fn capability(capability: u32, enabled: bool) {
+    if enabled {
+        gl::Enable(capability);
+    } else {
+        gl::Disable(capability);
+    }
+}
+
+/// A state applied by `StateCache::scope`, restores the previous one when dropped.
+pub struct StateScope<'a> {
+    cache: &'a mut StateCache,
+    previous: RenderState,
+}
+
+impl<'a> Deref for StateScope<'a> {
+    type Target = StateCache;
+
+    fn deref(&self) -> &StateCache {
+        self.cache
+    }
+}
+
+impl<'a> DerefMut for StateScope<'a> {
+    fn deref_mut(&mut self) -> &mut StateCache {
+        self.cache
+    }
+}
+
+impl<'a> Drop for StateScope<'a> {
+    fn drop(&mut self) {
+        let previous = self.previous;
+        self.cache.apply(&previous);
+    }
+}
@@ -0,0 +1,314 @@
+use glfw::Context;
+use rand::{rngs::StdRng, SeedableRng};
+
+use std::path::Path;
+
+use crate::camera::Camera;
+use crate::capture::{self, Recorder};
+use crate::framebuffer::ColorFormat;
+use crate::postprocess::{self, PostProcess};
+use crate::input::InputState;
+use crate::utils;
+
+// every scene draws its random numbers from this seed, so headless frames are reproducible
+pub const RNG_SEED: u64 = 42;
+
+// simulated time step of recordings taken from a window, independent of the real frame rate
+pub const RECORD_DELTA_TIME: f32 = 1.0 / 30.0;
+
+/// Timing and size of the frame that is being rendered.
+#[derive(Clone, Copy, Debug)]
+pub struct Frame {
+    pub width: u32,
+    pub height: u32,
+    // seconds since the scene started
+    pub time: f32,
+    pub delta_time: f32,
+    // the framebuffer the scene renders into, 0 for the window; offscreen passes bind it again at the end
+    pub framebuffer: u32,
+}
+
+impl Frame {
+    pub fn aspect_ratio(&self) -> f32 {
+        self.width as f32 / self.height as f32
+    }
+}
+
+/// An example that can be rendered by any runner, windowed or headless.
+///
+/// Scenes create their GL objects in their constructor, so they have to be built while a context is current.
+pub trait Scene {
+    /// The camera driven by mouse and keyboard in windowed runs.
+    fn camera(&mut self) -> Option<&mut Camera> {
+        None
+    }
+
+    /// Reacts to actions in windowed runs, e.g. to toggle options. Called before `update`.
+    fn handle_input(&mut self, _input: &InputState) {}
+
+    /// Animates the scene, only the frame's time may be used to keep headless runs deterministic.
+    fn update(&mut self, _frame: &Frame) {}
+
+    /// Renders into `frame.framebuffer`, which is bound with a viewport of the frame's size.
+    fn render(&mut self, frame: &Frame);
+}
+
+/// Random number generator for scene setup, seeded with `RNG_SEED`.
+pub fn seeded_rng() -> StdRng {
+    StdRng::seed_from_u64(RNG_SEED)
+}
+
+/// What a `Controller` wants the window to do after looking at this frame's input.
+pub enum Control {
+    // the input goes on to the camera and the default actions
+    Pass,
+    // the controller used the input itself, e.g. for an open menu
+    Capture,
+    // replace the scene, it is built after the GL state was reset
+    Switch(fn() -> Box<dyn Scene>),
+}
+
+/// Decides which scene a window shows, asked every frame before the camera moves.
+pub trait Controller {
+    fn title(&self) -> String;
+
+    fn control(&mut self, input: &InputState) -> Control;
+}
+
+// a window that always shows the same scene
+struct Fixed(String);
+
+impl Controller for Fixed {
+    fn title(&self) -> String {
+        self.0.clone()
+    }
+
+    fn control(&mut self, _input: &InputState) -> Control {
+        Control::Pass
+    }
+}
+
+/// Puts the GL state a scene may change back to GL's defaults, so the next scene starts clean.
+pub unsafe fn reset_gl_state() {
+    gl::Disable(gl::DEPTH_TEST);
+    gl::DepthFunc(gl::LESS);
+    gl::DepthMask(gl::TRUE);
+    gl::ClearDepth(1.0);
+
+    gl::Disable(gl::STENCIL_TEST);
+    gl::StencilFunc(gl::ALWAYS, 0, 0xFF);
+    gl::StencilOp(gl::KEEP, gl::KEEP, gl::KEEP);
+    gl::StencilMask(0xFF);
+    gl::ClearStencil(0);
+
+    gl::Disable(gl::BLEND);
+    gl::BlendFunc(gl::ONE, gl::ZERO);
+    gl::BlendEquation(gl::FUNC_ADD);
+    gl::BlendColor(0.0, 0.0, 0.0, 0.0);
+
+    gl::Disable(gl::CULL_FACE);
+    gl::CullFace(gl::BACK);
+    gl::FrontFace(gl::CCW);
+
+    gl::Disable(gl::SCISSOR_TEST);
+    gl::ColorMask(gl::TRUE, gl::TRUE, gl::TRUE, gl::TRUE);
+    gl::PolygonMode(gl::FRONT_AND_BACK, gl::FILL);
+    gl::ClearColor(0.0, 0.0, 0.0, 0.0);
+    gl::Disable(gl::TEXTURE_CUBE_MAP_SEAMLESS);
+    gl::Disable(gl::FRAMEBUFFER_SRGB);
+
+    // bindings, the objects themselves belong to the old scene
+    gl::UseProgram(0);
+    gl::BindVertexArray(0);
+    gl::BindBuffer(gl::ARRAY_BUFFER, 0);
+    gl::BindFramebuffer(gl::FRAMEBUFFER, 0);
+    let mut units = 0;
+    gl::GetIntegerv(gl::MAX_COMBINED_TEXTURE_IMAGE_UNITS, &mut units);
+    for unit in 0..units.min(32) as u32 {
+        gl::ActiveTexture(gl::TEXTURE0 + unit);
+        gl::BindTexture(gl::TEXTURE_2D, 0);
+        gl::BindTexture(gl::TEXTURE_CUBE_MAP, 0);
+    }
+    gl::ActiveTexture(gl::TEXTURE0);
+}
+
+/// Opens a window and runs the scene until the window is closed.
+pub fn run_windowed<F>(title: &str, width: u32, height: u32, build: F)
+where F: FnOnce() -> Box<dyn Scene> {
+    run_controlled(width, height, build, &mut Fixed(title.to_string()));
+}
+
+/// Like `run_windowed`, but the controller may switch to other scenes in the same window.
+pub fn run_controlled<F>(width: u32, height: u32, build: F, controller: &mut dyn Controller)
+where F: FnOnce() -> Box<dyn Scene> {
+    // init
+    let mut glfw = utils::initialize_glfw();
+    let mut title = controller.title();
+    let (mut window, events) = utils::create_window(&mut glfw, width, height, &title);
+
+    // gl: load all OpenGL function pointers
+    gl::load_with(|symbol| window.get_proc_address(symbol) as *const _);
+
+    let mut scene = build();
+    let mut input = InputState::from_config();
+    // scenes without a camera still get the quit and toggle actions
+    let mut fallback_camera = utils::default_camera();
+
+    let mut last_frame = glfw.get_time() as f32;
+    let mut time = 0.0;
+    let mut recorder: Option<Recorder> = None;
+    // the post-processing chain wrapped around any scene with the `postprocess` action, created
+    // on first use and kept while it is off, so the effect toggles survive
+    let mut post_process: Option<PostProcess> = None;
+    let mut post_processing = false;
+
+    while !window.should_close() {
+        let current_frame = glfw.get_time() as f32;
+        // while recording the scene advances by a fixed step, however long a frame takes
+        let delta_time = recorder.as_ref().map_or(current_frame - last_frame, Recorder::delta_time);
+        last_frame = current_frame;
+        time += delta_time;
+
+        let (width, height) = window.get_framebuffer_size();
+        let mut frame = Frame {
+            width: width.max(1) as u32,
+            height: height.max(1) as u32,
+            time,
+            delta_time,
+            framebuffer: 0,
+        };
+
+        // processing events here
+        input.poll_gamepad(&glfw);
+        utils::process_events_with_input(&events, &mut input, scene.camera().unwrap_or(&mut fallback_camera));
+        match controller.control(&input) {
+            Control::Pass => {
+                let camera = scene.camera().unwrap_or(&mut fallback_camera);
+                utils::process_input(&mut window, &input, frame.delta_time, camera);
+                scene.handle_input(&input);
+                if input.pressed("postprocess") {
+                    if post_process.is_none() {
+                        post_process = start_post_process();
+                    }
+                    post_processing = !post_processing && post_process.is_some();
+                    println!("Post-processing {}", if post_processing { "on" } else { "off" });
+                }
+                if let Some(chain) = post_process.as_mut().filter(|_| post_processing) {
+                    chain.handle_input(&input);
+                }
+            }
+            Control::Capture => (),
+            Control::Switch(create) => {
+                if let Some(recording) = recorder.take() {
+                    finish_recording(recording);
+                }
+                // the old scene goes first, the new one may set up state in its constructor
+                drop(scene);
+                unsafe { reset_gl_state(); }
+                scene = create();
+                fallback_camera = utils::default_camera();
+                // the new camera has to follow the cursor mode of the window
+                let captured = window.get_cursor_mode() == glfw::CursorMode::Disabled;
+                scene.camera().unwrap_or(&mut fallback_camera).set_cursor_captured(captured);
+                time = 0.0;
+                frame.time = 0.0;
+            }
+        }
+        if controller.title() != title {
+            title = controller.title();
+            window.set_title(&title);
+        }
+
+        unsafe {
+            gl::BindFramebuffer(gl::FRAMEBUFFER, 0);
+            gl::Viewport(0, 0, frame.width as i32, frame.height as i32);
+        }
+        scene.update(&frame);
+        match post_process.as_mut().filter(|_| post_processing) {
+            Some(chain) => {
+                let inner = chain.begin(&frame);
+                scene.render(&inner);
+                chain.end(&frame);
+            }
+            None => scene.render(&frame),
+        }
+
+        // capture before the swap, afterwards the back buffer is undefined
+        if input.pressed("screenshot") {
+            let image = unsafe { capture::read_framebuffer(0, frame.width, frame.height) };
+            let path = capture::next_free_path(Path::new(capture::CAPTURE_DIR), &title, "png");
+            match capture::save_png(&image, &path) {
+                Ok(()) => println!("Saved screenshot {}", path.display()),
+                Err(e) => println!("{}", e),
+            }
+        }
+        if input.pressed("record_gif") || input.pressed("record_frames") {
+            recorder = match recorder.take() {
+                Some(recording) => {
+                    finish_recording(recording);
+                    None
+                }
+                None => start_recording(&title, &frame, input.pressed("record_gif")),
+            };
+        }
+        if let Some(mut recording) = recorder.take() {
+            let image = unsafe { capture::read_framebuffer(0, frame.width, frame.height) };
+            match recording.add_frame(&image) {
+                Ok(()) => recorder = Some(recording),
+                // e.g. the window was resized
+                Err(e) => {
+                    println!("{} Recording stopped.", e);
+                    finish_recording(recording);
+                }
+            }
+        }
+
+        //Swap front and back buffers
+        window.swap_buffers();
+        glfw.poll_events();
+    }
+}
+
+fn start_post_process() -> Option<PostProcess> {
+    // half floats, so tone mapping has the HDR colors to work with
+    match PostProcess::new(ColorFormat::Rgba16F, postprocess::default_effects()) {
+        Ok(chain) => {
+            println!("The number keys toggle the post-processing effects:");
+            for (index, effect) in chain.effects.iter().enumerate() {
+                println!("  {} {}", index + 1, effect.name);
+            }
+            Some(chain)
+        }
+        Err(e) => {
+            println!("{}", e);
+            None
+        }
+    }
+}
+
+fn finish_recording(recording: Recorder) {
+    let frames = recording.frame_count();
+    match recording.finish() {
+        Ok(path) => println!("Recorded {} frames to {}", frames, path.display()),
+        Err(e) => println!("{}", e),
+    }
+}
+
+fn start_recording(title: &str, frame: &Frame, gif: bool) -> Option<Recorder> {
+    let dir = Path::new(capture::CAPTURE_DIR);
+    let recording = if gif {
+        Recorder::gif(&capture::next_free_path(dir, title, "gif"), frame.width, frame.height, RECORD_DELTA_TIME)
+    } else {
+        Recorder::frame_sequence(&capture::next_free_path(dir, title, ""), frame.width, frame.height, RECORD_DELTA_TIME)
+    };
+    match recording {
+        Ok(recording) => {
+            println!("Recording at a fixed {:.3}s time step, press the key again to stop", RECORD_DELTA_TIME);
+            Some(recording)
+        }
+        Err(e) => {
+            println!("{}", e);
+            None
+        }
+    }
+}
@@ -0,0 +1,89 @@
+use cgmath::{perspective, Deg, EuclideanSpace, Matrix4, Point3, SquareMatrix, Vector3};
+
+use crate::camera::Camera;
+use crate::model;
+use crate::scene::{Frame, Scene};
+use crate::scenes::shapes;
+use crate::shader::Shader;
+use crate::shading::LightingModel;
+use crate::utils;
+
+pub const LIGHT_POSITION: [f32; 3] = [0.0, 0.0, 0.0];
+
+/// Chapter 5: a floor lit by one light, with Phong highlights on the left and Blinn-Phong on the right.
+pub struct BlinnPhong {
+    camera: Camera,
+    floor_shader: Shader,
+    floor_vao: shapes::VertexArray,
+    floor_texture: u32,
+}
+
+impl BlinnPhong {
+    pub fn new() -> Self {
+        let floor_shader = super::floor_shader();
+        floor_shader.set_int("lightCount", 1);
+        floor_shader.set_vector3v("lightPositions[0]", Vector3::from(LIGHT_POSITION));
+        floor_shader.set_vector3v("lightColors[0]", Vector3::new(1.0, 1.0, 1.0));
+        // a low exponent, Phong's highlight gets cut off where the angle passes 90 degrees
+        floor_shader.set_float("shininess", 1.0);
+        floor_shader.set_float("attenuationPower", 0.0);
+
+        let (floor_vao, floor_texture) = unsafe {
+            (
+                shapes::create_vao(&shapes::LIT_PLANE_VERTICES, &[3, 3, 2]),
+                model::texture_from_file("./resources/textures/metal.png"),
+            )
+        };
+
+        BlinnPhong {
+            camera: utils::camera_at(Point3::new(0.0, 0.0, 3.0)),
+            floor_shader,
+            floor_vao,
+            floor_texture,
+        }
+    }
+}
+
+impl Scene for BlinnPhong {
+    fn camera(&mut self) -> Option<&mut Camera> {
+        Some(&mut self.camera)
+    }
+
+    fn render(&mut self, frame: &Frame) {
+        //each half shows the whole floor
+        let projection = perspective(Deg(45.0), frame.aspect_ratio() / 2.0, 0.1, 100.0);
+        let view = self.camera.calculate_view();
+
+        unsafe {
+            gl::Enable(gl::DEPTH_TEST);
+
+            gl::ClearColor(0.1, 0.1, 0.1, 1.0);
+            gl::Clear(gl::COLOR_BUFFER_BIT | gl::DEPTH_BUFFER_BIT);
+
+            gl::ActiveTexture(gl::TEXTURE0);
+            gl::BindTexture(gl::TEXTURE_2D, self.floor_texture);
+            gl::BindVertexArray(self.floor_vao.id);
+
+            self.floor_shader.use_program();
+            self.floor_shader.set_mat4("projection", projection);
+            self.floor_shader.set_mat4("view", view);
+            self.floor_shader.set_mat4("model", Matrix4::identity());
+            self.floor_shader.set_vector3v("cameraPosition", self.camera.position.to_vec());
+
+            for (half, &lighting_model) in [LightingModel::Phong, LightingModel::BlinnPhong].iter().enumerate() {
+                super::half_viewport(frame, half as u32);
+                lighting_model.apply(&self.floor_shader);
+                gl::DrawArrays(gl::TRIANGLES, 0, 6);
+            }
+
+            gl::Viewport(0, 0, frame.width as i32, frame.height as i32);
+            gl::BindVertexArray(0);
+        }
+    }
+}
+
+impl Drop for BlinnPhong {
+    fn drop(&mut self) {
+        unsafe { shapes::delete_textures(&[self.floor_texture]); }
+    }
+}
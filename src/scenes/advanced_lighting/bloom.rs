@@ -0,0 +1,212 @@
+use cgmath::{perspective, Deg, EuclideanSpace, InnerSpace, Matrix4, Point3, SquareMatrix, Vector3};
+
+use crate::bloom::{self, BloomSettings};
+use crate::camera::Camera;
+use crate::framebuffer::ColorFormat;
+use crate::hdr::ToneMapping;
+use crate::input::InputState;
+use crate::mesh::{Geometry, Mesh};
+use crate::model::{self, ColorSpace};
+use crate::postprocess::{Effect, PostProcess};
+use crate::scene::{Frame, Scene};
+use crate::scenes::shapes;
+use crate::shader::Shader;
+use crate::shading::LightingModel;
+use crate::utils;
+
+// four lights far brighter than white, in their own colors
+pub const LIGHT_POSITIONS: [[f32; 3]; 4] = [
+    [ 0.0, 0.5,  1.5],
+    [-4.0, 0.5, -3.0],
+    [ 3.0, 0.5,  1.0],
+    [-0.8, 2.4, -1.0]
+];
+
+pub const LIGHT_COLORS: [[f32; 3]; 4] = [
+    [5.0, 5.0, 5.0],
+    [10.0, 0.0, 0.0],
+    [0.0, 0.0, 15.0],
+    [0.0, 5.0, 0.0]
+];
+
+// the matrix code on the glowing crate
+const EMISSION_STRENGTH: f32 = 4.0;
+
+// bloom_intensity_up and bloom_intensity_down multiply or divide by this
+const INTENSITY_STEP: f32 = 1.25;
+
+/// Chapter 5: crates among colored lights, the lights and the glowing code on one crate bleed into
+/// their surroundings.
+///
+/// The scene renders into half floats, and the `tone_mapping` effect adds the `Bloom` of everything
+/// brighter than white before mapping the frame to the screen.
+pub struct Bloom {
+    camera: Camera,
+    shader: Shader,
+    emission_shader: Shader,
+    light_source_shader: Shader,
+    floor: Mesh,
+    cube: Mesh,
+    // container diffuse and specular map, the emission map and the floor
+    textures: [u32; 4],
+    chain: PostProcess,
+    // the bloom while toggle_bloom has it off
+    parked_bloom: Option<bloom::Bloom>,
+}
+
+impl Bloom {
+    pub fn new() -> Self {
+        let shader = super::floor_shader();
+        shader.set_int("lightCount", LIGHT_POSITIONS.len() as i32);
+        for (index, (position, color)) in LIGHT_POSITIONS.iter().zip(LIGHT_COLORS.iter()).enumerate() {
+            shader.set_vector3v(&format!("lightPositions[{}]", index), Vector3::from(*position));
+            shader.set_vector3v(&format!("lightColors[{}]", index), Vector3::from(*color));
+        }
+        shader.set_float("shininess", 16.0);
+        shader.set_float("attenuationPower", 2.0);
+        LightingModel::BlinnPhong.apply(&shader);
+
+        //the glowing crate, lit by the white light only
+        let emission_shader = Shader::new(
+            "./src/shaders/2_lighting/lighting_maps.vs",
+            "./src/shaders/2_lighting/emission_map.fs"
+        );
+        emission_shader.use_program();
+        emission_shader.set_int("material.diffuseTex", 0);
+        emission_shader.set_int("material.specularTex", 1);
+        emission_shader.set_int("material.emissionTex", 2);
+        emission_shader.set_float("material.shininess", 64.0);
+        emission_shader.set_float("emissionStrength", EMISSION_STRENGTH);
+        emission_shader.set_vector3v("light.position", Vector3::from(LIGHT_POSITIONS[0]));
+        emission_shader.set_vector3v("light.ambient", Vector3::new(0.05, 0.05, 0.05));
+        emission_shader.set_vector3v("light.diffuse", Vector3::new(1.0, 1.0, 1.0));
+        emission_shader.set_vector3v("light.specular", Vector3::new(1.0, 1.0, 1.0));
+
+        // color maps decoded to linear values, the lighting is linear up to the tone mapping
+        let textures = unsafe {
+            [
+                model::texture_from_file_with("./resources/textures/container2.png", ColorSpace::Srgb),
+                model::texture_from_file("./resources/textures/container2_specular.png"),
+                model::texture_from_file_with("./resources/textures/matrix.jpg", ColorSpace::Srgb),
+                model::texture_from_file_with("./resources/textures/metal.png", ColorSpace::Srgb),
+            ]
+        };
+
+        let bloom = bloom::Bloom::new(BloomSettings::default()).expect("Could not create the bloom levels");
+        let mut tone_mapping = Effect::tone_mapping(ToneMapping::default()).with_bloom(bloom);
+        tone_mapping.enabled = true;
+
+        Bloom {
+            camera: utils::camera_at(Point3::new(0.0, 1.5, 8.0)),
+            shader,
+            emission_shader,
+            light_source_shader: crate::scenes::lighting::light_source_shader(),
+            floor: shapes::lit_plane_mesh(),
+            cube: shapes::lit_cube_mesh(),
+            textures,
+            chain: PostProcess::new(ColorFormat::Rgba16F, vec![tone_mapping]).expect("Could not create the HDR target"),
+            parked_bloom: None,
+        }
+    }
+
+    // the crates around the glowing one
+    fn crate_models() -> Vec<Matrix4<f32>> {
+        let tilted = Vector3::new(1.0, 0.0, 1.0).normalize();
+        vec![
+            Matrix4::from_translation(Vector3::new(2.0, 0.0, -0.5)),
+            Matrix4::from_translation(Vector3::new(-1.8, 0.2, 1.2)) * Matrix4::from_axis_angle(tilted, Deg(60.0)),
+            Matrix4::from_translation(Vector3::new(0.5, 2.2, -2.0)) * Matrix4::from_axis_angle(tilted, Deg(23.0)),
+            Matrix4::from_translation(Vector3::new(-2.5, 0.0, -2.0)) * Matrix4::from_angle_y(Deg(124.0)),
+            Matrix4::from_translation(Vector3::new(-3.0, 0.0, 0.5)) * Matrix4::from_scale(0.75),
+        ]
+    }
+}
+
+impl Scene for Bloom {
+    fn camera(&mut self) -> Option<&mut Camera> {
+        Some(&mut self.camera)
+    }
+
+    fn handle_input(&mut self, input: &InputState) {
+        let effect = self.chain.effect_mut("tone_mapping").expect("The chain has a tone mapping effect");
+        if input.pressed("toggle_bloom") {
+            std::mem::swap(&mut effect.bloom, &mut self.parked_bloom);
+            println!("Bloom: {}", if effect.bloom.is_some() { "on" } else { "off" });
+        }
+        let step = if input.pressed("bloom_intensity_up") {
+            INTENSITY_STEP
+        } else if input.pressed("bloom_intensity_down") {
+            1.0 / INTENSITY_STEP
+        } else {
+            1.0
+        };
+        if let Some(bloom) = effect.bloom.as_mut().filter(|_| step != 1.0) {
+            let settings = bloom.settings();
+            bloom.set_settings(BloomSettings { intensity: settings.intensity * step, ..settings });
+            println!("Bloom intensity: {:.2}", settings.intensity * step);
+        }
+    }
+
+    fn render(&mut self, frame: &Frame) {
+        let projection = perspective(Deg(45.0), frame.aspect_ratio(), 0.1, 100.0);
+        let view = self.camera.calculate_view();
+        self.chain.begin(frame);
+
+        unsafe {
+            gl::Enable(gl::DEPTH_TEST);
+
+            gl::ClearColor(0.0, 0.0, 0.0, 1.0);
+            gl::Clear(gl::COLOR_BUFFER_BIT | gl::DEPTH_BUFFER_BIT);
+
+            self.shader.use_program();
+            self.shader.set_mat4("projection", projection);
+            self.shader.set_mat4("view", view);
+            self.shader.set_vector3v("cameraPosition", self.camera.position.to_vec());
+
+            //floor
+            gl::ActiveTexture(gl::TEXTURE0);
+            gl::BindTexture(gl::TEXTURE_2D, self.textures[3]);
+            self.shader.set_mat4("model", Matrix4::identity());
+            self.floor.draw_geometry();
+
+            //crates
+            gl::BindTexture(gl::TEXTURE_2D, self.textures[0]);
+            for &model in Bloom::crate_models().iter() {
+                self.shader.set_mat4("model", model);
+                self.cube.draw_geometry();
+            }
+
+            //the glowing crate
+            for (unit, &texture) in self.textures[..3].iter().enumerate() {
+                gl::ActiveTexture(gl::TEXTURE0 + unit as u32);
+                gl::BindTexture(gl::TEXTURE_2D, texture);
+            }
+            self.emission_shader.use_program();
+            self.emission_shader.set_mat4("projection", projection);
+            self.emission_shader.set_mat4("view", view);
+            self.emission_shader.set_mat4("model", Matrix4::from_angle_y(Deg(20.0)));
+            self.emission_shader.set_vector3v("cameraPos", self.camera.position.to_vec());
+            self.cube.draw_geometry();
+            gl::ActiveTexture(gl::TEXTURE0);
+
+            //the lights, in their own colors
+            self.light_source_shader.use_program();
+            self.light_source_shader.set_mat4("projection", projection);
+            self.light_source_shader.set_mat4("view", view);
+            for (position, color) in LIGHT_POSITIONS.iter().zip(LIGHT_COLORS.iter()) {
+                self.light_source_shader.set_vector3v("lightColor", Vector3::from(*color));
+                self.light_source_shader.set_mat4("model", Matrix4::from_translation(Vector3::from(*position)) * Matrix4::from_scale(0.25));
+                self.cube.draw_geometry();
+            }
+            gl::BindVertexArray(0);
+        }
+
+        self.chain.end(frame);
+    }
+}
+
+impl Drop for Bloom {
+    fn drop(&mut self) {
+        unsafe { shapes::delete_textures(&self.textures); }
+    }
+}
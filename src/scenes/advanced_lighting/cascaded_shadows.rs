@@ -0,0 +1,187 @@
+use cgmath::{perspective, Deg, EuclideanSpace, Matrix4, Point3, Vector3};
+
+use crate::camera::Camera;
+use crate::cascades::{CascadeSettings, CascadedShadowMap, ViewFrustum};
+use crate::input::InputState;
+use crate::mesh::{Geometry, Mesh};
+use crate::scene::{Frame, Scene};
+use crate::scenes::shapes;
+use crate::shader::Shader;
+use crate::shading::LightingModel;
+use crate::shadow::{self, ShadowSettings};
+use crate::utils;
+
+// a low sun, for long shadows
+pub const LIGHT_DIRECTION: [f32; 3] = [-1.0, -1.0, -0.6];
+
+pub const SHADOW_MAP_SIZE: u32 = 1024;
+// the cascades end here, the camera sees up to 100
+pub const SHADOW_DISTANCE: f32 = 80.0;
+
+// pillars on a grid of this spacing, PILLAR_ROWS deep and PILLAR_COLUMNS wide in front of the camera
+const PILLAR_SPACING: f32 = 6.0;
+const PILLAR_ROWS: i32 = 14;
+const PILLAR_COLUMNS: i32 = 11;
+// floor tiles of 20 by 20 each way from the center
+const FLOOR_TILES: i32 = 4;
+
+// pcf_kernel cycles through these radii
+const PCF_RADII: [u32; 3] = [0, 1, 2];
+
+/// Chapter 5: a field of pillars in the evening sun, shadowed near and far by cascades.
+pub struct CascadedShadows {
+    camera: Camera,
+    shader: Shader,
+    floor: Mesh,
+    cube: Mesh,
+    // container diffuse and specular map, and the floor
+    textures: [u32; 3],
+    cascades: CascadedShadowMap,
+}
+
+impl CascadedShadows {
+    pub fn new() -> Self {
+        let shader = Shader::new(
+            "./src/shaders/2_lighting/lighting_maps.vs",
+            "./src/shaders/2_lighting/multiple_lights.fs"
+        );
+        shader.use_program();
+        shader.set_int("material.diffuseTex", 0);
+        shader.set_int("material.specularTex", 1);
+        shader.set_float("material.shininess", 16.0);
+        LightingModel::BlinnPhong.apply(&shader);
+
+        //directional light
+        shader.set_vector3v("dirLight.direction", Vector3::from(LIGHT_DIRECTION));
+        shader.set_vector3v("dirLight.ambient", Vector3::new(0.15, 0.14, 0.12));
+        shader.set_vector3v("dirLight.diffuse", Vector3::new(0.8, 0.75, 0.65));
+        shader.set_vector3v("dirLight.specular", Vector3::new(0.3, 0.3, 0.3));
+
+        //the point lights and the flashlight stay dark, without dividing by zero
+        for index in 0..4 {
+            shader.set_float(&format!("pointLight[{}].constant", index), 1.0);
+        }
+        shader.set_float("spotLight.constant", 1.0);
+        shader.set_float("spotLight.cutOff", 1.0);
+        shader.set_float("spotLight.outerCutOff", 0.9);
+        shadow::assign_shadow_units(&shader);
+
+        let textures = unsafe {
+            [
+                shapes::load_texture("./resources/textures/container2.png", true),
+                shapes::load_texture("./resources/textures/container2_specular.png", true),
+                shapes::load_texture("./resources/textures/metal.png", true),
+            ]
+        };
+
+        CascadedShadows {
+            camera: utils::camera_at(Point3::new(0.0, 3.0, 10.0)),
+            shader,
+            floor: shapes::lit_plane_mesh(),
+            cube: shapes::lit_cube_mesh(),
+            textures,
+            cascades: CascadedShadowMap::new(SHADOW_MAP_SIZE, CascadeSettings::default()).expect("Could not create the cascaded shadow map"),
+        }
+    }
+
+    // heights repeat every few pillars, tall enough to shadow their neighbours
+    fn pillar_models() -> Vec<Matrix4<f32>> {
+        let mut models = Vec::new();
+        for row in 0..PILLAR_ROWS {
+            for column in 0..PILLAR_COLUMNS {
+                let x = (column - PILLAR_COLUMNS / 2) as f32 * PILLAR_SPACING;
+                let z = -(row as f32) * PILLAR_SPACING;
+                let height = 1.0 + ((row * 7 + column * 3) % 5) as f32;
+                models.push(
+                    Matrix4::from_translation(Vector3::new(x, -0.5 + height / 2.0, z))
+                        * Matrix4::from_nonuniform_scale(1.0, height, 1.0)
+                );
+            }
+        }
+        models
+    }
+
+    fn floor_models() -> Vec<Matrix4<f32>> {
+        let mut models = Vec::new();
+        for x in -FLOOR_TILES..FLOOR_TILES {
+            for z in -FLOOR_TILES..FLOOR_TILES {
+                models.push(Matrix4::from_translation(Vector3::new(x as f32 * 20.0 + 10.0, 0.0, z as f32 * 20.0 + 10.0)));
+            }
+        }
+        models
+    }
+}
+
+impl Scene for CascadedShadows {
+    fn camera(&mut self) -> Option<&mut Camera> {
+        Some(&mut self.camera)
+    }
+
+    fn handle_input(&mut self, input: &InputState) {
+        if input.pressed("shadow_debug") {
+            let debug = !self.cascades.debug();
+            self.cascades.set_debug(debug);
+            println!("Cascade colors: {}", if debug { "on" } else { "off" });
+        }
+        if input.pressed("pcf_kernel") {
+            let settings = self.cascades.shadow_settings();
+            let next = PCF_RADII.iter().position(|&radius| radius == settings.pcf_radius).map_or(0, |index| (index + 1) % PCF_RADII.len());
+            self.cascades.set_shadow_settings(ShadowSettings { pcf_radius: PCF_RADII[next], ..settings });
+            let size = 2 * PCF_RADII[next] + 1;
+            println!("PCF kernel: {}x{}", size, size);
+        }
+    }
+
+    fn render(&mut self, frame: &Frame) {
+        let pillar_models = CascadedShadows::pillar_models();
+        let floor_models = CascadedShadows::floor_models();
+
+        let projection = perspective(Deg(45.0), frame.aspect_ratio(), 0.1, 100.0);
+        let view = self.camera.calculate_view();
+
+        //depth from the light, one pass per cascade, the floor only receives
+        let frustum = ViewFrustum { fovy: Deg(45.0), aspect: frame.aspect_ratio(), near: 0.1, far: SHADOW_DISTANCE };
+        let casters: Vec<(&dyn Geometry, Matrix4<f32>)> = pillar_models.iter().map(|&model| (&self.cube as &dyn Geometry, model)).collect();
+        self.cascades.render(view, &frustum, Vector3::from(LIGHT_DIRECTION), &casters);
+
+        unsafe {
+            gl::Enable(gl::DEPTH_TEST);
+
+            gl::ClearColor(0.55, 0.6, 0.7, 1.0);
+            gl::Clear(gl::COLOR_BUFFER_BIT | gl::DEPTH_BUFFER_BIT);
+
+            self.shader.use_program();
+            self.shader.set_mat4("projection", projection);
+            self.shader.set_mat4("view", view);
+            self.shader.set_vector3v("cameraPosition", self.camera.position.to_vec());
+            self.cascades.apply(&self.shader);
+
+            //floor, metal as diffuse and specular map
+            for unit in 0..2 {
+                gl::ActiveTexture(gl::TEXTURE0 + unit);
+                gl::BindTexture(gl::TEXTURE_2D, self.textures[2]);
+            }
+            for &model in floor_models.iter() {
+                self.shader.set_mat4("model", model);
+                self.floor.draw_geometry();
+            }
+
+            //pillars
+            for unit in 0..2 {
+                gl::ActiveTexture(gl::TEXTURE0 + unit);
+                gl::BindTexture(gl::TEXTURE_2D, self.textures[unit as usize]);
+            }
+            for &model in pillar_models.iter() {
+                self.shader.set_mat4("model", model);
+                self.cube.draw_geometry();
+            }
+            gl::BindVertexArray(0);
+        }
+    }
+}
+
+impl Drop for CascadedShadows {
+    fn drop(&mut self) {
+        unsafe { shapes::delete_textures(&self.textures); }
+    }
+}
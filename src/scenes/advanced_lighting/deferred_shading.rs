@@ -0,0 +1,229 @@
+use cgmath::{perspective, Deg, EuclideanSpace, Matrix4, Point3, SquareMatrix, Vector3};
+use rand::Rng;
+
+use crate::camera::Camera;
+use crate::deferred::{DeferredRenderer, PointLight, SpotLight};
+use crate::framebuffer::ColorFormat;
+use crate::hdr::ToneMapping;
+use crate::input::InputState;
+use crate::mesh::{Geometry, Mesh};
+use crate::model::{self, ColorSpace};
+use crate::postprocess::{Effect, PostProcess};
+use crate::render_queue::{Bucket, RenderQueue};
+use crate::scene::{self, Frame, Scene};
+use crate::scenes::shapes;
+use crate::shader::Shader;
+use crate::utils;
+
+// more_lights and fewer_lights double or halve the lights in this range
+pub const MAX_LIGHTS: usize = 1024;
+const MIN_LIGHTS: usize = 16;
+const START_LIGHTS: usize = 256;
+
+// crates on a grid of this spacing, CRATE_ROWS by CRATE_ROWS in front of the camera
+const CRATE_SPACING: f32 = 3.0;
+const CRATE_ROWS: i32 = 5;
+// the lights circle the middle of the grid within this radius
+const LIGHT_AREA: f32 = 9.0;
+
+pub const WINDOW_POSITIONS: [[f32; 3]; 3] = [
+    [-1.5, 0.5, -4.5],
+    [ 1.5, 0.5, -1.5],
+    [ 4.5, 0.5, -7.5]
+];
+
+/// Chapter 5: hundreds of small colored lights over a field of crates, and a few spot lights,
+/// shaded in one pass per light volume. The windows are blended in a forward pass on top.
+pub struct DeferredShading {
+    camera: Camera,
+    renderer: DeferredRenderer,
+    light_source_shader: Shader,
+    window_shader: Shader,
+    floor: Mesh,
+    cube: Mesh,
+    quad_vao: shapes::VertexArray,
+    // container diffuse and specular map, the floor and the window
+    textures: [u32; 4],
+    // every light that can be turned on, orbiting at its own radius, height and speed
+    lights: Vec<(PointLight, f32)>,
+    light_count: usize,
+    // only tone mapping, the lights add up beyond white
+    chain: PostProcess,
+}
+
+impl DeferredShading {
+    pub fn new() -> Self {
+        let mut rng = scene::seeded_rng();
+        let lights = (0..MAX_LIGHTS).map(|_| {
+            // saturated colors, one channel at full strength
+            let mut color = [rng.gen_range(0.0, 0.5), rng.gen_range(0.0, 0.5), rng.gen_range(0.0, 0.5)];
+            color[rng.gen_range(0, 3)] = 1.0;
+            let light = PointLight {
+                position: Vector3::new(rng.gen_range(-LIGHT_AREA, LIGHT_AREA), rng.gen_range(0.0, 1.5), rng.gen_range(-LIGHT_AREA, LIGHT_AREA)),
+                color: Vector3::from(color) * 0.25,
+            };
+            (light, rng.gen_range(-0.5, 0.5))
+        }).collect();
+
+        let window_shader = Shader::new(
+            "./src/shaders/4_advanced_opengl/blending.vs",
+            "./src/shaders/4_advanced_opengl/blending.fs"
+        );
+        window_shader.use_program();
+        window_shader.set_int("texture0", 0);
+
+        // color maps decoded to linear values, the lighting is linear up to the tone mapping
+        let textures = unsafe {
+            [
+                model::texture_from_file_with("./resources/textures/container2.png", ColorSpace::Srgb),
+                model::texture_from_file("./resources/textures/container2_specular.png"),
+                model::texture_from_file_with("./resources/textures/metal.png", ColorSpace::Srgb),
+                model::texture_from_file_with("./resources/textures/blending_transparent_window.png", ColorSpace::Srgb),
+            ]
+        };
+
+        let mut tone_mapping = Effect::tone_mapping(ToneMapping::default());
+        tone_mapping.enabled = true;
+
+        DeferredShading {
+            camera: utils::camera_at(Point3::new(0.0, 2.5, 10.0)),
+            renderer: DeferredRenderer::new().expect("Could not create the G-buffer"),
+            light_source_shader: crate::scenes::lighting::light_source_shader(),
+            window_shader,
+            floor: shapes::lit_plane_mesh(),
+            cube: shapes::lit_cube_mesh(),
+            quad_vao: unsafe { shapes::create_vao(&shapes::QUAD_VERTICES, &[3, 2]) },
+            textures,
+            lights,
+            light_count: START_LIGHTS,
+            chain: PostProcess::new(ColorFormat::Rgba16F, vec![tone_mapping]).expect("Could not create the HDR target"),
+        }
+    }
+
+    // heights repeat every few crates
+    fn crate_models() -> Vec<Matrix4<f32>> {
+        let mut models = Vec::new();
+        for row in 0..CRATE_ROWS {
+            for column in 0..CRATE_ROWS {
+                let (x, z) = ((column - CRATE_ROWS / 2) as f32 * CRATE_SPACING, -(row as f32) * CRATE_SPACING);
+                let height = 1.0 + ((row * 3 + column) % 3) as f32 * 0.5;
+                models.push(
+                    Matrix4::from_translation(Vector3::new(x, -0.5 + height / 2.0, z))
+                        * Matrix4::from_angle_y(Deg((row * 5 + column * 7) as f32 * 10.0))
+                        * Matrix4::from_nonuniform_scale(1.0, height, 1.0)
+                );
+            }
+        }
+        models
+    }
+
+    // the lights turned on, each circling the middle of the grid
+    fn point_lights(&self, time: f32) -> Vec<PointLight> {
+        let center = Vector3::new(0.0, 0.0, -6.0);
+        self.lights[..self.light_count].iter().map(|&(light, speed)| {
+            let rotation = Matrix4::from_angle_y(Deg(time * speed * 45.0));
+            PointLight { position: center + (rotation * light.position.extend(1.0)).truncate(), ..light }
+        }).collect()
+    }
+
+    // four lamps above the grid, slowly sweeping their cones over it
+    fn spot_lights(time: f32) -> Vec<SpotLight> {
+        (0..4).map(|index| {
+            let angle = Deg(90.0 * index as f32 + time * 20.0);
+            let offset = Matrix4::from_angle_y(angle) * Vector3::new(4.0, 0.0, 0.0).extend(0.0);
+            SpotLight {
+                position: Vector3::new(0.0, 4.0, -6.0) + offset.truncate(),
+                direction: Vector3::new(offset.x * 0.1, -1.0, offset.z * 0.1),
+                color: Vector3::new(20.0, 18.0, 15.0),
+                cut_off: Deg(15.0),
+                outer_cut_off: Deg(22.0),
+            }
+        }).collect()
+    }
+}
+
+impl Scene for DeferredShading {
+    fn camera(&mut self) -> Option<&mut Camera> {
+        Some(&mut self.camera)
+    }
+
+    fn handle_input(&mut self, input: &InputState) {
+        let count = if input.pressed("more_lights") {
+            (self.light_count * 2).min(MAX_LIGHTS)
+        } else if input.pressed("fewer_lights") {
+            (self.light_count / 2).max(MIN_LIGHTS)
+        } else {
+            return;
+        };
+        self.light_count = count;
+        println!("Lights: {}", count);
+    }
+
+    fn render(&mut self, frame: &Frame) {
+        let projection = perspective(Deg(45.0), frame.aspect_ratio(), 0.1, 100.0);
+        let view = self.camera.calculate_view();
+        let point_lights = self.point_lights(frame.time);
+        let target = self.chain.begin(frame);
+
+        //the opaque geometry into the G-buffer
+        let shader = self.renderer.begin_geometry(frame, view, projection);
+        unsafe {
+            gl::ActiveTexture(gl::TEXTURE0);
+            gl::BindTexture(gl::TEXTURE_2D, self.textures[2]);
+            gl::ActiveTexture(gl::TEXTURE1);
+            gl::BindTexture(gl::TEXTURE_2D, self.textures[2]);
+            shader.set_mat4("model", Matrix4::identity());
+            self.floor.draw_geometry();
+
+            for unit in 0..2 {
+                gl::ActiveTexture(gl::TEXTURE0 + unit);
+                gl::BindTexture(gl::TEXTURE_2D, self.textures[unit as usize]);
+            }
+            for &model in DeferredShading::crate_models().iter() {
+                shader.set_mat4("model", model);
+                self.cube.draw_geometry();
+            }
+            gl::ActiveTexture(gl::TEXTURE0);
+        }
+
+        //every light over it
+        self.renderer.shade(&target, view, projection, &point_lights, &DeferredShading::spot_lights(frame.time), Vector3::new(0.0, 0.0, 0.0))
+            .expect("Could not shade the G-buffer");
+
+        //forward, on the depth of the G-buffer: the lights themselves, then the blended windows
+        let mut queue = RenderQueue::new();
+        let (light_source_shader, cube) = (&self.light_source_shader, &self.cube);
+        queue.push(Bucket::Opaque, Vector3::new(0.0, 0.0, -6.0), move || {
+            light_source_shader.use_program();
+            light_source_shader.set_mat4("projection", projection);
+            light_source_shader.set_mat4("view", view);
+            for light in point_lights.iter() {
+                light_source_shader.set_vector3v("lightColor", light.color * 4.0);
+                light_source_shader.set_mat4("model", Matrix4::from_translation(light.position) * Matrix4::from_scale(0.08));
+                cube.draw_geometry();
+            }
+        });
+        for &position in WINDOW_POSITIONS.iter() {
+            let (window_shader, quad_vao, texture) = (&self.window_shader, &self.quad_vao, self.textures[3]);
+            queue.push(Bucket::Transparent, Vector3::from(position), move || unsafe {
+                window_shader.use_program();
+                window_shader.set_mat4("projection", projection);
+                window_shader.set_mat4("view", view);
+                window_shader.set_mat4("model", Matrix4::from_translation(Vector3::from(position)));
+                gl::BindTexture(gl::TEXTURE_2D, texture);
+                gl::BindVertexArray(quad_vao.id);
+                gl::DrawArrays(gl::TRIANGLES, 0, 6);
+            });
+        }
+        queue.flush(self.camera.position.to_vec());
+        unsafe { gl::BindVertexArray(0); }
+
+        self.chain.end(frame);
+    }
+}
+
+impl Drop for DeferredShading {
+    fn drop(&mut self) {
+        unsafe { shapes::delete_textures(&self.textures); }
+    }
+}
@@ -0,0 +1,135 @@
+use cgmath::{perspective, Deg, EuclideanSpace, Matrix4, Point3, SquareMatrix, Vector3};
+
+use crate::camera::Camera;
+use crate::gamma::{GammaOutput, GammaPass};
+use crate::input::InputState;
+use crate::model::{self, ColorSpace};
+use crate::scene::{Frame, Scene};
+use crate::scenes::shapes;
+use crate::shader::Shader;
+use crate::shading::LightingModel;
+use crate::utils;
+
+pub const LIGHT_POSITIONS: [[f32; 3]; 4] = [
+    [-1.5, 0.0, 0.0],
+    [-0.5, 0.0, 0.0],
+    [ 0.5, 0.0, 0.0],
+    [ 1.5, 0.0, 0.0]
+];
+
+// each light twice as bright as the one two to the left
+pub const LIGHT_BRIGHTNESS: [f32; 4] = [0.25, 0.5, 0.75, 1.0];
+
+/// Chapter 5: four lights of rising brightness over a floor, lit on sRGB values on the left and gamma
+/// corrected on the right.
+///
+/// The left half samples the texture as stored and writes the result straight to the screen, the light
+/// falls off with `1 / distance` to look about right. The right half decodes the texture to linear
+/// values, falls off physically with `1 / distance^2` and encodes the result in a `GammaPass`.
+pub struct GammaCorrection {
+    camera: Camera,
+    floor_shader: Shader,
+    floor_vao: shapes::VertexArray,
+    // the same image, as stored and decoded from sRGB
+    textures: [u32; 2],
+    // switched with toggle_gamma
+    gamma: GammaPass,
+}
+
+impl GammaCorrection {
+    pub fn new() -> Self {
+        let floor_shader = super::floor_shader();
+        floor_shader.set_int("lightCount", LIGHT_POSITIONS.len() as i32);
+        for (index, (position, &brightness)) in LIGHT_POSITIONS.iter().zip(LIGHT_BRIGHTNESS.iter()).enumerate() {
+            floor_shader.set_vector3v(&format!("lightPositions[{}]", index), Vector3::from(*position));
+            floor_shader.set_vector3v(&format!("lightColors[{}]", index), Vector3::new(brightness, brightness, brightness));
+        }
+        floor_shader.set_float("shininess", 8.0);
+        LightingModel::BlinnPhong.apply(&floor_shader);
+
+        let (floor_vao, textures) = unsafe {
+            (
+                shapes::create_vao(&shapes::LIT_PLANE_VERTICES, &[3, 3, 2]),
+                [
+                    model::texture_from_file("./resources/textures/metal.png"),
+                    model::texture_from_file_with("./resources/textures/metal.png", ColorSpace::Srgb),
+                ]
+            )
+        };
+
+        GammaCorrection {
+            camera: utils::camera_at(Point3::new(0.0, 1.0, 6.0)),
+            floor_shader,
+            floor_vao,
+            textures,
+            gamma: GammaPass::new(GammaOutput::Shader).expect("Could not create the gamma pass"),
+        }
+    }
+}
+
+impl Scene for GammaCorrection {
+    fn camera(&mut self) -> Option<&mut Camera> {
+        Some(&mut self.camera)
+    }
+
+    fn handle_input(&mut self, input: &InputState) {
+        if input.pressed("toggle_gamma") {
+            let output = match self.gamma.output() {
+                GammaOutput::Shader => GammaOutput::SrgbFramebuffer,
+                GammaOutput::SrgbFramebuffer => GammaOutput::Shader,
+            };
+            self.gamma.set_output(output).expect("Could not switch the gamma output");
+            println!("Gamma correction: {:?}", output);
+        }
+    }
+
+    fn render(&mut self, frame: &Frame) {
+        //each half shows the whole floor
+        let projection = perspective(Deg(45.0), frame.aspect_ratio() / 2.0, 0.1, 100.0);
+        let view = self.camera.calculate_view();
+
+        unsafe {
+            gl::Enable(gl::DEPTH_TEST);
+
+            gl::ClearColor(0.0, 0.0, 0.0, 1.0);
+            gl::Clear(gl::COLOR_BUFFER_BIT | gl::DEPTH_BUFFER_BIT);
+
+            self.floor_shader.use_program();
+            self.floor_shader.set_mat4("projection", projection);
+            self.floor_shader.set_mat4("view", view);
+            self.floor_shader.set_mat4("model", Matrix4::identity());
+            self.floor_shader.set_vector3v("cameraPosition", self.camera.position.to_vec());
+
+            //left, uncorrected
+            super::half_viewport(frame, 0);
+            self.draw_floor(self.textures[0], 1.0);
+
+            //right, linear lighting through the gamma pass
+            super::half_viewport(frame, 1);
+            self.gamma.begin().expect("Could not resize the gamma target");
+            gl::Clear(gl::COLOR_BUFFER_BIT | gl::DEPTH_BUFFER_BIT);
+            self.draw_floor(self.textures[1], 2.0);
+            self.gamma.end();
+
+            gl::Viewport(0, 0, frame.width as i32, frame.height as i32);
+            gl::BindVertexArray(0);
+        }
+    }
+}
+
+impl GammaCorrection {
+    unsafe fn draw_floor(&self, texture: u32, attenuation_power: f32) {
+        self.floor_shader.use_program();
+        self.floor_shader.set_float("attenuationPower", attenuation_power);
+        gl::ActiveTexture(gl::TEXTURE0);
+        gl::BindTexture(gl::TEXTURE_2D, texture);
+        gl::BindVertexArray(self.floor_vao.id);
+        gl::DrawArrays(gl::TRIANGLES, 0, 6);
+    }
+}
+
+impl Drop for GammaCorrection {
+    fn drop(&mut self) {
+        unsafe { shapes::delete_textures(&self.textures); }
+    }
+}
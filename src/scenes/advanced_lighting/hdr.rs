@@ -0,0 +1,146 @@
+use cgmath::{perspective, Deg, EuclideanSpace, Matrix4, Point3, Vector3};
+
+use crate::camera::Camera;
+use crate::framebuffer::ColorFormat;
+use crate::hdr::{AutoExposure, ExposureSettings, ToneMapping};
+use crate::input::InputState;
+use crate::mesh::{Geometry, Mesh};
+use crate::model::{self, ColorSpace};
+use crate::postprocess::{Effect, PostProcess};
+use crate::scene::{Frame, Scene};
+use crate::scenes::shapes;
+use crate::shader::Shader;
+use crate::shading::LightingModel;
+use crate::utils;
+
+// one very bright light at the end of the tunnel, three dim ones near the entrance
+pub const LIGHT_POSITIONS: [[f32; 3]; 4] = [
+    [ 0.0,  0.0, -49.5],
+    [-1.4, -1.9,  -9.0],
+    [ 0.0, -1.8,  -4.0],
+    [ 0.8, -1.7,  -6.0]
+];
+
+pub const LIGHT_COLORS: [[f32; 3]; 4] = [
+    [200.0, 200.0, 200.0],
+    [0.1, 0.0, 0.0],
+    [0.0, 0.0, 0.2],
+    [0.0, 0.1, 0.0]
+];
+
+// exposure_up and exposure_down multiply or divide by this
+const EXPOSURE_STEP: f32 = 1.25;
+
+/// Chapter 5: a long tunnel with a light 200 times brighter than white at its end.
+///
+/// The tunnel renders into half floats, so the end keeps its brightness instead of clipping to white,
+/// and a `tone_mapping` effect maps it back to the screen. With automatic exposure the dim entrance
+/// is brightened and the bright end darkened, following where the camera looks.
+pub struct Hdr {
+    camera: Camera,
+    shader: Shader,
+    tunnel: Mesh,
+    texture: u32,
+    // only tone mapping, its operator cycled with tone_mapping
+    chain: PostProcess,
+    operator: ToneMapping,
+    // the automatic exposure while auto_exposure has it off
+    parked_exposure: Option<AutoExposure>,
+}
+
+impl Hdr {
+    pub fn new() -> Self {
+        let shader = super::floor_shader();
+        shader.set_int("lightCount", LIGHT_POSITIONS.len() as i32);
+        for (index, (position, color)) in LIGHT_POSITIONS.iter().zip(LIGHT_COLORS.iter()).enumerate() {
+            shader.set_vector3v(&format!("lightPositions[{}]", index), Vector3::from(*position));
+            shader.set_vector3v(&format!("lightColors[{}]", index), Vector3::from(*color));
+        }
+        shader.set_float("shininess", 8.0);
+        shader.set_float("attenuationPower", 2.0);
+        shader.set_bool("inverseNormals", true);
+        LightingModel::BlinnPhong.apply(&shader);
+
+        let auto_exposure = AutoExposure::new(ExposureSettings::default()).expect("Could not create the automatic exposure");
+        let mut tone_mapping = Effect::tone_mapping(ToneMapping::default()).with_auto_exposure(auto_exposure);
+        tone_mapping.enabled = true;
+
+        Hdr {
+            camera: utils::camera_at(Point3::new(0.0, 0.0, 1.0)),
+            shader,
+            tunnel: shapes::lit_cube_mesh(),
+            // lit in linear space, like the gamma corrected floor
+            texture: unsafe { model::texture_from_file_with("./resources/textures/metal.png", ColorSpace::Srgb) },
+            chain: PostProcess::new(ColorFormat::Rgba16F, vec![tone_mapping]).expect("Could not create the HDR target"),
+            operator: ToneMapping::default(),
+            parked_exposure: None,
+        }
+    }
+}
+
+impl Scene for Hdr {
+    fn camera(&mut self) -> Option<&mut Camera> {
+        Some(&mut self.camera)
+    }
+
+    fn handle_input(&mut self, input: &InputState) {
+        let effect = self.chain.effect_mut("tone_mapping").expect("The chain has a tone mapping effect");
+        if input.pressed("tone_mapping") {
+            self.operator = self.operator.next();
+            effect.set_param("operator", self.operator.index()).unwrap();
+            println!("Tone mapping: {}", self.operator.name());
+        }
+        if input.pressed("auto_exposure") {
+            std::mem::swap(&mut effect.auto_exposure, &mut self.parked_exposure);
+            // adapt from scratch instead of from where it was turned off
+            if let Some(auto_exposure) = effect.auto_exposure.as_mut() {
+                auto_exposure.reset();
+            }
+            println!("Automatic exposure: {}", if effect.auto_exposure.is_some() { "on" } else { "off" });
+        }
+        let exposure = effect.param("exposure").unwrap_or(1.0);
+        if input.pressed("exposure_up") {
+            effect.set_param("exposure", exposure * EXPOSURE_STEP).unwrap();
+            println!("Exposure: {:.2}", exposure * EXPOSURE_STEP);
+        }
+        if input.pressed("exposure_down") {
+            effect.set_param("exposure", exposure / EXPOSURE_STEP).unwrap();
+            println!("Exposure: {:.2}", exposure / EXPOSURE_STEP);
+        }
+    }
+
+    fn render(&mut self, frame: &Frame) {
+        let projection = perspective(Deg(45.0), frame.aspect_ratio(), 0.1, 100.0);
+        let view = self.camera.calculate_view();
+        self.chain.begin(frame);
+
+        unsafe {
+            gl::Enable(gl::DEPTH_TEST);
+
+            gl::ClearColor(0.0, 0.0, 0.0, 1.0);
+            gl::Clear(gl::COLOR_BUFFER_BIT | gl::DEPTH_BUFFER_BIT);
+
+            self.shader.use_program();
+            self.shader.set_mat4("projection", projection);
+            self.shader.set_mat4("view", view);
+            self.shader.set_vector3v("cameraPosition", self.camera.position.to_vec());
+
+            //a cube stretched from z 2.5 to -52.5, lit from the inside
+            let model = Matrix4::from_translation(Vector3::new(0.0, 0.0, -25.0))
+                * Matrix4::from_nonuniform_scale(5.0, 5.0, 55.0);
+            self.shader.set_mat4("model", model);
+            gl::ActiveTexture(gl::TEXTURE0);
+            gl::BindTexture(gl::TEXTURE_2D, self.texture);
+            self.tunnel.draw_geometry();
+            gl::BindVertexArray(0);
+        }
+
+        self.chain.end(frame);
+    }
+}
+
+impl Drop for Hdr {
+    fn drop(&mut self) {
+        unsafe { shapes::delete_textures(&[self.texture]); }
+    }
+}
@@ -0,0 +1,31 @@
+//! Chapter 5: Advanced Lighting
+
+pub mod blinn_phong;
+pub mod gamma_correction;
+pub mod shadow_mapping;
+pub mod point_shadows;
+pub mod cascaded_shadows;
+pub mod normal_mapping;
+pub mod hdr;
+pub mod bloom;
+pub mod deferred_shading;
+
+use crate::scene::Frame;
+use crate::shader::Shader;
+
+/// The shader lighting the floor with up to four point lights.
+pub(crate) fn floor_shader() -> Shader {
+    let shader = Shader::new(
+        "./src/shaders/2_lighting/lighting_maps.vs",
+        "./src/shaders/5_advanced_lighting/advanced_lighting.fs"
+    );
+    shader.use_program();
+    shader.set_int("floorTexture", 0);
+    shader
+}
+
+/// Limits drawing to the left (0) or right (1) half of the frame, for side by side comparisons.
+pub(crate) unsafe fn half_viewport(frame: &Frame, half: u32) {
+    let width = frame.width / 2;
+    gl::Viewport((half * width) as i32, 0, width as i32, frame.height as i32);
+}
@@ -0,0 +1,133 @@
+use cgmath::{perspective, Deg, EuclideanSpace, Matrix4, Point3, SquareMatrix, Vector3};
+
+use crate::camera::Camera;
+use crate::input::InputState;
+use crate::mesh::{Geometry, Mesh, Texture};
+use crate::relief::{self, ParallaxMode, NORMAL_MAPPING_FRAGMENT_SHADER, NORMAL_MAPPING_VERTEX_SHADER};
+use crate::scene::{Frame, Scene};
+use crate::scenes::shapes;
+use crate::shader::Shader;
+use crate::utils;
+
+pub const LIGHT_POSITION: [f32; 3] = [1.0, 1.5, 1.75];
+
+// the relief textures, four by four tiles
+const RELIEF_SIZE: usize = 256;
+const RELIEF_TILES: usize = 4;
+const RELIEF_BEVEL: f32 = 0.15;
+// steeper normals than the heights alone, the bevels are only a few texels wide
+const NORMAL_STRENGTH: f32 = 8.0;
+const HEIGHT_SCALE: f32 = 0.05;
+
+/// Chapter 5: marble tiles on a cube and the floor, their grout and bevels only in the textures.
+pub struct NormalMapping {
+    camera: Camera,
+    shader: Shader,
+    light_source_shader: Shader,
+    floor: Mesh,
+    cube: Mesh,
+    // marble, the height map and the normal map
+    textures: [u32; 3],
+    // switched with toggle_normal_map, and cycled with parallax_mode
+    normal_mapping: bool,
+    parallax: ParallaxMode,
+}
+
+impl NormalMapping {
+    pub fn new() -> Self {
+        let shader = Shader::new(NORMAL_MAPPING_VERTEX_SHADER, NORMAL_MAPPING_FRAGMENT_SHADER);
+        shader.use_program();
+        shader.set_vector3v("lightColor", Vector3::new(1.0, 1.0, 1.0));
+        shader.set_float("shininess", 64.0);
+        shader.set_float("heightScale", HEIGHT_SCALE);
+
+        let (height_map, normal_map) = unsafe {
+            relief::relief_textures(&relief::tile_heights(RELIEF_SIZE, RELIEF_TILES, RELIEF_BEVEL), RELIEF_SIZE, NORMAL_STRENGTH)
+        };
+        let textures = [unsafe { shapes::load_texture("./resources/textures/marble.jpg", true) }, height_map, normal_map];
+        // bound by Mesh::Draw to texture_diffuse1, texture_height1 and texture_normal1
+        let materials = vec![
+            Texture { id: textures[0], type_: "texture_diffuse".into(), path: "./resources/textures/marble.jpg".into() },
+            Texture { id: height_map, type_: "texture_height".into(), path: String::new() },
+            Texture { id: normal_map, type_: "texture_normal".into(), path: String::new() },
+        ];
+        let mut floor = shapes::lit_plane_mesh();
+        let mut cube = shapes::lit_cube_mesh();
+        floor.textures = materials.clone();
+        cube.textures = materials;
+
+        NormalMapping {
+            camera: utils::camera_at(Point3::new(0.0, 1.25, 5.0)),
+            shader,
+            light_source_shader: crate::scenes::lighting::light_source_shader(),
+            floor,
+            cube,
+            textures,
+            normal_mapping: true,
+            parallax: ParallaxMode::Occlusion,
+        }
+    }
+}
+
+impl Scene for NormalMapping {
+    fn camera(&mut self) -> Option<&mut Camera> {
+        Some(&mut self.camera)
+    }
+
+    fn handle_input(&mut self, input: &InputState) {
+        if input.pressed("toggle_normal_map") {
+            self.normal_mapping = !self.normal_mapping;
+            println!("Normal mapping: {}", if self.normal_mapping { "on" } else { "off" });
+        }
+        if input.pressed("parallax_mode") {
+            self.parallax = self.parallax.next();
+            println!("Parallax mapping: {}", self.parallax.name());
+        }
+    }
+
+    fn render(&mut self, frame: &Frame) {
+        let projection = perspective(Deg(45.0), frame.aspect_ratio(), 0.1, 100.0);
+        let view = self.camera.calculate_view();
+        let light_position = Vector3::from(LIGHT_POSITION);
+
+        unsafe {
+            gl::Enable(gl::DEPTH_TEST);
+
+            gl::ClearColor(0.1, 0.1, 0.1, 1.0);
+            gl::Clear(gl::COLOR_BUFFER_BIT | gl::DEPTH_BUFFER_BIT);
+
+            self.shader.use_program();
+            self.shader.set_mat4("projection", projection);
+            self.shader.set_mat4("view", view);
+            self.shader.set_vector3v("cameraPosition", self.camera.position.to_vec());
+            self.shader.set_vector3v("lightPosition", light_position);
+            self.shader.set_bool("normalMapping", self.normal_mapping);
+            self.parallax.apply(&self.shader);
+
+            //floor
+            self.shader.set_mat4("model", Matrix4::identity());
+            self.floor.Draw(&self.shader);
+
+            //cube, turning slowly to show the tiles at every angle
+            let model = Matrix4::from_translation(Vector3::new(0.0, 0.25, 0.0))
+                * Matrix4::from_angle_y(Deg(30.0 + frame.time * 15.0))
+                * Matrix4::from_scale(1.5);
+            self.shader.set_mat4("model", model);
+            self.cube.Draw(&self.shader);
+
+            //the light itself
+            self.light_source_shader.use_program();
+            self.light_source_shader.set_mat4("projection", projection);
+            self.light_source_shader.set_mat4("view", view);
+            self.light_source_shader.set_mat4("model", Matrix4::from_translation(light_position) * Matrix4::from_scale(0.1));
+            self.cube.draw_geometry();
+            gl::BindVertexArray(0);
+        }
+    }
+}
+
+impl Drop for NormalMapping {
+    fn drop(&mut self) {
+        unsafe { shapes::delete_textures(&self.textures); }
+    }
+}
@@ -0,0 +1,176 @@
+use cgmath::{perspective, Deg, EuclideanSpace, InnerSpace, Matrix4, Point3, SquareMatrix, Vector3};
+
+use crate::camera::Camera;
+use crate::input::InputState;
+use crate::mesh::{Geometry, Mesh};
+use crate::scene::{Frame, Scene};
+use crate::scenes::lighting::light_casters::{CONSTANT, LINEAR, QUADRATIC};
+use crate::scenes::shapes;
+use crate::shader::Shader;
+use crate::shading::LightingModel;
+use crate::shadow::{self, PointShadowMap, PointShadowSettings};
+use crate::utils;
+
+// position, scale and angle around (1, 0, 1) of the cubes around the light
+pub const CUBES: [([f32; 3], f32, f32); 5] = [
+    ([ 2.5, 0.0,  0.0], 0.5, 0.0),
+    ([-2.5, 0.5,  1.0], 1.0, 30.0),
+    ([ 0.0, 0.0, -3.0], 0.75, 0.0),
+    ([ 1.5, 0.25, 2.5], 0.5, 45.0),
+    ([-1.5, 2.5, -1.5], 0.5, 60.0)
+];
+
+pub const SHADOW_MAP_SIZE: u32 = 1024;
+pub const SHADOW_FAR_PLANE: f32 = 25.0;
+
+/// Chapter 5: a point light circling between cubes, casting shadows in every direction.
+pub struct PointShadows {
+    camera: Camera,
+    shader: Shader,
+    light_source_shader: Shader,
+    floor: Mesh,
+    cube: Mesh,
+    // container diffuse and specular map, and the floor
+    textures: [u32; 3],
+    shadow_map: PointShadowMap,
+    // switched with toggle_shadows, and between soft and hard edges with pcf_kernel
+    shadows: bool,
+}
+
+impl PointShadows {
+    pub fn new() -> Self {
+        let shader = Shader::new(
+            "./src/shaders/2_lighting/lighting_maps.vs",
+            "./src/shaders/2_lighting/multiple_lights.fs"
+        );
+        shader.use_program();
+        shader.set_int("material.diffuseTex", 0);
+        shader.set_int("material.specularTex", 1);
+        shader.set_float("material.shininess", 16.0);
+        LightingModel::BlinnPhong.apply(&shader);
+
+        //the first point light, the others, the sun and the flashlight stay dark without dividing by zero
+        shader.set_vector3v("pointLight[0].ambient", Vector3::new(0.05, 0.05, 0.05));
+        shader.set_vector3v("pointLight[0].diffuse", Vector3::new(0.8, 0.8, 0.8));
+        shader.set_vector3v("pointLight[0].specular", Vector3::new(1.0, 1.0, 1.0));
+        shader.set_float("pointLight[0].linear", LINEAR);
+        shader.set_float("pointLight[0].quadratic", QUADRATIC);
+        for index in 0..4 {
+            shader.set_float(&format!("pointLight[{}].constant", index), CONSTANT);
+        }
+        shader.set_float("spotLight.constant", 1.0);
+        shader.set_float("spotLight.cutOff", 1.0);
+        shader.set_float("spotLight.outerCutOff", 0.9);
+        shadow::assign_shadow_units(&shader);
+
+        let textures = unsafe {
+            [
+                shapes::load_texture("./resources/textures/container2.png", true),
+                shapes::load_texture("./resources/textures/container2_specular.png", true),
+                shapes::load_texture("./resources/textures/metal.png", true),
+            ]
+        };
+
+        PointShadows {
+            camera: utils::camera_at(Point3::new(0.0, 2.0, 8.0)),
+            shader,
+            light_source_shader: crate::scenes::lighting::light_source_shader(),
+            floor: shapes::lit_plane_mesh(),
+            cube: shapes::lit_cube_mesh(),
+            textures,
+            shadow_map: PointShadowMap::new(SHADOW_MAP_SIZE, SHADOW_FAR_PLANE).expect("Could not create the point shadow map"),
+            shadows: true,
+        }
+    }
+
+    fn cube_models() -> Vec<Matrix4<f32>> {
+        CUBES.iter().map(|&(position, scale, angle)| {
+            Matrix4::from_translation(Vector3::from(position))
+                * Matrix4::from_axis_angle(Vector3::new(1.0, 0.0, 1.0).normalize(), Deg(angle))
+                * Matrix4::from_scale(scale)
+        }).collect()
+    }
+}
+
+impl Scene for PointShadows {
+    fn camera(&mut self) -> Option<&mut Camera> {
+        Some(&mut self.camera)
+    }
+
+    fn handle_input(&mut self, input: &InputState) {
+        if input.pressed("toggle_shadows") {
+            self.shadows = !self.shadows;
+            println!("Point light shadows: {}", if self.shadows { "on" } else { "off" });
+        }
+        if input.pressed("pcf_kernel") {
+            let settings = self.shadow_map.settings();
+            let filter_radius = if settings.filter_radius > 0.0 { 0.0 } else { PointShadowSettings::default().filter_radius };
+            self.shadow_map.set_settings(PointShadowSettings { filter_radius, ..settings });
+            println!("Point shadow edges: {}", if filter_radius > 0.0 { "soft" } else { "hard" });
+        }
+    }
+
+    fn render(&mut self, frame: &Frame) {
+        let cube_models = PointShadows::cube_models();
+        let light_position = Vector3::new((frame.time * 0.5).sin() * 1.5, 1.0, (frame.time * 0.5).cos() * 1.5);
+
+        //distances from the light, the floor only receives
+        if self.shadows {
+            let casters: Vec<(&dyn Geometry, Matrix4<f32>)> = cube_models.iter().map(|&model| (&self.cube as &dyn Geometry, model)).collect();
+            self.shadow_map.render(light_position, &casters);
+        }
+
+        let projection = perspective(Deg(45.0), frame.aspect_ratio(), 0.1, 100.0);
+        let view = self.camera.calculate_view();
+
+        unsafe {
+            gl::Enable(gl::DEPTH_TEST);
+
+            gl::ClearColor(0.1, 0.1, 0.1, 1.0);
+            gl::Clear(gl::COLOR_BUFFER_BIT | gl::DEPTH_BUFFER_BIT);
+
+            self.shader.use_program();
+            self.shader.set_mat4("projection", projection);
+            self.shader.set_mat4("view", view);
+            self.shader.set_vector3v("cameraPosition", self.camera.position.to_vec());
+            self.shader.set_vector3v("pointLight[0].position", light_position);
+            if self.shadows {
+                self.shadow_map.apply(&self.shader, 0);
+            } else {
+                self.shader.set_bool("pointLight[0].shadows", false);
+            }
+
+            //floor, metal as diffuse and specular map
+            for unit in 0..2 {
+                gl::ActiveTexture(gl::TEXTURE0 + unit);
+                gl::BindTexture(gl::TEXTURE_2D, self.textures[2]);
+            }
+            self.shader.set_mat4("model", Matrix4::identity());
+            self.floor.draw_geometry();
+
+            //cubes
+            for unit in 0..2 {
+                gl::ActiveTexture(gl::TEXTURE0 + unit);
+                gl::BindTexture(gl::TEXTURE_2D, self.textures[unit as usize]);
+            }
+            for &model in cube_models.iter() {
+                self.shader.set_mat4("model", model);
+                self.cube.draw_geometry();
+            }
+
+            //the light itself
+            self.light_source_shader.use_program();
+            self.light_source_shader.set_mat4("projection", projection);
+            self.light_source_shader.set_mat4("view", view);
+            self.light_source_shader.set_mat4("model", Matrix4::from_translation(light_position) * Matrix4::from_scale(0.1));
+            self.cube.draw_geometry();
+            gl::BindVertexArray(0);
+        }
+    }
+}
+
+impl Drop for PointShadows {
+    fn drop(&mut self) {
+        unsafe { shapes::delete_textures(&self.textures); }
+    }
+}
@@ -0,0 +1,176 @@
+use cgmath::{perspective, Deg, EuclideanSpace, InnerSpace, Matrix4, Point3, SquareMatrix, Vector3};
+
+use crate::camera::Camera;
+use crate::input::InputState;
+use crate::mesh::{Geometry, Mesh};
+use crate::scene::{Frame, Scene};
+use crate::scenes::shapes;
+use crate::shader::Shader;
+use crate::shading::LightingModel;
+use crate::shadow::{self, Bounds, ShadowMap, ShadowSettings};
+use crate::utils;
+
+pub const LIGHT_DIRECTION: [f32; 3] = [2.0, -4.0, 1.0];
+
+// position, scale and angle around (1, 0, 1) of the cubes
+pub const CUBES: [([f32; 3], f32, f32); 3] = [
+    ([ 0.0, 1.5, 0.0], 0.5, 0.0),
+    ([ 2.0, 0.0, 1.0], 0.5, 0.0),
+    ([-1.0, 0.0, 2.0], 0.25, 60.0)
+];
+
+pub const SHADOW_MAP_SIZE: u32 = 1024;
+
+// the part of the floor around the cubes, the depth map covers only this box
+const SHADOW_BOUNDS: ([f32; 3], [f32; 3]) = ([-4.0, -0.5, -4.0], [4.0, 2.0, 4.0]);
+
+// pcf_kernel cycles through these radii
+const PCF_RADII: [u32; 4] = [0, 1, 2, 3];
+
+/// Chapter 5: cubes casting shadows of the directional light of `multiple_lights.fs` onto a floor.
+pub struct ShadowMapping {
+    camera: Camera,
+    shader: Shader,
+    floor: Mesh,
+    cube: Mesh,
+    // container diffuse and specular map, and the floor
+    textures: [u32; 3],
+    shadow_map: ShadowMap,
+    // the depth map in the bottom left corner, switched with shadow_debug
+    debug: bool,
+}
+
+impl ShadowMapping {
+    pub fn new() -> Self {
+        let shader = Shader::new(
+            "./src/shaders/2_lighting/lighting_maps.vs",
+            "./src/shaders/2_lighting/multiple_lights.fs"
+        );
+        shader.use_program();
+        shader.set_int("material.diffuseTex", 0);
+        shader.set_int("material.specularTex", 1);
+        shader.set_float("material.shininess", 16.0);
+        LightingModel::BlinnPhong.apply(&shader);
+
+        //directional light
+        shader.set_vector3v("dirLight.direction", Vector3::from(LIGHT_DIRECTION));
+        shader.set_vector3v("dirLight.ambient", Vector3::new(0.15, 0.15, 0.15));
+        shader.set_vector3v("dirLight.diffuse", Vector3::new(0.7, 0.7, 0.7));
+        shader.set_vector3v("dirLight.specular", Vector3::new(0.4, 0.4, 0.4));
+
+        //the point lights and the flashlight stay dark, without dividing by zero
+        for index in 0..4 {
+            shader.set_float(&format!("pointLight[{}].constant", index), 1.0);
+        }
+        shader.set_float("spotLight.constant", 1.0);
+        shader.set_float("spotLight.cutOff", 1.0);
+        shader.set_float("spotLight.outerCutOff", 0.9);
+        shadow::assign_shadow_units(&shader);
+
+        let textures = unsafe {
+            [
+                shapes::load_texture("./resources/textures/container2.png", true),
+                shapes::load_texture("./resources/textures/container2_specular.png", true),
+                shapes::load_texture("./resources/textures/metal.png", true),
+            ]
+        };
+
+        ShadowMapping {
+            camera: utils::camera_at(Point3::new(0.0, 1.0, 5.0)),
+            shader,
+            floor: shapes::lit_plane_mesh(),
+            cube: shapes::lit_cube_mesh(),
+            textures,
+            shadow_map: ShadowMap::new(SHADOW_MAP_SIZE).expect("Could not create the shadow map"),
+            debug: false,
+        }
+    }
+
+    fn cube_models() -> Vec<Matrix4<f32>> {
+        CUBES.iter().map(|&(position, scale, angle)| {
+            Matrix4::from_translation(Vector3::from(position))
+                * Matrix4::from_axis_angle(Vector3::new(1.0, 0.0, 1.0).normalize(), Deg(angle))
+                * Matrix4::from_scale(scale)
+        }).collect()
+    }
+}
+
+impl Scene for ShadowMapping {
+    fn camera(&mut self) -> Option<&mut Camera> {
+        Some(&mut self.camera)
+    }
+
+    fn handle_input(&mut self, input: &InputState) {
+        if input.pressed("shadow_debug") {
+            self.debug = !self.debug;
+        }
+        if input.pressed("pcf_kernel") {
+            let settings = self.shadow_map.settings();
+            let next = PCF_RADII.iter().position(|&radius| radius == settings.pcf_radius).map_or(0, |index| (index + 1) % PCF_RADII.len());
+            self.shadow_map.set_settings(ShadowSettings { pcf_radius: PCF_RADII[next], ..settings });
+            let size = 2 * PCF_RADII[next] + 1;
+            println!("PCF kernel: {}x{}", size, size);
+        }
+    }
+
+    fn render(&mut self, frame: &Frame) {
+        let cube_models = ShadowMapping::cube_models();
+
+        //depth from the light
+        let (min, max) = SHADOW_BOUNDS;
+        self.shadow_map.begin(Vector3::from(LIGHT_DIRECTION), &Bounds::new(Point3::from(min), Point3::from(max)));
+        self.shadow_map.cast(&self.floor, Matrix4::identity());
+        for &model in cube_models.iter() {
+            self.shadow_map.cast(&self.cube, model);
+        }
+        self.shadow_map.end();
+
+        let projection = perspective(Deg(45.0), frame.aspect_ratio(), 0.1, 100.0);
+        let view = self.camera.calculate_view();
+
+        unsafe {
+            gl::Enable(gl::DEPTH_TEST);
+
+            gl::ClearColor(0.1, 0.1, 0.1, 1.0);
+            gl::Clear(gl::COLOR_BUFFER_BIT | gl::DEPTH_BUFFER_BIT);
+
+            self.shader.use_program();
+            self.shader.set_mat4("projection", projection);
+            self.shader.set_mat4("view", view);
+            self.shader.set_vector3v("cameraPosition", self.camera.position.to_vec());
+            self.shadow_map.apply(&self.shader, 2);
+
+            //floor, metal as diffuse and specular map
+            for unit in 0..2 {
+                gl::ActiveTexture(gl::TEXTURE0 + unit);
+                gl::BindTexture(gl::TEXTURE_2D, self.textures[2]);
+            }
+            self.shader.set_mat4("model", Matrix4::identity());
+            self.floor.draw_geometry();
+
+            //cubes
+            for unit in 0..2 {
+                gl::ActiveTexture(gl::TEXTURE0 + unit);
+                gl::BindTexture(gl::TEXTURE_2D, self.textures[unit as usize]);
+            }
+            for &model in cube_models.iter() {
+                self.shader.set_mat4("model", model);
+                self.cube.draw_geometry();
+            }
+            gl::BindVertexArray(0);
+
+            if self.debug {
+                let size = (frame.width.min(frame.height) / 3) as i32;
+                gl::Viewport(0, 0, size, size);
+                self.shadow_map.draw_debug();
+                gl::Viewport(0, 0, frame.width as i32, frame.height as i32);
+            }
+        }
+    }
+}
+
+impl Drop for ShadowMapping {
+    fn drop(&mut self) {
+        unsafe { shapes::delete_textures(&self.textures); }
+    }
+}
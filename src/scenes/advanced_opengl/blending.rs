@@ -0,0 +1,188 @@
+use cgmath::{perspective, Deg, EuclideanSpace, Matrix4, Point3, SquareMatrix, Vector3};
+
+use crate::camera::Camera;
+use crate::instancing::{Instance, InstanceBuffer};
+use crate::model;
+use crate::render_queue::{Bucket, RenderQueue};
+use crate::render_state::{RenderState, StateCache};
+use crate::scene::{Frame, Scene};
+use crate::scenes::shapes;
+use crate::shader::Shader;
+use crate::utils;
+
+//positions for objects
+pub const VEGETATION_POSITIONS: [[f32; 3]; 3] = [
+    [-1.5, 0.0, -0.48],
+    [ 1.5, 0.0,  0.51],
+    [ 0.0, 0.0,  0.7]
+];
+
+pub const CUBE_POSITIONS: [[f32; 3]; 2] = [
+    [0.0, 0.0, 0.0],
+    [1.0, 0.0, 2.0]
+];
+
+pub const WINDOW_POSITIONS: [[f32; 3]; 3] = [
+    [-1.5, 0.0, -1.0],
+    [-0.3, 0.0, -2.3],
+    [ 0.5, 0.0, -0.6]
+];
+
+/// Chapter 4: grass discarded by alpha and semi-transparent windows blended over two cubes, sorted by a `RenderQueue`.
+pub struct Blending {
+    camera: Camera,
+    cube_shader: Shader,
+    plane_shader: Shader,
+    vegetation_shader: Shader,
+    window_shader: Shader,
+    cube_vao: shapes::VertexArray,
+    plane_vao: shapes::VertexArray,
+    quad_vao: shapes::VertexArray,
+    vegetation: InstanceBuffer,
+    // cube, plane, vegetation and window texture, bound to units 0 to 3
+    textures: [u32; 4],
+    state: StateCache,
+}
+
+impl Blending {
+    pub fn new() -> Self {
+        //set shader files
+        let cube_shader = Shader::new(
+            "./src/shaders/4_advanced_opengl/blending.vs",
+            "./src/shaders/4_advanced_opengl/blending.fs"
+        );
+        let plane_shader = Shader::new(
+            "./src/shaders/4_advanced_opengl/blending.vs",
+            "./src/shaders/4_advanced_opengl/blending.fs"
+        );
+        // the grass quads are instanced, their model matrices come from `vegetation`
+        let vegetation_shader = Shader::new(
+            "./src/shaders/4_advanced_opengl/blending_instanced.vs",
+            "./src/shaders/4_advanced_opengl/blending_object.fs"
+        );
+        let window_shader = Shader::new(
+            "./src/shaders/4_advanced_opengl/blending.vs",
+            "./src/shaders/4_advanced_opengl/blending.fs"
+        );
+
+        let (cube_vao, plane_vao, quad_vao, textures) = unsafe {
+            (
+                shapes::create_vao(&shapes::CUBE_VERTICES, &[3, 2]),
+                shapes::create_vao(&shapes::PLANE_VERTICES, &[3, 2]),
+                shapes::create_vao(&shapes::QUAD_VERTICES, &[3, 2]),
+                [
+                    model::texture_from_file("./resources/textures/marble.jpg"),
+                    model::texture_from_file("./resources/textures/metal.png"),
+                    model::texture_from_file_transparent("./resources/textures/grass.png"),
+                    model::texture_from_file("./resources/textures/blending_transparent_window.png"),
+                ]
+            )
+        };
+
+        let positions: Vec<Instance> = VEGETATION_POSITIONS.iter()
+            .map(|&position| Instance::new(Matrix4::from_translation(Vector3::from(position))))
+            .collect();
+        let vegetation = InstanceBuffer::new(&positions);
+        vegetation.attach(quad_vao.id);
+
+        for (unit, shader) in [&cube_shader, &plane_shader, &vegetation_shader, &window_shader].iter().enumerate() {
+            shader.use_program();
+            shader.set_int("texture0", unit as i32);
+        }
+
+        Blending {
+            camera: utils::camera_at(Point3::new(0.0, 0.5, 5.0)),
+            cube_shader,
+            plane_shader,
+            vegetation_shader,
+            window_shader,
+            cube_vao,
+            plane_vao,
+            quad_vao,
+            vegetation,
+            textures,
+            state: StateCache::new(),
+        }
+    }
+}
+
+impl Scene for Blending {
+    fn camera(&mut self) -> Option<&mut Camera> {
+        Some(&mut self.camera)
+    }
+
+    fn render(&mut self, frame: &Frame) {
+        let projection = perspective(Deg(45.0), frame.aspect_ratio(), 0.1, 100.0);
+        let view = self.camera.calculate_view();
+
+        // depth tested until the end of the frame, the queue switches blending per bucket
+        let _depth_tested = self.state.scope(&RenderState::opaque());
+        unsafe {
+            //reset context
+            gl::ClearColor(0.2, 0.2, 0.4, 1.0);
+            gl::Clear(gl::COLOR_BUFFER_BIT | gl::DEPTH_BUFFER_BIT);
+
+            for (unit, &texture) in self.textures.iter().enumerate() {
+                gl::ActiveTexture(gl::TEXTURE0 + unit as u32);
+                gl::BindTexture(gl::TEXTURE_2D, texture);
+            }
+        }
+
+        for shader in [&self.plane_shader, &self.cube_shader, &self.vegetation_shader, &self.window_shader].iter() {
+            shader.use_program();
+            shader.set_mat4("projection", projection);
+            shader.set_mat4("view", view);
+        }
+
+        // the queue sorts the draws, so the windows can be pushed in any order
+        let mut queue = RenderQueue::new();
+
+        //draw plane
+        let (plane_shader, plane_vao) = (&self.plane_shader, &self.plane_vao);
+        queue.push(Bucket::Opaque, Vector3::new(0.0, -0.5, 0.0), move || unsafe {
+            plane_shader.use_program();
+            plane_shader.set_mat4("model", Matrix4::identity());
+            gl::BindVertexArray(plane_vao.id);
+            gl::DrawArrays(gl::TRIANGLES, 0, 6);
+        });
+
+        //draw cubes
+        for &position in CUBE_POSITIONS.iter() {
+            let (cube_shader, cube_vao) = (&self.cube_shader, &self.cube_vao);
+            queue.push(Bucket::Opaque, Vector3::from(position), move || unsafe {
+                cube_shader.use_program();
+                cube_shader.set_mat4("model", Matrix4::from_translation(Vector3::from(position)));
+                gl::BindVertexArray(cube_vao.id);
+                gl::DrawArrays(gl::TRIANGLES, 0, 36);
+            });
+        }
+
+        //draw vegetation, all grass quads in one call
+        let (vegetation_shader, quad_vao, vegetation) = (&self.vegetation_shader, &self.quad_vao, &self.vegetation);
+        queue.push(Bucket::AlphaTested, Vector3::new(0.0, 0.0, 0.0), move || unsafe {
+            vegetation_shader.use_program();
+            gl::BindVertexArray(quad_vao.id);
+            gl::DrawArraysInstanced(gl::TRIANGLES, 0, 6, vegetation.len() as i32);
+        });
+
+        //windows are blended, the farthest one first
+        for &position in WINDOW_POSITIONS.iter() {
+            let (window_shader, quad_vao) = (&self.window_shader, &self.quad_vao);
+            queue.push(Bucket::Transparent, Vector3::from(position), move || unsafe {
+                window_shader.use_program();
+                window_shader.set_mat4("model", Matrix4::from_translation(Vector3::from(position)));
+                gl::BindVertexArray(quad_vao.id);
+                gl::DrawArrays(gl::TRIANGLES, 0, 6);
+            });
+        }
+
+        queue.flush(self.camera.position.to_vec());
+        unsafe { gl::BindVertexArray(0); }
+    }
+}
+
+impl Drop for Blending {
+    fn drop(&mut self) {
+        unsafe { shapes::delete_textures(&self.textures); }
+    }
+}
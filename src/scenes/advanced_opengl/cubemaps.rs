@@ -0,0 +1,97 @@
+use cgmath::{perspective, Deg, InnerSpace, Matrix4, Point3, Vector3};
+
+use crate::camera::Camera;
+use crate::cubemap::{self, Skybox};
+use crate::model;
+use crate::scene::{Frame, Scene};
+use crate::scenes::shapes;
+use crate::shader::Shader;
+use crate::utils;
+
+// the faces in GL's order: right, left, top, bottom, front (+Z) and back (-Z)
+pub const SKYBOX_FACES: [&str; 6] = [
+    "./resources/textures/skybox/right.jpg",
+    "./resources/textures/skybox/left.jpg",
+    "./resources/textures/skybox/top.jpg",
+    "./resources/textures/skybox/bottom.jpg",
+    "./resources/textures/skybox/front.jpg",
+    "./resources/textures/skybox/back.jpg",
+];
+
+/// Chapter 4: container cubes in front of a skybox, the sky is drawn last with the LEQUAL depth trick.
+pub struct Cubemaps {
+    camera: Camera,
+    cube_shader: Shader,
+    cube_vao: shapes::VertexArray,
+    cube_tex: u32,
+    skybox: Skybox,
+    skybox_tex: u32,
+}
+
+impl Cubemaps {
+    pub fn new() -> Self {
+        let cube_shader = Shader::new(
+            "./src/shaders/4_advanced_opengl/blending.vs",
+            "./src/shaders/4_advanced_opengl/blending.fs"
+        );
+        cube_shader.use_program();
+        cube_shader.set_int("texture0", 0);
+
+        let (cube_vao, cube_tex, skybox_tex) = unsafe {
+            (
+                shapes::create_vao(&shapes::CUBE_VERTICES, &[3, 2]),
+                model::texture_from_file("./resources/textures/container.jpg"),
+                cubemap::cubemap_from_files(&SKYBOX_FACES).expect("Could not load the skybox"),
+            )
+        };
+
+        Cubemaps {
+            camera: utils::camera_at(Point3::new(0.0, 0.5, 4.0)),
+            cube_shader,
+            cube_vao,
+            cube_tex,
+            skybox: Skybox::new(),
+            skybox_tex,
+        }
+    }
+}
+
+impl Scene for Cubemaps {
+    fn camera(&mut self) -> Option<&mut Camera> {
+        Some(&mut self.camera)
+    }
+
+    fn render(&mut self, frame: &Frame) {
+        let projection = perspective(Deg(45.0), frame.aspect_ratio(), 0.1, 100.0);
+        let view = self.camera.calculate_view();
+
+        unsafe {
+            gl::Enable(gl::DEPTH_TEST);
+            gl::ClearColor(0.1, 0.1, 0.1, 1.0);
+            gl::Clear(gl::COLOR_BUFFER_BIT | gl::DEPTH_BUFFER_BIT);
+
+            //draw cubes first, the skybox only fills what is left
+            self.cube_shader.use_program();
+            self.cube_shader.set_mat4("projection", projection);
+            self.cube_shader.set_mat4("view", view);
+            gl::ActiveTexture(gl::TEXTURE0);
+            gl::BindTexture(gl::TEXTURE_2D, self.cube_tex);
+            gl::BindVertexArray(self.cube_vao.id);
+            for (index, &position) in [Vector3::new(-1.0, 0.0, -1.0), Vector3::new(1.5, 0.5, -2.5)].iter().enumerate() {
+                let angle = Deg(20.0 * frame.time + 35.0 * index as f32);
+                let model = Matrix4::from_translation(position) * Matrix4::from_axis_angle(Vector3::new(0.3, 1.0, 0.0).normalize(), angle);
+                self.cube_shader.set_mat4("model", model);
+                gl::DrawArrays(gl::TRIANGLES, 0, 36);
+            }
+            gl::BindVertexArray(0);
+        }
+
+        self.skybox.draw(self.skybox_tex, view, projection);
+    }
+}
+
+impl Drop for Cubemaps {
+    fn drop(&mut self) {
+        unsafe { shapes::delete_textures(&[self.cube_tex, self.skybox_tex]); }
+    }
+}
@@ -0,0 +1,72 @@
+use cgmath::{perspective, Deg, Matrix4, Point3, Vector3};
+
+use crate::camera::Camera;
+use crate::render_state::{RenderState, StateCache};
+use crate::scene::{Frame, Scene};
+use crate::scenes::shapes;
+use crate::shader::Shader;
+use crate::utils;
+
+/// Chapter 4: visualizes the linearized depth buffer of two cubes on a plane.
+pub struct DepthTesting {
+    camera: Camera,
+    depth_shader: Shader,
+    cube_vao: shapes::VertexArray,
+    plane_vao: shapes::VertexArray,
+    state: StateCache,
+}
+
+impl DepthTesting {
+    pub fn new() -> Self {
+        let depth_shader = Shader::new(
+            "./src/shaders/4_advanced_opengl/depth_testing.vs",
+            "./src/shaders/4_advanced_opengl/depth_testing.fs"
+        );
+
+        let (cube_vao, plane_vao) = unsafe {
+            (shapes::create_vao(&shapes::CUBE_VERTICES, &[3, 2]), shapes::create_vao(&shapes::PLANE_VERTICES, &[3, 2]))
+        };
+
+        DepthTesting {
+            camera: utils::camera_at(Point3::new(0.0, 0.5, 5.0)),
+            depth_shader,
+            cube_vao,
+            plane_vao,
+            state: StateCache::new(),
+        }
+    }
+}
+
+impl Scene for DepthTesting {
+    fn camera(&mut self) -> Option<&mut Camera> {
+        Some(&mut self.camera)
+    }
+
+    fn render(&mut self, frame: &Frame) {
+        // enable depth perspective, until the end of the frame
+        let _depth_tested = self.state.scope(&RenderState::opaque());
+        unsafe {
+            gl::ClearColor(0.2, 0.2, 0.2, 1.0);
+            gl::Clear(gl::COLOR_BUFFER_BIT | gl::DEPTH_BUFFER_BIT);
+
+            //draw plane
+            self.depth_shader.use_program();
+            //recalculate stuff
+            let projection = perspective(Deg(45.0), frame.aspect_ratio(), 0.1, 100.0);
+            self.depth_shader.set_mat4("projection", projection);
+            self.depth_shader.set_mat4("view", self.camera.calculate_view());
+            self.depth_shader.set_mat4("model", Matrix4::from_translation(Vector3::new(0.0, 0.0, 0.0)));
+
+            gl::BindVertexArray(self.plane_vao.id);
+            gl::DrawArrays(gl::TRIANGLES, 0, 6);
+
+            gl::BindVertexArray(self.cube_vao.id);
+            gl::DrawArrays(gl::TRIANGLES, 0, 36);
+
+            self.depth_shader.set_mat4("model", Matrix4::from_translation(Vector3::new(1.0, 0.0, 2.0)));
+
+            gl::BindVertexArray(self.cube_vao.id);
+            gl::DrawArrays(gl::TRIANGLES, 0, 36);
+        }
+    }
+}
@@ -0,0 +1,148 @@
+use cgmath::{perspective, Deg, EuclideanSpace, InnerSpace, Matrix4, Point3, Vector3};
+
+use crate::camera::Camera;
+use crate::cubemap::{self, Skybox};
+use crate::environment::{EnvironmentMaterial, EnvironmentProbe, IOR_GLASS, IOR_WATER};
+use crate::model;
+use crate::scene::{Frame, Scene};
+use crate::scenes::advanced_opengl::cubemaps::SKYBOX_FACES;
+use crate::scenes::shapes;
+use crate::shader::Shader;
+use crate::utils;
+
+// the probe renders every other frame, faces of 128 pixels are plenty for a cube this small
+const PROBE_SIZE: u32 = 128;
+const PROBE_INTERVAL: u32 = 2;
+
+// everything the probe sees: the sky and two marble cubes circling the mirror
+struct Surroundings {
+    shader: Shader,
+    vao: shapes::VertexArray,
+    texture: u32,
+    skybox: Skybox,
+    skybox_tex: u32,
+}
+
+impl Surroundings {
+    fn draw(&self, view: Matrix4<f32>, projection: Matrix4<f32>, time: f32) {
+        unsafe {
+            self.shader.use_program();
+            self.shader.set_mat4("projection", projection);
+            self.shader.set_mat4("view", view);
+            gl::ActiveTexture(gl::TEXTURE0);
+            gl::BindTexture(gl::TEXTURE_2D, self.texture);
+            gl::BindVertexArray(self.vao.id);
+            for index in 0..2 {
+                // they start on both sides of the mirror, where its faces show them to the camera
+                let angle = 0.8 * time + std::f32::consts::PI * (index as f32 + 0.5);
+                let position = Vector3::new(1.8 * angle.sin(), 0.0, 1.8 * angle.cos());
+                let model = Matrix4::from_translation(position)
+                    * Matrix4::from_axis_angle(Vector3::new(0.5, 1.0, 0.0).normalize(), Deg(40.0 * time))
+                    * Matrix4::from_scale(0.6);
+                self.shader.set_mat4("model", model);
+                gl::DrawArrays(gl::TRIANGLES, 0, 36);
+            }
+            gl::BindVertexArray(0);
+        }
+        self.skybox.draw(self.skybox_tex, view, projection);
+    }
+}
+
+impl Drop for Surroundings {
+    fn drop(&mut self) {
+        unsafe { shapes::delete_textures(&[self.texture, self.skybox_tex]); }
+    }
+}
+
+/// Chapter 4: a mirror cube reflecting the marble cubes around it through a dynamic probe,
+/// next to a glass and a water cube that only see the static skybox.
+pub struct EnvironmentMapping {
+    camera: Camera,
+    surroundings: Surroundings,
+    shader: Shader,
+    vao: shapes::VertexArray,
+    probe: EnvironmentProbe,
+}
+
+impl EnvironmentMapping {
+    pub fn new() -> Self {
+        let shader = Shader::new(
+            "./src/shaders/4_advanced_opengl/blending.vs",
+            "./src/shaders/4_advanced_opengl/blending.fs"
+        );
+        shader.use_program();
+        shader.set_int("texture0", 0);
+
+        let surroundings = unsafe {
+            Surroundings {
+                shader,
+                vao: shapes::create_vao(&shapes::CUBE_VERTICES, &[3, 2]),
+                texture: model::texture_from_file("./resources/textures/marble.jpg"),
+                skybox: Skybox::new(),
+                skybox_tex: cubemap::cubemap_from_files(&SKYBOX_FACES).expect("Could not load the skybox"),
+            }
+        };
+
+        EnvironmentMapping {
+            camera: utils::camera_at(Point3::new(0.0, 0.3, 5.0)),
+            surroundings,
+            shader: EnvironmentMaterial::shader(),
+            // the environment shader needs the normals
+            vao: unsafe { shapes::create_vao(&shapes::LIT_CUBE_VERTICES, &[3, 3, 2]) },
+            probe: EnvironmentProbe::new(Vector3::new(0.0, 0.0, 0.0), PROBE_SIZE, PROBE_INTERVAL)
+                .expect("Could not create the environment probe"),
+        }
+    }
+}
+
+impl Scene for EnvironmentMapping {
+    fn camera(&mut self) -> Option<&mut Camera> {
+        Some(&mut self.camera)
+    }
+
+    fn render(&mut self, frame: &Frame) {
+        let projection = perspective(Deg(45.0), frame.aspect_ratio(), 0.1, 100.0);
+        let view = self.camera.calculate_view();
+        let camera_position = self.camera.position.to_vec();
+
+        unsafe {
+            gl::Enable(gl::DEPTH_TEST);
+            // the probe's faces are rendered separately, without this their edges show in the mirror
+            gl::Enable(gl::TEXTURE_CUBE_MAP_SEAMLESS);
+        }
+
+        // the mirror itself is not drawn into its own probe
+        let surroundings = &self.surroundings;
+        self.probe.update(|view, projection| surroundings.draw(view, projection, frame.time));
+
+        unsafe {
+            gl::ClearColor(0.1, 0.1, 0.1, 1.0);
+            gl::Clear(gl::COLOR_BUFFER_BIT | gl::DEPTH_BUFFER_BIT);
+        }
+
+        self.shader.use_program();
+        self.shader.set_mat4("projection", projection);
+        self.shader.set_mat4("view", view);
+        let objects = [
+            (EnvironmentMaterial::reflective(), self.probe.texture(), self.probe.position),
+            (EnvironmentMaterial::refractive(IOR_GLASS), self.surroundings.skybox_tex, Vector3::new(-2.0, 1.2, -1.5)),
+            (
+                EnvironmentMaterial::fresnel(IOR_WATER).with_tint(Vector3::new(0.8, 0.9, 1.0)),
+                self.surroundings.skybox_tex,
+                Vector3::new(2.0, 1.2, -1.5)
+            ),
+        ];
+        for (material, cubemap, position) in objects.iter() {
+            material.apply(&self.shader, *cubemap, camera_position);
+            let model = Matrix4::from_translation(*position) * Matrix4::from_angle_y(Deg(45.0));
+            self.shader.set_mat4("model", model);
+            unsafe {
+                gl::BindVertexArray(self.vao.id);
+                gl::DrawArrays(gl::TRIANGLES, 0, 36);
+                gl::BindVertexArray(0);
+            }
+        }
+
+        self.surroundings.draw(view, projection, frame.time);
+    }
+}
@@ -0,0 +1,96 @@
+use cgmath::{perspective, Deg, Matrix4, Point3, Vector3};
+
+use crate::camera::Camera;
+use crate::model;
+use crate::render_state::{CullState, RenderState, StateCache};
+use crate::scene::{Frame, Scene};
+use crate::scenes::shapes;
+use crate::shader::Shader;
+use crate::utils;
+
+/// Chapter 4: two marble cubes, the second one with its front faces culled.
+pub struct FaceCulling {
+    camera: Camera,
+    cube_shader: Shader,
+    cube_vao: shapes::VertexArray,
+    cube_tex: u32,
+    state: StateCache,
+}
+
+impl FaceCulling {
+    pub fn new() -> Self {
+        let cube_shader = Shader::new(
+            "./src/shaders/4_advanced_opengl/blending.vs",
+            "./src/shaders/4_advanced_opengl/blending.fs"
+        );
+
+        let (cube_vao, cube_tex) = unsafe {
+            let cube_vao = shapes::create_vao(&shapes::CUBE_VERTICES, &[3, 2]);
+            let cube_tex = model::texture_from_file("./resources/textures/marble.jpg");
+            cube_shader.use_program();
+            cube_shader.set_int("texture0", 0);
+            (cube_vao, cube_tex)
+        };
+
+        FaceCulling {
+            camera: utils::camera_at(Point3::new(0.0, 0.5, 5.0)),
+            cube_shader,
+            cube_vao,
+            cube_tex,
+            // built once, the scopes in render put back what they change
+            state: StateCache::new(),
+        }
+    }
+}
+
+impl Scene for FaceCulling {
+    fn camera(&mut self) -> Option<&mut Camera> {
+        Some(&mut self.camera)
+    }
+
+    fn render(&mut self, frame: &Frame) {
+        unsafe {
+            //reset context
+            gl::ClearColor(0.2, 0.2, 0.4, 1.0);
+            gl::Clear(gl::COLOR_BUFFER_BIT | gl::DEPTH_BUFFER_BIT);
+        }
+
+        //cull face function, to specify which face to cull
+        //gl::BACK is default, other options are gl::FRONT and gl::FRONT_AND_BACK
+        //front face tells opengl which faces are supposed to be front
+        //determined by CounterClockWise or ClockWise
+        //vertices are set up in a CCW -> front face way
+        //more info in https://learnopengl.com/Advanced-OpenGL/Face-culling
+        let back_faces = RenderState { cull: CullState::culling(gl::BACK, gl::CCW), ..RenderState::opaque() };
+
+        let projection = perspective(Deg(45.0), frame.aspect_ratio(), 0.1, 100.0);
+        self.cube_shader.use_program();
+        self.cube_shader.set_mat4("projection", projection);
+        self.cube_shader.set_mat4("view", self.camera.calculate_view());
+
+        // the scopes switch culling on and put the state back when they end, so the
+        // clockwise front faces of the second cube don't leak into the next frame
+        let mut culled = self.state.scope(&back_faces);
+        unsafe {
+            gl::ActiveTexture(gl::TEXTURE0);
+            gl::BindTexture(gl::TEXTURE_2D, self.cube_tex);
+            gl::BindVertexArray(self.cube_vao.id);
+
+            self.cube_shader.set_mat4("model", Matrix4::from_translation(Vector3::new(-1.0, 0.0, 2.0)));
+            gl::DrawArrays(gl::TRIANGLES, 0, 36);
+        }
+
+        //will cull the "front" face of the cube
+        let _clockwise = culled.scope(&RenderState { cull: CullState::culling(gl::BACK, gl::CW), ..back_faces });
+        unsafe {
+            self.cube_shader.set_mat4("model", Matrix4::from_translation(Vector3::new(1.0, 0.0, 2.0)));
+            gl::DrawArrays(gl::TRIANGLES, 0, 36);
+        }
+    }
+}
+
+impl Drop for FaceCulling {
+    fn drop(&mut self) {
+        unsafe { shapes::delete_textures(&[self.cube_tex]); }
+    }
+}
@@ -0,0 +1,151 @@
+use cgmath::{perspective, Deg, Matrix4, Point3, SquareMatrix, Vector3};
+
+use crate::camera::Camera;
+use crate::framebuffer::{Framebuffer, FramebufferDesc, FullscreenQuad, FULLSCREEN_VERTEX_SHADER};
+use crate::model;
+use crate::scene::{Frame, Scene};
+use crate::scenes::shapes;
+use crate::shader::Shader;
+use crate::utils;
+
+// the rear-view mirror covers this fraction of the window width and height
+const MIRROR_SIZE: f32 = 0.25;
+
+/// Chapter 4: the scene rendered into a texture and shown on a fullscreen quad, with a rear-view mirror.
+pub struct Framebuffers {
+    camera: Camera,
+    object_shader: Shader,
+    screen_shader: Shader,
+    cube_vao: shapes::VertexArray,
+    plane_vao: shapes::VertexArray,
+    cube_tex: u32,
+    plane_tex: u32,
+    quad: FullscreenQuad,
+    // both are resized to the frame in render
+    scene_target: Framebuffer,
+    mirror_target: Framebuffer,
+}
+
+impl Framebuffers {
+    pub fn new() -> Self {
+        let object_shader = Shader::new(
+            "./src/shaders/4_advanced_opengl/blending.vs",
+            "./src/shaders/4_advanced_opengl/blending.fs"
+        );
+        let screen_shader = Shader::new(
+            FULLSCREEN_VERTEX_SHADER,
+            "./src/shaders/4_advanced_opengl/framebuffers_screen.fs"
+        );
+
+        let (cube_vao, plane_vao, cube_tex, plane_tex) = unsafe {
+            (
+                shapes::create_vao(&shapes::CUBE_VERTICES, &[3, 2]),
+                shapes::create_vao(&shapes::PLANE_VERTICES, &[3, 2]),
+                model::texture_from_file("./resources/textures/container.jpg"),
+                model::texture_from_file("./resources/textures/metal.png"),
+            )
+        };
+        object_shader.use_program();
+        object_shader.set_int("texture0", 0);
+        screen_shader.use_program();
+        screen_shader.set_int("screenTexture", 0);
+
+        Framebuffers {
+            camera: utils::camera_at(Point3::new(0.0, 0.5, 5.0)),
+            object_shader,
+            screen_shader,
+            cube_vao,
+            plane_vao,
+            cube_tex,
+            plane_tex,
+            quad: FullscreenQuad::new(),
+            scene_target: Framebuffer::new(FramebufferDesc::color_depth(1, 1)).expect("Could not create the scene framebuffer"),
+            mirror_target: Framebuffer::new(FramebufferDesc::color_depth(1, 1)).expect("Could not create the mirror framebuffer"),
+        }
+    }
+
+    // the cubes on the floor, into the bound framebuffer
+    fn draw_objects(&self, projection: Matrix4<f32>, view: Matrix4<f32>) {
+        unsafe {
+            gl::Enable(gl::DEPTH_TEST);
+            gl::ClearColor(0.1, 0.1, 0.1, 1.0);
+            gl::Clear(gl::COLOR_BUFFER_BIT | gl::DEPTH_BUFFER_BIT);
+
+            self.object_shader.use_program();
+            self.object_shader.set_mat4("projection", projection);
+            self.object_shader.set_mat4("view", view);
+            gl::ActiveTexture(gl::TEXTURE0);
+
+            //draw plane
+            gl::BindTexture(gl::TEXTURE_2D, self.plane_tex);
+            self.object_shader.set_mat4("model", Matrix4::identity());
+            gl::BindVertexArray(self.plane_vao.id);
+            gl::DrawArrays(gl::TRIANGLES, 0, 6);
+
+            //draw cubes
+            gl::BindTexture(gl::TEXTURE_2D, self.cube_tex);
+            gl::BindVertexArray(self.cube_vao.id);
+            for position in [Vector3::new(-1.0, 0.0, -1.0), Vector3::new(2.0, 0.0, 0.0), Vector3::new(0.0, 0.0, 8.0)] {
+                self.object_shader.set_mat4("model", Matrix4::from_translation(position));
+                gl::DrawArrays(gl::TRIANGLES, 0, 36);
+            }
+            gl::BindVertexArray(0);
+        }
+    }
+}
+
+impl Scene for Framebuffers {
+    fn camera(&mut self) -> Option<&mut Camera> {
+        Some(&mut self.camera)
+    }
+
+    fn render(&mut self, frame: &Frame) {
+        let mirror_width = ((frame.width as f32 * MIRROR_SIZE) as u32).max(1);
+        let mirror_height = ((frame.height as f32 * MIRROR_SIZE) as u32).max(1);
+        self.scene_target.resize(frame.width, frame.height).expect("Could not resize the scene framebuffer");
+        self.mirror_target.resize(mirror_width, mirror_height).expect("Could not resize the mirror framebuffer");
+
+        let projection = perspective(Deg(45.0), frame.aspect_ratio(), 0.1, 100.0);
+
+        // first pass: the scene into a texture instead of the window
+        self.scene_target.bind();
+        self.draw_objects(projection, self.camera.calculate_view());
+
+        // the mirror looks backwards from the same position
+        let camera = &self.camera;
+        let mirror_view = Matrix4::look_at(camera.position, camera.position - camera.front, camera.up);
+        self.mirror_target.bind();
+        self.draw_objects(projection, mirror_view);
+
+        // second pass: the texture on a quad covering the frame
+        unsafe {
+            gl::BindFramebuffer(gl::FRAMEBUFFER, frame.framebuffer);
+            gl::Viewport(0, 0, frame.width as i32, frame.height as i32);
+            gl::ClearColor(1.0, 1.0, 1.0, 1.0);
+            gl::Clear(gl::COLOR_BUFFER_BIT);
+        }
+        self.quad.pass(&self.screen_shader, &[self.scene_target.color_texture(0)]);
+
+        // the mirror is copied to the top center without a shader, blits ignore the viewport
+        let (x0, y1) = ((frame.width - mirror_width) as i32 / 2, frame.height as i32 - 10);
+        unsafe {
+            // a white frame around the mirror, clears are limited by the scissor test
+            gl::Enable(gl::SCISSOR_TEST);
+            gl::Scissor(x0 - 2, y1 - mirror_height as i32 - 2, mirror_width as i32 + 4, mirror_height as i32 + 4);
+            gl::Clear(gl::COLOR_BUFFER_BIT);
+            gl::Disable(gl::SCISSOR_TEST);
+        }
+        self.mirror_target.blit_region(
+            frame.framebuffer,
+            [x0, y1 - mirror_height as i32, x0 + mirror_width as i32, y1],
+            gl::COLOR_BUFFER_BIT,
+            gl::NEAREST
+        );
+    }
+}
+
+impl Drop for Framebuffers {
+    fn drop(&mut self) {
+        unsafe { shapes::delete_textures(&[self.cube_tex, self.plane_tex]); }
+    }
+}
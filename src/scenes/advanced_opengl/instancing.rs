@@ -0,0 +1,129 @@
+use cgmath::{perspective, Deg, Matrix4, Point3, Vector3, Vector4};
+use rand::Rng;
+
+use crate::camera::Camera;
+use crate::instancing::{Instance, InstanceBuffer};
+use crate::model;
+use crate::scene::{self, Frame, Scene};
+use crate::scenes::shapes;
+use crate::shader::Shader;
+use crate::utils;
+
+// blades on a square of FIELD_SIZE units around the origin
+const BLADE_COUNT: usize = 10_000;
+const FIELD_SIZE: f32 = 20.0;
+
+/// Chapter 4: ten thousand swaying grass blades on a metal floor, drawn with one instanced call.
+pub struct Instancing {
+    camera: Camera,
+    grass_shader: Shader,
+    floor_shader: Shader,
+    quad_vao: shapes::VertexArray,
+    floor_vao: shapes::VertexArray,
+    blades: InstanceBuffer,
+    // grass and floor texture, bound to units 0 and 1
+    textures: [u32; 2],
+}
+
+impl Instancing {
+    pub fn new() -> Self {
+        let grass_shader = Shader::new(
+            "./src/shaders/4_advanced_opengl/instancing_grass.vs",
+            "./src/shaders/4_advanced_opengl/instancing_grass.fs"
+        );
+        let floor_shader = Shader::new(
+            "./src/shaders/4_advanced_opengl/blending.vs",
+            "./src/shaders/4_advanced_opengl/blending.fs"
+        );
+        grass_shader.use_program();
+        grass_shader.set_int("texture0", 0);
+        floor_shader.use_program();
+        floor_shader.set_int("texture0", 1);
+
+        // random position, turn, size and tint per blade, the data holds phase and strength of the wind
+        let mut rng = scene::seeded_rng();
+        let instances: Vec<Instance> = (0..BLADE_COUNT).map(|_| {
+            let (x, z) = (rng.gen_range(-0.5, 0.5) * FIELD_SIZE, rng.gen_range(-0.5, 0.5) * FIELD_SIZE);
+            let scale = rng.gen_range(0.3, 0.7);
+            // the quad's root moves to the origin, so it turns around its middle and stands on the floor
+            let model = Matrix4::from_translation(Vector3::new(x, -0.5, z))
+                * Matrix4::from_angle_y(Deg(rng.gen_range(0.0, 180.0)))
+                * Matrix4::from_scale(scale)
+                * Matrix4::from_translation(Vector3::new(-0.5, 0.5, 0.0));
+            let green = rng.gen_range(0.7, 1.0);
+            Instance {
+                model,
+                color: Vector4::new(green * 0.8, green, green * 0.7, 1.0),
+                data: Vector4::new(rng.gen_range(0.0, 2.0 * std::f32::consts::PI), rng.gen_range(0.05, 0.15), 0.0, 0.0),
+            }
+        }).collect();
+
+        let (quad_vao, floor_vao, textures) = unsafe {
+            (
+                shapes::create_vao(&shapes::QUAD_VERTICES, &[3, 2]),
+                shapes::create_vao(&shapes::PLANE_VERTICES, &[3, 2]),
+                [
+                    model::texture_from_file_transparent("./resources/textures/grass.png"),
+                    model::texture_from_file("./resources/textures/metal.png"),
+                ]
+            )
+        };
+        let blades = InstanceBuffer::new(&instances);
+        blades.attach(quad_vao.id);
+
+        Instancing {
+            camera: utils::camera_at(Point3::new(0.0, 1.0, 6.0)),
+            grass_shader,
+            floor_shader,
+            quad_vao,
+            floor_vao,
+            blades,
+            textures,
+        }
+    }
+}
+
+impl Scene for Instancing {
+    fn camera(&mut self) -> Option<&mut Camera> {
+        Some(&mut self.camera)
+    }
+
+    fn render(&mut self, frame: &Frame) {
+        let projection = perspective(Deg(45.0), frame.aspect_ratio(), 0.1, 100.0);
+        let view = self.camera.calculate_view();
+
+        unsafe {
+            gl::Enable(gl::DEPTH_TEST);
+            gl::ClearColor(0.5, 0.7, 0.9, 1.0);
+            gl::Clear(gl::COLOR_BUFFER_BIT | gl::DEPTH_BUFFER_BIT);
+
+            for (unit, &texture) in self.textures.iter().enumerate() {
+                gl::ActiveTexture(gl::TEXTURE0 + unit as u32);
+                gl::BindTexture(gl::TEXTURE_2D, texture);
+            }
+
+            // the floor plane is 10 units wide, stretched to the field
+            self.floor_shader.use_program();
+            self.floor_shader.set_mat4("projection", projection);
+            self.floor_shader.set_mat4("view", view);
+            self.floor_shader.set_mat4("model", Matrix4::from_nonuniform_scale(FIELD_SIZE / 10.0, 1.0, FIELD_SIZE / 10.0));
+            gl::BindVertexArray(self.floor_vao.id);
+            gl::DrawArrays(gl::TRIANGLES, 0, 6);
+
+            // every blade in one call
+            self.grass_shader.use_program();
+            self.grass_shader.set_mat4("projection", projection);
+            self.grass_shader.set_mat4("view", view);
+            self.grass_shader.set_float("time", frame.time);
+            gl::BindVertexArray(self.quad_vao.id);
+            gl::DrawArraysInstanced(gl::TRIANGLES, 0, 6, self.blades.len() as i32);
+            gl::BindVertexArray(0);
+        }
+    }
+}
+
+impl Drop for Instancing {
+    fn drop(&mut self) {
+        unsafe { shapes::delete_textures(&self.textures); }
+    }
+}
@@ -0,0 +1,12 @@
+//! Chapter 4: Advanced OpenGL
+
+pub mod depth_testing;
+pub mod stencil_testing;
+pub mod blending;
+pub mod face_culling;
+pub mod framebuffers;
+pub mod post_processing;
+pub mod cubemaps;
+pub mod environment_mapping;
+pub mod instancing;
+pub mod order_independent_transparency;
@@ -0,0 +1,158 @@
+use cgmath::{perspective, Deg, EuclideanSpace, Matrix4, Point3, SquareMatrix, Vector3, Vector4};
+
+use crate::camera::Camera;
+use crate::model;
+use crate::oit::OitPass;
+use crate::render_queue::{Bucket, RenderQueue};
+use crate::scene::{Frame, Scene};
+use crate::scenes::shapes;
+use crate::shader::Shader;
+use crate::utils;
+
+// three panes crossing in their middle, no order of them is right for every pixel
+const PANE_COLORS: [[f32; 4]; 3] = [
+    [1.0, 0.2, 0.2, 0.5],
+    [0.2, 1.0, 0.2, 0.5],
+    [0.2, 0.4, 1.0, 0.5],
+];
+
+// the sorted panes on the left, the order-independent ones on the right
+const SORTED_CENTER: [f32; 3] = [-1.2, 0.1, 0.0];
+const OIT_CENTER: [f32; 3] = [1.2, 0.1, 0.0];
+
+/// Chapter 4: intersecting glass panes, sorted per pane on the left and with weighted blended
+/// order-independent transparency on the right.
+pub struct OrderIndependentTransparency {
+    camera: Camera,
+    texture_shader: Shader,
+    sorted_shader: Shader,
+    oit_shader: Shader,
+    floor_vao: shapes::VertexArray,
+    cube_vao: shapes::VertexArray,
+    pane_vao: shapes::VertexArray,
+    // marble and metal, bound to units 0 and 1
+    textures: [u32; 2],
+    oit: OitPass,
+}
+
+impl OrderIndependentTransparency {
+    pub fn new() -> Self {
+        let texture_shader = Shader::new(
+            "./src/shaders/4_advanced_opengl/blending.vs",
+            "./src/shaders/4_advanced_opengl/blending.fs"
+        );
+        let sorted_shader = Shader::new(
+            "./src/shaders/4_advanced_opengl/blending.vs",
+            "./src/shaders/4_advanced_opengl/transparent_color.fs"
+        );
+        let oit_shader = Shader::new(
+            "./src/shaders/4_advanced_opengl/blending.vs",
+            "./src/shaders/4_advanced_opengl/oit_accumulate.fs"
+        );
+
+        let (floor_vao, cube_vao, pane_vao, textures) = unsafe {
+            (
+                shapes::create_vao(&shapes::PLANE_VERTICES, &[3, 2]),
+                shapes::create_vao(&shapes::CUBE_VERTICES, &[3, 2]),
+                shapes::create_vao(&shapes::QUAD_VERTICES, &[3, 2]),
+                [
+                    model::texture_from_file("./resources/textures/marble.jpg"),
+                    model::texture_from_file("./resources/textures/metal.png"),
+                ]
+            )
+        };
+
+        OrderIndependentTransparency {
+            camera: utils::camera_at(Point3::new(0.0, 0.6, 5.0)),
+            texture_shader,
+            sorted_shader,
+            oit_shader,
+            floor_vao,
+            cube_vao,
+            pane_vao,
+            textures,
+            // resized to the viewport on the first frame
+            oit: OitPass::new(1, 1).expect("Could not create the OIT targets"),
+        }
+    }
+}
+
+impl Scene for OrderIndependentTransparency {
+    fn camera(&mut self) -> Option<&mut Camera> {
+        Some(&mut self.camera)
+    }
+
+    fn render(&mut self, frame: &Frame) {
+        let projection = perspective(Deg(45.0), frame.aspect_ratio(), 0.1, 100.0);
+        let view = self.camera.calculate_view();
+
+        unsafe {
+            gl::Enable(gl::DEPTH_TEST);
+            // the OIT pass clears its own targets with this color
+            gl::ClearColor(0.2, 0.2, 0.4, 1.0);
+            gl::Clear(gl::COLOR_BUFFER_BIT | gl::DEPTH_BUFFER_BIT);
+
+            for (unit, &texture) in self.textures.iter().enumerate() {
+                gl::ActiveTexture(gl::TEXTURE0 + unit as u32);
+                gl::BindTexture(gl::TEXTURE_2D, texture);
+            }
+        }
+        for shader in [&self.texture_shader, &self.sorted_shader, &self.oit_shader].iter() {
+            shader.use_program();
+            shader.set_mat4("projection", projection);
+            shader.set_mat4("view", view);
+        }
+
+        let mut queue = RenderQueue::new();
+
+        let (texture_shader, floor_vao, cube_vao) = (&self.texture_shader, &self.floor_vao, &self.cube_vao);
+        queue.push(Bucket::Opaque, Vector3::new(0.0, -0.5, 0.0), move || unsafe {
+            texture_shader.use_program();
+            texture_shader.set_int("texture0", 1);
+            texture_shader.set_mat4("model", Matrix4::identity());
+            gl::BindVertexArray(floor_vao.id);
+            gl::DrawArrays(gl::TRIANGLES, 0, 6);
+        });
+        // a cube behind the panes, it has to show through both of them
+        queue.push(Bucket::Opaque, Vector3::new(0.0, 0.0, -2.0), move || unsafe {
+            texture_shader.use_program();
+            texture_shader.set_int("texture0", 0);
+            texture_shader.set_mat4("model", Matrix4::from_translation(Vector3::new(0.0, 0.0, -2.0)) * Matrix4::from_nonuniform_scale(4.0, 1.0, 0.5));
+            gl::BindVertexArray(cube_vao.id);
+            gl::DrawArrays(gl::TRIANGLES, 0, 36);
+        });
+
+        // the same panes twice, only the bucket differs
+        for &(bucket, shader, center) in [
+            (Bucket::Transparent, &self.sorted_shader, SORTED_CENTER),
+            (Bucket::OrderIndependent, &self.oit_shader, OIT_CENTER),
+        ].iter() {
+            for (index, &color) in PANE_COLORS.iter().enumerate() {
+                let pane_vao = &self.pane_vao;
+                let angle = Deg(15.0 * frame.time + 60.0 * index as f32);
+                // the quad spans x from 0 to 1, its middle moves onto the axis of rotation
+                let model = Matrix4::from_translation(Vector3::from(center))
+                    * Matrix4::from_angle_y(angle)
+                    * Matrix4::from_scale(1.4)
+                    * Matrix4::from_translation(Vector3::new(-0.5, 0.0, 0.0));
+                // all panes share their center, sorting by it is only as good as the push order
+                queue.push(bucket, Vector3::from(center), move || unsafe {
+                    shader.use_program();
+                    shader.set_mat4("model", model);
+                    shader.set_vector4v("color", Vector4::from(color));
+                    gl::BindVertexArray(pane_vao.id);
+                    gl::DrawArrays(gl::TRIANGLES, 0, 6);
+                });
+            }
+        }
+
+        queue.flush_with(self.camera.position.to_vec(), Some(&mut self.oit)).expect("Could not resize the OIT targets");
+        unsafe { gl::BindVertexArray(0); }
+    }
+}
+
+impl Drop for OrderIndependentTransparency {
+    fn drop(&mut self) {
+        unsafe { shapes::delete_textures(&self.textures); }
+    }
+}
@@ -0,0 +1,10 @@
+use crate::postprocess::PostProcessed;
+use crate::scenes::advanced_opengl::blending::Blending;
+
+// the effects that are on from the start, the number keys toggle every effect of the chain
+pub const ENABLED_EFFECTS: [&str; 3] = ["vignette", "chromatic_aberration", "film_grain"];
+
+/// Chapter 4: the blending scene rendered through a chain of post-processing effects.
+pub fn post_processing() -> PostProcessed {
+    PostProcessed::new(Box::new(Blending::new()), &ENABLED_EFFECTS)
+}
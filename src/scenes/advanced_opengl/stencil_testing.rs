@@ -0,0 +1,123 @@
+use cgmath::{perspective, Deg, Matrix4, Point3, SquareMatrix, Vector3};
+
+use crate::camera::Camera;
+use crate::mesh::Mesh;
+use crate::model;
+use crate::outline::{Outline, OutlineStyle};
+use crate::render_state::{RenderState, StateCache};
+use crate::scene::{Frame, Scene};
+use crate::scenes::shapes;
+use crate::shader::Shader;
+use crate::utils;
+
+/// Chapter 4: two marble cubes with outlines, one extruded through the stencil buffer and one
+/// dilated in screen space.
+pub struct StencilTesting {
+    camera: Camera,
+    plane_shader: Shader,
+    cube_shader: Shader,
+    plane_vao: shapes::VertexArray,
+    cube: Mesh,
+    plane_texture: u32,
+    cube_texture: u32,
+    extruded: Outline,
+    dilated: Outline,
+    state: StateCache,
+}
+
+impl StencilTesting {
+    pub fn new() -> Self {
+        let plane_shader = Shader::new(
+            "./src/shaders/4_advanced_opengl/stencil_testing_plane.vs",
+            "./src/shaders/4_advanced_opengl/stencil_testing_plane.fs"
+        );
+        let cube_shader = Shader::new(
+            "./src/shaders/4_advanced_opengl/stencil_testing_cube.vs",
+            "./src/shaders/4_advanced_opengl/stencil_testing_cube.fs"
+        );
+
+        let (plane_vao, plane_texture, cube_texture) = unsafe {
+            (
+                shapes::create_vao(&shapes::PLANE_VERTICES, &[3, 2]),
+                model::texture_from_file("./resources/textures/metal.png"),
+                model::texture_from_file("./resources/textures/marble.jpg"),
+            )
+        };
+
+        plane_shader.use_program();
+        plane_shader.set_int("planeTex", 0);
+        cube_shader.use_program();
+        cube_shader.set_int("cubeTexture", 1);
+
+        StencilTesting {
+            camera: utils::camera_at(Point3::new(0.0, 0.5, 5.0)),
+            plane_shader,
+            cube_shader,
+            plane_vao,
+            cube: shapes::lit_cube_mesh(),
+            plane_texture,
+            cube_texture,
+            extruded: Outline::new(OutlineStyle::Extrude).expect("Could not create the outline"),
+            dilated: Outline::new(OutlineStyle::Dilate).expect("Could not create the outline"),
+            state: StateCache::new(),
+        }
+    }
+}
+
+impl Scene for StencilTesting {
+    fn camera(&mut self) -> Option<&mut Camera> {
+        Some(&mut self.camera)
+    }
+
+    fn render(&mut self, frame: &Frame) {
+        let projection = perspective(Deg(45.0), frame.aspect_ratio(), 0.1, 100.0);
+        let view = self.camera.calculate_view();
+
+        // away from the origin, where scaling the cube up would have shifted its border
+        let cubes = [
+            Matrix4::from_translation(Vector3::new(-1.0, 0.0, -1.0)),
+            Matrix4::from_translation(Vector3::new(1.5, 0.0, 0.0)) * Matrix4::from_angle_y(Deg(30.0 * frame.time)),
+        ];
+
+        // depth tested until the end of the frame, the outlines put back what they change
+        let _depth_tested = self.state.scope(&RenderState::opaque());
+        unsafe {
+            gl::ClearColor(0.2, 0.2, 0.2, 1.0);
+            gl::Clear(gl::COLOR_BUFFER_BIT | gl::DEPTH_BUFFER_BIT | gl::STENCIL_BUFFER_BIT);
+
+            //draw plane
+            self.plane_shader.use_program();
+            self.plane_shader.set_mat4("projection", projection);
+            self.plane_shader.set_mat4("view", view);
+            self.plane_shader.set_mat4("model", Matrix4::identity());
+
+            gl::ActiveTexture(gl::TEXTURE0);
+            gl::BindTexture(gl::TEXTURE_2D, self.plane_texture);
+            gl::BindVertexArray(self.plane_vao.id);
+            gl::DrawArrays(gl::TRIANGLES, 0, 6);
+
+            //draw the cubes with texture
+            self.cube_shader.use_program();
+            self.cube_shader.set_mat4("projection", projection);
+            self.cube_shader.set_mat4("view", view);
+            gl::ActiveTexture(gl::TEXTURE1);
+            gl::BindTexture(gl::TEXTURE_2D, self.cube_texture);
+            for &model in cubes.iter() {
+                self.cube_shader.set_mat4("model", model);
+                self.cube.Draw(&self.cube_shader);
+            }
+        }
+
+        // the outlines go around the cubes afterwards, through everything in front of them
+        // 1. the stencil buffer marks the cube, the cube grown along its normals is drawn around the mark
+        // 2. the cube is drawn into a mask, pixels near the mask but outside of it are colored
+        self.extruded.draw(&self.cube, cubes[0], view, projection).expect("Could not draw the outline");
+        self.dilated.draw(&self.cube, cubes[1], view, projection).expect("Could not draw the outline");
+    }
+}
+
+impl Drop for StencilTesting {
+    fn drop(&mut self) {
+        unsafe { shapes::delete_textures(&[self.plane_texture, self.cube_texture]); }
+    }
+}
@@ -0,0 +1,98 @@
+use cgmath::{Matrix4, Point3, Vector3};
+
+use crate::camera::Camera;
+use crate::scene::{Frame, Scene};
+use crate::utils;
+
+use super::coordinate_systems::TexturedCubes;
+
+/// Chapter 1: the camera circles around the cubes, always looking at the origin.
+pub struct CameraCircle {
+    cubes: TexturedCubes,
+}
+
+impl CameraCircle {
+    pub fn new() -> Self {
+        CameraCircle { cubes: TexturedCubes::new() }
+    }
+}
+
+impl Scene for CameraCircle {
+    fn render(&mut self, frame: &Frame) {
+        // we need 3 axes for our camera: the direction of the camera, the x-axis of the camera and the up axis
+        // look_at calculates them from the position, the target and the world up vector
+        let radius: f32 = 10.0;
+        let cam_x = frame.time.sin() * radius;
+        let cam_z = frame.time.cos() * radius;
+        let look_at_mat = Matrix4::look_at(
+            Point3::new(cam_x, 0.0, cam_z),
+            Point3::new(0.0, 0.0, 0.0),
+            Vector3::new(0.0, 1.0, 0.0));
+
+        unsafe {
+            self.cubes.prepare(look_at_mat, frame);
+            self.cubes.draw_cubes();
+        }
+    }
+}
+
+/// Chapter 1: walk between the cubes, the view is built from the camera position and front vector.
+pub struct CameraMove {
+    camera: Camera,
+    cubes: TexturedCubes,
+}
+
+impl CameraMove {
+    pub fn new() -> Self {
+        CameraMove {
+            camera: utils::camera_at(Point3::new(0.0, 0.0, 3.0)),
+            cubes: TexturedCubes::new(),
+        }
+    }
+}
+
+impl Scene for CameraMove {
+    fn camera(&mut self) -> Option<&mut Camera> {
+        Some(&mut self.camera)
+    }
+
+    fn render(&mut self, frame: &Frame) {
+        // lookAt matrix, which will be used as the view matrix
+        // make sure not to use look_at_dir(...)
+        let camera = &self.camera;
+        let look_at_mat = Matrix4::look_at(camera.position, camera.position + camera.front, camera.up);
+
+        unsafe {
+            self.cubes.prepare(look_at_mat, frame);
+            self.cubes.draw_cubes();
+        }
+    }
+}
+
+/// Chapter 1: the same walk, with the view calculated by the `Camera` class.
+pub struct CameraSystem {
+    camera: Camera,
+    cubes: TexturedCubes,
+}
+
+impl CameraSystem {
+    pub fn new() -> Self {
+        CameraSystem {
+            camera: utils::camera_at(Point3::new(0.0, 0.0, 3.0)),
+            cubes: TexturedCubes::new(),
+        }
+    }
+}
+
+impl Scene for CameraSystem {
+    fn camera(&mut self) -> Option<&mut Camera> {
+        Some(&mut self.camera)
+    }
+
+    fn render(&mut self, frame: &Frame) {
+        unsafe {
+            self.cubes.prepare(self.camera.calculate_view(), frame);
+            self.cubes.draw_cubes();
+        }
+    }
+}
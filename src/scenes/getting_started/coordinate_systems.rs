@@ -0,0 +1,189 @@
+use std::ptr;
+
+use cgmath::{perspective, Deg, InnerSpace, Matrix4, Rad, Vector3};
+
+use crate::instancing::{Instance, InstanceBuffer};
+use crate::scene::{Frame, Scene};
+use crate::scenes::shapes;
+use crate::shader::Shader;
+
+use super::textures;
+
+const RECTANGLE_VERTICES: [f32; 20] = [
+    // positions       // texture coords
+     0.5,  0.5, 0.0,   1.0, 1.0,   // top right
+     0.5, -0.5, 0.0,   1.0, 0.0,   // bottom right
+    -0.5, -0.5, 0.0,   0.0, 0.0,   // bottom left
+    -0.5,  0.5, 0.0,   0.0, 1.0    // top left
+];
+
+// shader, cube and textures shared by the 3D scenes of this chapter
+pub(crate) struct TexturedCubes {
+    pub shaders: Shader,
+    // the same shader with the model matrix per instance, for the ten cubes
+    pub instanced_shaders: Shader,
+    pub vao: shapes::VertexArray,
+    pub textures: [u32; 2],
+    instances: InstanceBuffer,
+}
+
+impl TexturedCubes {
+    pub fn new() -> Self {
+        let shaders = Shader::new(
+            "./src/shaders/1_getting_started/coord_systems.vs",
+            "./src/shaders/1_getting_started/multiple_tex.fs");
+        let instanced_shaders = Shader::new(
+            "./src/shaders/1_getting_started/coord_systems_instanced.vs",
+            "./src/shaders/1_getting_started/multiple_tex.fs");
+
+        let vao = unsafe { shapes::create_vao(&shapes::CUBE_VERTICES, &[3, 2]) };
+        let textures = textures::load_container_and_face(&shaders);
+        instanced_shaders.use_program();
+        instanced_shaders.set_int("texture1", 0);
+        instanced_shaders.set_int("texture2", 1);
+
+        // the ten cubes, each rotated a bit more than the last
+        let cubes: Vec<Instance> = shapes::CUBE_POSITIONS.iter().enumerate().map(|(index, position)| {
+            let angle = 20.0 * index as f32;
+            Instance::new(Matrix4::<f32>::from_translation(Vector3::from(*position)) * Matrix4::<f32>::from_axis_angle(Vector3::new(1.0, 0.3, 0.5).normalize(), Deg(angle)))
+        }).collect();
+        let instances = InstanceBuffer::new(&cubes);
+        instances.attach(vao.id);
+
+        TexturedCubes { shaders, instanced_shaders, vao, textures, instances }
+    }
+
+    // clears, binds everything and sets view and projection
+    pub unsafe fn prepare(&self, view: Matrix4<f32>, frame: &Frame) {
+        gl::Enable(gl::DEPTH_TEST);
+
+        gl::ClearColor(0.2, 0.3, 0.3, 1.0);
+        gl::Clear(gl::COLOR_BUFFER_BIT | gl::DEPTH_BUFFER_BIT);
+
+        textures::bind_container_and_face(&self.textures);
+
+        let projection = perspective(Deg(45.0), frame.aspect_ratio(), 0.1, 100.0);
+        self.instanced_shaders.use_program();
+        self.instanced_shaders.set_mat4("view", view);
+        self.instanced_shaders.set_mat4("projection", projection);
+        self.shaders.use_program();
+        self.shaders.set_mat4("view", view);
+        self.shaders.set_mat4("projection", projection);
+
+        gl::BindVertexArray(self.vao.id);
+    }
+
+    // the ten cubes in one draw call, the model matrices come from the instance buffer
+    pub unsafe fn draw_cubes(&self) {
+        self.instanced_shaders.use_program();
+        gl::DrawArraysInstanced(gl::TRIANGLES, 0, 36, self.instances.len() as i32);
+    }
+}
+
+impl Drop for TexturedCubes {
+    fn drop(&mut self) {
+        unsafe { shapes::delete_textures(&self.textures); }
+    }
+}
+
+/// Chapter 1: the textured rectangle laid on the floor with model, view and projection matrices.
+pub struct CoordinateSystem {
+    shaders: Shader,
+    vao: shapes::VertexArray,
+    textures: [u32; 2],
+}
+
+impl CoordinateSystem {
+    pub fn new() -> Self {
+        let shaders = Shader::new(
+            "./src/shaders/1_getting_started/coord_systems.vs",
+            "./src/shaders/1_getting_started/multiple_tex.fs");
+
+        let vao = unsafe { shapes::create_indexed_vao(&RECTANGLE_VERTICES, &textures::RECTANGLE_INDICES, &[3, 2]) };
+        let textures = textures::load_container_and_face(&shaders);
+
+        CoordinateSystem { shaders, vao, textures }
+    }
+}
+
+impl Scene for CoordinateSystem {
+    fn render(&mut self, frame: &Frame) {
+        // local -> world: rotate the rectangle so it lies on the floor
+        let model = Matrix4::<f32>::from_angle_x(Deg(-55.0));
+        // world -> view: moving the camera back is moving the scene forward
+        let view = Matrix4::<f32>::from_translation(Vector3::new(0.0, 0.0, -3.0));
+        // view -> clip
+        let projection = perspective(Deg(45.0), frame.aspect_ratio(), 0.1, 100.0);
+
+        unsafe {
+            gl::ClearColor(0.2, 0.3, 0.3, 1.0);
+            gl::Clear(gl::COLOR_BUFFER_BIT);
+
+            textures::bind_container_and_face(&self.textures);
+
+            // setting up uniforms
+            self.shaders.use_program();
+            self.shaders.set_mat4("model", model);
+            self.shaders.set_mat4("view", view);
+            self.shaders.set_mat4("projection", projection);
+
+            gl::BindVertexArray(self.vao.id);
+            gl::DrawElements(gl::TRIANGLES, 6, gl::UNSIGNED_INT, ptr::null());
+        }
+    }
+}
+
+impl Drop for CoordinateSystem {
+    fn drop(&mut self) {
+        unsafe { shapes::delete_textures(&self.textures); }
+    }
+}
+
+/// Chapter 1: a rotating cube, needs the depth test.
+pub struct Cube3D {
+    cubes: TexturedCubes,
+}
+
+impl Cube3D {
+    pub fn new() -> Self {
+        Cube3D { cubes: TexturedCubes::new() }
+    }
+}
+
+impl Scene for Cube3D {
+    fn render(&mut self, frame: &Frame) {
+        let view = Matrix4::<f32>::from_translation(Vector3::new(0.0, 0.0, -3.0));
+
+        // Let the cube rotate
+        let model = Matrix4::<f32>::from_angle_x(Deg(-55.0)) * Matrix4::<f32>::from_angle_z(Rad(frame.time));
+
+        unsafe {
+            self.cubes.prepare(view, frame);
+            self.cubes.shaders.set_mat4("model", model);
+
+            gl::DrawArrays(gl::TRIANGLES, 0, 36);
+        }
+    }
+}
+
+/// Chapter 1: ten cubes placed in the world.
+pub struct Cubes {
+    cubes: TexturedCubes,
+}
+
+impl Cubes {
+    pub fn new() -> Self {
+        Cubes { cubes: TexturedCubes::new() }
+    }
+}
+
+impl Scene for Cubes {
+    fn render(&mut self, frame: &Frame) {
+        let view = Matrix4::<f32>::from_translation(Vector3::new(0.0, 0.0, -3.0));
+
+        unsafe {
+            self.cubes.prepare(view, frame);
+            self.cubes.draw_cubes();
+        }
+    }
+}
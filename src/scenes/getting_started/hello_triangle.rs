@@ -0,0 +1,408 @@
+#![allow(non_snake_case, non_upper_case_globals)]
+
+use std::{ffi::{CString, c_void}, ptr, mem};
+use gl::types::*;
+
+use crate::scene::{Frame, Scene};
+use crate::scenes::shapes;
+
+const vertexShaderSource: &str = r#"
+    #version 330 core
+
+    layout (location = 0) in vec3 aPos;
+
+    void main() {
+       gl_Position = vec4(aPos.x, aPos.y, aPos.z, 1.0);
+    }
+"#;
+
+const fragmentShaderSource: &str = r#"
+    #version 330 core
+
+    out vec4 FragColor;
+
+    void main() {
+       FragColor = vec4(1.0f, 0.5f, 0.2f, 1.0f);
+    }
+"#;
+
+const fragmentShaderSource_white: &str = r#"
+    #version 330 core
+
+    out vec4 FragColor;
+
+    void main() {
+       FragColor = vec4(1.0f, 1.0f, 1.0f, 1.0f);
+    }
+"#;
+
+/// Compiles and links a shader program from GLSL sources, without the `Shader` helper.
+pub unsafe fn create_program(vertex_source: &str, fragment_source: &str) -> u32 {
+    // vertex shader
+    let vertex_shader = gl::CreateShader(gl::VERTEX_SHADER);
+    let c_str_vert = CString::new(vertex_source.as_bytes()).unwrap();
+    // arguments: shader object, number of strings, source of the shader
+    gl::ShaderSource(vertex_shader, 1, &c_str_vert.as_ptr(), ptr::null());
+    gl::CompileShader(vertex_shader);
+
+    // fragment shader
+    let fragment_shader = gl::CreateShader(gl::FRAGMENT_SHADER);
+    let c_str_frag = CString::new(fragment_source.as_bytes()).unwrap();
+    // arguments: shader object, number of strings, source of the shader
+    gl::ShaderSource(fragment_shader, 1, &c_str_frag.as_ptr(), ptr::null());
+    gl::CompileShader(fragment_shader);
+
+    // shader program
+    let shader_program = gl::CreateProgram();
+    // link shaders
+    gl::AttachShader(shader_program, vertex_shader);
+    gl::AttachShader(shader_program, fragment_shader);
+    gl::LinkProgram(shader_program);
+
+    // clean up, delete shaders
+    gl::DeleteShader(vertex_shader);
+    gl::DeleteShader(fragment_shader);
+
+    shader_program
+}
+
+// creates a VAO for positions only
+unsafe fn create_position_vao(vertices: &[f32]) -> shapes::VertexArray {
+    // initialize vbo and vao
+    // VBO - vertex buffer object
+    // this buffer holds the data(vertices), and will be copied to the graphics card
+    // VAO - vertex array object
+    // object, holds how the data should be used
+    let (mut VBO, mut VAO) = (0, 0);
+
+    // set openGL object for VBO and VAO
+    gl::GenBuffers(1, &mut VBO);
+
+    // bind buffer will set the buffer as the current OpenGl State
+    // then storing the data inside this buffer
+    gl::BindBuffer(gl::ARRAY_BUFFER, VBO);
+    gl::BufferData(gl::ARRAY_BUFFER, mem::size_of_val(vertices) as GLsizeiptr, &vertices[0] as *const f32 as *const c_void, gl::STATIC_DRAW);
+
+    // bind and configurate VAO
+    gl::GenVertexArrays(1, &mut VAO);
+    gl::BindVertexArray(VAO);
+    gl::VertexAttribPointer(0, 3, gl::FLOAT, gl::FALSE, 3 * mem::size_of::<GLfloat>() as GLsizei, ptr::null());
+
+    // we use only 1 VAO so the index of the location is 0
+    gl::EnableVertexAttribArray(0);
+
+    // unbind VBO
+    gl::BindBuffer(gl::ARRAY_BUFFER, 0);
+    // unbind VAO
+    gl::BindVertexArray(0);
+
+    shapes::VertexArray { id: VAO, buffers: vec![VBO] }
+}
+
+// creates a VAO for positions only, drawn through an element buffer
+unsafe fn create_indexed_vao(vertices: &[f32], indices: &[i32]) -> shapes::VertexArray {
+    let (mut VBO, mut VAO, mut EBO) = (0, 0, 0);
+
+    // set up opengl objects
+    gl::GenVertexArrays(1, &mut VAO);
+    gl::GenBuffers(1, &mut VBO);
+    gl::GenBuffers(1, &mut EBO);
+
+    // bind VAO first
+    gl::BindVertexArray(VAO);
+
+    // bind buffer to opengl state and fill with data
+    gl::BindBuffer(gl::ARRAY_BUFFER, VBO);
+    gl::BufferData(gl::ARRAY_BUFFER, mem::size_of_val(vertices) as GLsizeiptr, &vertices[0] as *const f32 as *const c_void, gl::STATIC_DRAW);
+
+    // bind buffer to opengl state and fill with data
+    // the element buffer stays bound to the VAO
+    gl::BindBuffer(gl::ELEMENT_ARRAY_BUFFER, EBO);
+    gl::BufferData(gl::ELEMENT_ARRAY_BUFFER, mem::size_of_val(indices) as GLsizeiptr, &indices[0] as *const i32 as *const c_void, gl::STATIC_DRAW);
+
+    //configurate VAO
+    gl::VertexAttribPointer(0, 3, gl::FLOAT, gl::FALSE, 3 * mem::size_of::<GLfloat>() as GLsizei, ptr::null());
+    gl::EnableVertexAttribArray(0);
+
+    // unbind VBO
+    gl::BindBuffer(gl::ARRAY_BUFFER, 0);
+    // unbind VAO
+    gl::BindVertexArray(0);
+
+    shapes::VertexArray { id: VAO, buffers: vec![VBO, EBO] }
+}
+
+/// Chapter 1: a single orange triangle.
+pub struct HelloTriangle {
+    program: u32,
+    vao: shapes::VertexArray,
+}
+
+impl HelloTriangle {
+    pub fn new() -> Self {
+        unsafe {
+            // vertices for a triangle
+            let vertices: [f32; 9] = [
+                // x    y    z
+                -0.5, -0.5, 0.0, // left
+                 0.5, -0.5, 0.0, // right
+                 0.0,  0.5, 0.0  // top
+            ];
+
+            HelloTriangle {
+                program: create_program(vertexShaderSource, fragmentShaderSource),
+                vao: create_position_vao(&vertices),
+            }
+        }
+    }
+}
+
+impl Scene for HelloTriangle {
+    fn render(&mut self, _frame: &Frame) {
+        unsafe {
+            gl::ClearColor(0.2, 0.3, 0.3, 1.0);
+            gl::Clear(gl::COLOR_BUFFER_BIT);
+
+            gl::UseProgram(self.program);
+            gl::BindVertexArray(self.vao.id);
+            gl::DrawArrays(gl::TRIANGLES, 0, 3);
+        }
+    }
+}
+
+impl Drop for HelloTriangle {
+    fn drop(&mut self) {
+        unsafe { gl::DeleteProgram(self.program); }
+    }
+}
+
+/// Chapter 1: a rectangle from two indexed triangles.
+pub struct HelloIndexed {
+    program: u32,
+    vao: shapes::VertexArray,
+}
+
+impl HelloIndexed {
+    pub fn new() -> Self {
+        unsafe {
+            let vertices: [f32; 12] = [
+                // x    y    z
+                -0.5, -0.5, 0.0,
+                 0.5, -0.5, 0.0,
+                -0.5,  0.5, 0.0,
+                 0.5,  0.5, 0.0
+            ];
+
+            let indices = [
+                0, 1, 2,
+                1, 2, 3
+            ];
+
+            HelloIndexed {
+                program: create_program(vertexShaderSource, fragmentShaderSource),
+                vao: create_indexed_vao(&vertices, &indices),
+            }
+        }
+    }
+}
+
+impl Scene for HelloIndexed {
+    fn render(&mut self, _frame: &Frame) {
+        unsafe {
+            gl::ClearColor(0.2, 0.3, 0.3, 1.0);
+            gl::Clear(gl::COLOR_BUFFER_BIT);
+
+            gl::UseProgram(self.program);
+            gl::BindVertexArray(self.vao.id);
+            gl::DrawElements(gl::TRIANGLES, 6, gl::UNSIGNED_INT, ptr::null());
+        }
+    }
+}
+
+impl Drop for HelloIndexed {
+    fn drop(&mut self) {
+        unsafe { gl::DeleteProgram(self.program); }
+    }
+}
+
+//
+// Exercise:
+// Try to draw 2 triangles next to each other using glDrawArrays by adding more vertices to your data
+// https://learnopengl.com/Getting-started/Hello-Triangle
+//
+
+/// Chapter 1, exercise 1: two triangles next to each other, as wireframe.
+pub struct Exercise1 {
+    program: u32,
+    vao: shapes::VertexArray,
+}
+
+impl Exercise1 {
+    pub fn new() -> Self {
+        unsafe {
+            let vertices: [f32; 12] = [
+                // x    y    z
+                -0.5, -0.5, 0.0,
+                 0.0,  0.5, 0.0,
+                 0.0, -0.5, 0.0,
+                 0.5, -0.5, 0.0
+            ];
+
+            let indices = [
+                0, 1, 2,
+                1, 2, 3
+            ];
+
+            Exercise1 {
+                program: create_program(vertexShaderSource, fragmentShaderSource),
+                vao: create_indexed_vao(&vertices, &indices),
+            }
+        }
+    }
+}
+
+impl Scene for Exercise1 {
+    fn render(&mut self, _frame: &Frame) {
+        unsafe {
+            gl::ClearColor(0.2, 0.3, 0.3, 1.0);
+            gl::Clear(gl::COLOR_BUFFER_BIT);
+
+            // WireFrame
+            gl::PolygonMode(gl::FRONT_AND_BACK, gl::LINE);
+
+            gl::UseProgram(self.program);
+            gl::BindVertexArray(self.vao.id);
+            gl::DrawElements(gl::TRIANGLES, 6, gl::UNSIGNED_INT, ptr::null());
+
+            // the polygon mode is global, other scenes expect filled polygons
+            gl::PolygonMode(gl::FRONT_AND_BACK, gl::FILL);
+        }
+    }
+}
+
+impl Drop for Exercise1 {
+    fn drop(&mut self) {
+        unsafe { gl::DeleteProgram(self.program); }
+    }
+}
+
+/// Chapter 1, exercise 2: the same two triangles, each with its own VAO and VBO.
+pub struct Exercise2 {
+    program: u32,
+    vaos: [shapes::VertexArray; 2],
+}
+
+impl Exercise2 {
+    pub fn new() -> Self {
+        unsafe {
+            let first_triangle: [f32; 9] = [
+                // x    y    z
+                -0.5, -0.5, 0.0,
+                 0.0,  0.5, 0.0,
+                 0.0, -0.5, 0.0,
+            ];
+
+            let second_triangle: [f32; 9] = [
+                 0.0,  0.5, 0.0,
+                 0.0, -0.5, 0.0,
+                 0.5, -0.5, 0.0
+            ];
+
+            Exercise2 {
+                program: create_program(vertexShaderSource, fragmentShaderSource),
+                vaos: [create_position_vao(&first_triangle), create_position_vao(&second_triangle)],
+            }
+        }
+    }
+}
+
+impl Scene for Exercise2 {
+    fn render(&mut self, _frame: &Frame) {
+        unsafe {
+            gl::ClearColor(0.2, 0.3, 0.3, 1.0);
+            gl::Clear(gl::COLOR_BUFFER_BIT);
+
+            // WireFrame
+            gl::PolygonMode(gl::FRONT_AND_BACK, gl::LINE);
+
+            gl::UseProgram(self.program);
+            for vao in self.vaos.iter() {
+                gl::BindVertexArray(vao.id);
+                gl::DrawArrays(gl::TRIANGLES, 0, 3);
+            }
+
+            // the polygon mode is global, other scenes expect filled polygons
+            gl::PolygonMode(gl::FRONT_AND_BACK, gl::FILL);
+        }
+    }
+}
+
+impl Drop for Exercise2 {
+    fn drop(&mut self) {
+        unsafe { gl::DeleteProgram(self.program); }
+    }
+}
+
+/// Chapter 1, exercise 3: two triangles drawn with two different shader programs.
+pub struct Exercise3 {
+    program_orange: u32,
+    program_white: u32,
+    vaos: [shapes::VertexArray; 2],
+}
+
+impl Exercise3 {
+    pub fn new() -> Self {
+        unsafe {
+            let first_triangle: [f32; 9] = [
+                // x    y    z
+                -0.5, -0.5, 0.0,
+                -0.1,  0.5, 0.0,
+                -0.1, -0.5, 0.0,
+            ];
+
+            let second_triangle: [f32; 9] = [
+                 0.1,  0.5, 0.0,
+                 0.1, -0.5, 0.0,
+                 0.5, -0.5, 0.0
+            ];
+
+            Exercise3 {
+                program_orange: create_program(vertexShaderSource, fragmentShaderSource),
+                program_white: create_program(vertexShaderSource, fragmentShaderSource_white),
+                vaos: [create_position_vao(&first_triangle), create_position_vao(&second_triangle)],
+            }
+        }
+    }
+}
+
+impl Scene for Exercise3 {
+    fn render(&mut self, _frame: &Frame) {
+        unsafe {
+            gl::ClearColor(0.2, 0.3, 0.3, 1.0);
+            gl::Clear(gl::COLOR_BUFFER_BIT);
+
+            // WireFrame
+            gl::PolygonMode(gl::FRONT_AND_BACK, gl::LINE);
+
+            gl::UseProgram(self.program_orange);
+            gl::BindVertexArray(self.vaos[0].id);
+            gl::DrawArrays(gl::TRIANGLES, 0, 3);
+
+            gl::UseProgram(self.program_white);
+            gl::BindVertexArray(self.vaos[1].id);
+            gl::DrawArrays(gl::TRIANGLES, 0, 3);
+
+            // the polygon mode is global, other scenes expect filled polygons
+            gl::PolygonMode(gl::FRONT_AND_BACK, gl::FILL);
+        }
+    }
+}
+
+impl Drop for Exercise3 {
+    fn drop(&mut self) {
+        unsafe {
+            gl::DeleteProgram(self.program_orange);
+            gl::DeleteProgram(self.program_white);
+        }
+    }
+}
@@ -0,0 +1,8 @@
+//! Chapter 1: Getting started
+
+pub mod hello_triangle;
+pub mod shaders;
+pub mod textures;
+pub mod transformations;
+pub mod coordinate_systems;
+pub mod camera;
@@ -0,0 +1,107 @@
+#![allow(non_upper_case_globals)]
+
+use crate::scene::{Frame, Scene};
+use crate::scenes::shapes;
+use crate::shader::Shader;
+
+use super::hello_triangle::create_program;
+
+const vertexShaderSource: &str = r#"
+    #version 330 core
+
+    layout (location = 0) in vec3 aPos;
+    layout (location = 1) in vec3 aColor;
+
+    out vec3 ourColor;
+
+    void main() {
+       gl_Position = vec4(aPos.x, aPos.y, aPos.z, 1.0);
+       ourColor = aColor;
+    }
+"#;
+
+const fragmentShaderSource: &str = r#"
+    #version 330 core
+
+    in vec3 ourColor;
+    out vec4 FragColor;
+
+    void main() {
+       FragColor = vec4(ourColor, 1.0);
+    }
+"#;
+
+// triangle with a color per vertex
+const VERTICES_COLOR: [f32; 18] = [
+    // x    y    z      // color(rgb)
+    -0.5, -0.5, 0.0,    1.0, 0.0, 0.0,
+     0.5, -0.5, 0.0,    0.0, 1.0, 0.0,
+     0.0,  0.5, 0.0,    0.0, 0.0, 1.0
+];
+
+/// Chapter 1: the vertex colors are interpolated across the triangle.
+pub struct Interpolation {
+    program: u32,
+    vao: shapes::VertexArray,
+}
+
+impl Interpolation {
+    pub fn new() -> Self {
+        unsafe {
+            Interpolation {
+                program: create_program(vertexShaderSource, fragmentShaderSource),
+                // configure position attribute aPos and color attribute aColor
+                vao: shapes::create_vao(&VERTICES_COLOR, &[3, 3]),
+            }
+        }
+    }
+}
+
+impl Scene for Interpolation {
+    fn render(&mut self, _frame: &Frame) {
+        unsafe {
+            gl::ClearColor(0.2, 0.3, 0.3, 1.0);
+            gl::Clear(gl::COLOR_BUFFER_BIT);
+
+            gl::UseProgram(self.program);
+            gl::BindVertexArray(self.vao.id);
+            gl::DrawArrays(gl::TRIANGLES, 0, 3);
+        }
+    }
+}
+
+impl Drop for Interpolation {
+    fn drop(&mut self) {
+        unsafe { gl::DeleteProgram(self.program); }
+    }
+}
+
+/// Chapter 1: the same triangle, with the shaders loaded by the `Shader` class.
+pub struct ShaderClass {
+    shader_pair: Shader,
+    vao: shapes::VertexArray,
+}
+
+impl ShaderClass {
+    pub fn new() -> Self {
+        let shader_pair = Shader::new("./src/shaders/1_getting_started/shader.vs", "./src/shaders/1_getting_started/shader.fs");
+
+        ShaderClass {
+            shader_pair,
+            vao: unsafe { shapes::create_vao(&VERTICES_COLOR, &[3, 3]) },
+        }
+    }
+}
+
+impl Scene for ShaderClass {
+    fn render(&mut self, _frame: &Frame) {
+        unsafe {
+            gl::ClearColor(0.2, 0.3, 0.3, 1.0);
+            gl::Clear(gl::COLOR_BUFFER_BIT);
+
+            self.shader_pair.use_program();
+            gl::BindVertexArray(self.vao.id);
+            gl::DrawArrays(gl::TRIANGLES, 0, 3);
+        }
+    }
+}
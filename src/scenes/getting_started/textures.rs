@@ -0,0 +1,132 @@
+use std::ptr;
+
+use crate::scene::{Frame, Scene};
+use crate::scenes::shapes;
+use crate::shader::Shader;
+
+pub const RECTANGLE_VERTICES: [f32; 32] = [
+    // positions          // colors           // texture coords
+     0.5,  0.5, 0.0,   1.0, 0.0, 0.0,   1.0, 1.0,   // top right
+     0.5, -0.5, 0.0,   0.0, 1.0, 0.0,   1.0, 0.0,   // bottom right
+    -0.5, -0.5, 0.0,   0.0, 0.0, 1.0,   0.0, 0.0,   // bottom left
+    -0.5,  0.5, 0.0,   1.0, 1.0, 0.0,   0.0, 1.0    // top left
+];
+
+pub const RECTANGLE_INDICES: [u32; 6] = [
+    0, 1, 2,
+    2, 3, 0
+];
+
+/// Loads the container and the smiley for `multiple_tex.fs` and points its samplers to units 0 and 1.
+pub(crate) fn load_container_and_face(shader: &Shader) -> [u32; 2] {
+    unsafe {
+        let textures = [
+            shapes::load_texture("resources/textures/container.jpg", true),
+            shapes::load_texture("resources/textures/awesomeface.png", true),
+        ];
+
+        // make sure to use the program befor setting uniforms
+        shader.use_program();
+        // set uniforms for textures
+        shader.set_int("texture1", 0);
+        shader.set_int("texture2", 1);
+
+        textures
+    }
+}
+
+/// Binds the textures to the units `load_container_and_face` set up.
+pub(crate) unsafe fn bind_container_and_face(textures: &[u32; 2]) {
+    // set active texture group and bind the texture
+    gl::ActiveTexture(gl::TEXTURE0);
+    gl::BindTexture(gl::TEXTURE_2D, textures[0]);
+    gl::ActiveTexture(gl::TEXTURE1);
+    gl::BindTexture(gl::TEXTURE_2D, textures[1]);
+}
+
+/// Chapter 1: a textured rectangle, tinted by its vertex colors.
+pub struct Textures {
+    shaders: Shader,
+    vao: shapes::VertexArray,
+    texture: u32,
+}
+
+impl Textures {
+    pub fn new() -> Self {
+        let shaders = Shader::new(
+            "./src/shaders/1_getting_started/tex_shader.vs",
+            "./src/shaders/1_getting_started/tex_shader.fs");
+
+        unsafe {
+            // configure position attribute aPos, color attribute aColor and texture coords
+            let vao = shapes::create_indexed_vao(&RECTANGLE_VERTICES, &RECTANGLE_INDICES, &[3, 3, 2]);
+            // not flipped yet, the container shows upside down
+            let texture = shapes::load_texture("resources/textures/container.jpg", false);
+
+            Textures { shaders, vao, texture }
+        }
+    }
+}
+
+impl Scene for Textures {
+    fn render(&mut self, _frame: &Frame) {
+        unsafe {
+            gl::ClearColor(0.2, 0.3, 0.3, 1.0);
+            gl::Clear(gl::COLOR_BUFFER_BIT);
+
+            gl::ActiveTexture(gl::TEXTURE0);
+            gl::BindTexture(gl::TEXTURE_2D, self.texture);
+
+            self.shaders.use_program();
+            gl::BindVertexArray(self.vao.id);
+            gl::DrawElements(gl::TRIANGLES, 6, gl::UNSIGNED_INT, ptr::null());
+        }
+    }
+}
+
+impl Drop for Textures {
+    fn drop(&mut self) {
+        unsafe { shapes::delete_textures(&[self.texture]); }
+    }
+}
+
+/// Chapter 1: two textures mixed in the fragment shader.
+pub struct MultipleTextures {
+    shaders: Shader,
+    vao: shapes::VertexArray,
+    textures: [u32; 2],
+}
+
+impl MultipleTextures {
+    pub fn new() -> Self {
+        let shaders = Shader::new(
+            "./src/shaders/1_getting_started/tex_shader.vs",
+            "./src/shaders/1_getting_started/multiple_tex.fs");
+
+        let vao = unsafe { shapes::create_indexed_vao(&RECTANGLE_VERTICES, &RECTANGLE_INDICES, &[3, 3, 2]) };
+        let textures = load_container_and_face(&shaders);
+
+        MultipleTextures { shaders, vao, textures }
+    }
+}
+
+impl Scene for MultipleTextures {
+    fn render(&mut self, _frame: &Frame) {
+        unsafe {
+            gl::ClearColor(0.2, 0.3, 0.3, 1.0);
+            gl::Clear(gl::COLOR_BUFFER_BIT);
+
+            bind_container_and_face(&self.textures);
+
+            self.shaders.use_program();
+            gl::BindVertexArray(self.vao.id);
+            gl::DrawElements(gl::TRIANGLES, 6, gl::UNSIGNED_INT, ptr::null());
+        }
+    }
+}
+
+impl Drop for MultipleTextures {
+    fn drop(&mut self) {
+        unsafe { shapes::delete_textures(&self.textures); }
+    }
+}
@@ -0,0 +1,72 @@
+use std::ptr;
+
+use cgmath::{Matrix4, Rad, Vector3};
+
+use crate::scene::{Frame, Scene};
+use crate::scenes::shapes;
+use crate::shader::Shader;
+
+use super::textures::{self, RECTANGLE_INDICES, RECTANGLE_VERTICES};
+
+/// Chapter 1: the textured rectangle spinning in the bottom right corner.
+pub struct Transformations {
+    shaders: Shader,
+    vao: shapes::VertexArray,
+    textures: [u32; 2],
+}
+
+impl Transformations {
+    pub fn new() -> Self {
+        let shaders = Shader::new(
+            "./src/shaders/1_getting_started/transform.vs",
+            "./src/shaders/1_getting_started/multiple_tex.fs");
+
+        let vao = unsafe { shapes::create_indexed_vao(&RECTANGLE_VERTICES, &RECTANGLE_INDICES, &[3, 3, 2]) };
+        let textures = textures::load_container_and_face(&shaders);
+
+        Transformations { shaders, vao, textures }
+    }
+}
+
+impl Scene for Transformations {
+    fn render(&mut self, frame: &Frame) {
+        // translation matrix
+        //  1   0   0    0.5
+        //  0   1   0   -0.5
+        //  0   0   1    0
+        //  0   0   0    1
+        let translation_mat = Matrix4::<f32>::from_translation(Vector3::new(0.5, -0.5, 0.0));
+        // rotation matrix
+        //  cosθ   -sinθ   0    0
+        //  sinθ    cosθ   0    0
+        //  0       0      1    0
+        //  0       0      0    1
+        let rotation_z_axis = Matrix4::from_angle_z(Rad(frame.time));
+
+        // order of the transformation is important and will change the outcome
+        // translation mat * rotation mat => first rotation then translation
+        // outcome will be a texture spinning around it's center
+        // (rotation mat * translation mat would spin it around the origin instead)
+        let rotation_translation_mat = translation_mat * rotation_z_axis;
+
+        unsafe {
+            gl::ClearColor(0.2, 0.3, 0.3, 1.0);
+            gl::Clear(gl::COLOR_BUFFER_BIT);
+
+            textures::bind_container_and_face(&self.textures);
+
+            // uniforms are set on the program in use
+            self.shaders.use_program();
+            self.shaders.set_mat4("transform", rotation_translation_mat);
+
+            gl::BindVertexArray(self.vao.id);
+            gl::DrawElements(gl::TRIANGLES, 6, gl::UNSIGNED_INT, ptr::null());
+        }
+    }
+}
+
+impl Drop for Transformations {
+    fn drop(&mut self) {
+        unsafe { shapes::delete_textures(&self.textures); }
+    }
+}
@@ -0,0 +1,180 @@
+use cgmath::{perspective, Deg, Matrix4, Point3, SquareMatrix, Vector3};
+
+use crate::camera::Camera;
+use crate::scene::{Frame, Scene};
+use crate::scenes::shapes;
+use crate::shader::Shader;
+use crate::utils;
+
+fn phong_shader(fragment_shader: &str) -> Shader {
+    let shader = Shader::new("./src/shaders/2_lighting/phong_light.vs", fragment_shader);
+
+    // uniforms for objectColor and lightColor
+    shader.use_program();
+    shader.set_vector3("objectColor", 1.0, 0.5, 0.31);
+    shader.set_vector3("lightColor", 1.0, 1.0, 1.0);
+    shader
+}
+
+// draws the cube with a phong shader, lightPos has to be set already
+unsafe fn draw_cube(shader: &Shader, camera: &Camera, projection: Matrix4<f32>, model: Matrix4<f32>) {
+    shader.use_program();
+    shader.set_mat4("projection", projection);
+    shader.set_mat4("view", camera.calculate_view());
+    shader.set_mat4("model", model);
+    shader.set_vector3("cameraPos", camera.position.x, camera.position.y, camera.position.z);
+
+    gl::DrawArrays(gl::TRIANGLES, 0, 36);
+}
+
+unsafe fn clear() {
+    // enable depth perspective
+    gl::Enable(gl::DEPTH_TEST);
+
+    gl::ClearColor(0.2, 0.3, 0.3, 1.0);
+    gl::Clear(gl::COLOR_BUFFER_BIT | gl::DEPTH_BUFFER_BIT);
+}
+
+/// Chapter 2: ambient, diffuse and specular lighting side by side.
+pub struct PhongSplit {
+    camera: Camera,
+    ambient_shader: Shader,
+    diffuse_shader: Shader,
+    specular_shader: Shader,
+    light_cube_shader: Shader,
+    vao: shapes::VertexArray,
+}
+
+impl PhongSplit {
+    pub fn new() -> Self {
+        let light_pos = Vector3::new(0.0, 1.5, 2.0);
+
+        let ambient_shader = phong_shader("./src/shaders/2_lighting/ambient_light.fs");
+        let diffuse_shader = phong_shader("./src/shaders/2_lighting/diffuse_light.fs");
+        diffuse_shader.set_vector3v("lightPos", light_pos);
+        let specular_shader = phong_shader("./src/shaders/2_lighting/specular_light.fs");
+        specular_shader.set_vector3v("lightPos", light_pos);
+
+        PhongSplit {
+            camera: utils::camera_at(Point3::new(0.0, 0.5, 5.0)),
+            ambient_shader,
+            diffuse_shader,
+            specular_shader,
+            light_cube_shader: super::light_source_shader(),
+            vao: unsafe { shapes::create_vao(&shapes::LIT_CUBE_VERTICES, &[3, 3, 2]) },
+        }
+    }
+}
+
+impl Scene for PhongSplit {
+    fn camera(&mut self) -> Option<&mut Camera> {
+        Some(&mut self.camera)
+    }
+
+    fn render(&mut self, frame: &Frame) {
+        let light_pos = Vector3::new(0.0, 1.5, 2.0);
+        let projection = perspective(Deg(45.0), frame.aspect_ratio(), 0.1, 100.0);
+
+        unsafe {
+            clear();
+            gl::BindVertexArray(self.vao.id);
+
+            // ambient cube on the left, diffuse in the middle, specular on the right
+            draw_cube(&self.ambient_shader, &self.camera, projection, Matrix4::from_translation(Vector3::new(-1.5, 0.0, 0.0)));
+            draw_cube(&self.diffuse_shader, &self.camera, projection, Matrix4::identity());
+            draw_cube(&self.specular_shader, &self.camera, projection, Matrix4::from_translation(Vector3::new(1.5, 0.0, 0.0)));
+
+            super::draw_light_source(&self.light_cube_shader, projection, self.camera.calculate_view(), light_pos, 0.2);
+        }
+    }
+}
+
+/// Chapter 2: all three phong components combined.
+pub struct PhongLighting {
+    camera: Camera,
+    cube_shader: Shader,
+    light_source_shader: Shader,
+    vao: shapes::VertexArray,
+}
+
+impl PhongLighting {
+    pub fn new() -> Self {
+        let light_pos = Vector3::new(0.7, 1.0, 2.0);
+
+        let cube_shader = phong_shader("./src/shaders/2_lighting/phong_light.fs");
+        cube_shader.set_vector3v("lightPos", light_pos);
+
+        PhongLighting {
+            camera: utils::camera_at(Point3::new(0.0, 0.5, 5.0)),
+            cube_shader,
+            light_source_shader: super::light_source_shader(),
+            vao: unsafe { shapes::create_vao(&shapes::LIT_CUBE_VERTICES, &[3, 3, 2]) },
+        }
+    }
+}
+
+impl Scene for PhongLighting {
+    fn camera(&mut self) -> Option<&mut Camera> {
+        Some(&mut self.camera)
+    }
+
+    fn render(&mut self, frame: &Frame) {
+        let light_pos = Vector3::new(0.7, 1.0, 2.0);
+        let projection = perspective(Deg(45.0), frame.aspect_ratio(), 0.1, 100.0);
+
+        unsafe {
+            clear();
+            gl::BindVertexArray(self.vao.id);
+
+            draw_cube(&self.cube_shader, &self.camera, projection, Matrix4::identity());
+            super::draw_light_source(&self.light_source_shader, projection, self.camera.calculate_view(), light_pos, 0.2);
+        }
+    }
+}
+
+/// Chapter 2, exercise: the light circles around the cube.
+pub struct PhongExercise1 {
+    camera: Camera,
+    cube_shader: Shader,
+    light_source_shader: Shader,
+    vao: shapes::VertexArray,
+    light_pos: Vector3<f32>,
+}
+
+impl PhongExercise1 {
+    pub fn new() -> Self {
+        PhongExercise1 {
+            camera: utils::camera_at(Point3::new(0.0, 0.5, 5.0)),
+            cube_shader: phong_shader("./src/shaders/2_lighting/phong_light.fs"),
+            light_source_shader: super::light_source_shader(),
+            vao: unsafe { shapes::create_vao(&shapes::LIT_CUBE_VERTICES, &[3, 3, 2]) },
+            light_pos: Vector3::new(0.0, 1.5, 0.0),
+        }
+    }
+}
+
+impl Scene for PhongExercise1 {
+    fn camera(&mut self) -> Option<&mut Camera> {
+        Some(&mut self.camera)
+    }
+
+    fn update(&mut self, frame: &Frame) {
+        //light source position
+        self.light_pos = super::orbiting_light(frame.time);
+    }
+
+    fn render(&mut self, frame: &Frame) {
+        let projection = perspective(Deg(45.0), frame.aspect_ratio(), 0.1, 100.0);
+
+        unsafe {
+            clear();
+            gl::BindVertexArray(self.vao.id);
+
+            self.cube_shader.use_program();
+            self.cube_shader.set_vector3v("lightPos", self.light_pos);
+            draw_cube(&self.cube_shader, &self.camera, projection, Matrix4::identity());
+
+            super::draw_light_source(&self.light_source_shader, projection, self.camera.calculate_view(), self.light_pos, 0.2);
+        }
+    }
+}
@@ -0,0 +1,66 @@
+use cgmath::{perspective, Deg, Matrix4, Point3, SquareMatrix, Vector3};
+
+use crate::camera::Camera;
+use crate::scene::{Frame, Scene};
+use crate::scenes::shapes;
+use crate::shader::Shader;
+use crate::utils;
+
+/// Chapter 2: a coral cube lit by a white light, color = object color * light color.
+pub struct Colors {
+    camera: Camera,
+    lighting_shader: Shader,
+    light_cube_shader: Shader,
+    vao: shapes::VertexArray,
+}
+
+impl Colors {
+    pub fn new() -> Self {
+        let lighting_shader = Shader::new(
+            "./src/shaders/2_lighting/basic_lighting.vs",
+            "./src/shaders/2_lighting/basic_lighting.fs"
+        );
+
+        // lighting
+        lighting_shader.use_program();
+        lighting_shader.set_vector3("objectColor", 1.0, 0.5, 0.31);
+        lighting_shader.set_vector3("lightColor", 1.0, 1.0, 1.0);
+
+        Colors {
+            camera: utils::camera_at(Point3::new(0.0, 0.0, 3.0)),
+            lighting_shader,
+            light_cube_shader: super::light_source_shader(),
+            vao: unsafe { shapes::create_vao(&shapes::LIT_CUBE_VERTICES, &[3, 3, 2]) },
+        }
+    }
+}
+
+impl Scene for Colors {
+    fn camera(&mut self) -> Option<&mut Camera> {
+        Some(&mut self.camera)
+    }
+
+    fn render(&mut self, frame: &Frame) {
+        let light_pos = Vector3::new(1.5, 1.0, -2.0);
+        let projection = perspective(Deg(45.0), frame.aspect_ratio(), 0.1, 100.0);
+        let view = self.camera.calculate_view();
+
+        unsafe {
+            // enable depth perspective
+            gl::Enable(gl::DEPTH_TEST);
+
+            gl::ClearColor(0.2, 0.3, 0.3, 1.0);
+            gl::Clear(gl::COLOR_BUFFER_BIT | gl::DEPTH_BUFFER_BIT);
+
+            self.lighting_shader.use_program();
+            self.lighting_shader.set_mat4("projection", projection);
+            self.lighting_shader.set_mat4("view", view);
+            self.lighting_shader.set_mat4("model", Matrix4::<f32>::identity());
+
+            gl::BindVertexArray(self.vao.id);
+            gl::DrawArrays(gl::TRIANGLES, 0, 36);
+
+            super::draw_light_source(&self.light_cube_shader, projection, view, light_pos, 0.2);
+        }
+    }
+}
@@ -0,0 +1,238 @@
+use cgmath::{perspective, Deg, EuclideanSpace, InnerSpace, Matrix4, Point3, Vector3};
+
+use crate::camera::Camera;
+use crate::scene::{Frame, Scene};
+use crate::scenes::shapes;
+use crate::shader::Shader;
+use crate::utils;
+
+// attenuation for a range of about 50 units
+// more info in https://learnopengl.com/Lighting/Light-casters
+pub(crate) const CONSTANT: f32 = 1.0;
+pub(crate) const LINEAR: f32 = 0.09;
+pub(crate) const QUADRATIC: f32 = 0.032;
+
+// the ten containers with diffuse and specular map, shaded by one of the light caster shaders
+pub(crate) struct Containers {
+    pub shader: Shader,
+    pub vao: shapes::VertexArray,
+    maps: [u32; 2],
+}
+
+impl Containers {
+    pub fn new(fragment_shader: &str, shininess: f32) -> Self {
+        let shader = Shader::new("./src/shaders/2_lighting/lighting_maps.vs", fragment_shader);
+
+        //texture
+        let maps = unsafe {
+            [
+                shapes::load_texture("./resources/textures/container2.png", true),
+                shapes::load_texture("./resources/textures/container2_specular.png", true),
+            ]
+        };
+
+        //material
+        shader.use_program();
+        shader.set_int("material.diffuseTex", 0);
+        shader.set_int("material.specularTex", 1);
+        shader.set_float("material.shininess", shininess);
+
+        Containers {
+            shader,
+            vao: unsafe { shapes::create_vao(&shapes::LIT_CUBE_VERTICES, &[3, 3, 2]) },
+            maps,
+        }
+    }
+
+    // binds the maps and the shader, the caller sets the light uniforms afterwards
+    pub unsafe fn prepare(&self, projection: Matrix4<f32>, view: Matrix4<f32>) {
+        //bind diffuse map texture
+        gl::ActiveTexture(gl::TEXTURE0);
+        gl::BindTexture(gl::TEXTURE_2D, self.maps[0]);
+        //bind specular map texture
+        gl::ActiveTexture(gl::TEXTURE1);
+        gl::BindTexture(gl::TEXTURE_2D, self.maps[1]);
+
+        self.shader.use_program();
+        self.shader.set_mat4("projection", projection);
+        self.shader.set_mat4("view", view);
+
+        gl::BindVertexArray(self.vao.id);
+    }
+
+    pub unsafe fn draw(&self) {
+        for model in Containers::models() {
+            self.shader.set_mat4("model", model);
+
+            gl::DrawArrays(gl::TRIANGLES, 0, 36);
+        }
+    }
+
+    // model matrices of the ten containers, e.g. for drawing them as shadow casters
+    pub fn models() -> Vec<Matrix4<f32>> {
+        shapes::CUBE_POSITIONS.iter().enumerate().map(|(index, position)| {
+            let angle = 20.0 * index as f32;
+            Matrix4::<f32>::from_translation(Vector3::from(*position)) * Matrix4::<f32>::from_axis_angle(Vector3::new(1.0, 0.3, 0.5).normalize(), Deg(angle))
+        }).collect()
+    }
+}
+
+impl Drop for Containers {
+    fn drop(&mut self) {
+        unsafe { shapes::delete_textures(&self.maps); }
+    }
+}
+
+unsafe fn clear() {
+    // enable depth perspective
+    gl::Enable(gl::DEPTH_TEST);
+
+    gl::ClearColor(0.2, 0.3, 0.3, 1.0);
+    gl::Clear(gl::COLOR_BUFFER_BIT | gl::DEPTH_BUFFER_BIT);
+}
+
+/// Chapter 2: a light without position, like the sun.
+pub struct DirectionalLight {
+    camera: Camera,
+    containers: Containers,
+}
+
+impl DirectionalLight {
+    pub fn new() -> Self {
+        let containers = Containers::new("./src/shaders/2_lighting/directional_light.fs", 64.0);
+
+        //directional light
+        containers.shader.set_vector3v("light.direction", Vector3::new(-0.2, -1.0, -0.3));
+        containers.shader.set_vector3("light.ambient", 0.5, 0.5, 0.5);
+        containers.shader.set_vector3("light.diffuse", 0.4, 0.4, 0.4);
+        containers.shader.set_vector3("light.specular", 1.0, 1.0, 1.0);
+
+        DirectionalLight {
+            camera: utils::camera_at(Point3::new(0.0, 0.5, 5.0)),
+            containers,
+        }
+    }
+}
+
+impl Scene for DirectionalLight {
+    fn camera(&mut self) -> Option<&mut Camera> {
+        Some(&mut self.camera)
+    }
+
+    fn render(&mut self, frame: &Frame) {
+        let projection = perspective(Deg(45.0), frame.aspect_ratio(), 0.1, 100.0);
+
+        unsafe {
+            clear();
+
+            self.containers.prepare(projection, self.camera.calculate_view());
+            self.containers.shader.set_vector3v("cameraPos", self.camera.position.to_vec());
+            self.containers.draw();
+        }
+    }
+}
+
+/// Chapter 2: a point light whose intensity fades with the distance.
+pub struct PointLight {
+    camera: Camera,
+    containers: Containers,
+    light_cube_shader: Shader,
+}
+
+impl PointLight {
+    pub fn new() -> Self {
+        let containers = Containers::new("./src/shaders/2_lighting/point_light.fs", 64.0);
+
+        containers.shader.set_vector3v("light.position", PointLight::POSITION);
+        containers.shader.set_vector3("light.ambient", 0.5, 0.5, 0.5);
+        containers.shader.set_vector3("light.diffuse", 0.4, 0.4, 0.4);
+        containers.shader.set_vector3("light.specular", 1.0, 1.0, 1.0);
+
+        //point light parameters
+        containers.shader.set_float("light.constant", CONSTANT);
+        containers.shader.set_float("light.linear", LINEAR);
+        containers.shader.set_float("light.quadratic", QUADRATIC);
+
+        PointLight {
+            camera: utils::camera_at(Point3::new(0.0, 0.5, 5.0)),
+            containers,
+            light_cube_shader: super::light_source_shader(),
+        }
+    }
+
+    const POSITION: Vector3<f32> = Vector3 { x: 0.0, y: 2.0, z: -2.0 };
+}
+
+impl Scene for PointLight {
+    fn camera(&mut self) -> Option<&mut Camera> {
+        Some(&mut self.camera)
+    }
+
+    fn render(&mut self, frame: &Frame) {
+        let projection = perspective(Deg(45.0), frame.aspect_ratio(), 0.1, 100.0);
+        let view = self.camera.calculate_view();
+
+        unsafe {
+            clear();
+
+            self.containers.prepare(projection, view);
+            self.containers.shader.set_vector3v("cameraPos", self.camera.position.to_vec());
+            self.containers.draw();
+
+            //point light/cube
+            super::draw_light_source(&self.light_cube_shader, projection, view, PointLight::POSITION, 0.2);
+        }
+    }
+}
+
+/// Chapter 2: a flashlight, a spot light following the camera.
+pub struct SpotLight {
+    camera: Camera,
+    containers: Containers,
+}
+
+impl SpotLight {
+    pub fn new() -> Self {
+        let containers = Containers::new("./src/shaders/2_lighting/spot_light.fs", 32.0);
+
+        //light parameters
+        containers.shader.set_vector3("light.specular", 1.0, 1.0, 1.0);
+        containers.shader.set_vector3("light.diffuse", 0.8, 0.8, 0.8);
+        containers.shader.set_vector3("light.ambient", 0.1, 0.1, 0.1);
+
+        //the cutoffs are the cosines of the inner and outer angle, about 3.8 and 17.6 degrees
+        //more info in the spotlight section:
+        //https://learnopengl.com/Lighting/Light-casters
+        containers.shader.set_float("light.cutOff", 0.9978);
+        containers.shader.set_float("light.outerCutOff", 0.953);
+
+        //point light parameters
+        containers.shader.set_float("light.constant", CONSTANT);
+        containers.shader.set_float("light.linear", LINEAR);
+        containers.shader.set_float("light.quadratic", QUADRATIC);
+
+        SpotLight {
+            camera: utils::camera_at(Point3::new(0.0, 0.5, 5.0)),
+            containers,
+        }
+    }
+}
+
+impl Scene for SpotLight {
+    fn camera(&mut self) -> Option<&mut Camera> {
+        Some(&mut self.camera)
+    }
+
+    fn render(&mut self, frame: &Frame) {
+        let projection = perspective(Deg(45.0), frame.aspect_ratio(), 0.1, 100.0);
+
+        unsafe {
+            clear();
+
+            self.containers.prepare(projection, self.camera.calculate_view());
+            self.containers.shader.set_vector3v("light.position", self.camera.position.to_vec());
+            self.containers.shader.set_vector3v("light.direction", self.camera.front);
+            self.containers.draw();
+        }
+    }
+}
@@ -0,0 +1,117 @@
+use cgmath::{perspective, Deg, Matrix4, Point3, SquareMatrix, Vector3};
+
+use crate::camera::Camera;
+use crate::scene::{Frame, Scene};
+use crate::scenes::shapes;
+use crate::shader::Shader;
+use crate::utils;
+
+/// Chapter 2: the container with a diffuse and a specular map, lit by an orbiting light.
+/// With an emission map the matrix code glows on the wood.
+pub struct LightingMaps {
+    camera: Camera,
+    cube_shader: Shader,
+    light_source_shader: Shader,
+    vao: shapes::VertexArray,
+    // diffuse, specular and optionally emission map, bound to units 0, 1 and 2
+    maps: Vec<u32>,
+    light_pos: Vector3<f32>,
+}
+
+impl LightingMaps {
+    pub fn new() -> Self {
+        LightingMaps::with_shader("./src/shaders/2_lighting/lighting_maps.fs", false)
+    }
+
+    /// The exercise version, adding `matrix.jpg` as emission map.
+    pub fn with_emission() -> Self {
+        LightingMaps::with_shader("./src/shaders/2_lighting/emission_map.fs", true)
+    }
+
+    fn with_shader(fragment_shader: &str, emission: bool) -> Self {
+        let cube_shader = Shader::new("./src/shaders/2_lighting/lighting_maps.vs", fragment_shader);
+
+        //texture
+        let mut maps = unsafe {
+            vec![
+                shapes::load_texture("./resources/textures/container2.png", true),
+                shapes::load_texture("./resources/textures/container2_specular.png", true),
+            ]
+        };
+        if emission {
+            maps.push(unsafe { shapes::load_texture("./resources/textures/matrix.jpg", true) });
+        }
+
+        //material
+        cube_shader.use_program();
+        cube_shader.set_int("material.diffuseTex", 0);
+        cube_shader.set_int("material.specularTex", 1);
+        if emission {
+            cube_shader.set_int("material.emissionTex", 2);
+        }
+        cube_shader.set_float("material.shininess", 64.0);
+
+        //light source setup
+        let light_color = Vector3::new(1.0, 1.0, 1.0);
+        cube_shader.set_vector3("light.specular", 1.0, 1.0, 1.0);
+        cube_shader.set_vector3v("light.diffuse", light_color * 0.4);
+        cube_shader.set_vector3v("light.ambient", light_color * 0.5);
+
+        LightingMaps {
+            camera: utils::camera_at(Point3::new(0.0, 0.5, 5.0)),
+            cube_shader,
+            light_source_shader: super::light_source_shader(),
+            vao: unsafe { shapes::create_vao(&shapes::LIT_CUBE_VERTICES, &[3, 3, 2]) },
+            maps,
+            light_pos: Vector3::new(2.0, 1.5, 0.0),
+        }
+    }
+}
+
+impl Scene for LightingMaps {
+    fn camera(&mut self) -> Option<&mut Camera> {
+        Some(&mut self.camera)
+    }
+
+    fn update(&mut self, frame: &Frame) {
+        //light source position
+        self.light_pos = super::orbiting_light(frame.time);
+    }
+
+    fn render(&mut self, frame: &Frame) {
+        let projection = perspective(Deg(45.0), frame.aspect_ratio(), 0.1, 100.0);
+        let view = self.camera.calculate_view();
+
+        unsafe {
+            // enable depth perspective
+            gl::Enable(gl::DEPTH_TEST);
+
+            gl::ClearColor(0.2, 0.3, 0.3, 1.0);
+            gl::Clear(gl::COLOR_BUFFER_BIT | gl::DEPTH_BUFFER_BIT);
+
+            //bind diffuse, specular and emission map texture
+            for (unit, &map) in self.maps.iter().enumerate() {
+                gl::ActiveTexture(gl::TEXTURE0 + unit as u32);
+                gl::BindTexture(gl::TEXTURE_2D, map);
+            }
+
+            self.cube_shader.use_program();
+            self.cube_shader.set_mat4("projection", projection);
+            self.cube_shader.set_mat4("view", view);
+            self.cube_shader.set_mat4("model", Matrix4::<f32>::identity());
+            self.cube_shader.set_vector3("cameraPos", self.camera.position.x, self.camera.position.y, self.camera.position.z);
+            self.cube_shader.set_vector3v("light.position", self.light_pos);
+
+            gl::BindVertexArray(self.vao.id);
+            gl::DrawArrays(gl::TRIANGLES, 0, 36);
+
+            super::draw_light_source(&self.light_source_shader, projection, view, self.light_pos, 0.2);
+        }
+    }
+}
+
+impl Drop for LightingMaps {
+    fn drop(&mut self) {
+        unsafe { shapes::delete_textures(&self.maps); }
+    }
+}
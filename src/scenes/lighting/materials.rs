@@ -0,0 +1,87 @@
+use cgmath::{perspective, Deg, Matrix4, Point3, SquareMatrix, Vector3};
+
+use crate::camera::Camera;
+use crate::scene::{Frame, Scene};
+use crate::scenes::shapes;
+use crate::shader::Shader;
+use crate::utils;
+
+/// Chapter 2: material and light structs, the light changes its color over time.
+pub struct PhongMaterial {
+    camera: Camera,
+    cube_shader: Shader,
+    light_source_shader: Shader,
+    vao: shapes::VertexArray,
+    light_pos: Vector3<f32>,
+    light_color: Vector3<f32>,
+}
+
+impl PhongMaterial {
+    pub fn new() -> Self {
+        let cube_shader = Shader::new(
+            "./src/shaders/2_lighting/phong_light.vs",
+            "./src/shaders/2_lighting/material.fs"
+        );
+
+        cube_shader.use_program();
+        cube_shader.set_vector3("material.ambient", 1.0, 0.5, 0.31);
+        cube_shader.set_vector3("material.diffuse", 1.0, 0.5, 0.31);
+        cube_shader.set_vector3("material.specular", 0.5, 0.5, 0.5);
+        cube_shader.set_float("material.shininess", 32.0);
+        // material can be found under http://devernay.free.fr/cours/opengl/materials.html
+        cube_shader.set_vector3("light.specular", 1.0, 1.0, 1.0);
+
+        PhongMaterial {
+            camera: utils::camera_at(Point3::new(0.0, 0.5, 5.0)),
+            cube_shader,
+            light_source_shader: super::light_source_shader(),
+            vao: unsafe { shapes::create_vao(&shapes::LIT_CUBE_VERTICES, &[3, 3, 2]) },
+            light_pos: Vector3::new(0.0, 1.5, 0.0),
+            light_color: Vector3::new(1.0, 1.0, 1.0),
+        }
+    }
+}
+
+impl Scene for PhongMaterial {
+    fn camera(&mut self) -> Option<&mut Camera> {
+        Some(&mut self.camera)
+    }
+
+    fn update(&mut self, frame: &Frame) {
+        //light source position
+        self.light_pos = super::orbiting_light(frame.time);
+
+        //changing light color
+        self.light_color.x = (frame.time * 2.0).sin();
+        self.light_color.y = (frame.time * 0.7).sin();
+        self.light_color.z = (frame.time * 1.3).sin();
+    }
+
+    fn render(&mut self, frame: &Frame) {
+        let projection = perspective(Deg(45.0), frame.aspect_ratio(), 0.1, 100.0);
+        let view = self.camera.calculate_view();
+        let (light_pos, light_color) = (self.light_pos, self.light_color);
+
+        unsafe {
+            // enable depth perspective
+            gl::Enable(gl::DEPTH_TEST);
+
+            gl::ClearColor(0.2, 0.3, 0.3, 1.0);
+            gl::Clear(gl::COLOR_BUFFER_BIT | gl::DEPTH_BUFFER_BIT);
+
+            self.cube_shader.use_program();
+            self.cube_shader.set_mat4("projection", projection);
+            self.cube_shader.set_mat4("view", view);
+            self.cube_shader.set_mat4("model", Matrix4::<f32>::identity());
+            self.cube_shader.set_vector3("cameraPos", self.camera.position.x, self.camera.position.y, self.camera.position.z);
+            self.cube_shader.set_vector3v("light.position", light_pos);
+            self.cube_shader.set_vector3v("light.ambient", light_color * 0.5);
+            self.cube_shader.set_vector3v("light.diffuse", light_color * 0.2);
+
+            gl::BindVertexArray(self.vao.id);
+            gl::DrawArrays(gl::TRIANGLES, 0, 36);
+
+            super::draw_light_source(&self.light_source_shader, projection, view, light_pos, 0.2);
+        }
+    }
+}
@@ -0,0 +1,35 @@
+//! Chapter 2: Lighting
+
+pub mod colors;
+pub mod basic_lighting;
+pub mod materials;
+pub mod lighting_maps;
+pub mod light_casters;
+pub mod multiple_lights;
+
+use cgmath::{Matrix4, Vector3};
+
+use crate::shader::Shader;
+
+/// The shader drawing the light sources as plain white cubes.
+pub(crate) fn light_source_shader() -> Shader {
+    Shader::new(
+        "./src/shaders/2_lighting/basic_lighting.vs",
+        "./src/shaders/2_lighting/light_source.fs"
+    )
+}
+
+/// Draws a light source as a small cube, the cube VAO has to be bound.
+pub(crate) unsafe fn draw_light_source(shader: &Shader, projection: Matrix4<f32>, view: Matrix4<f32>, position: Vector3<f32>, scale: f32) {
+    shader.use_program();
+    shader.set_mat4("projection", projection);
+    shader.set_mat4("view", view);
+    shader.set_mat4("model", Matrix4::<f32>::from_translation(position) * Matrix4::<f32>::from_scale(scale));
+
+    gl::DrawArrays(gl::TRIANGLES, 0, 36);
+}
+
+/// Light position circling around the origin over time.
+pub(crate) fn orbiting_light(time: f32) -> Vector3<f32> {
+    Vector3::new(time.cos() * 2.0, time.cos() * 1.5, time.sin() * 2.0)
+}
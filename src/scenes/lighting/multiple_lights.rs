@@ -0,0 +1,149 @@
+use cgmath::{perspective, Deg, EuclideanSpace, Matrix4, Point3, Vector3};
+
+use crate::camera::Camera;
+use crate::input::InputState;
+use crate::mesh::{Geometry, Mesh};
+use crate::scene::{Frame, Scene};
+use crate::shader::Shader;
+use crate::scenes::shapes;
+use crate::shading::LightingModel;
+use crate::shadow::{self, PointShadowMap};
+use crate::utils;
+
+use super::light_casters::{Containers, CONSTANT, LINEAR, QUADRATIC};
+
+pub const POINT_LIGHT_POSITIONS: [[f32; 3]; 4] = [
+    [ 0.7,  0.2,  2.0],
+    [ 2.3, -3.3, -4.0],
+    [-4.0,  2.0, -12.0],
+    [ 0.0,  0.0, -3.0]
+];
+
+// point shadows reach the farthest container from every light
+const SHADOW_FAR_PLANE: f32 = 25.0;
+const SHADOW_MAP_SIZE: u32 = 256;
+
+/// Chapter 2: a directional light, four point lights and a flashlight combined.
+pub struct MultipleLights {
+    camera: Camera,
+    containers: Containers,
+    light_source_shader: Shader,
+    // switched with toggle_lighting_model
+    lighting_model: LightingModel,
+    // the containers as shadow casters, one cubemap per point light, switched with toggle_shadows
+    cube: Mesh,
+    point_shadows: Vec<PointShadowMap>,
+    shadows: bool,
+}
+
+impl MultipleLights {
+    pub fn new() -> Self {
+        let containers = Containers::new("./src/shaders/2_lighting/multiple_lights.fs", 32.0);
+        let lighting_shader = &containers.shader;
+
+        //directional light
+        lighting_shader.set_vector3v("dirLight.direction", Vector3::new(-0.2, -1.0, -0.3));
+        lighting_shader.set_vector3v("dirLight.ambient", Vector3::new(0.05, 0.05, 0.05));
+        lighting_shader.set_vector3v("dirLight.diffuse", Vector3::new(0.4, 0.4, 0.4));
+        lighting_shader.set_vector3v("dirLight.specular", Vector3::new(0.5, 0.5, 0.5));
+
+        //point light
+        for (index, position) in POINT_LIGHT_POSITIONS.iter().enumerate() {
+            let light = format!("pointLight[{}]", index);
+            lighting_shader.set_vector3v(&format!("{}.position", light), Vector3::from(*position));
+            lighting_shader.set_vector3v(&format!("{}.ambient", light), Vector3::new(0.05, 0.05, 0.05));
+            lighting_shader.set_vector3v(&format!("{}.diffuse", light), Vector3::new(0.8, 0.8, 0.8));
+            lighting_shader.set_vector3v(&format!("{}.specular", light), Vector3::new(1.0, 1.0, 1.0));
+            lighting_shader.set_float(&format!("{}.constant", light), CONSTANT);
+            lighting_shader.set_float(&format!("{}.linear", light), LINEAR);
+            lighting_shader.set_float(&format!("{}.quadratic", light), QUADRATIC);
+        }
+
+        //spot light
+        lighting_shader.set_vector3v("spotLight.ambient", Vector3::new(0.0, 0.0, 0.0));
+        lighting_shader.set_vector3v("spotLight.diffuse", Vector3::new(1.0, 1.0, 1.0));
+        lighting_shader.set_vector3v("spotLight.specular", Vector3::new(1.0, 1.0, 1.0));
+        lighting_shader.set_float("spotLight.constant", CONSTANT);
+        lighting_shader.set_float("spotLight.linear", LINEAR);
+        lighting_shader.set_float("spotLight.quadratic", QUADRATIC);
+        lighting_shader.set_float("spotLight.cutOff", 0.9978);
+        lighting_shader.set_float("spotLight.outerCutOff", 0.953);
+
+        //point light shadows, off until toggled
+        shadow::assign_shadow_units(lighting_shader);
+        let point_shadows = POINT_LIGHT_POSITIONS.iter()
+            .map(|_| PointShadowMap::new(SHADOW_MAP_SIZE, SHADOW_FAR_PLANE).expect("Could not create the point shadow maps"))
+            .collect();
+
+        MultipleLights {
+            camera: utils::camera_at(Point3::new(0.0, 0.5, 5.0)),
+            containers,
+            light_source_shader: super::light_source_shader(),
+            lighting_model: LightingModel::Phong,
+            cube: shapes::lit_cube_mesh(),
+            point_shadows,
+            shadows: false,
+        }
+    }
+}
+
+impl Scene for MultipleLights {
+    fn camera(&mut self) -> Option<&mut Camera> {
+        Some(&mut self.camera)
+    }
+
+    fn handle_input(&mut self, input: &InputState) {
+        if input.pressed("toggle_lighting_model") {
+            self.lighting_model = self.lighting_model.toggled();
+            println!("Lighting model: {}", self.lighting_model.name());
+        }
+        if input.pressed("toggle_shadows") {
+            self.shadows = !self.shadows;
+            println!("Point light shadows: {}", if self.shadows { "on" } else { "off" });
+        }
+    }
+
+    fn render(&mut self, frame: &Frame) {
+        //recalculate stuff
+        let projection = perspective(Deg(45.0), frame.aspect_ratio(), 0.1, 100.0);
+        let view = self.camera.calculate_view();
+        let camera_position = self.camera.position.to_vec();
+
+        //distances from every point light
+        if self.shadows {
+            let models = Containers::models();
+            let casters: Vec<(&dyn Geometry, Matrix4<f32>)> = models.iter().map(|&model| (&self.cube as &dyn Geometry, model)).collect();
+            for (map, position) in self.point_shadows.iter_mut().zip(POINT_LIGHT_POSITIONS.iter()) {
+                map.render(Vector3::from(*position), &casters);
+            }
+        }
+
+        unsafe {
+            gl::Enable(gl::DEPTH_TEST);
+
+            gl::ClearColor(0.1, 0.1, 0.1, 1.0);
+            gl::Clear(gl::COLOR_BUFFER_BIT | gl::DEPTH_BUFFER_BIT);
+
+            //cube using lighting shader
+            self.containers.prepare(projection, view);
+            self.containers.shader.set_vector3v("cameraPosition", camera_position);
+            self.lighting_model.apply(&self.containers.shader);
+            for (light, map) in self.point_shadows.iter().enumerate() {
+                if self.shadows {
+                    map.apply(&self.containers.shader, light as u32);
+                } else {
+                    self.containers.shader.set_bool(&format!("pointLight[{}].shadows", light), false);
+                }
+            }
+            //spot light on camera
+            self.containers.shader.set_vector3v("spotLight.direction", self.camera.front);
+            self.containers.shader.set_vector3v("spotLight.position", camera_position);
+            self.containers.draw();
+
+            //light source using light_source_shader
+            for position in POINT_LIGHT_POSITIONS.iter() {
+                super::draw_light_source(&self.light_source_shader, projection, view, Vector3::from(*position), 0.02);
+            }
+        }
+    }
+}
@@ -0,0 +1,288 @@
+//! The examples as `Scene`s, so they can also be rendered headless.
+
+pub mod shapes;
+pub mod getting_started;
+pub mod lighting;
+pub mod model_loading;
+pub mod advanced_opengl;
+pub mod advanced_lighting;
+
+use crate::scene::Scene;
+
+use self::advanced_lighting::*;
+use self::advanced_opengl::*;
+use self::getting_started::*;
+use self::lighting::*;
+use self::model_loading::*;
+
+/// A scene in the registry, named like the cargo example showing it.
+pub struct Entry {
+    pub name: &'static str,
+    pub chapter: &'static str,
+    // one line for the launcher's list
+    pub description: &'static str,
+    /// Builds the scene, a GL context has to be current.
+    pub create: fn() -> Box<dyn Scene>,
+}
+
+/// Every example scene in chapter order.
+pub const SCENES: &[Entry] = &[
+    Entry {
+        name: "hello_triangle", chapter: "1_getting_started",
+        description: "A single orange triangle.",
+        create: || Box::new(hello_triangle::HelloTriangle::new()),
+    },
+    Entry {
+        name: "hello_indexed", chapter: "1_getting_started",
+        description: "A rectangle from two indexed triangles.",
+        create: || Box::new(hello_triangle::HelloIndexed::new()),
+    },
+    Entry {
+        name: "exercise_1_1", chapter: "1_getting_started",
+        description: "Two triangles next to each other, as wireframe.",
+        create: || Box::new(hello_triangle::Exercise1::new()),
+    },
+    Entry {
+        name: "exercise_1_2", chapter: "1_getting_started",
+        description: "The same two triangles, each with its own VAO and VBO.",
+        create: || Box::new(hello_triangle::Exercise2::new()),
+    },
+    Entry {
+        name: "exercise_1_3", chapter: "1_getting_started",
+        description: "Two triangles drawn with two different shader programs.",
+        create: || Box::new(hello_triangle::Exercise3::new()),
+    },
+    Entry {
+        name: "interpolation", chapter: "1_getting_started",
+        description: "The vertex colors are interpolated across the triangle.",
+        create: || Box::new(shaders::Interpolation::new()),
+    },
+    Entry {
+        name: "shader_class", chapter: "1_getting_started",
+        description: "The same triangle, with the shaders loaded by the Shader class.",
+        create: || Box::new(shaders::ShaderClass::new()),
+    },
+    Entry {
+        name: "textures", chapter: "1_getting_started",
+        description: "A textured rectangle, tinted by its vertex colors.",
+        create: || Box::new(textures::Textures::new()),
+    },
+    Entry {
+        name: "multiple_textures", chapter: "1_getting_started",
+        description: "Two textures mixed in the fragment shader.",
+        create: || Box::new(textures::MultipleTextures::new()),
+    },
+    Entry {
+        name: "transformations", chapter: "1_getting_started",
+        description: "The textured rectangle spinning in the bottom right corner.",
+        create: || Box::new(transformations::Transformations::new()),
+    },
+    Entry {
+        name: "coordinate_system", chapter: "1_getting_started",
+        description: "The textured rectangle laid on the floor with model, view and projection matrices.",
+        create: || Box::new(coordinate_systems::CoordinateSystem::new()),
+    },
+    Entry {
+        name: "3D_cube", chapter: "1_getting_started",
+        description: "A rotating cube, needs the depth test.",
+        create: || Box::new(coordinate_systems::Cube3D::new()),
+    },
+    Entry {
+        name: "cubes", chapter: "1_getting_started",
+        description: "Ten cubes placed in the world.",
+        create: || Box::new(coordinate_systems::Cubes::new()),
+    },
+    Entry {
+        name: "camera_circle", chapter: "1_getting_started",
+        description: "The camera circles around the cubes, always looking at the origin.",
+        create: || Box::new(camera::CameraCircle::new()),
+    },
+    Entry {
+        name: "camera_move", chapter: "1_getting_started",
+        description: "Walk between the cubes with WASD and the mouse.",
+        create: || Box::new(camera::CameraMove::new()),
+    },
+    Entry {
+        name: "camera_system", chapter: "1_getting_started",
+        description: "The same walk, with the view calculated by the Camera class.",
+        create: || Box::new(camera::CameraSystem::new()),
+    },
+
+    Entry {
+        name: "colors", chapter: "2_lighting",
+        description: "A coral cube lit by a white light.",
+        create: || Box::new(colors::Colors::new()),
+    },
+    Entry {
+        name: "phong_split", chapter: "2_lighting",
+        description: "Ambient, diffuse and specular lighting side by side.",
+        create: || Box::new(basic_lighting::PhongSplit::new()),
+    },
+    Entry {
+        name: "phong_lighting", chapter: "2_lighting",
+        description: "All three phong components combined.",
+        create: || Box::new(basic_lighting::PhongLighting::new()),
+    },
+    Entry {
+        name: "phong_exercise_1", chapter: "2_lighting",
+        description: "The light circles around the cube.",
+        create: || Box::new(basic_lighting::PhongExercise1::new()),
+    },
+    Entry {
+        name: "phong_material", chapter: "2_lighting",
+        description: "Material and light structs, the light changes its color over time.",
+        create: || Box::new(materials::PhongMaterial::new()),
+    },
+    Entry {
+        name: "lighting_maps", chapter: "2_lighting",
+        description: "A container with diffuse and specular map.",
+        create: || Box::new(lighting_maps::LightingMaps::new()),
+    },
+    Entry {
+        name: "emission_map", chapter: "2_lighting",
+        description: "The container with the matrix code glowing on the wood.",
+        create: || Box::new(lighting_maps::LightingMaps::with_emission()),
+    },
+    Entry {
+        name: "directional_light", chapter: "2_lighting",
+        description: "A light without position, like the sun.",
+        create: || Box::new(light_casters::DirectionalLight::new()),
+    },
+    Entry {
+        name: "point_light", chapter: "2_lighting",
+        description: "A point light whose intensity fades with the distance.",
+        create: || Box::new(light_casters::PointLight::new()),
+    },
+    Entry {
+        name: "spot_light", chapter: "2_lighting",
+        description: "A flashlight, a spot light following the camera.",
+        create: || Box::new(light_casters::SpotLight::new()),
+    },
+    Entry {
+        name: "multiple_lights", chapter: "2_lighting",
+        description: "A directional light, four point lights and a flashlight combined.",
+        create: || Box::new(multiple_lights::MultipleLights::new()),
+    },
+
+    Entry {
+        name: "backpack", chapter: "3_model_loading",
+        description: "The backpack model loaded from an obj file.",
+        create: || Box::new(backpack::Backpack::new()),
+    },
+    Entry {
+        name: "backpack_lighting", chapter: "3_model_loading",
+        description: "The backpack lit by a point light flying around it.",
+        create: || Box::new(backpack::BackpackLighting::new()),
+    },
+
+    Entry {
+        name: "depth_testing", chapter: "4_advanced_opengl",
+        description: "Visualizes the linearized depth buffer of two cubes on a plane.",
+        create: || Box::new(depth_testing::DepthTesting::new()),
+    },
+    Entry {
+        name: "stencil", chapter: "4_advanced_opengl",
+        description: "Marble cubes outlined through the stencil buffer and by screen-space dilation.",
+        create: || Box::new(stencil_testing::StencilTesting::new()),
+    },
+    Entry {
+        name: "blending", chapter: "4_advanced_opengl",
+        description: "Grass discarded by alpha and windows blended over two cubes.",
+        create: || Box::new(blending::Blending::new()),
+    },
+    Entry {
+        name: "face_culling", chapter: "4_advanced_opengl",
+        description: "Two marble cubes, the second one with its front faces culled.",
+        create: || Box::new(face_culling::FaceCulling::new()),
+    },
+    Entry {
+        name: "framebuffers", chapter: "4_advanced_opengl",
+        description: "The scene rendered into a texture and shown on a quad, with a rear-view mirror on top.",
+        create: || Box::new(framebuffers::Framebuffers::new()),
+    },
+    Entry {
+        name: "post_processing", chapter: "4_advanced_opengl",
+        description: "The blending scene through vignette, chromatic aberration and film grain, 1-8 toggle effects.",
+        create: || Box::new(post_processing::post_processing()),
+    },
+    Entry {
+        name: "cubemaps", chapter: "4_advanced_opengl",
+        description: "Two container cubes in front of a skybox drawn from a cubemap.",
+        create: || Box::new(cubemaps::Cubemaps::new()),
+    },
+    Entry {
+        name: "environment_mapping", chapter: "4_advanced_opengl",
+        description: "A mirror cube reflecting the cubes circling it, next to a glass and a water cube.",
+        create: || Box::new(environment_mapping::EnvironmentMapping::new()),
+    },
+    Entry {
+        name: "backpack_reflection", chapter: "4_advanced_opengl",
+        description: "The backpack model as glass, reflecting and refracting the skybox.",
+        create: || Box::new(backpack::BackpackReflection::new()),
+    },
+    Entry {
+        name: "instancing", chapter: "4_advanced_opengl",
+        description: "Ten thousand grass blades swaying in the wind, drawn in a single instanced call.",
+        create: || Box::new(instancing::Instancing::new()),
+    },
+    Entry {
+        name: "order_independent_transparency", chapter: "4_advanced_opengl",
+        description: "Intersecting glass panes, sorted per pane on the left and order-independent on the right.",
+        create: || Box::new(order_independent_transparency::OrderIndependentTransparency::new()),
+    },
+    Entry {
+        name: "blinn_phong", chapter: "5_advanced_lighting",
+        description: "A floor under a single light, with Phong highlights on the left and Blinn-Phong on the right.",
+        create: || Box::new(blinn_phong::BlinnPhong::new()),
+    },
+    Entry {
+        name: "gamma_correction", chapter: "5_advanced_lighting",
+        description: "Four lights of rising brightness, lit on sRGB values on the left and gamma corrected on the right.",
+        create: || Box::new(gamma_correction::GammaCorrection::new()),
+    },
+    Entry {
+        name: "shadow_mapping", chapter: "5_advanced_lighting",
+        description: "Cubes casting soft shadows of a directional light onto the floor.",
+        create: || Box::new(shadow_mapping::ShadowMapping::new()),
+    },
+    Entry {
+        name: "point_shadows", chapter: "5_advanced_lighting",
+        description: "A point light circling between cubes, shadowing in every direction through a depth cubemap.",
+        create: || Box::new(point_shadows::PointShadows::new()),
+    },
+    Entry {
+        name: "cascaded_shadows", chapter: "5_advanced_lighting",
+        description: "A field of pillars in a low sun, shadowed near and far by cascades, colored by cascade on demand.",
+        create: || Box::new(cascaded_shadows::CascadedShadows::new()),
+    },
+    Entry {
+        name: "normal_mapping", chapter: "5_advanced_lighting",
+        description: "Tiles on a cube and the floor from normal and height maps, with simple, steep and occlusion parallax.",
+        create: || Box::new(normal_mapping::NormalMapping::new()),
+    },
+    Entry {
+        name: "hdr", chapter: "5_advanced_lighting",
+        description: "A tunnel with a very bright light at its end, tone mapped with adjustable and automatic exposure.",
+        create: || Box::new(hdr::Hdr::new()),
+    },
+    Entry {
+        name: "bloom", chapter: "5_advanced_lighting",
+        description: "Colored lights and a glowing crate bleeding into their surroundings, through a chain of downsampled levels.",
+        create: || Box::new(bloom::Bloom::new()),
+    },
+    Entry {
+        name: "deferred_shading", chapter: "5_advanced_lighting",
+        description: "Hundreds of colored lights and sweeping spot lights over crates, shaded from a G-buffer with light volumes.",
+        create: || Box::new(deferred_shading::DeferredShading::new()),
+    },
+];
+
+/// Looks up a scene by its example name.
+pub fn find(name: &str) -> Option<&'static Entry> {
+    SCENES.iter().find(|entry| entry.name == name)
+}
+
+/// Builds a scene by its example name, a GL context has to be current.
+pub fn create(name: &str) -> Option<Box<dyn Scene>> {
+    find(name).map(|entry| (entry.create)())
+}
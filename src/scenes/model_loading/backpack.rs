@@ -0,0 +1,202 @@
+use cgmath::{perspective, Deg, EuclideanSpace, Matrix4, Point3, SquareMatrix, Vector3};
+
+use crate::camera::Camera;
+use crate::cubemap::{self, Skybox};
+use crate::environment::{EnvironmentMaterial, IOR_GLASS};
+use crate::input::InputState;
+use crate::model::Model;
+use crate::scene::{Frame, Scene};
+use crate::scenes::advanced_opengl::cubemaps::SKYBOX_FACES;
+use crate::scenes::lighting::light_casters::{CONSTANT, LINEAR, QUADRATIC};
+use crate::scenes::shapes;
+use crate::shader::Shader;
+use crate::shading::LightingModel;
+use crate::utils;
+
+pub const BACKPACK_DIRECTORY: &str = "./resources/obj/backpack";
+pub const BACKPACK_FILE: &str = "backpack.obj";
+
+/// Chapter 3: the backpack model loaded with tobj, only its diffuse texture.
+pub struct Backpack {
+    camera: Camera,
+    shader: Shader,
+    model: Model,
+}
+
+impl Backpack {
+    pub fn new() -> Self {
+        Backpack {
+            camera: utils::camera_at(Point3::new(0.0, 0.5, 5.0)),
+            shader: Shader::new(
+                "./src/shaders/3_model_loading/model_loading.vs",
+                "./src/shaders/3_model_loading/model_loading.fs"
+            ),
+            model: Model::new(BACKPACK_DIRECTORY, BACKPACK_FILE),
+        }
+    }
+}
+
+impl Scene for Backpack {
+    fn camera(&mut self) -> Option<&mut Camera> {
+        Some(&mut self.camera)
+    }
+
+    fn render(&mut self, frame: &Frame) {
+        let projection = perspective(Deg(45.0), frame.aspect_ratio(), 0.1, 100.0);
+
+        unsafe {
+            gl::Enable(gl::DEPTH_TEST);
+
+            gl::ClearColor(0.1, 0.5, 0.5, 1.0);
+            gl::Clear(gl::COLOR_BUFFER_BIT | gl::DEPTH_BUFFER_BIT);
+        }
+
+        self.shader.use_program();
+        self.shader.set_mat4("projection", projection);
+        self.shader.set_mat4("view", self.camera.calculate_view());
+        self.shader.set_mat4("model", Matrix4::<f32>::identity());
+        self.model.Draw(&self.shader);
+    }
+}
+
+/// Chapter 3: the backpack lit by a point light flying around it.
+pub struct BackpackLighting {
+    camera: Camera,
+    model_shader: Shader,
+    light_source_shader: Shader,
+    model: Model,
+    vao: shapes::VertexArray,
+    light_position: Vector3<f32>,
+    // switched with toggle_lighting_model
+    lighting_model: LightingModel,
+}
+
+impl BackpackLighting {
+    pub fn new() -> Self {
+        let model_shader = Shader::new(
+            "./src/shaders/3_model_loading/model_lighting.vs",
+            "./src/shaders/3_model_loading/model_lighting.fs"
+        );
+
+        //light uniform
+        model_shader.use_program();
+        model_shader.set_vector3("light.ambient", 0.5, 0.5, 0.5);
+        model_shader.set_vector3("light.diffuse", 0.8, 0.8, 0.8);
+        model_shader.set_vector3("light.specular", 1.0, 1.0, 1.0);
+        model_shader.set_float("light.constant", CONSTANT);
+        model_shader.set_float("light.linear", LINEAR);
+        model_shader.set_float("light.quadratic", QUADRATIC);
+
+        BackpackLighting {
+            camera: utils::camera_at(Point3::new(0.0, 0.5, 5.0)),
+            model_shader,
+            light_source_shader: crate::scenes::lighting::light_source_shader(),
+            model: Model::new(BACKPACK_DIRECTORY, BACKPACK_FILE),
+            vao: unsafe { shapes::create_vao(&shapes::LIT_CUBE_VERTICES, &[3, 3, 2]) },
+            light_position: Vector3::new(2.0, 2.0, 2.0),
+            lighting_model: LightingModel::Phong,
+        }
+    }
+}
+
+impl Scene for BackpackLighting {
+    fn camera(&mut self) -> Option<&mut Camera> {
+        Some(&mut self.camera)
+    }
+
+    fn handle_input(&mut self, input: &InputState) {
+        if input.pressed("toggle_lighting_model") {
+            self.lighting_model = self.lighting_model.toggled();
+            println!("Lighting model: {}", self.lighting_model.name());
+        }
+    }
+
+    fn update(&mut self, frame: &Frame) {
+        self.light_position = Vector3::new(
+            2.0 * (frame.time * 2.0).cos(),
+            frame.time.cos(),
+            2.0 * (frame.time * 2.0).sin(),
+        );
+    }
+
+    fn render(&mut self, frame: &Frame) {
+        let projection = perspective(Deg(45.0), frame.aspect_ratio(), 0.1, 100.0);
+        let view = self.camera.calculate_view();
+
+        unsafe {
+            gl::Enable(gl::DEPTH_TEST);
+
+            gl::ClearColor(0.2, 0.2, 0.2, 1.0);
+            gl::Clear(gl::COLOR_BUFFER_BIT | gl::DEPTH_BUFFER_BIT);
+
+            self.model_shader.use_program();
+            self.model_shader.set_mat4("projection", projection);
+            self.model_shader.set_mat4("view", view);
+            self.model_shader.set_mat4("model", Matrix4::<f32>::from_scale(0.5));
+            self.model_shader.set_vector3v("light.position", self.light_position);
+            self.model_shader.set_vector3v("cameraPos", self.camera.position.to_vec());
+            self.lighting_model.apply(&self.model_shader);
+            self.model.Draw(&self.model_shader);
+
+            //stuff for lighting
+            gl::BindVertexArray(self.vao.id);
+            crate::scenes::lighting::draw_light_source(&self.light_source_shader, projection, view, self.light_position, 0.1);
+        }
+    }
+}
+
+/// Chapter 4: the backpack as polished glass in front of the skybox, the environment material on a `Model`.
+pub struct BackpackReflection {
+    camera: Camera,
+    shader: Shader,
+    model: Model,
+    material: EnvironmentMaterial,
+    skybox: Skybox,
+    skybox_tex: u32,
+}
+
+impl BackpackReflection {
+    pub fn new() -> Self {
+        BackpackReflection {
+            camera: utils::camera_at(Point3::new(0.0, 0.5, 5.0)),
+            shader: EnvironmentMaterial::shader(),
+            model: Model::new(BACKPACK_DIRECTORY, BACKPACK_FILE),
+            material: EnvironmentMaterial::fresnel(IOR_GLASS),
+            skybox: Skybox::new(),
+            skybox_tex: unsafe { cubemap::cubemap_from_files(&SKYBOX_FACES).expect("Could not load the skybox") },
+        }
+    }
+}
+
+impl Scene for BackpackReflection {
+    fn camera(&mut self) -> Option<&mut Camera> {
+        Some(&mut self.camera)
+    }
+
+    fn render(&mut self, frame: &Frame) {
+        let projection = perspective(Deg(45.0), frame.aspect_ratio(), 0.1, 100.0);
+        let view = self.camera.calculate_view();
+
+        unsafe {
+            gl::Enable(gl::DEPTH_TEST);
+            gl::Enable(gl::TEXTURE_CUBE_MAP_SEAMLESS);
+            gl::ClearColor(0.1, 0.1, 0.1, 1.0);
+            gl::Clear(gl::COLOR_BUFFER_BIT | gl::DEPTH_BUFFER_BIT);
+        }
+
+        // the model's textures are bound too, the environment shader just does not sample them
+        self.material.apply(&self.shader, self.skybox_tex, self.camera.position.to_vec());
+        self.shader.set_mat4("projection", projection);
+        self.shader.set_mat4("view", view);
+        self.shader.set_mat4("model", Matrix4::from_angle_y(Deg(20.0 * frame.time)) * Matrix4::from_scale(0.5));
+        self.model.Draw(&self.shader);
+
+        self.skybox.draw(self.skybox_tex, view, projection);
+    }
+}
+
+impl Drop for BackpackReflection {
+    fn drop(&mut self) {
+        unsafe { shapes::delete_textures(&[self.skybox_tex]); }
+    }
+}
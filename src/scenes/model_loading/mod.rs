@@ -0,0 +1,3 @@
+//! Chapter 3: Model loading
+
+pub mod backpack;
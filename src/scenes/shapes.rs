@@ -0,0 +1,285 @@
+use std::{ffi::c_void, mem, ptr};
+
+use image::DynamicImage::{ImageRgb8, ImageRgba8};
+use image::GenericImage;
+
+use cgmath::{Vector2, Vector3};
+
+use crate::mesh::{self, Mesh, Vertex};
+
+// cube with counter-clockwise front faces, so it also works with face culling
+pub const CUBE_VERTICES: [f32; 180] = [
+    // positions       // texture Coords
+    // Back face
+    -0.5, -0.5, -0.5,  0.0, 0.0, // Bottom-left
+     0.5,  0.5, -0.5,  1.0, 1.0, // top-right
+     0.5, -0.5, -0.5,  1.0, 0.0, // bottom-right
+     0.5,  0.5, -0.5,  1.0, 1.0, // top-right
+    -0.5, -0.5, -0.5,  0.0, 0.0, // bottom-left
+    -0.5,  0.5, -0.5,  0.0, 1.0, // top-left
+    // Front face
+    -0.5, -0.5,  0.5,  0.0, 0.0, // bottom-left
+     0.5, -0.5,  0.5,  1.0, 0.0, // bottom-right
+     0.5,  0.5,  0.5,  1.0, 1.0, // top-right
+     0.5,  0.5,  0.5,  1.0, 1.0, // top-right
+    -0.5,  0.5,  0.5,  0.0, 1.0, // top-left
+    -0.5, -0.5,  0.5,  0.0, 0.0, // bottom-left
+    // Left face
+    -0.5,  0.5,  0.5,  1.0, 0.0, // top-right
+    -0.5,  0.5, -0.5,  1.0, 1.0, // top-left
+    -0.5, -0.5, -0.5,  0.0, 1.0, // bottom-left
+    -0.5, -0.5, -0.5,  0.0, 1.0, // bottom-left
+    -0.5, -0.5,  0.5,  0.0, 0.0, // bottom-right
+    -0.5,  0.5,  0.5,  1.0, 0.0, // top-right
+    // Right face
+     0.5,  0.5,  0.5,  1.0, 0.0, // top-left
+     0.5, -0.5, -0.5,  0.0, 1.0, // bottom-right
+     0.5,  0.5, -0.5,  1.0, 1.0, // top-right
+     0.5, -0.5, -0.5,  0.0, 1.0, // bottom-right
+     0.5,  0.5,  0.5,  1.0, 0.0, // top-left
+     0.5, -0.5,  0.5,  0.0, 0.0, // bottom-left
+    // Bottom face
+    -0.5, -0.5, -0.5,  0.0, 1.0, // top-right
+     0.5, -0.5, -0.5,  1.0, 1.0, // top-left
+     0.5, -0.5,  0.5,  1.0, 0.0, // bottom-left
+     0.5, -0.5,  0.5,  1.0, 0.0, // bottom-left
+    -0.5, -0.5,  0.5,  0.0, 0.0, // bottom-right
+    -0.5, -0.5, -0.5,  0.0, 1.0, // top-right
+    // Top face
+    -0.5,  0.5, -0.5,  0.0, 1.0, // top-left
+     0.5,  0.5,  0.5,  1.0, 0.0, // bottom-right
+     0.5,  0.5, -0.5,  1.0, 1.0, // top-right
+     0.5,  0.5,  0.5,  1.0, 0.0, // bottom-right
+    -0.5,  0.5, -0.5,  0.0, 1.0, // top-left
+    -0.5,  0.5,  0.5,  0.0, 0.0  // bottom-left
+];
+
+// floor plane, counter-clockwise when seen from above
+pub const PLANE_VERTICES: [f32; 30] = [
+    // positions          // texture Coords (note we set these higher than 1 (together with GL_REPEAT as texture wrapping mode). this will cause the floor texture to repeat)
+     5.0, -0.5,  5.0,  2.0, 0.0,
+    -5.0, -0.5, -5.0,  0.0, 2.0,
+    -5.0, -0.5,  5.0,  0.0, 0.0,
+
+     5.0, -0.5,  5.0,  2.0, 0.0,
+     5.0, -0.5, -5.0,  2.0, 2.0,
+    -5.0, -0.5, -5.0,  0.0, 2.0
+];
+
+// cube with normals for the lighting chapter
+pub const LIT_CUBE_VERTICES: [f32; 288] = [
+    // positions       // normals        // texture coords
+    // vec3            // vec3           // vec2
+    -0.5, -0.5, -0.5,  0.0,  0.0, -1.0,  0.0,  0.0,
+     0.5, -0.5, -0.5,  0.0,  0.0, -1.0,  1.0,  0.0,
+     0.5,  0.5, -0.5,  0.0,  0.0, -1.0,  1.0,  1.0,
+     0.5,  0.5, -0.5,  0.0,  0.0, -1.0,  1.0,  1.0,
+    -0.5,  0.5, -0.5,  0.0,  0.0, -1.0,  0.0,  1.0,
+    -0.5, -0.5, -0.5,  0.0,  0.0, -1.0,  0.0,  0.0,
+
+    -0.5, -0.5,  0.5,  0.0,  0.0,  1.0,  0.0,  0.0,
+     0.5, -0.5,  0.5,  0.0,  0.0,  1.0,  1.0,  0.0,
+     0.5,  0.5,  0.5,  0.0,  0.0,  1.0,  1.0,  1.0,
+     0.5,  0.5,  0.5,  0.0,  0.0,  1.0,  1.0,  1.0,
+    -0.5,  0.5,  0.5,  0.0,  0.0,  1.0,  0.0,  1.0,
+    -0.5, -0.5,  0.5,  0.0,  0.0,  1.0,  0.0,  0.0,
+
+    -0.5,  0.5,  0.5, -1.0,  0.0,  0.0,  1.0,  0.0,
+    -0.5,  0.5, -0.5, -1.0,  0.0,  0.0,  1.0,  1.0,
+    -0.5, -0.5, -0.5, -1.0,  0.0,  0.0,  0.0,  1.0,
+    -0.5, -0.5, -0.5, -1.0,  0.0,  0.0,  0.0,  1.0,
+    -0.5, -0.5,  0.5, -1.0,  0.0,  0.0,  0.0,  0.0,
+    -0.5,  0.5,  0.5, -1.0,  0.0,  0.0,  1.0,  0.0,
+
+     0.5,  0.5,  0.5,  1.0,  0.0,  0.0,  1.0,  0.0,
+     0.5,  0.5, -0.5,  1.0,  0.0,  0.0,  1.0,  1.0,
+     0.5, -0.5, -0.5,  1.0,  0.0,  0.0,  0.0,  1.0,
+     0.5, -0.5, -0.5,  1.0,  0.0,  0.0,  0.0,  1.0,
+     0.5, -0.5,  0.5,  1.0,  0.0,  0.0,  0.0,  0.0,
+     0.5,  0.5,  0.5,  1.0,  0.0,  0.0,  1.0,  0.0,
+
+    -0.5, -0.5, -0.5,  0.0, -1.0,  0.0,  0.0,  1.0,
+     0.5, -0.5, -0.5,  0.0, -1.0,  0.0,  1.0,  1.0,
+     0.5, -0.5,  0.5,  0.0, -1.0,  0.0,  1.0,  0.0,
+     0.5, -0.5,  0.5,  0.0, -1.0,  0.0,  1.0,  0.0,
+    -0.5, -0.5,  0.5,  0.0, -1.0,  0.0,  0.0,  0.0,
+    -0.5, -0.5, -0.5,  0.0, -1.0,  0.0,  0.0,  1.0,
+
+    -0.5,  0.5, -0.5,  0.0,  1.0,  0.0,  0.0,  1.0,
+     0.5,  0.5, -0.5,  0.0,  1.0,  0.0,  1.0,  1.0,
+     0.5,  0.5,  0.5,  0.0,  1.0,  0.0,  1.0,  0.0,
+     0.5,  0.5,  0.5,  0.0,  1.0,  0.0,  1.0,  0.0,
+    -0.5,  0.5,  0.5,  0.0,  1.0,  0.0,  0.0,  0.0,
+    -0.5,  0.5, -0.5,  0.0,  1.0,  0.0,  0.0,  1.0
+];
+
+// a 20 by 20 floor with normals, the texture repeats ten times
+pub const LIT_PLANE_VERTICES: [f32; 48] = [
+    // positions          // normals       // texture coords
+     10.0, -0.5,  10.0,   0.0, 1.0, 0.0,   10.0,  0.0,
+    -10.0, -0.5,  10.0,   0.0, 1.0, 0.0,    0.0,  0.0,
+    -10.0, -0.5, -10.0,   0.0, 1.0, 0.0,    0.0, 10.0,
+
+     10.0, -0.5,  10.0,   0.0, 1.0, 0.0,   10.0,  0.0,
+    -10.0, -0.5, -10.0,   0.0, 1.0, 0.0,    0.0, 10.0,
+     10.0, -0.5, -10.0,   0.0, 1.0, 0.0,   10.0, 10.0
+];
+
+pub const QUAD_VERTICES: [f32; 30] = [
+    // positions         // texture Coords (swapped y coordinates because texture is flipped upside down)
+    0.0,  0.5,  0.0,  0.0,  0.0,
+    0.0, -0.5,  0.0,  0.0,  1.0,
+    1.0, -0.5,  0.0,  1.0,  1.0,
+
+    0.0,  0.5,  0.0,  0.0,  0.0,
+    1.0, -0.5,  0.0,  1.0,  1.0,
+    1.0,  0.5,  0.0,  1.0,  0.0
+];
+
+// positions of the ten cubes from the coordinate systems chapter onwards
+pub const CUBE_POSITIONS: [[f32; 3]; 10] = [
+    [ 0.0,  0.0,  0.0],
+    [ 2.0,  5.0, -15.0],
+    [-1.5, -2.2, -2.5],
+    [-3.8, -2.0, -12.3],
+    [ 2.4, -0.4, -3.5],
+    [-1.7,  3.0, -7.5],
+    [ 1.3, -2.0, -2.5],
+    [ 1.5,  2.0, -2.5],
+    [ 1.5,  0.2, -1.5],
+    [-1.3,  1.0, -1.5],
+];
+
+/// `LIT_CUBE_VERTICES` as a `Mesh` without textures, for code that takes meshes and models.
+pub fn lit_cube_mesh() -> Mesh {
+    lit_mesh(&LIT_CUBE_VERTICES)
+}
+
+/// `LIT_PLANE_VERTICES` as a `Mesh` without textures.
+pub fn lit_plane_mesh() -> Mesh {
+    lit_mesh(&LIT_PLANE_VERTICES)
+}
+
+// positions, normals and texture coordinates, one vertex per index, tangents for normal maps
+fn lit_mesh(vertices: &[f32]) -> Mesh {
+    let mut vertices: Vec<Vertex> = vertices.chunks(8).map(|v| Vertex {
+        position: Vector3::new(v[0], v[1], v[2]),
+        normal: Vector3::new(v[3], v[4], v[5]),
+        tex_coords: Vector2::new(v[6], v[7]),
+        ..Vertex::default()
+    }).collect();
+    let indices: Vec<u32> = (0..vertices.len() as u32).collect();
+    mesh::calculate_tangents(&mut vertices, &indices);
+    Mesh::new(vertices, indices, Vec::new())
+}
+
+/// A vertex array object and the buffers it reads from, deleted together when dropped.
+pub struct VertexArray {
+    pub id: u32,
+    pub buffers: Vec<u32>,
+}
+
+impl Drop for VertexArray {
+    fn drop(&mut self) {
+        unsafe {
+            gl::DeleteVertexArrays(1, &self.id);
+            gl::DeleteBuffers(self.buffers.len() as i32, self.buffers.as_ptr());
+        }
+    }
+}
+
+/// Uploads interleaved vertices, `layout` holds the number of floats of each attribute in order.
+pub unsafe fn create_vao(vertices: &[f32], layout: &[i32]) -> VertexArray {
+    let (mut vbo, mut vao) = (0, 0);
+
+    gl::GenBuffers(1, &mut vbo);
+    gl::GenVertexArrays(1, &mut vao);
+
+    gl::BindVertexArray(vao);
+
+    gl::BindBuffer(gl::ARRAY_BUFFER, vbo);
+    gl::BufferData(
+        gl::ARRAY_BUFFER,
+        mem::size_of_val(vertices) as isize,
+        &vertices[0] as *const f32 as *const c_void,
+        gl::STATIC_DRAW
+    );
+
+    let stride = (layout.iter().sum::<i32>() as usize * mem::size_of::<f32>()) as i32;
+    let mut offset = 0;
+    for (index, &size) in layout.iter().enumerate() {
+        let pointer = if offset == 0 { ptr::null() } else { (offset * mem::size_of::<f32>()) as *const c_void };
+        gl::VertexAttribPointer(index as u32, size, gl::FLOAT, gl::FALSE, stride, pointer);
+        gl::EnableVertexAttribArray(index as u32);
+        offset += size as usize;
+    }
+
+    gl::BindVertexArray(0);
+    VertexArray { id: vao, buffers: vec![vbo] }
+}
+
+/// Like `create_vao`, plus an element buffer with `indices` that stays bound to the VAO.
+pub unsafe fn create_indexed_vao(vertices: &[f32], indices: &[u32], layout: &[i32]) -> VertexArray {
+    let mut vao = create_vao(vertices, layout);
+    let mut ebo = 0;
+
+    gl::BindVertexArray(vao.id);
+    gl::GenBuffers(1, &mut ebo);
+    gl::BindBuffer(gl::ELEMENT_ARRAY_BUFFER, ebo);
+    gl::BufferData(
+        gl::ELEMENT_ARRAY_BUFFER,
+        mem::size_of_val(indices) as isize,
+        &indices[0] as *const u32 as *const c_void,
+        gl::STATIC_DRAW
+    );
+    vao.buffers.push(ebo);
+
+    gl::BindVertexArray(0);
+    vao
+}
+
+/// Deletes textures created by `load_texture` and the like.
+pub unsafe fn delete_textures(textures: &[u32]) {
+    gl::DeleteTextures(textures.len() as i32, textures.as_ptr());
+}
+
+/// Loads an RGB or RGBA texture with repeat wrapping and linear filtering.
+/// Most images have to be flipped, OpenGL expects the first row at the bottom.
+pub unsafe fn load_texture(path: &str, flip: bool) -> u32 {
+    let mut texture = 0;
+
+    let img = image::open(path).expect("Could not open file");
+    let img = if flip { img.flipv() } else { img };
+    let format = match img {
+        ImageRgb8(_) => gl::RGB,
+        ImageRgba8(_) => gl::RGBA,
+        _ => panic!("Problem with the format")
+    };
+    let data = img.raw_pixels();
+
+    gl::GenTextures(1, &mut texture);
+    gl::BindTexture(gl::TEXTURE_2D, texture);
+
+    gl::TexImage2D(
+        gl::TEXTURE_2D,
+        0,
+        format as i32,
+        img.width() as i32,
+        img.height() as i32,
+        0,
+        format,
+        gl::UNSIGNED_BYTE,
+        &data[0] as *const u8 as *const c_void
+    );
+
+    gl::GenerateMipmap(gl::TEXTURE_2D);
+
+    //wrapping
+    gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_WRAP_S, gl::REPEAT as i32);
+    gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_WRAP_T, gl::REPEAT as i32);
+    //filtering
+    gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MIN_FILTER, gl::LINEAR as i32);
+    gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MAG_FILTER, gl::LINEAR as i32);
+
+    texture
+}
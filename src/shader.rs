@@ -1,5 +1,5 @@
 use std::{fs::{self, File}, ffi::CString, ptr};
-use cgmath::{Matrix4, Matrix, Vector3};
+use cgmath::{Matrix4, Matrix, Vector3, Vector4};
 use gl::types::{GLint, GLchar};
 
 #[allow(temporary_cstring_as_ptr)]
@@ -96,6 +96,10 @@ impl Shader {
         unsafe { gl::Uniform1f(gl::GetUniformLocation(self.program, CString::new(name).unwrap().as_ptr()), value); }
     }
 
+    pub fn set_vector2(&self, name: &str, vector_x: f32, vector_y: f32) {
+        unsafe { gl::Uniform2f(gl::GetUniformLocation(self.program, CString::new(name).unwrap().as_ptr()), vector_x, vector_y); }
+    }
+
     pub fn set_vector3(&self, name: &str, vector_x: f32, vector_y: f32, vector_z: f32) {
         unsafe { gl::Uniform3f(gl::GetUniformLocation(self.program, CString::new(name).unwrap().as_ptr()), vector_x, vector_y, vector_z); }
     }
@@ -104,7 +108,17 @@ impl Shader {
         unsafe { gl::Uniform3f(gl::GetUniformLocation(self.program, CString::new(name).unwrap().as_ptr()), vector.x, vector.y, vector.z); }
     }
 
+    pub fn set_vector4v(&self, name: &str, vector: Vector4<f32>) {
+        unsafe { gl::Uniform4f(gl::GetUniformLocation(self.program, CString::new(name).unwrap().as_ptr()), vector.x, vector.y, vector.z, vector.w); }
+    }
+
     pub fn set_mat4(&self,  name: &str, matrix: Matrix4<f32>) {
         unsafe { gl::UniformMatrix4fv(gl::GetUniformLocation(self.program, CString::new(name).unwrap().as_ptr()), 1, gl::FALSE, matrix.as_ptr()); }
     }
-}
\ No newline at end of file
+}
+
+impl Drop for Shader {
+    fn drop(&mut self) {
+        unsafe { gl::DeleteProgram(self.program); }
+    }
+}
@@ -24,10 +24,12 @@ in vec2 texCoord;
 uniform Material material;
 uniform Light light;
 uniform vec3 cameraPos;
+//scales the emission map, beyond 1 to glow in HDR
+uniform float emissionStrength = 1.0;
 
 //main
 void main() {
-    vec3 emission;
+    vec3 emission = vec3(0.0);
     //ambient
     vec3 ambient = vec3(texture(material.diffuseTex, texCoord)) * light.ambient;
     //diffuse
@@ -41,7 +43,7 @@ void main() {
     vec3 specTex = vec3(texture(material.specularTex, texCoord));
     vec3 specular = specTex * spec * light.specular;
     if (specTex == vec3(0.0))
-        emission = vec3(texture(material.emissionTex, texCoord));
+        emission = vec3(texture(material.emissionTex, texCoord)) * emissionStrength;
     //result
     FragColor = vec4((ambient + diffuse + specular + emission), 1.0);
 }
\ No newline at end of file
@@ -1,7 +1,10 @@
 #version 330 core
 out vec4 FragColor;
 
+// white unless set, beyond 1 for lights that bloom
+uniform vec3 lightColor = vec3(1.0);
+
 void main()
 {
-    FragColor = vec4(1.0);
+    FragColor = vec4(lightColor, 1.0);
 }
\ No newline at end of file
@@ -23,6 +23,9 @@ struct PointLight {
     float constant;
     float linear;
     float quadratic;
+    // casts shadows from pointShadowMaps, storing the distance divided by farPlane
+    bool shadows;
+    float farPlane;
 };
 #define NR_POINT_LIGHTS 4
 
@@ -49,11 +52,42 @@ uniform DirectionalLight dirLight;
 uniform PointLight pointLight[NR_POINT_LIGHTS];
 uniform SpotLight spotLight;
 uniform vec3 cameraPosition;
+// Blinn-Phong instead of Phong highlights, see shading::LightingModel
+uniform bool blinn;
+// shadow map of the directional light, see shadow::ShadowMap
+uniform bool shadows;
+uniform sampler2D shadowMap;
+uniform mat4 lightSpaceMatrix;
+uniform float shadowMinBias;
+uniform float shadowSlopeBias;
+uniform int pcfRadius;
+// shadow maps of the point lights, see shadow::PointShadowMap
+uniform samplerCube pointShadowMaps[NR_POINT_LIGHTS];
+uniform float pointShadowBias;
+uniform float pointShadowRadius;
+// cascades of the directional light, see cascades::CascadedShadowMap
+#define MAX_CASCADES 4
+uniform bool cascaded;
+uniform sampler2DArray cascadeMap;
+uniform mat4 cascadeMatrices[MAX_CASCADES];
+// view depths, cascade i covers cascadeSplits[i] to cascadeSplits[i + 1]
+uniform float cascadeSplits[MAX_CASCADES + 1];
+uniform int cascadeCount;
+// the last part of each cascade fades into the next one
+uniform float cascadeBlend;
+// tints the fragments by cascade
+uniform bool cascadeDebug;
+uniform mat4 view;
 
 //function prototypes
 vec3 CalcDirLight(DirectionalLight light, vec3 normal, vec3 viewDir);
-vec3 CalcPointLight(PointLight light, vec3 normal, vec3 viewDir, vec3 fragPos);
+vec3 CalcPointLight(PointLight light, int index, vec3 normal, vec3 viewDir, vec3 fragPos);
 vec3 CalcSpotLight(SpotLight light, vec3 normal, vec3 viewDir, vec3 fragPos);
+float CalcSpecular(vec3 lightDir, vec3 normal, vec3 viewDir);
+float CalcShadow(vec3 normal, vec3 lightDir);
+float CalcPointShadow(PointLight light, int index);
+float CalcCascadedShadow(vec3 normal, vec3 lightDir);
+int CascadeIndex(float depth);
 
 //main
 void main() {
@@ -61,12 +95,20 @@ void main() {
     vec3 viewDir = normalize(cameraPosition - fragPos);
 
     vec3 direction = CalcDirLight(dirLight, norm, viewDir);
-    vec3 points;
+    vec3 points = vec3(0.0);
     for(int i = 0; i < NR_POINT_LIGHTS; i++) {
-        points += CalcPointLight(pointLight[i], norm, viewDir, fragPos);
+        points += CalcPointLight(pointLight[i], i, norm, viewDir, fragPos);
     }
     vec3 spot = CalcSpotLight(spotLight, norm, viewDir, fragPos);
     vec3 result = direction + points + spot;
+    if (cascaded && cascadeDebug) {
+        //red, green, blue and yellow from near to far
+        const vec3 tints[MAX_CASCADES] = vec3[](vec3(1.0, 0.3, 0.3), vec3(0.3, 1.0, 0.3), vec3(0.3, 0.3, 1.0), vec3(1.0, 1.0, 0.3));
+        float depth = -(view * vec4(fragPos, 1.0)).z;
+        if (depth < cascadeSplits[cascadeCount]) {
+            result *= tints[CascadeIndex(depth)];
+        }
+    }
     //result
     FragColor = vec4(result, 1.0);
 }
@@ -79,14 +121,15 @@ vec3 CalcDirLight(DirectionalLight light, vec3 normal, vec3 viewDir) {
     float diff = max(dot(lightDir, normal), 0.0);
     vec3 diffuse = vec3(texture(material.diffuseTex, texCoord)) * diff * light.diffuse;
     //specular
-    vec3 reflected = reflect(-lightDir, normal);
-    float spec = pow(max(dot(reflected, viewDir), 0.0), material.shininess);
+    float spec = CalcSpecular(lightDir, normal, viewDir);
     vec3 specular = vec3(texture(material.specularTex, texCoord)) * spec * light.specular;
+    //shadow, the ambient light reaches everywhere
+    float shadow = cascaded ? CalcCascadedShadow(normal, lightDir) : shadows ? CalcShadow(normal, lightDir) : 0.0;
     //result
-    return (ambient + diffuse + specular);
+    return (ambient + (1.0 - shadow) * (diffuse + specular));
 }
 
-vec3 CalcPointLight(PointLight light, vec3 normal, vec3 viewDir, vec3 fragPos) {
+vec3 CalcPointLight(PointLight light, int index, vec3 normal, vec3 viewDir, vec3 fragPos) {
     vec3 lightDir = normalize(light.position - fragPos);
     //ambient
     vec3 ambient = vec3(texture(material.diffuseTex, texCoord)) * light.ambient;
@@ -94,14 +137,15 @@ vec3 CalcPointLight(PointLight light, vec3 normal, vec3 viewDir, vec3 fragPos) {
     float diff = max(dot(lightDir, normal), 0.0);
     vec3 diffuse = vec3(texture(material.diffuseTex, texCoord)) * diff * light.diffuse;
     //specular
-    vec3 reflected = reflect(-lightDir, normal);
-    float spec = pow(max(dot(reflected, viewDir), 0.0), material.shininess);
+    float spec = CalcSpecular(lightDir, normal, viewDir);
     vec3 specular = vec3(texture(material.specularTex, texCoord)) * spec * light.specular;
     //attentuation
     float distance = length(light.position - fragPos);
     float attenuation = 1.0/(light.constant + light.linear * distance + light.quadratic * distance * distance);
+    //shadow
+    float shadow = light.shadows ? CalcPointShadow(light, index) : 0.0;
     //result
-    return ((ambient + diffuse + specular) * attenuation);
+    return ((ambient + (1.0 - shadow) * (diffuse + specular)) * attenuation);
 }
 
 vec3 CalcSpotLight(SpotLight light, vec3 normal, vec3 viewDir, vec3 fragPos) {
@@ -115,8 +159,7 @@ vec3 CalcSpotLight(SpotLight light, vec3 normal, vec3 viewDir, vec3 fragPos) {
     float diff = max(dot(lightDir, normal), 0.0);
     vec3 diffuse = vec3(texture(material.diffuseTex, texCoord)) * diff * light.diffuse;
     //specular
-    vec3 reflected = reflect(-lightDir, normal);
-    float spec = pow(max(dot(reflected, viewDir), 0.0), material.shininess);
+    float spec = CalcSpecular(lightDir, normal, viewDir);
     vec3 specular = vec3(texture(material.specularTex, texCoord)) * spec * light.specular;
     //attenuation
     float distance = length(light.position - fragPos);
@@ -130,3 +173,127 @@ vec3 CalcSpotLight(SpotLight light, vec3 normal, vec3 viewDir, vec3 fragPos) {
     //result
     return (ambient + diffuse + specular);
 }
+
+float CalcSpecular(vec3 lightDir, vec3 normal, vec3 viewDir) {
+    if (blinn) {
+        //halfway between light and view, never more than 90 degrees from the normal
+        vec3 halfway = normalize(lightDir + viewDir);
+        //the highlight is wider, a higher exponent keeps it about the same size
+        return pow(max(dot(normal, halfway), 0.0), material.shininess * 4.0);
+    }
+    vec3 reflected = reflect(-lightDir, normal);
+    return pow(max(dot(reflected, viewDir), 0.0), material.shininess);
+}
+
+//slope scaled bias, a texel covers more depth on surfaces at grazing angles
+float ShadowBias(vec3 normal, vec3 lightDir) {
+    float cosine = clamp(dot(normal, lightDir), 0.05, 1.0);
+    float slope = sqrt(1.0 - cosine * cosine) / cosine;
+    return shadowMinBias + shadowSlopeBias * slope;
+}
+
+//1 in shadow, 0 lit, in between at the edges
+float CalcShadow(vec3 normal, vec3 lightDir) {
+    vec4 lightSpace = lightSpaceMatrix * vec4(fragPos, 1.0);
+    vec3 projected = lightSpace.xyz / lightSpace.w * 0.5 + 0.5;
+    //beyond the far plane of the light, nothing there casts a shadow
+    if (projected.z > 1.0) {
+        return 0.0;
+    }
+    float bias = ShadowBias(normal, lightDir);
+    //percentage-closer filtering over the neighbouring texels
+    vec2 texelSize = 1.0 / vec2(textureSize(shadowMap, 0));
+    float shadow = 0.0;
+    for (int x = -pcfRadius; x <= pcfRadius; x++) {
+        for (int y = -pcfRadius; y <= pcfRadius; y++) {
+            float closest = texture(shadowMap, projected.xy + vec2(x, y) * texelSize).r;
+            shadow += projected.z - bias > closest ? 1.0 : 0.0;
+        }
+    }
+    float samples = float((2 * pcfRadius + 1) * (2 * pcfRadius + 1));
+    return shadow / samples;
+}
+
+//sampler arrays can only be indexed with constants in GLSL 3.30
+float ClosestDistance(int index, vec3 direction) {
+    if (index == 0) return texture(pointShadowMaps[0], direction).r;
+    if (index == 1) return texture(pointShadowMaps[1], direction).r;
+    if (index == 2) return texture(pointShadowMaps[2], direction).r;
+    return texture(pointShadowMaps[3], direction).r;
+}
+
+//directions spread around the sampled one, for soft edges
+const vec3 shadowOffsets[20] = vec3[](
+    vec3( 1,  1,  1), vec3( 1, -1,  1), vec3(-1, -1,  1), vec3(-1,  1,  1),
+    vec3( 1,  1, -1), vec3( 1, -1, -1), vec3(-1, -1, -1), vec3(-1,  1, -1),
+    vec3( 1,  1,  0), vec3( 1, -1,  0), vec3(-1, -1,  0), vec3(-1,  1,  0),
+    vec3( 1,  0,  1), vec3(-1,  0,  1), vec3( 1,  0, -1), vec3(-1,  0, -1),
+    vec3( 0,  1,  1), vec3( 0, -1,  1), vec3( 0, -1, -1), vec3( 0,  1, -1)
+);
+
+//1 in shadow, 0 lit, in between at the edges
+float CalcPointShadow(PointLight light, int index) {
+    vec3 fromLight = fragPos - light.position;
+    float current = length(fromLight);
+    //the map ends at the far plane, nothing beyond it casts a shadow
+    if (current > light.farPlane) {
+        return 0.0;
+    }
+    //one sample for hard shadows
+    int samples = pointShadowRadius > 0.0 ? 20 : 1;
+    float shadow = 0.0;
+    for (int i = 0; i < samples; i++) {
+        float closest = ClosestDistance(index, fromLight + shadowOffsets[i] * pointShadowRadius) * light.farPlane;
+        shadow += current - pointShadowBias > closest ? 1.0 : 0.0;
+    }
+    return shadow / float(samples);
+}
+
+int CascadeIndex(float depth) {
+    for (int i = 0; i < cascadeCount - 1; i++) {
+        if (depth < cascadeSplits[i + 1]) {
+            return i;
+        }
+    }
+    return cascadeCount - 1;
+}
+
+//like CalcShadow, the layer is a texture coordinate so it can vary
+float CascadeShadow(int cascade, vec3 normal, vec3 lightDir) {
+    vec4 lightSpace = cascadeMatrices[cascade] * vec4(fragPos, 1.0);
+    vec3 projected = lightSpace.xyz / lightSpace.w * 0.5 + 0.5;
+    //casters in front of the near plane were clamped onto it, receivers there are lit
+    if (projected.z > 1.0) {
+        return 0.0;
+    }
+    float bias = ShadowBias(normal, lightDir);
+    vec2 texelSize = 1.0 / vec2(textureSize(cascadeMap, 0));
+    float shadow = 0.0;
+    for (int x = -pcfRadius; x <= pcfRadius; x++) {
+        for (int y = -pcfRadius; y <= pcfRadius; y++) {
+            float closest = texture(cascadeMap, vec3(projected.xy + vec2(x, y) * texelSize, float(cascade))).r;
+            shadow += projected.z - bias > closest ? 1.0 : 0.0;
+        }
+    }
+    float samples = float((2 * pcfRadius + 1) * (2 * pcfRadius + 1));
+    return shadow / samples;
+}
+
+//1 in shadow, 0 lit, in between at the edges
+float CalcCascadedShadow(vec3 normal, vec3 lightDir) {
+    float depth = -(view * vec4(fragPos, 1.0)).z;
+    //past the last cascade nothing casts a shadow
+    if (depth > cascadeSplits[cascadeCount]) {
+        return 0.0;
+    }
+    int cascade = CascadeIndex(depth);
+    float shadow = CascadeShadow(cascade, normal, lightDir);
+    //fade into the next cascade, so the change in texel size does not show as a line
+    float start = cascadeSplits[cascade];
+    float end = cascadeSplits[cascade + 1];
+    float fade = (end - depth) / (end - start);
+    if (cascade < cascadeCount - 1 && fade < cascadeBlend) {
+        shadow = mix(CascadeShadow(cascade + 1, normal, lightDir), shadow, fade / cascadeBlend);
+    }
+    return shadow;
+}
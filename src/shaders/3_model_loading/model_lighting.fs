@@ -20,6 +20,8 @@ uniform sampler2D texture_diffuse1;
 uniform sampler2D texture_specular1;
 uniform Light light;
 uniform vec3 cameraPos;
+// Blinn-Phong instead of Phong highlights, see shading::LightingModel
+uniform bool blinn;
 
 void main()
 {   
@@ -32,8 +34,14 @@ void main()
     float diff_factor = max(dot(fragToLight, normal), 0.0);
     vec3 diffuse = vec3(texture(texture_diffuse1, TexCoords)) * diff_factor * light.diffuse;
     //specular
-    vec3 reflected = reflect(-fragToLight, normal);
-    float spec_factor = pow(max(dot(reflected, cameraDir), 0.0), 16);
+    float spec_factor;
+    if (blinn) {
+        vec3 halfway = normalize(fragToLight + cameraDir);
+        spec_factor = pow(max(dot(normal, halfway), 0.0), 64);
+    } else {
+        vec3 reflected = reflect(-fragToLight, normal);
+        spec_factor = pow(max(dot(reflected, cameraDir), 0.0), 16);
+    }
     vec3 specular = vec3(texture(texture_specular1, TexCoords)) * spec_factor * light.specular;
     //attenuation
     float distance = length(light.position - FragPos);
@@ -0,0 +1,14 @@
+#version 330 core
+layout (location = 0) in vec3 aPos;
+layout (location = 1) in vec2 aTexCoord;
+layout (location = 5) in mat4 aInstanceModel;
+
+uniform mat4 projection;
+uniform mat4 view;
+
+out vec2 texCoord;
+
+void main() {
+    texCoord = aTexCoord;
+    gl_Position = projection * view * aInstanceModel * vec4(aPos, 1.0);
+}
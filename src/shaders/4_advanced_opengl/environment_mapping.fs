@@ -0,0 +1,40 @@
+#version 330 core
+
+out vec4 FragColor;
+
+in vec3 Normal;
+in vec3 Position;
+
+// 0 reflects, 1 refracts, 2 blends both with the fresnel term
+uniform int mode;
+// index of refraction of the material, the outside is air with 1.0
+uniform float ior;
+uniform vec3 tint;
+uniform vec3 cameraPos;
+uniform samplerCube environment;
+
+// Schlick's approximation of the reflected fraction of the light
+float schlick(float cosTheta) {
+    float r0 = (1.0 - ior) / (1.0 + ior);
+    r0 = r0 * r0;
+    return r0 + (1.0 - r0) * pow(1.0 - cosTheta, 5.0);
+}
+
+void main() {
+    vec3 I = normalize(Position - cameraPos);
+    vec3 N = normalize(Normal);
+
+    vec3 reflected = texture(environment, reflect(I, N)).rgb;
+    // only the first surface bends the ray, the way out of the object is ignored
+    vec3 refracted = texture(environment, refract(I, N, 1.0 / ior)).rgb;
+
+    vec3 color;
+    if (mode == 0) {
+        color = reflected;
+    } else if (mode == 1) {
+        color = refracted;
+    } else {
+        color = mix(refracted, reflected, schlick(max(dot(-I, N), 0.0)));
+    }
+    FragColor = vec4(color * tint, 1.0);
+}
@@ -0,0 +1,15 @@
+#version 330 core
+
+out vec4 FragColor;
+in vec2 texCoord;
+in vec4 color;
+uniform sampler2D texture0;
+
+void main() {
+    float alpha = texture(texture0, texCoord).a;
+    if (alpha < 0.1)
+        discard;
+    // the smaller mipmaps average in the white of the transparent texels, the far blades would turn pale
+    vec3 texColor = textureLod(texture0, texCoord, 0.0).rgb;
+    FragColor = vec4(texColor, 1.0) * color;
+}
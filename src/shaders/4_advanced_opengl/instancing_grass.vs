@@ -0,0 +1,24 @@
+#version 330 core
+layout (location = 0) in vec3 aPos;
+layout (location = 1) in vec2 aTexCoord;
+layout (location = 5) in mat4 aInstanceModel;
+layout (location = 9) in vec4 aInstanceColor;
+// x: phase of the swaying, y: how far the blade bends
+layout (location = 10) in vec4 aInstanceData;
+
+uniform mat4 projection;
+uniform mat4 view;
+uniform float time;
+
+out vec2 texCoord;
+out vec4 color;
+
+void main() {
+    texCoord = aTexCoord;
+    color = aInstanceColor;
+    vec4 position = aInstanceModel * vec4(aPos, 1.0);
+    // the top of the quad (tex coord y = 0) sways in the wind, the root stays in the ground
+    float height = 1.0 - aTexCoord.y;
+    position.x += sin(time * 1.5 + aInstanceData.x) * aInstanceData.y * height * height;
+    gl_Position = projection * view * position;
+}
@@ -0,0 +1,14 @@
+#version 330 core
+
+// see src/oit.rs for what the two targets hold
+layout (location = 0) out vec4 accumulation;
+layout (location = 1) out float weight;
+
+uniform vec4 color;
+
+void main() {
+    // near and opaque fragments weigh more, equation 10 of the paper
+    float w = clamp(pow(min(1.0, color.a * 10.0) + 0.01, 3.0) * 1e8 * pow(1.0 - gl_FragCoord.z * 0.9, 3.0), 1e-2, 3e3);
+    accumulation = vec4(color.rgb * color.a * w, color.a);
+    weight = color.a * w;
+}
@@ -0,0 +1,20 @@
+#version 330 core
+
+out vec4 FragColor;
+in vec2 TexCoords;
+
+// rgb: sum of the weighted colors, a: revealage
+uniform sampler2D accumulation;
+// r: sum of the weights
+uniform sampler2D weights;
+
+void main() {
+    vec4 accum = texture(accumulation, TexCoords);
+    float revealage = accum.a;
+    // nothing transparent in front of this pixel
+    if (revealage == 1.0)
+        discard;
+    vec3 average = accum.rgb / max(texture(weights, TexCoords).r, 1e-5);
+    // blended with SRC_ALPHA, ONE_MINUS_SRC_ALPHA, the opaque scene keeps the revealed part
+    FragColor = vec4(average, 1.0 - revealage);
+}
@@ -2,6 +2,8 @@
 
 out vec4 FragColor;
 
+uniform vec4 color;
+
 void main() {
-    FragColor = vec4(0.04, 0.28, 0.26, 1.0);
-}
\ No newline at end of file
+    FragColor = color;
+}
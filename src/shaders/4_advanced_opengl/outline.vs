@@ -0,0 +1,15 @@
+#version 330 core
+layout (location = 0) in vec3 aPos;
+layout (location = 1) in vec3 aNormal;
+
+uniform mat4 projection;
+uniform mat4 view;
+uniform mat4 model;
+// how far every vertex moves along its normal, in world units
+uniform float thickness;
+
+void main() {
+    vec3 normal = normalize(mat3(transpose(inverse(model))) * aNormal);
+    vec3 position = vec3(model * vec4(aPos, 1.0)) + normal * thickness;
+    gl_Position = projection * view * vec4(position, 1.0);
+}
@@ -0,0 +1,30 @@
+#version 330 core
+
+out vec4 FragColor;
+
+in vec2 TexCoords;
+
+// 1 where the object covers the pixel
+uniform sampler2D mask;
+// in pixels
+uniform int radius;
+uniform vec4 color;
+
+void main() {
+    // inside the object, it keeps its own color
+    if (texture(mask, TexCoords).r > 0.5)
+        discard;
+
+    vec2 texel = 1.0 / vec2(textureSize(mask, 0));
+    float covered = 0.0;
+    for (int x = -radius; x <= radius; x++) {
+        for (int y = -radius; y <= radius; y++) {
+            // a disc, a square would square off the corners of the outline
+            if (x * x + y * y <= radius * radius)
+                covered = max(covered, texture(mask, TexCoords + vec2(x, y) * texel).r);
+        }
+    }
+    if (covered < 0.5)
+        discard;
+    FragColor = color;
+}
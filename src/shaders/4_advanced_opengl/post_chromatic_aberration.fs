@@ -0,0 +1,17 @@
+#version 330 core
+
+out vec4 FragColor;
+in vec2 TexCoords;
+
+uniform sampler2D screenTexture;
+// how far red and blue are pulled apart at the border, in texture coordinates
+uniform float strength;
+
+void main() {
+    // like a cheap lens, the offset grows towards the border
+    vec2 offset = (TexCoords - vec2(0.5)) * strength;
+    float red = texture(screenTexture, TexCoords + offset).r;
+    float green = texture(screenTexture, TexCoords).g;
+    float blue = texture(screenTexture, TexCoords - offset).b;
+    FragColor = vec4(red, green, blue, 1.0);
+}
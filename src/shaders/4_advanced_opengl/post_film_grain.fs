@@ -0,0 +1,22 @@
+#version 330 core
+
+out vec4 FragColor;
+in vec2 TexCoords;
+
+uniform sampler2D screenTexture;
+uniform float amount;
+// seconds since the scene started, the grain changes every frame
+uniform float time;
+
+// hash without sin, the same on every driver
+float random(vec2 p) {
+    vec3 p3 = fract(vec3(p.xyx) * 0.1031);
+    p3 += dot(p3, p3.yzx + 33.33);
+    return fract((p3.x + p3.y) * p3.z);
+}
+
+void main() {
+    vec3 color = texture(screenTexture, TexCoords).rgb;
+    float noise = random(gl_FragCoord.xy + fract(time) * 1000.0) - 0.5;
+    FragColor = vec4(color + noise * amount, 1.0);
+}
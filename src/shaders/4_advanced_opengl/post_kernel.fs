@@ -0,0 +1,24 @@
+#version 330 core
+
+out vec4 FragColor;
+in vec2 TexCoords;
+
+uniform sampler2D screenTexture;
+// size of one pixel in texture coordinates
+uniform vec2 texelSize;
+// 3x3 weights, row by row from the top left
+uniform float kernel[9];
+
+void main() {
+    vec2 offsets[9] = vec2[](
+        vec2(-texelSize.x,  texelSize.y), vec2(0.0,  texelSize.y), vec2(texelSize.x,  texelSize.y),
+        vec2(-texelSize.x,  0.0),         vec2(0.0,  0.0),         vec2(texelSize.x,  0.0),
+        vec2(-texelSize.x, -texelSize.y), vec2(0.0, -texelSize.y), vec2(texelSize.x, -texelSize.y)
+    );
+
+    vec3 color = vec3(0.0);
+    for (int i = 0; i < 9; i++) {
+        color += texture(screenTexture, TexCoords + offsets[i]).rgb * kernel[i];
+    }
+    FragColor = vec4(color, 1.0);
+}
@@ -0,0 +1,16 @@
+#version 330 core
+
+out vec4 FragColor;
+in vec2 TexCoords;
+
+uniform sampler2D screenTexture;
+// distance from the center where the darkening starts and how dark the corners get
+uniform float radius;
+uniform float strength;
+
+void main() {
+    vec3 color = texture(screenTexture, TexCoords).rgb;
+    float distance = length(TexCoords - vec2(0.5)) * 1.41421356;
+    float darkening = smoothstep(radius, 1.0, distance) * strength;
+    FragColor = vec4(color * (1.0 - darkening), 1.0);
+}
@@ -0,0 +1,14 @@
+#version 330 core
+layout (location = 0) in vec3 aPos;
+
+uniform mat4 projection;
+uniform mat4 view;
+
+out vec3 TexCoords;
+
+void main() {
+    TexCoords = aPos;
+    vec4 position = projection * view * vec4(aPos, 1.0);
+    // z = w gives the depth 1.0 after the perspective divide, the sky stays behind everything
+    gl_Position = position.xyww;
+}
@@ -0,0 +1,29 @@
+#version 330 core
+out vec4 FragColor;
+
+in vec2 TexCoords;
+
+// log luminance of the frame, its last mip level is the average
+uniform sampler2D logLuminance;
+uniform float level;
+// the adapted luminance of the previous frame
+uniform sampler2D previous;
+uniform float deltaTime;
+uniform float speed;
+uniform float minLuminance;
+uniform float maxLuminance;
+// no previous frame to adapt from
+uniform bool reset;
+
+void main() {
+    float average = exp(textureLod(logLuminance, vec2(0.5), level).r);
+    average = clamp(average, minLuminance, maxLuminance);
+    if (reset) {
+        FragColor = vec4(average, 0.0, 0.0, 1.0);
+        return;
+    }
+    float adapted = texture(previous, vec2(0.5)).r;
+    //the same fraction of the remaining difference every second, whatever the frame rate
+    adapted += (average - adapted) * (1.0 - exp(-deltaTime * speed));
+    FragColor = vec4(adapted, 0.0, 0.0, 1.0);
+}
@@ -0,0 +1,54 @@
+#version 330 core
+out vec4 FragColor;
+
+in vec3 fragPos;
+in vec3 normal;
+in vec2 texCoord;
+
+#define MAX_LIGHTS 4
+
+uniform sampler2D floorTexture;
+uniform vec3 lightPositions[MAX_LIGHTS];
+uniform vec3 lightColors[MAX_LIGHTS];
+uniform int lightCount;
+uniform vec3 cameraPosition;
+uniform float shininess;
+// Blinn-Phong instead of Phong highlights, see shading::LightingModel
+uniform bool blinn;
+// light falls off with 1 / distance^attenuationPower, 0 for no falloff
+uniform float attenuationPower;
+// lights the inside of a closed mesh
+uniform bool inverseNormals;
+
+vec3 CalcLight(vec3 normal, vec3 lightPos, vec3 lightColor) {
+    vec3 lightDir = normalize(lightPos - fragPos);
+    vec3 viewDir = normalize(cameraPosition - fragPos);
+    //diffuse
+    float diff = max(dot(lightDir, normal), 0.0);
+    vec3 diffuse = diff * lightColor;
+    //specular
+    float spec;
+    if (blinn) {
+        vec3 halfway = normalize(lightDir + viewDir);
+        spec = pow(max(dot(normal, halfway), 0.0), shininess * 4.0);
+    } else {
+        vec3 reflected = reflect(-lightDir, normal);
+        spec = pow(max(dot(viewDir, reflected), 0.0), shininess);
+    }
+    vec3 specular = spec * lightColor * 0.3;
+    //attenuation
+    float distance = length(lightPos - fragPos);
+    float attenuation = 1.0 / pow(distance, attenuationPower);
+    return (diffuse + specular) * attenuation;
+}
+
+void main() {
+    vec3 color = texture(floorTexture, texCoord).rgb;
+    vec3 norm = inverseNormals ? -normalize(normal) : normalize(normal);
+    vec3 lighting = vec3(0.0);
+    for (int i = 0; i < lightCount; i++) {
+        lighting += CalcLight(norm, lightPositions[i], lightColors[i]);
+    }
+    //a little ambient, so the floor does not vanish between the lights
+    FragColor = vec4(color * (lighting + 0.05), 1.0);
+}
@@ -0,0 +1,54 @@
+#version 330 core
+out vec4 FragColor;
+
+in vec2 TexCoords;
+
+// the next larger level, or the HDR frame for the first one
+uniform sampler2D source;
+uniform vec2 texelSize;
+// the first level only keeps what is brighter than the threshold
+uniform bool prefilter;
+uniform float threshold;
+// fraction of the threshold over which the cut fades in
+uniform float knee;
+
+vec3 Sample(vec2 offset) {
+    return texture(source, TexCoords + offset * texelSize).rgb;
+}
+
+vec3 Prefilter(vec3 color) {
+    float brightness = max(color.r, max(color.g, color.b));
+    //a quadratic curve from threshold - knee to threshold + knee, then linear
+    float width = threshold * knee;
+    float soft = clamp(brightness - threshold + width, 0.0, 2.0 * width);
+    soft = soft * soft / (4.0 * width + 0.0001);
+    float contribution = max(soft, brightness - threshold) / max(brightness, 0.0001);
+    return color * contribution;
+}
+
+void main() {
+    //13 taps around the center, the inner 2x2 box counts most, see Jimenez, "Next generation post
+    //processing in Call of Duty: Advanced Warfare"
+    vec3 a = Sample(vec2(-2.0,  2.0));
+    vec3 b = Sample(vec2( 0.0,  2.0));
+    vec3 c = Sample(vec2( 2.0,  2.0));
+    vec3 d = Sample(vec2(-2.0,  0.0));
+    vec3 e = Sample(vec2( 0.0,  0.0));
+    vec3 f = Sample(vec2( 2.0,  0.0));
+    vec3 g = Sample(vec2(-2.0, -2.0));
+    vec3 h = Sample(vec2( 0.0, -2.0));
+    vec3 i = Sample(vec2( 2.0, -2.0));
+    vec3 j = Sample(vec2(-1.0,  1.0));
+    vec3 k = Sample(vec2( 1.0,  1.0));
+    vec3 l = Sample(vec2(-1.0, -1.0));
+    vec3 m = Sample(vec2( 1.0, -1.0));
+
+    vec3 color = e * 0.125;
+    color += (a + c + g + i) * 0.03125;
+    color += (b + d + f + h) * 0.0625;
+    color += (j + k + l + m) * 0.125;
+    if (prefilter) {
+        color = Prefilter(color);
+    }
+    FragColor = vec4(color, 1.0);
+}
@@ -0,0 +1,27 @@
+#version 330 core
+out vec4 FragColor;
+
+in vec2 TexCoords;
+
+// the next smaller level, already upsampled from the ones below it
+uniform sampler2D source;
+// the downsampled level of this size
+uniform sampler2D current;
+uniform vec2 texelSize;
+// spread of the tent filter in texels of the source
+uniform float radius;
+
+void main() {
+    vec2 offset = texelSize * radius;
+    //a 3x3 tent, smoother than bilinear alone
+    vec3 color = texture(source, TexCoords).rgb * 4.0;
+    color += (texture(source, TexCoords + vec2(-offset.x, 0.0)).rgb
+        + texture(source, TexCoords + vec2(offset.x, 0.0)).rgb
+        + texture(source, TexCoords + vec2(0.0, -offset.y)).rgb
+        + texture(source, TexCoords + vec2(0.0, offset.y)).rgb) * 2.0;
+    color += texture(source, TexCoords - offset).rgb
+        + texture(source, TexCoords + offset).rgb
+        + texture(source, TexCoords + vec2(-offset.x, offset.y)).rgb
+        + texture(source, TexCoords + vec2(offset.x, -offset.y)).rgb;
+    FragColor = vec4(texture(current, TexCoords).rgb + color / 16.0, 1.0);
+}
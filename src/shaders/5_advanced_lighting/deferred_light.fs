@@ -0,0 +1,56 @@
+#version 330 core
+out vec4 FragColor;
+
+// the G-buffer, see deferred::DeferredRenderer
+uniform sampler2D gNormal;
+uniform sampler2D gAlbedoSpecular;
+uniform sampler2D gDepth;
+// back from the depth to world space
+uniform mat4 inverseViewProjection;
+uniform vec2 screenSize;
+uniform vec3 cameraPosition;
+uniform float shininess;
+
+// 0 the ambient light over the whole frame, 1 a point light, 2 a spot light
+uniform int lightType;
+uniform vec3 ambient;
+uniform vec3 lightPosition;
+uniform vec3 lightDirection;
+uniform vec3 lightColor;
+uniform float lightRadius;
+// cosines of the inner and outer cone angle
+uniform float cutOff;
+uniform float outerCutOff;
+
+void main() {
+    //the volumes cover only part of the frame, the G-buffer is read by pixel
+    vec2 uv = gl_FragCoord.xy / screenSize;
+    float depth = texture(gDepth, uv).r;
+    if (depth == 1.0)
+        discard;
+    vec4 albedoSpecular = texture(gAlbedoSpecular, uv);
+    if (lightType == 0) {
+        FragColor = vec4(albedoSpecular.rgb * ambient, 1.0);
+        return;
+    }
+
+    vec4 position = inverseViewProjection * vec4(vec3(uv, depth) * 2.0 - 1.0, 1.0);
+    vec3 fragPos = position.xyz / position.w;
+    vec3 normal = normalize(texture(gNormal, uv).xyz);
+
+    vec3 toLight = lightPosition - fragPos;
+    float distance = length(toLight);
+    vec3 lightDir = toLight / distance;
+    vec3 viewDir = normalize(cameraPosition - fragPos);
+    //Blinn-Phong
+    float diff = max(dot(normal, lightDir), 0.0);
+    float spec = pow(max(dot(normal, normalize(lightDir + viewDir)), 0.0), shininess);
+    //inverse square, faded out towards the radius so the edge of the volume does not show
+    float window = clamp(1.0 - pow(distance / lightRadius, 4.0), 0.0, 1.0);
+    float attenuation = window * window / (1.0 + distance * distance);
+    if (lightType == 2) {
+        float theta = dot(lightDir, -lightDirection);
+        attenuation *= clamp((theta - outerCutOff) / (cutOff - outerCutOff), 0.0, 1.0);
+    }
+    FragColor = vec4((albedoSpecular.rgb * diff + albedoSpecular.a * spec) * lightColor * attenuation, 1.0);
+}
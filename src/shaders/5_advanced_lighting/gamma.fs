@@ -0,0 +1,13 @@
+#version 330 core
+out vec4 FragColor;
+
+in vec2 TexCoords;
+
+// the frame in linear space
+uniform sampler2D screenTexture;
+uniform float gamma;
+
+void main() {
+    vec3 color = texture(screenTexture, TexCoords).rgb;
+    FragColor = vec4(pow(color, vec3(1.0 / gamma)), 1.0);
+}
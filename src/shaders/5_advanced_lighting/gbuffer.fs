@@ -0,0 +1,19 @@
+#version 330 core
+layout (location = 0) out vec4 gNormal;
+layout (location = 1) out vec4 gAlbedoSpecular;
+
+in vec3 normal;
+in vec2 texCoord;
+
+struct Material {
+    sampler2D diffuseTex;
+    sampler2D specularTex;
+};
+
+uniform Material material;
+
+void main() {
+    //only what the lighting pass needs, the position comes back from the depth
+    gNormal = vec4(normalize(normal), 1.0);
+    gAlbedoSpecular = vec4(texture(material.diffuseTex, texCoord).rgb, texture(material.specularTex, texCoord).r);
+}
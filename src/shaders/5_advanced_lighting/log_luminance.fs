@@ -0,0 +1,14 @@
+#version 330 core
+out vec4 FragColor;
+
+in vec2 TexCoords;
+
+// the HDR frame
+uniform sampler2D screenTexture;
+
+void main() {
+    vec3 color = texture(screenTexture, TexCoords).rgb;
+    float luminance = dot(color, vec3(0.2126, 0.7152, 0.0722));
+    //averaging logarithms gives the geometric mean, a few bright lights do not outweigh the rest
+    FragColor = vec4(log(max(luminance, 0.0001)), 0.0, 0.0, 1.0);
+}
@@ -0,0 +1,80 @@
+#version 330 core
+out vec4 FragColor;
+
+in vec2 texCoord;
+in vec3 tangentLightPos;
+in vec3 tangentViewPos;
+in vec3 tangentFragPos;
+
+//named like Mesh::Draw binds them
+uniform sampler2D texture_diffuse1;
+uniform sampler2D texture_normal1;
+//1 at the surface, 0 at the deepest
+uniform sampler2D texture_height1;
+
+uniform bool normalMapping;
+//0 off, 1 simple, 2 steep, 3 parallax occlusion, see relief::ParallaxMode
+uniform int parallaxMode;
+//how deep height 0 lies, in texture coordinates
+uniform float heightScale;
+uniform vec3 lightColor;
+uniform float shininess;
+
+vec2 ParallaxMapping(vec2 coords, vec3 viewDir);
+
+void main() {
+    vec3 viewDir = normalize(tangentViewPos - tangentFragPos);
+    //coordinates past the edge repeat, the relief textures tile
+    vec2 coords = parallaxMode == 0 ? texCoord : ParallaxMapping(texCoord, viewDir);
+
+    //without the normal map the surface points straight out, z in tangent space
+    vec3 normal = vec3(0.0, 0.0, 1.0);
+    if (normalMapping) {
+        normal = normalize(texture(texture_normal1, coords).rgb * 2.0 - 1.0);
+    }
+    vec3 color = texture(texture_diffuse1, coords).rgb;
+    vec3 lightDir = normalize(tangentLightPos - tangentFragPos);
+    //ambient
+    vec3 ambient = 0.1 * color;
+    //diffuse
+    vec3 diffuse = max(dot(lightDir, normal), 0.0) * color;
+    //specular, Blinn-Phong
+    vec3 halfway = normalize(lightDir + viewDir);
+    vec3 specular = vec3(0.3) * pow(max(dot(normal, halfway), 0.0), shininess);
+    //result
+    FragColor = vec4((ambient + diffuse + specular) * lightColor, 1.0);
+}
+
+vec2 ParallaxMapping(vec2 coords, vec3 viewDir) {
+    //the gradients of the unshifted coordinates, the loops below are not uniform control flow
+    vec2 dx = dFdx(coords);
+    vec2 dy = dFdy(coords);
+    float depth = 1.0 - textureGrad(texture_height1, coords, dx, dy).r;
+    if (parallaxMode == 1) {
+        //one step along the view direction, longer at grazing angles
+        return coords - viewDir.xy / viewDir.z * depth * heightScale;
+    }
+
+    //more layers at grazing angles, where the ray crosses more texels
+    float layers = mix(32.0, 8.0, abs(viewDir.z));
+    float layerDepth = 1.0 / layers;
+    vec2 step = viewDir.xy / viewDir.z * heightScale / layers;
+    float currentDepth = 0.0;
+    vec2 current = coords;
+    //down the view ray until it is below the surface
+    while (currentDepth < depth) {
+        current -= step;
+        depth = 1.0 - textureGrad(texture_height1, current, dx, dy).r;
+        currentDepth += layerDepth;
+    }
+    if (parallaxMode == 2) {
+        return current;
+    }
+
+    //where the surface crosses the ray between the last two layers
+    vec2 previous = current + step;
+    float after = depth - currentDepth;
+    float before = (1.0 - textureGrad(texture_height1, previous, dx, dy).r) - (currentDepth - layerDepth);
+    float weight = after / (after - before);
+    return mix(current, previous, weight);
+}
@@ -0,0 +1,41 @@
+#version 330 core
+layout (location = 0) in vec3 aPos;
+layout (location = 1) in vec3 aNormal;
+layout (location = 2) in vec2 aTexCoord;
+layout (location = 3) in vec3 aTangent;
+layout (location = 4) in vec3 aBitangent;
+
+out vec2 texCoord;
+//in tangent space, where the normals of the normal map are
+out vec3 tangentLightPos;
+out vec3 tangentViewPos;
+out vec3 tangentFragPos;
+
+uniform mat4 projection;
+uniform mat4 view;
+uniform mat4 model;
+uniform vec3 lightPosition;
+uniform vec3 cameraPosition;
+
+void main()
+{
+    vec3 fragPos = vec3(model * vec4(aPos, 1.0));
+    gl_Position = projection * view * vec4(fragPos, 1.0);
+    texCoord = aTexCoord;
+
+    mat3 normalMatrix = mat3(transpose(inverse(model)));
+    vec3 N = normalize(normalMatrix * aNormal);
+    vec3 T = normalize(normalMatrix * aTangent);
+    //averaged tangents are not quite perpendicular to the normal, Gram-Schmidt
+    T = normalize(T - dot(T, N) * N);
+    //mirrored texture coordinates flip the bitangent, keep its side
+    vec3 B = cross(N, T);
+    if (dot(B, normalMatrix * aBitangent) < 0.0) {
+        B = -B;
+    }
+    //orthonormal, so the transpose turns world into tangent space
+    mat3 TBN = transpose(mat3(T, B, N));
+    tangentLightPos = TBN * lightPosition;
+    tangentViewPos = TBN * cameraPosition;
+    tangentFragPos = TBN * fragPos;
+}
@@ -0,0 +1,12 @@
+#version 330 core
+
+in vec3 fragPos;
+
+uniform vec3 lightPosition;
+uniform float farPlane;
+
+// the distance to the light instead of the perspective depth, the same along every direction
+void main()
+{
+    gl_FragDepth = length(fragPos - lightPosition) / farPlane;
+}
@@ -0,0 +1,14 @@
+#version 330 core
+layout (location = 0) in vec3 aPos;
+
+out vec3 fragPos;
+
+// projection and view of the face being rendered
+uniform mat4 faceMatrix;
+uniform mat4 model;
+
+void main()
+{
+    fragPos = vec3(model * vec4(aPos, 1.0));
+    gl_Position = faceMatrix * vec4(fragPos, 1.0);
+}
@@ -0,0 +1,13 @@
+#version 330 core
+out vec4 FragColor;
+
+in vec2 TexCoords;
+
+uniform sampler2D depthMap;
+
+void main()
+{
+    // orthographic depth is linear already, near is black and far is white
+    float depth = texture(depthMap, TexCoords).r;
+    FragColor = vec4(vec3(depth), 1.0);
+}
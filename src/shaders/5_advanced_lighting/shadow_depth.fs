@@ -0,0 +1,6 @@
+#version 330 core
+
+// only the depth is written, there is no color attachment
+void main()
+{
+}
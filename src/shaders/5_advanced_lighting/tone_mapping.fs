@@ -0,0 +1,56 @@
+#version 330 core
+out vec4 FragColor;
+
+in vec2 TexCoords;
+
+// the HDR frame
+uniform sampler2D screenTexture;
+// the adapted luminance in a single texel, see hdr::AutoExposure
+uniform sampler2D luminanceTexture;
+uniform bool autoExposure;
+// the glow of the bright parts, see bloom::Bloom
+uniform sampler2D bloomTexture;
+uniform bool bloom;
+uniform float bloomIntensity;
+// scales the colors before the curve, with autoExposure on top of key / luminance
+uniform float exposure;
+// the luminance the average of the frame is mapped to, middle gray
+uniform float key;
+// 0 Reinhard, 1 exposure, 2 ACES filmic, 3 Uncharted 2, see hdr::ToneMapping
+uniform float operator;
+uniform float gamma;
+
+vec3 Uncharted2(vec3 x) {
+    const float A = 0.15;
+    const float B = 0.50;
+    const float C = 0.10;
+    const float D = 0.20;
+    const float E = 0.02;
+    const float F = 0.30;
+    return ((x * (A * x + C * B) + D * E) / (x * (A * x + B) + D * F)) - E / F;
+}
+
+void main() {
+    vec3 color = texture(screenTexture, TexCoords).rgb;
+    if (bloom) {
+        color += texture(bloomTexture, TexCoords).rgb * bloomIntensity;
+    }
+    color *= exposure;
+    if (autoExposure) {
+        color *= key / texture(luminanceTexture, vec2(0.5)).r;
+    }
+
+    vec3 mapped;
+    int curve = int(operator + 0.5);
+    if (curve == 0) {
+        mapped = color / (1.0 + color);
+    } else if (curve == 1) {
+        mapped = 1.0 - exp(-color);
+    } else if (curve == 2) {
+        mapped = clamp(color * (2.51 * color + 0.03) / (color * (2.43 * color + 0.59) + 0.14), 0.0, 1.0);
+    } else {
+        //made for twice the exposure, white at 11.2 and rising past it
+        mapped = min(Uncharted2(color * 2.0) / Uncharted2(vec3(11.2)), 1.0);
+    }
+    FragColor = vec4(pow(mapped, vec3(1.0 / gamma)), 1.0);
+}
@@ -0,0 +1,40 @@
+//! Choices shared by the lighting shaders.
+
+use crate::shader::Shader;
+
+/// How the specular highlight is computed, the shaders read it from `uniform bool blinn`.
+///
+/// Phong compares the view direction with the reflected light direction. Past 90 degrees between
+/// them the dot product is cut off at 0, which leaves a hard edge in the highlight of rough
+/// surfaces seen at grazing angles. Blinn-Phong compares the normal with the halfway vector of
+/// view and light direction, which never gets further than 90 degrees from the normal on the
+/// lit side. Its highlight is wider for the same shininess, the shaders raise the exponent
+/// fourfold to keep the size about the same.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum LightingModel {
+    #[default]
+    Phong,
+    BlinnPhong,
+}
+
+impl LightingModel {
+    /// Sets `blinn` on `shader`, which has to be in use.
+    pub fn apply(self, shader: &Shader) {
+        shader.set_bool("blinn", self == LightingModel::BlinnPhong);
+    }
+
+    /// The other one, for a key that switches between them.
+    pub fn toggled(self) -> Self {
+        match self {
+            LightingModel::Phong => LightingModel::BlinnPhong,
+            LightingModel::BlinnPhong => LightingModel::Phong,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            LightingModel::Phong => "Phong",
+            LightingModel::BlinnPhong => "Blinn-Phong",
+        }
+    }
+}
@@ -0,0 +1,452 @@
+//! Shadow maps for directional lights.
+//!
+//! The scene is rendered once from the light into a depth texture, with an orthographic projection
+//! that just fits a box around everything casting or receiving shadows. The lit pass transforms
+//! each fragment into the same light space and compares its depth with the stored one: further
+//! away than what the light saw means something is in between.
+//!
+//! ```ignore
+//! shadow_map.begin(light_direction, &bounds);
+//! shadow_map.cast(&floor, Matrix4::identity());
+//! shadow_map.cast(&model, model_matrix);
+//! shadow_map.end();
+//! // lit pass, the shader has the uniforms of multiple_lights.fs
+//! shader.use_program();
+//! shadow_map.apply(&shader, 2);
+//! ```
+//!
+//! Two artifacts need handling. A depth map texel covers a patch of a surface, half of which lies
+//! in front of the stored depth and half behind it, which shades lit surfaces in stripes ("shadow
+//! acne"). A bias moves the comparison towards the light, more on surfaces at grazing angles where
+//! a texel covers more depth. And the texels show as jagged shadow edges, percentage-closer
+//! filtering (PCF) averages the comparison over a square of neighbouring texels.
+//!
+//! Point lights shine in every direction, a `PointShadowMap` renders the distance to the light
+//! into the six faces of a depth cubemap instead, and the lit pass looks it up along the
+//! direction from the light to the fragment.
+
+use std::ptr;
+
+use cgmath::{ortho, perspective, Deg, EuclideanSpace, InnerSpace, Matrix4, Point3, SquareMatrix, Transform, Vector3};
+
+use crate::cubemap::CUBE_FACES;
+
+use crate::framebuffer::{DepthFormat, Framebuffer, FramebufferDesc, FullscreenQuad, Storage, FULLSCREEN_VERTEX_SHADER};
+use crate::mesh::Geometry;
+use crate::shader::Shader;
+
+pub const SHADOW_DEPTH_VERTEX_SHADER: &str = "./src/shaders/5_advanced_lighting/shadow_depth.vs";
+pub const SHADOW_DEPTH_FRAGMENT_SHADER: &str = "./src/shaders/5_advanced_lighting/shadow_depth.fs";
+pub const SHADOW_DEBUG_SHADER: &str = "./src/shaders/5_advanced_lighting/shadow_debug.fs";
+pub const POINT_SHADOW_DEPTH_VERTEX_SHADER: &str = "./src/shaders/5_advanced_lighting/point_shadow_depth.vs";
+pub const POINT_SHADOW_DEPTH_FRAGMENT_SHADER: &str = "./src/shaders/5_advanced_lighting/point_shadow_depth.fs";
+
+/// Texture unit of the shadow map of point light 0 in `multiple_lights.fs`, light `i` uses `POINT_SHADOW_UNIT + i`.
+pub const POINT_SHADOW_UNIT: u32 = 4;
+/// Point lights of `multiple_lights.fs`, each can have a shadow map.
+pub const POINT_SHADOW_LIGHTS: u32 = 4;
+/// Texture unit of the cascades of `multiple_lights.fs`, see `cascades::CascadedShadowMap`.
+pub const CASCADE_UNIT: u32 = POINT_SHADOW_UNIT + POINT_SHADOW_LIGHTS;
+
+/// An axis aligned box in world space.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Bounds {
+    pub min: Point3<f32>,
+    pub max: Point3<f32>,
+}
+
+impl Bounds {
+    pub fn new(min: Point3<f32>, max: Point3<f32>) -> Self {
+        Bounds { min, max }
+    }
+
+    /// The smallest box around `points`, which must not be empty.
+    pub fn around(points: &[Point3<f32>]) -> Self {
+        let mut bounds = Bounds::new(points[0], points[0]);
+        for point in &points[1..] {
+            bounds.min = Point3::new(bounds.min.x.min(point.x), bounds.min.y.min(point.y), bounds.min.z.min(point.z));
+            bounds.max = Point3::new(bounds.max.x.max(point.x), bounds.max.y.max(point.y), bounds.max.z.max(point.z));
+        }
+        bounds
+    }
+
+    pub fn center(&self) -> Point3<f32> {
+        self.min.midpoint(self.max)
+    }
+
+    pub fn corners(&self) -> [Point3<f32>; 8] {
+        let (min, max) = (self.min, self.max);
+        [
+            Point3::new(min.x, min.y, min.z), Point3::new(max.x, min.y, min.z),
+            Point3::new(min.x, max.y, min.z), Point3::new(max.x, max.y, min.z),
+            Point3::new(min.x, min.y, max.z), Point3::new(max.x, min.y, max.z),
+            Point3::new(min.x, max.y, max.z), Point3::new(max.x, max.y, max.z),
+        ]
+    }
+}
+
+/// Projection times view of a directional light shining along `direction`, fitted tightly around `bounds`.
+///
+/// Every point of the box ends up inside the clip volume, so the depth map spends its texels and
+/// its depth precision on the box only.
+pub fn light_space_matrix(direction: Vector3<f32>, bounds: &Bounds) -> Matrix4<f32> {
+    let direction = direction.normalize();
+    let center = bounds.center();
+    let radius = (bounds.max - bounds.min).magnitude() / 2.0;
+    // look_at needs an up vector that is not parallel to the view direction
+    let up = if direction.y.abs() > 0.99 { Vector3::unit_z() } else { Vector3::unit_y() };
+    // from outside the box, so the whole box lies in front of the light
+    let view = Matrix4::look_at(center - direction * radius, center, up);
+
+    let corners = bounds.corners();
+    let in_view: Vec<Point3<f32>> = corners.iter().map(|&corner| view.transform_point(corner)).collect();
+    let fitted = Bounds::around(&in_view);
+    // the light looks down -z, near and far are distances in front of it
+    let projection = ortho(fitted.min.x, fitted.max.x, fitted.min.y, fitted.max.y, -fitted.max.z, -fitted.min.z);
+    projection * view
+}
+
+/// How the lit pass compares against the depth map.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ShadowSettings {
+    // depth bias on surfaces facing the light, in depth map units; PCF compares neighbouring texels
+    // against the fragment's own depth, wider kernels need more
+    pub min_bias: f32,
+    // added bias per unit of slope, the tangent of the angle between normal and light
+    pub slope_bias: f32,
+    // averages (2 * pcf_radius + 1)^2 texels, 0 takes a single hard sample
+    pub pcf_radius: u32,
+}
+
+impl Default for ShadowSettings {
+    fn default() -> Self {
+        ShadowSettings { min_bias: 0.005, slope_bias: 0.002, pcf_radius: 1 }
+    }
+}
+
+/// A square depth texture rendered from a directional light.
+pub struct ShadowMap {
+    target: Framebuffer,
+    settings: ShadowSettings,
+    light_space: Matrix4<f32>,
+    depth_shader: Shader,
+    debug_shader: Shader,
+    quad: FullscreenQuad,
+    // framebuffer, viewport and depth test to return to in `end`
+    previous: u32,
+    viewport: [i32; 4],
+    depth_test: bool,
+}
+
+impl ShadowMap {
+    /// A `size` by `size` depth map, everything outside of it counts as lit.
+    pub fn new(size: u32) -> Result<Self, String> {
+        let target = Framebuffer::new(FramebufferDesc {
+            width: size,
+            height: size,
+            colors: Vec::new(),
+            depth: Some((DepthFormat::Depth32F, Storage::Texture)),
+        })?;
+        unsafe {
+            gl::BindTexture(gl::TEXTURE_2D, target.depth_texture().unwrap());
+            // samples past the edge read the far plane, nothing there casts a shadow
+            gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_WRAP_S, gl::CLAMP_TO_BORDER as i32);
+            gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_WRAP_T, gl::CLAMP_TO_BORDER as i32);
+            let border = [1.0f32; 4];
+            gl::TexParameterfv(gl::TEXTURE_2D, gl::TEXTURE_BORDER_COLOR, border.as_ptr());
+            gl::BindTexture(gl::TEXTURE_2D, 0);
+        }
+
+        let debug_shader = Shader::new(FULLSCREEN_VERTEX_SHADER, SHADOW_DEBUG_SHADER);
+        debug_shader.use_program();
+        debug_shader.set_int("depthMap", 0);
+
+        Ok(ShadowMap {
+            target,
+            settings: ShadowSettings::default(),
+            light_space: Matrix4::identity(),
+            depth_shader: Shader::new(SHADOW_DEPTH_VERTEX_SHADER, SHADOW_DEPTH_FRAGMENT_SHADER),
+            debug_shader,
+            quad: FullscreenQuad::new(),
+            previous: 0,
+            viewport: [0; 4],
+            depth_test: false,
+        })
+    }
+
+    pub fn with_settings(mut self, settings: ShadowSettings) -> Self {
+        self.settings = settings;
+        self
+    }
+
+    pub fn settings(&self) -> ShadowSettings {
+        self.settings
+    }
+
+    pub fn set_settings(&mut self, settings: ShadowSettings) {
+        self.settings = settings;
+    }
+
+    pub fn size(&self) -> u32 {
+        self.target.width()
+    }
+
+    pub fn depth_texture(&self) -> u32 {
+        self.target.depth_texture().unwrap()
+    }
+
+    /// The matrix of the last `begin`, from world space to the light's clip space.
+    pub fn light_space(&self) -> Matrix4<f32> {
+        self.light_space
+    }
+
+    /// Binds and clears the depth map for a light shining along `direction` onto `bounds`.
+    /// Draw the shadow casters with `cast`, then call `end`.
+    pub fn begin(&mut self, direction: Vector3<f32>, bounds: &Bounds) {
+        self.light_space = light_space_matrix(direction, bounds);
+        unsafe {
+            let mut previous = 0;
+            gl::GetIntegerv(gl::DRAW_FRAMEBUFFER_BINDING, &mut previous);
+            gl::GetIntegerv(gl::VIEWPORT, self.viewport.as_mut_ptr());
+            self.previous = previous as u32;
+            self.depth_test = gl::IsEnabled(gl::DEPTH_TEST) == gl::TRUE;
+
+            self.target.bind();
+            gl::Enable(gl::DEPTH_TEST);
+            gl::Clear(gl::DEPTH_BUFFER_BIT);
+        }
+        self.depth_shader.use_program();
+        self.depth_shader.set_mat4("lightSpaceMatrix", self.light_space);
+    }
+
+    /// Draws `geometry` into the depth map, between `begin` and `end`.
+    pub fn cast<G: Geometry + ?Sized>(&self, geometry: &G, model: Matrix4<f32>) {
+        self.depth_shader.use_program();
+        self.depth_shader.set_mat4("model", model);
+        geometry.draw_geometry();
+    }
+
+    /// Goes back to the framebuffer and viewport bound at `begin`.
+    pub fn end(&self) {
+        let [x, y, width, height] = self.viewport;
+        unsafe {
+            gl::BindVertexArray(0);
+            gl::BindFramebuffer(gl::FRAMEBUFFER, self.previous);
+            gl::Viewport(x, y, width, height);
+            if !self.depth_test {
+                gl::Disable(gl::DEPTH_TEST);
+            }
+        }
+    }
+
+    /// Binds the depth map to texture `unit` and sets the shadow uniforms of `shader`, which has to be in use.
+    pub fn apply(&self, shader: &Shader, unit: u32) {
+        unsafe {
+            gl::ActiveTexture(gl::TEXTURE0 + unit);
+            gl::BindTexture(gl::TEXTURE_2D, self.depth_texture());
+            gl::ActiveTexture(gl::TEXTURE0);
+        }
+        shader.set_bool("shadows", true);
+        shader.set_int("shadowMap", unit as i32);
+        shader.set_mat4("lightSpaceMatrix", self.light_space);
+        shader.set_float("shadowMinBias", self.settings.min_bias);
+        shader.set_float("shadowSlopeBias", self.settings.slope_bias);
+        shader.set_int("pcfRadius", self.settings.pcf_radius as i32);
+    }
+
+    /// Shows the depth map in the bound framebuffer's viewport, near in black and far in white.
+    pub fn draw_debug(&self) {
+        self.quad.pass(&self.debug_shader, &[self.depth_texture()]);
+    }
+}
+
+/// How the lit pass compares against a point shadow map.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PointShadowSettings {
+    // distance bias in world units
+    pub bias: f32,
+    // spread of the 20 samples around the direction to the fragment, in world units, 0 for one hard sample
+    pub filter_radius: f32,
+}
+
+impl Default for PointShadowSettings {
+    fn default() -> Self {
+        PointShadowSettings { bias: 0.05, filter_radius: 0.03 }
+    }
+}
+
+/// Sets the samplers of every point shadow map and the cascades of `shader` to their own units, which has to be in use.
+///
+/// Unused `samplerCube`s and `sampler2DArray`s would point to unit 0 with the diffuse map, and a unit
+/// sampled as two different types fails the draw. Lights without a `PointShadowMap` stay unshadowed,
+/// and the directional light has no cascades until a `CascadedShadowMap` is applied.
+pub fn assign_shadow_units(shader: &Shader) {
+    for light in 0..POINT_SHADOW_LIGHTS {
+        shader.set_int(&format!("pointShadowMaps[{}]", light), (POINT_SHADOW_UNIT + light) as i32);
+        shader.set_bool(&format!("pointLight[{}].shadows", light), false);
+    }
+    shader.set_int("cascadeMap", CASCADE_UNIT as i32);
+    shader.set_bool("cascaded", false);
+}
+
+/// A depth cubemap around a point light, holding the distance to the nearest caster divided by the far plane.
+///
+/// The faces are rendered one after the other, like an `EnvironmentProbe`.
+pub struct PointShadowMap {
+    settings: PointShadowSettings,
+    position: Vector3<f32>,
+    far_plane: f32,
+    size: u32,
+    texture: u32,
+    framebuffer: u32,
+    depth_shader: Shader,
+}
+
+impl PointShadowMap {
+    /// Faces of `size` pixels, casters further than `far_plane` from the light are left out.
+    pub fn new(size: u32, far_plane: f32) -> Result<Self, String> {
+        if size == 0 {
+            return Err("A point shadow map needs faces of at least one pixel".to_string());
+        }
+        let mut map = PointShadowMap {
+            settings: PointShadowSettings::default(),
+            position: Vector3::new(0.0, 0.0, 0.0),
+            far_plane,
+            size,
+            texture: 0,
+            framebuffer: 0,
+            depth_shader: Shader::new(POINT_SHADOW_DEPTH_VERTEX_SHADER, POINT_SHADOW_DEPTH_FRAGMENT_SHADER),
+        };
+        unsafe {
+            let mut previous = 0;
+            gl::GetIntegerv(gl::FRAMEBUFFER_BINDING, &mut previous);
+
+            gl::GenTextures(1, &mut map.texture);
+            gl::BindTexture(gl::TEXTURE_CUBE_MAP, map.texture);
+            for face in CUBE_FACES.iter() {
+                gl::TexImage2D(face.gl_target(), 0, gl::DEPTH_COMPONENT32F as i32, size as i32, size as i32, 0, gl::DEPTH_COMPONENT, gl::FLOAT, ptr::null());
+            }
+            gl::TexParameteri(gl::TEXTURE_CUBE_MAP, gl::TEXTURE_MIN_FILTER, gl::NEAREST as i32);
+            gl::TexParameteri(gl::TEXTURE_CUBE_MAP, gl::TEXTURE_MAG_FILTER, gl::NEAREST as i32);
+            gl::TexParameteri(gl::TEXTURE_CUBE_MAP, gl::TEXTURE_WRAP_S, gl::CLAMP_TO_EDGE as i32);
+            gl::TexParameteri(gl::TEXTURE_CUBE_MAP, gl::TEXTURE_WRAP_T, gl::CLAMP_TO_EDGE as i32);
+            gl::TexParameteri(gl::TEXTURE_CUBE_MAP, gl::TEXTURE_WRAP_R, gl::CLAMP_TO_EDGE as i32);
+            gl::BindTexture(gl::TEXTURE_CUBE_MAP, 0);
+
+            // depth only, like `ShadowMap`
+            gl::GenFramebuffers(1, &mut map.framebuffer);
+            gl::BindFramebuffer(gl::FRAMEBUFFER, map.framebuffer);
+            gl::FramebufferTexture2D(gl::FRAMEBUFFER, gl::DEPTH_ATTACHMENT, CUBE_FACES[0].gl_target(), map.texture, 0);
+            gl::DrawBuffer(gl::NONE);
+            gl::ReadBuffer(gl::NONE);
+            let status = gl::CheckFramebufferStatus(gl::FRAMEBUFFER);
+            gl::BindFramebuffer(gl::FRAMEBUFFER, previous as u32);
+            if status != gl::FRAMEBUFFER_COMPLETE {
+                return Err(format!("The point shadow framebuffer is incomplete: {}", crate::framebuffer::status_message(status)));
+            }
+        }
+        Ok(map)
+    }
+
+    pub fn with_settings(mut self, settings: PointShadowSettings) -> Self {
+        self.settings = settings;
+        self
+    }
+
+    pub fn settings(&self) -> PointShadowSettings {
+        self.settings
+    }
+
+    pub fn set_settings(&mut self, settings: PointShadowSettings) {
+        self.settings = settings;
+    }
+
+    pub fn far_plane(&self) -> f32 {
+        self.far_plane
+    }
+
+    pub fn set_far_plane(&mut self, far_plane: f32) {
+        self.far_plane = far_plane;
+    }
+
+    pub fn size(&self) -> u32 {
+        self.size
+    }
+
+    /// The depth cubemap, look it up with the direction from the light.
+    pub fn texture(&self) -> u32 {
+        self.texture
+    }
+
+    /// The light position of the last `render`.
+    pub fn position(&self) -> Vector3<f32> {
+        self.position
+    }
+
+    /// Projection times view of the face with index `face` in `CUBE_FACES`, seen from the light.
+    pub fn face_matrix(&self, face: usize) -> Matrix4<f32> {
+        // 90 degrees wide and square, the six faces cover every direction exactly once
+        let projection = perspective(Deg(90.0), 1.0, 0.05, self.far_plane);
+        projection * CUBE_FACES[face].view() * Matrix4::from_translation(-self.position)
+    }
+
+    /// Renders the distance of `casters` from a light at `position` into all six faces.
+    /// The framebuffer, viewport and depth test of the caller are restored after.
+    pub fn render(&mut self, position: Vector3<f32>, casters: &[(&dyn Geometry, Matrix4<f32>)]) {
+        self.position = position;
+        self.depth_shader.use_program();
+        self.depth_shader.set_vector3v("lightPosition", position);
+        self.depth_shader.set_float("farPlane", self.far_plane);
+        unsafe {
+            let mut previous = 0;
+            let mut viewport = [0; 4];
+            gl::GetIntegerv(gl::FRAMEBUFFER_BINDING, &mut previous);
+            gl::GetIntegerv(gl::VIEWPORT, viewport.as_mut_ptr());
+            let depth_test = gl::IsEnabled(gl::DEPTH_TEST) == gl::TRUE;
+
+            gl::BindFramebuffer(gl::FRAMEBUFFER, self.framebuffer);
+            gl::Viewport(0, 0, self.size as i32, self.size as i32);
+            gl::Enable(gl::DEPTH_TEST);
+            for (index, face) in CUBE_FACES.iter().enumerate() {
+                gl::FramebufferTexture2D(gl::FRAMEBUFFER, gl::DEPTH_ATTACHMENT, face.gl_target(), self.texture, 0);
+                gl::Clear(gl::DEPTH_BUFFER_BIT);
+                self.depth_shader.set_mat4("faceMatrix", self.face_matrix(index));
+                for &(geometry, model) in casters.iter() {
+                    self.depth_shader.set_mat4("model", model);
+                    geometry.draw_geometry();
+                }
+            }
+
+            gl::BindVertexArray(0);
+            gl::BindFramebuffer(gl::FRAMEBUFFER, previous as u32);
+            gl::Viewport(viewport[0], viewport[1], viewport[2], viewport[3]);
+            if !depth_test {
+                gl::Disable(gl::DEPTH_TEST);
+            }
+        }
+    }
+
+    /// Binds the map to the unit of point light `light` and turns its shadows on in `shader`, which has to be in use.
+    /// The samplers have to be set up by `assign_shadow_units`, bias and filter radius are shared
+    /// by all lights, the last map applied sets them.
+    pub fn apply(&self, shader: &Shader, light: u32) {
+        unsafe {
+            gl::ActiveTexture(gl::TEXTURE0 + POINT_SHADOW_UNIT + light);
+            gl::BindTexture(gl::TEXTURE_CUBE_MAP, self.texture);
+            gl::ActiveTexture(gl::TEXTURE0);
+        }
+        shader.set_bool(&format!("pointLight[{}].shadows", light), true);
+        shader.set_float(&format!("pointLight[{}].farPlane", light), self.far_plane);
+        shader.set_float("pointShadowBias", self.settings.bias);
+        shader.set_float("pointShadowRadius", self.settings.filter_radius);
+    }
+}
+
+impl Drop for PointShadowMap {
+    fn drop(&mut self) {
+        unsafe {
+            gl::DeleteFramebuffers(1, &self.framebuffer);
+            gl::DeleteTextures(1, &self.texture);
+        }
+    }
+}
@@ -1,6 +1,11 @@
+use cgmath::{Point3, Vector2, Vector3};
 use glfw::{Action, Context, GlfwReceiver, Key};
 
 use crate::camera;
+use crate::input::InputState;
+
+// movement speed multiplier while the sprint action is held
+const SPRINT_FACTOR: f32 = 2.0;
 
 pub fn process_input_keyboard(window: &mut glfw::Window, delta_time: f32, camera: &mut camera::Camera) {
     // quit application
@@ -22,16 +27,104 @@ pub fn process_input_keyboard(window: &mut glfw::Window, delta_time: f32, camera
     }
 }
 
+pub fn process_input(window: &mut glfw::Window, input: &InputState, delta_time: f32, camera: &mut camera::Camera) {
+    // quit application
+    if input.pressed("quit") {
+        window.set_should_close(true)
+    }
+
+    if input.pressed("toggle_cursor") {
+        toggle_cursor_captured(window, camera);
+    }
+
+    if input.pressed("toggle_wireframe") {
+        unsafe {
+            let mut mode = [0; 2];
+            gl::GetIntegerv(gl::POLYGON_MODE, mode.as_mut_ptr());
+            let next = if mode[0] == gl::LINE as i32 { gl::FILL } else { gl::LINE };
+            gl::PolygonMode(gl::FRONT_AND_BACK, next);
+        }
+    }
+
+    // analog bindings move the camera proportionally
+    let speed = if input.held("sprint") { SPRINT_FACTOR } else { 1.0 };
+    let forward = input.axis_pair("move_backward", "move_forward");
+    let right = input.axis_pair("move_left", "move_right");
+    if forward > 0.0 {
+        camera.process_movement(camera::CameraMovement::Forward, delta_time * speed * forward);
+    }
+    if forward < 0.0 {
+        camera.process_movement(camera::CameraMovement::Backward, delta_time * speed * -forward);
+    }
+    if right > 0.0 {
+        camera.process_movement(camera::CameraMovement::Right, delta_time * speed * right);
+    }
+    if right < 0.0 {
+        camera.process_movement(camera::CameraMovement::Left, delta_time * speed * -right);
+    }
+}
+
+pub fn process_events_with_input(events: &GlfwReceiver<(f64, glfw::WindowEvent)>, input: &mut InputState, camera: &mut camera::Camera) {
+    for (_, event) in glfw::flush_messages(events) {
+        input.handle_event(&event);
+        match event {
+            glfw::WindowEvent::CursorPos(x_pos, y_pos) => camera.process_cursor(x_pos as f32, y_pos as f32),
+            glfw::WindowEvent::Focus(focused) => camera.set_focused(focused),
+            _ => ()
+        }
+    }
+    input.update();
+}
+
 pub fn process_events(events: &GlfwReceiver<(f64, glfw::WindowEvent)>, camera: &mut camera::Camera) {
     for (_, event) in glfw::flush_messages(events) {
         println!("{:?}", event);
         match event {
             glfw::WindowEvent::CursorPos(x_pos, y_pos) => camera.process_cursor(x_pos as f32, y_pos as f32),
+            glfw::WindowEvent::Focus(focused) => camera.set_focused(focused),
             _ => ()
         }
     }
 }
 
+/// Switches between a hidden cursor that turns the camera and a free cursor.
+pub fn set_cursor_captured(window: &mut glfw::Window, camera: &mut camera::Camera, captured: bool) {
+    let mode = if captured { glfw::CursorMode::Disabled } else { glfw::CursorMode::Normal };
+    window.set_cursor_mode(mode);
+    camera.set_cursor_captured(captured);
+}
+
+pub fn toggle_cursor_captured(window: &mut glfw::Window, camera: &mut camera::Camera) {
+    let captured = window.get_cursor_mode() == glfw::CursorMode::Disabled;
+    set_cursor_captured(window, camera, !captured);
+}
+
+/// Camera looking down the negative z axis, the starting point of most examples.
+pub fn camera_at(position: Point3<f32>) -> camera::Camera {
+    camera::Camera::new(
+        position,
+        Vector3::new(0.0, 0.0, -1.0),
+        Vector3::new(0.0, 1.0, 0.0),
+        // replaced by the first cursor event
+        Vector2::new(0.0, 0.0),
+    )
+}
+
+pub fn default_camera() -> camera::Camera {
+    camera_at(Point3::new(0.0, 0.0, 3.0))
+}
+
+/// Reads a region of the bound read framebuffer, flipped so that the top row comes first.
+pub unsafe fn read_pixels(x: i32, y: i32, width: u32, height: u32) -> image::RgbaImage {
+    let mut data = vec![0u8; (width * height * 4) as usize];
+    gl::PixelStorei(gl::PACK_ALIGNMENT, 1);
+    gl::ReadPixels(x, y, width as i32, height as i32, gl::RGBA, gl::UNSIGNED_BYTE, data.as_mut_ptr() as *mut std::ffi::c_void);
+
+    // OpenGL starts at the bottom row, images at the top row
+    let image = image::RgbaImage::from_raw(width, height, data).expect("Pixel buffer has the wrong size");
+    image::imageops::flip_vertical(&image)
+}
+
 pub fn initialize_glfw() -> glfw::Glfw {
     use glfw::fail_on_errors;
     let mut glfw: glfw::Glfw = glfw::init(glfw::fail_on_errors!()).expect("Failed to initialize GLFW");
@@ -50,7 +143,9 @@ pub fn create_window(glfw: &mut glfw::Glfw, width: u32, height: u32, title: &str
     window.set_key_polling(true);
     window.set_framebuffer_size_polling(true);
     window.set_cursor_pos_polling(true);
+    window.set_mouse_button_polling(true);
     window.set_scroll_polling(true);
+    window.set_focus_polling(true);
     window.set_cursor_mode(glfw::CursorMode::Disabled);
     (window, events)
 }
\ No newline at end of file
@@ -0,0 +1,66 @@
+//! Bloom only spreads what is brighter than the threshold, and reaches further with more levels.
+#![cfg(unix)]
+
+mod common;
+
+use learn_opengl_rust::bloom::{Bloom, BloomSettings};
+use learn_opengl_rust::headless::HeadlessContext;
+use learn_opengl_rust::postprocess::PostProcessed;
+use learn_opengl_rust::scene::{Frame, Scene};
+
+// a black frame with a square of `value` in the middle, 4 texels wide
+struct Spot(f32);
+
+impl Scene for Spot {
+    fn render(&mut self, frame: &Frame) {
+        unsafe {
+            gl::ClearColor(0.0, 0.0, 0.0, 1.0);
+            gl::Clear(gl::COLOR_BUFFER_BIT | gl::DEPTH_BUFFER_BIT);
+            gl::Enable(gl::SCISSOR_TEST);
+            gl::Scissor(frame.width as i32 / 2 - 2, frame.height as i32 / 2 - 2, 4, 4);
+            gl::ClearColor(self.0, self.0, self.0, 1.0);
+            gl::Clear(gl::COLOR_BUFFER_BIT);
+            gl::Disable(gl::SCISSOR_TEST);
+        }
+    }
+}
+
+// the red channel along the row through the spot, from its center to the right edge
+fn row(context: &HeadlessContext, value: f32, bloom: Option<BloomSettings>) -> Vec<i32> {
+    let mut scene = PostProcessed::hdr(Box::new(Spot(value)), &["tone_mapping"]);
+    if let Some(settings) = bloom {
+        scene.chain.effect_mut("tone_mapping").unwrap().bloom = Some(Bloom::new(settings).unwrap());
+    }
+    let image = context.render_scene(&mut scene, 1, 0.0);
+    (context.width / 2..context.width).map(|x| image.get_pixel(x, context.height / 2).data[0] as i32).collect()
+}
+
+#[test]
+fn only_bright_colors_bloom() {
+    let context = match common::context(64, 64, "bloom tests") { Some(context) => context, None => return };
+
+    // below the knee nothing changes
+    assert_eq!(row(&context, 0.5, Some(BloomSettings::default())), row(&context, 0.5, None));
+
+    let sharp = row(&context, 50.0, None);
+    let bloomed = row(&context, 50.0, Some(BloomSettings::default()));
+    assert!(sharp[4..].iter().all(|&red| red == 0), "{:?}", sharp);
+    assert!(bloomed[8] > 10, "{:?}", bloomed);
+    // falling off with the distance
+    assert!(bloomed[4..].windows(2).all(|pair| pair[0] >= pair[1]), "{:?}", bloomed);
+}
+
+#[test]
+fn more_levels_reach_further() {
+    let context = match common::context(128, 128, "bloom tests") { Some(context) => context, None => return };
+
+    let settings = BloomSettings { intensity: 4.0, ..BloomSettings::default() };
+    let near = row(&context, 50.0, Some(BloomSettings { levels: 2, ..settings }));
+    let far = row(&context, 50.0, Some(BloomSettings { levels: 6, ..settings }));
+    assert!(near[32] <= 1, "{:?}", near);
+    assert!(far[32] > near[32] + 4, "{:?}", far);
+
+    // a wider filter reaches further with the same levels
+    let wide = row(&context, 50.0, Some(BloomSettings { levels: 2, radius: 2.0, ..settings }));
+    assert!(wide[12] > near[12], "{:?} against {:?}", wide, near);
+}
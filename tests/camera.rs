@@ -0,0 +1,69 @@
+//! Cursor capture and focus handling of the camera, no window needed.
+
+use cgmath::{Point3, Vector2, Vector3};
+
+use learn_opengl_rust::camera::{Camera, CameraMovement};
+
+fn camera() -> Camera {
+    Camera::new(Point3::new(0.0, 0.0, 3.0), Vector3::new(0.0, 0.0, -1.0), Vector3::unit_y(), Vector2::new(400.0, 300.0))
+}
+
+fn angles(camera: &Camera) -> (f32, f32) {
+    (camera.yaw, camera.pitch)
+}
+
+#[test]
+fn first_event_only_sets_the_cursor() {
+    let mut camera = camera();
+    let start = angles(&camera);
+
+    // the first event after creation jumps from wherever the cursor was
+    camera.process_cursor(10.0, 20.0);
+    assert_eq!(angles(&camera), start);
+    assert_eq!((camera.cursor_pos.x, camera.cursor_pos.y), (10.0, 20.0));
+
+    // the next one turns the camera
+    camera.process_cursor(110.0, 20.0);
+    assert!(camera.yaw > start.0);
+    assert_eq!(camera.pitch, start.1);
+}
+
+#[test]
+fn capture_resets_the_first_event() {
+    let mut camera = camera();
+    camera.process_cursor(0.0, 0.0);
+
+    // no turning while the cursor is free
+    camera.set_cursor_captured(false);
+    let free = angles(&camera);
+    camera.process_cursor(500.0, 500.0);
+    assert_eq!(angles(&camera), free);
+
+    // capturing again must not turn by the distance the free cursor moved
+    camera.set_cursor_captured(true);
+    camera.process_cursor(700.0, 100.0);
+    assert_eq!(angles(&camera), free);
+    camera.process_cursor(700.0, 200.0);
+    assert!(camera.pitch < free.1);
+}
+
+#[test]
+fn unfocused_camera_ignores_input() {
+    let mut camera = camera();
+    camera.process_cursor(0.0, 0.0);
+
+    camera.set_focused(false);
+    let (position, before) = (camera.position, angles(&camera));
+    camera.process_cursor(300.0, 300.0);
+    camera.process_movement(CameraMovement::Forward, 1.0);
+    camera.process_movement(CameraMovement::Left, 1.0);
+    assert_eq!(angles(&camera), before);
+    assert_eq!(camera.position, position);
+
+    // regaining the focus skips the first event like capturing does
+    camera.set_focused(true);
+    camera.process_cursor(900.0, 900.0);
+    assert_eq!(angles(&camera), before);
+    camera.process_movement(CameraMovement::Forward, 1.0);
+    assert!(camera.position.z < position.z);
+}
@@ -0,0 +1,85 @@
+//! Output paths and recordings of frame sequences and GIFs, no GL needed.
+
+use std::fs::{self, File};
+use std::path::{Path, PathBuf};
+
+use image::{Rgba, RgbaImage};
+
+use learn_opengl_rust::capture::{self, Recorder};
+
+// a fresh directory per test, tests run in parallel
+fn test_dir(name: &str) -> PathBuf {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("target/capture_tests").join(name);
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+fn frame(shade: u8) -> RgbaImage {
+    RgbaImage::from_pixel(8, 4, Rgba([shade, shade, shade, 255]))
+}
+
+// frame count and total delay in 1/100 s of a GIF file
+fn read_gif(path: &Path) -> (u32, u32) {
+    let mut reader = gif::Decoder::new(File::open(path).unwrap()).read_info().unwrap();
+    let (mut frames, mut delay) = (0, 0);
+    while let Some(frame) = reader.read_next_frame().unwrap() {
+        frames += 1;
+        delay += frame.delay as u32;
+    }
+    (frames, delay)
+}
+
+#[test]
+fn next_free_path_counts_up() {
+    let dir = test_dir("next_free_path");
+    let first = capture::next_free_path(&dir, "Chapter 4: Blending", "png");
+    assert_eq!(first, dir.join("chapter_4_blending_001.png"));
+
+    fs::write(&first, b"").unwrap();
+    assert_eq!(capture::next_free_path(&dir, "Chapter 4: Blending", "png"), dir.join("chapter_4_blending_002.png"));
+    // directories for frame sequences have no extension
+    assert_eq!(capture::next_free_path(&dir, "Chapter 4: Blending", ""), dir.join("chapter_4_blending_001"));
+}
+
+#[test]
+fn frame_sequence_names_and_size_mismatch() {
+    let dir = test_dir("frame_sequence").join("frames");
+    let mut recorder = Recorder::from_path(&dir, 8, 4, 1.0 / 30.0).unwrap();
+    recorder.add_frame(&frame(0)).unwrap();
+    recorder.add_frame(&frame(255)).unwrap();
+
+    let error = recorder.add_frame(&RgbaImage::new(4, 4)).expect_err("a frame of the wrong size");
+    assert!(error.contains("4x4") && error.contains("8x4"), "{}", error);
+    assert_eq!(recorder.frame_count(), 2);
+
+    assert_eq!(recorder.finish().unwrap(), dir);
+    assert!(dir.join("frame_00000.png").exists());
+    assert_eq!(image::open(dir.join("frame_00001.png")).unwrap().to_rgba().get_pixel(0, 0).data, [255, 255, 255, 255]);
+    assert!(!dir.join("frame_00002.png").exists());
+}
+
+#[test]
+fn gif_keeps_the_recorded_length() {
+    let dir = test_dir("gif");
+
+    // 1/30 s rounds to 3/100 s, the carried remainder adds up to 20/100 s for six frames
+    let path = dir.join("thirty.gif");
+    let mut recorder = Recorder::from_path(&path, 8, 4, 1.0 / 30.0).unwrap();
+    for index in 0..6 {
+        recorder.add_frame(&frame(index * 40)).unwrap();
+    }
+    assert_eq!(recorder.written_frame_count(), 6);
+    assert_eq!(recorder.finish().unwrap(), path);
+    assert_eq!(read_gif(&path), (6, 20));
+
+    // 1/60 s is below the smallest delay, every third frame is dropped instead
+    let path = dir.join("sixty.gif");
+    let mut recorder = Recorder::from_path(&path, 8, 4, 1.0 / 60.0).unwrap();
+    for index in 0..6 {
+        recorder.add_frame(&frame(index * 40)).unwrap();
+    }
+    assert_eq!(recorder.frame_count(), 6);
+    recorder.finish().unwrap();
+    assert_eq!(read_gif(&path), (4, 10));
+}
@@ -0,0 +1,84 @@
+//! Face directions and the conversion of cross and equirectangular images into cubemap faces.
+
+use cgmath::{InnerSpace, Vector3};
+use image::{Rgba, RgbaImage};
+
+use learn_opengl_rust::cubemap::{self, CubeFace, CUBE_FACES};
+
+fn solid(size: u32, color: [u8; 4]) -> RgbaImage {
+    RgbaImage::from_pixel(size, size, Rgba(color))
+}
+
+// a different color per face, with a marker in the top left corner of each face
+fn face_color(index: usize) -> [u8; 4] {
+    [index as u8 * 40, 255 - index as u8 * 40, 100, 255]
+}
+
+#[test]
+fn face_centers_point_along_their_axis() {
+    let axes = [
+        Vector3::unit_x(), -Vector3::unit_x(),
+        Vector3::unit_y(), -Vector3::unit_y(),
+        Vector3::unit_z(), -Vector3::unit_z(),
+    ];
+    for (face, axis) in CUBE_FACES.iter().zip(axes.iter()) {
+        assert!((face.direction(0.5, 0.5) - axis).magnitude() < 1e-6, "{:?}", face);
+    }
+    // the top of the side faces' images is up
+    assert!(CubeFace::PositiveX.direction(0.5, 0.0).y > 0.5);
+    assert!(CubeFace::NegativeZ.direction(0.5, 0.0).y > 0.5);
+    // cubemaps are left-handed, the right edge of the -Z face lies towards -X
+    assert!(CubeFace::NegativeZ.direction(1.0, 0.5).x < -0.5);
+}
+
+#[test]
+fn horizontal_and_vertical_crosses() {
+    let size = 4;
+    // horizontal: cells in face order, see faces_from_cross
+    let cells = [(2, 1), (0, 1), (1, 0), (1, 2), (1, 1), (3, 1)];
+    let mut horizontal = RgbaImage::new(size * 4, size * 3);
+    for (index, &(cx, cy)) in cells.iter().enumerate() {
+        for y in 0..size {
+            for x in 0..size {
+                horizontal.put_pixel(cx * size + x, cy * size + y, Rgba(face_color(index)));
+            }
+        }
+        horizontal.put_pixel(cx * size, cy * size, Rgba([255, 255, 255, 255]));
+    }
+    let faces = cubemap::faces_from_cross(&horizontal).unwrap();
+    for (index, face) in faces.iter().enumerate() {
+        assert_eq!(face.dimensions(), (size, size));
+        assert_eq!(face.get_pixel(1, 1).data, face_color(index));
+        assert_eq!(face.get_pixel(0, 0).data, [255, 255, 255, 255]);
+    }
+
+    // vertical: the back face is upside down below the bottom face
+    let mut vertical = RgbaImage::new(size * 3, size * 4);
+    vertical.put_pixel(size + size - 1, 3 * size + size - 1, Rgba([255, 255, 255, 255]));
+    let faces = cubemap::faces_from_cross(&vertical).unwrap();
+    assert_eq!(faces[5].get_pixel(0, 0).data, [255, 255, 255, 255]);
+
+    assert!(cubemap::faces_from_cross(&solid(8, [0, 0, 0, 255])).is_err());
+    assert!(cubemap::faces_from_cross(&RgbaImage::new(0, 0)).is_err());
+}
+
+#[test]
+fn equirectangular_panorama() {
+    // the upper half is sky, the lower half ground, and the center column marks the front
+    let panorama = RgbaImage::from_fn(64, 32, |x, y| {
+        if x == 31 || x == 32 {
+            Rgba([255, 0, 0, 255])
+        } else if y < 16 {
+            Rgba([0, 0, 255, 255])
+        } else {
+            Rgba([0, 255, 0, 255])
+        }
+    });
+    let faces = cubemap::faces_from_equirectangular(&panorama, 16);
+
+    assert_eq!(faces[CubeFace::PositiveY as usize].get_pixel(3, 3).data, [0, 0, 255, 255]);
+    assert_eq!(faces[CubeFace::NegativeY as usize].get_pixel(3, 3).data, [0, 255, 0, 255]);
+    // the center column of the panorama runs through the middle of the -Z face
+    assert!(faces[CubeFace::NegativeZ as usize].get_pixel(8, 2).data[0] > 128);
+    assert_eq!(faces[CubeFace::PositiveZ as usize].get_pixel(8, 2).data[0], 0);
+}
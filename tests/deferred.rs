@@ -0,0 +1,236 @@
+//! Lights reach as far as their volumes, the volumes face outward, and forward passes keep the
+//! depth of the G-buffer.
+#![cfg(unix)]
+
+mod common;
+
+use cgmath::{ortho, Deg, InnerSpace, Matrix4, Point3, SquareMatrix, Vector3};
+
+use learn_opengl_rust::deferred::{self, DeferredRenderer, PointLight, SpotLight, LIGHT_CUTOFF};
+use learn_opengl_rust::framebuffer::{ColorFormat, DepthFormat, Framebuffer, FramebufferDesc, Storage};
+use learn_opengl_rust::headless::HeadlessContext;
+use learn_opengl_rust::mesh::{Geometry, Mesh};
+use learn_opengl_rust::render_queue::{Bucket, RenderQueue};
+use learn_opengl_rust::render_state::RenderState;
+use learn_opengl_rust::scene::{self, Frame, Scene};
+use learn_opengl_rust::scenes::shapes;
+use learn_opengl_rust::shader::Shader;
+
+#[test]
+fn lights_fall_off_to_the_cutoff_at_their_radius() {
+    for &brightest in [0.1, 1.0, 25.0].iter() {
+        let light = PointLight { position: Vector3::new(0.0, 0.0, 0.0), color: Vector3::new(brightest * 0.5, brightest, 0.0) };
+        let radius = light.radius();
+        assert!((brightest / (1.0 + radius * radius) - LIGHT_CUTOFF).abs() < 1e-5, "{} reaches {}", brightest, radius);
+    }
+    // too dim to ever be seen
+    assert_eq!(deferred::light_radius(Vector3::new(0.01, 0.0, 0.0)), 0.0);
+}
+
+// every triangle of `vertices` with its normal pointing away from `inside`, and the whole volume
+// around the points of `enclosed`
+fn assert_closed_around(vertices: &[f32], inside: Vector3<f32>, enclosed: &[Vector3<f32>]) {
+    let triangles: Vec<[Vector3<f32>; 3]> = vertices.chunks(9)
+        .map(|t| [Vector3::new(t[0], t[1], t[2]), Vector3::new(t[3], t[4], t[5]), Vector3::new(t[6], t[7], t[8])])
+        .collect();
+    for triangle in triangles.iter() {
+        let normal = (triangle[1] - triangle[0]).cross(triangle[2] - triangle[0]);
+        let center = (triangle[0] + triangle[1] + triangle[2]) / 3.0;
+        assert!(normal.dot(center - inside) > 0.0, "{:?} faces inward", triangle);
+        for &point in enclosed {
+            assert!(normal.dot(point - triangle[0]) <= 1e-5, "{:?} outside of {:?}", point, triangle);
+        }
+    }
+}
+
+#[test]
+fn volumes_face_outward_around_their_shapes() {
+    let sphere = deferred::sphere_vertices(16);
+    // points on the unit sphere between the corners
+    let on_sphere: Vec<Vector3<f32>> = (0..64).map(|i| {
+        let (theta, phi) = (i as f32 * 0.37, i as f32 * 0.91);
+        Vector3::new(theta.sin() * phi.cos(), theta.cos(), theta.sin() * phi.sin())
+    }).collect();
+    assert_closed_around(&sphere, Vector3::new(0.0, 0.0, 0.0), &on_sphere);
+
+    // the tip at the origin, and the circle of radius 1 at z -1
+    let cone = deferred::cone_vertices(16);
+    let mut on_cone: Vec<Vector3<f32>> = (0..32).map(|i| {
+        let angle = i as f32 * 0.53;
+        Vector3::new(angle.cos(), angle.sin(), -1.0)
+    }).collect();
+    on_cone.push(Vector3::new(0.0, 0.0, 0.0));
+    assert_closed_around(&cone, Vector3::new(0.0, 0.0, -0.5), &on_cone);
+}
+
+// a white floor seen from straight above, 8 pixels to a unit, lit only by the given lights
+struct Lit {
+    renderer: DeferredRenderer,
+    floor: Mesh,
+    cube: Mesh,
+    flat_shader: Shader,
+    white: u32,
+    point_lights: Vec<PointLight>,
+    spot_lights: Vec<SpotLight>,
+    // red cubes drawn forward after shading
+    forward: Vec<Vector3<f32>>,
+}
+
+impl Lit {
+    fn new(ambient: f32) -> Self {
+        let mut renderer = DeferredRenderer::new().unwrap();
+        renderer.ambient = Vector3::new(ambient, ambient, ambient);
+        let mut white = 0;
+        unsafe {
+            gl::GenTextures(1, &mut white);
+            gl::BindTexture(gl::TEXTURE_2D, white);
+            gl::TexImage2D(gl::TEXTURE_2D, 0, gl::RGBA8 as i32, 1, 1, 0, gl::RGBA, gl::UNSIGNED_BYTE, [255u8; 4].as_ptr() as *const _);
+            gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MIN_FILTER, gl::NEAREST as i32);
+        }
+        Lit {
+            renderer,
+            floor: shapes::lit_plane_mesh(),
+            cube: shapes::lit_cube_mesh(),
+            flat_shader: Shader::new("./src/shaders/2_lighting/basic_lighting.vs", "./src/shaders/2_lighting/light_source.fs"),
+            white,
+            point_lights: Vec::new(),
+            spot_lights: Vec::new(),
+            forward: Vec::new(),
+        }
+    }
+}
+
+impl Scene for Lit {
+    fn render(&mut self, frame: &Frame) {
+        let view = Matrix4::look_at(Point3::new(0.0, 10.0, 0.0), Point3::new(0.0, 0.0, 0.0), Vector3::new(0.0, 0.0, -1.0));
+        let projection = ortho(-4.0, 4.0, -4.0, 4.0, 1.0, 20.0);
+
+        let shader = self.renderer.begin_geometry(frame, view, projection);
+        unsafe {
+            for unit in 0..2 {
+                gl::ActiveTexture(gl::TEXTURE0 + unit);
+                gl::BindTexture(gl::TEXTURE_2D, self.white);
+            }
+            gl::ActiveTexture(gl::TEXTURE0);
+        }
+        shader.set_mat4("model", Matrix4::identity());
+        self.floor.draw_geometry();
+        self.renderer.shade(frame, view, projection, &self.point_lights, &self.spot_lights, Vector3::new(0.0, 0.0, 0.0)).unwrap();
+
+        let mut queue = RenderQueue::new();
+        for &position in self.forward.iter() {
+            let (shader, cube) = (&self.flat_shader, &self.cube);
+            queue.push(Bucket::Transparent, position, move || {
+                shader.use_program();
+                shader.set_mat4("projection", projection);
+                shader.set_mat4("view", view);
+                shader.set_mat4("model", Matrix4::from_translation(position));
+                shader.set_vector3v("lightColor", Vector3::new(1.0, 0.0, 0.0));
+                cube.draw_geometry();
+            });
+        }
+        queue.flush(Vector3::new(0.0, 10.0, 0.0));
+    }
+}
+
+impl Drop for Lit {
+    fn drop(&mut self) {
+        unsafe { shapes::delete_textures(&[self.white]); }
+    }
+}
+
+// the red channel along the row through the middle, from the center to the right edge
+fn row(context: &HeadlessContext, scene: &mut Lit) -> Vec<i32> {
+    let image = context.render_scene(scene, 1, 0.0);
+    (context.width / 2..context.width).map(|x| image.get_pixel(x, context.height / 2).data[0] as i32).collect()
+}
+
+#[test]
+fn point_lights_reach_their_radius_only() {
+    let context = match common::context(64, 64, "deferred tests") { Some(context) => context, None => return };
+    unsafe { scene::reset_gl_state(); }
+
+    // a radius of 3, reaching the floor within 2.96 units or 23.7 pixels of the middle
+    let gray = LIGHT_CUTOFF * 10.0;
+    let mut scene = Lit::new(0.0);
+    scene.point_lights.push(PointLight { position: Vector3::new(0.0, 0.0, 0.0), color: Vector3::new(gray, gray, gray) });
+    let lit = row(&context, &mut scene);
+    assert!(lit[0] > 20, "{:?}", lit);
+    assert!(lit[26..].iter().all(|&red| red == 0), "{:?}", lit);
+    assert!(lit.windows(2).all(|pair| pair[0] >= pair[1]), "{:?}", lit);
+
+    // hundreds of them add up
+    scene.point_lights = vec![scene.point_lights[0]; 200];
+    let many = row(&context, &mut scene);
+    assert_eq!(many[0], 255, "{:?}", many);
+    assert!(many[26..].iter().all(|&red| red == 0), "{:?}", many);
+}
+
+#[test]
+fn spot_lights_reach_their_cone_only() {
+    let context = match common::context(64, 64, "deferred tests") { Some(context) => context, None => return };
+    unsafe { scene::reset_gl_state(); }
+
+    // 3.5 above the floor, the outer cone reaches 1.27 units or 10.2 pixels from the middle
+    let mut scene = Lit::new(0.0);
+    scene.spot_lights.push(SpotLight {
+        position: Vector3::new(0.0, 3.0, 0.0),
+        direction: Vector3::new(0.0, -1.0, 0.0),
+        color: Vector3::new(4.0, 4.0, 4.0),
+        cut_off: Deg(15.0),
+        outer_cut_off: Deg(20.0),
+    });
+    let lit = row(&context, &mut scene);
+    assert!(lit[0] > 40, "{:?}", lit);
+    assert!(lit[6] > 40, "{:?}", lit);
+    assert!(lit[14..].iter().all(|&red| red == 0), "{:?}", lit);
+}
+
+#[test]
+fn forward_passes_are_hidden_behind_the_gbuffer() {
+    let context = match common::context(64, 64, "deferred tests") { Some(context) => context, None => return };
+    unsafe { scene::reset_gl_state(); }
+
+    // one cube above the floor, one below it
+    let mut scene = Lit::new(0.25);
+    scene.forward = vec![Vector3::new(-2.0, 1.0, 0.0), Vector3::new(2.0, -2.0, 0.0)];
+    let image = context.render_scene(&mut scene, 1, 0.0);
+    let (above, below) = (image.get_pixel(16, 32).data, image.get_pixel(48, 32).data);
+    assert_eq!((above[0], above[1]), (255, 0), "{:?}", above);
+    assert!(below[0] > 0 && below[0] == below[1], "{:?}", below);
+}
+
+#[test]
+fn shading_needs_a_depth_stencil_target_and_gives_back_the_state() {
+    let _context = match common::context(16, 16, "deferred tests") { Some(context) => context, None => return };
+    unsafe { scene::reset_gl_state(); }
+
+    let mut renderer = DeferredRenderer::new().unwrap();
+    let mut shade_into = |depth: Option<(DepthFormat, Storage)>| -> Result<(), String> {
+        let target = Framebuffer::new(FramebufferDesc { width: 16, height: 16, colors: vec![ColorFormat::Rgba8], depth }).unwrap();
+        let mut id = 0;
+        unsafe {
+            target.bind_draw();
+            gl::GetIntegerv(gl::DRAW_FRAMEBUFFER_BINDING, &mut id);
+        }
+        let frame = Frame { width: 16, height: 16, time: 0.0, delta_time: 0.0, framebuffer: id as u32 };
+        let light = PointLight { position: Vector3::new(0.0, 0.0, 0.0), color: Vector3::new(1.0, 1.0, 1.0) };
+        renderer.begin_geometry(&frame, Matrix4::identity(), Matrix4::identity());
+
+        let mut clear_color = [0.0; 4];
+        unsafe { gl::ClearColor(0.25, 0.5, 0.75, 1.0); }
+        let before = RenderState::query();
+        let shaded = renderer.shade(&frame, Matrix4::identity(), Matrix4::identity(), &[light], &[], Vector3::new(1.0, 0.0, 0.0));
+        unsafe { gl::GetFloatv(gl::COLOR_CLEAR_VALUE, clear_color.as_mut_ptr()); }
+        assert_eq!(RenderState::query(), before);
+        assert_eq!(clear_color, [0.25, 0.5, 0.75, 1.0]);
+        shaded
+    };
+
+    assert_eq!(shade_into(Some((DepthFormat::Depth24Stencil8, Storage::Renderbuffer))), Ok(()));
+    for &depth in [None, Some((DepthFormat::Depth24, Storage::Renderbuffer)), Some((DepthFormat::Depth32F, Storage::Texture))].iter() {
+        let error = shade_into(depth).unwrap_err();
+        assert!(error.contains("Depth24Stencil8"), "{}", error);
+    }
+    assert_eq!(unsafe { gl::GetError() }, gl::NO_ERROR);
+}
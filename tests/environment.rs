@@ -0,0 +1,69 @@
+//! The fresnel term of the environment material and the faces and interval of environment probes.
+
+#[cfg(unix)]
+mod common;
+
+use cgmath::{InnerSpace, Vector3, Vector4};
+
+use learn_opengl_rust::cubemap::CUBE_FACES;
+use learn_opengl_rust::environment::{self, EnvironmentProbe, IOR_GLASS, IOR_WATER};
+
+#[test]
+fn schlick_fresnel() {
+    // head on, glass reflects about 4 percent
+    assert!((environment::schlick(1.0, IOR_GLASS) - 0.0426).abs() < 1e-3);
+    // at grazing angles everything is reflected
+    assert!((environment::schlick(0.0, IOR_GLASS) - 1.0).abs() < 1e-6);
+    // the reflection grows towards grazing angles and with the index of refraction
+    assert!(environment::schlick(0.3, IOR_GLASS) > environment::schlick(0.7, IOR_GLASS));
+    assert!(environment::schlick(1.0, IOR_WATER) < environment::schlick(1.0, IOR_GLASS));
+    // no reflection between equal media
+    assert_eq!(environment::schlick(1.0, 1.0), 0.0);
+}
+
+#[cfg(unix)]
+#[test]
+fn probe_renders_every_face_at_its_interval() {
+    let _context = match common::context(16, 16, "environment probe tests") { Some(context) => context, None => return };
+
+    let position = Vector3::new(1.0, 2.0, 3.0);
+    let mut probe = EnvironmentProbe::new(position, 4, 3).expect("probe");
+    assert!(EnvironmentProbe::new(position, 0, 3).is_err());
+
+    // a point one unit along each face's axis from the probe is in the middle of that face
+    let axes = [
+        Vector3::unit_x(), -Vector3::unit_x(),
+        Vector3::unit_y(), -Vector3::unit_y(),
+        Vector3::unit_z(), -Vector3::unit_z(),
+    ];
+    for (index, axis) in axes.iter().enumerate() {
+        let point = position + axis;
+        let clip = probe.projection() * probe.face_view(index) * Vector4::new(point.x, point.y, point.z, 1.0);
+        assert!(clip.w > 0.0 && (clip.truncate().truncate() / clip.w).magnitude() < 1e-5, "face {}", index);
+    }
+
+    // every face is cleared to its own color
+    let mut faces = 0;
+    let rendered = probe.update(|_, _| unsafe {
+        faces += 1;
+        gl::ClearColor(faces as f32 / 10.0, 0.0, 0.0, 1.0);
+        gl::Clear(gl::COLOR_BUFFER_BIT);
+    });
+    assert!(rendered);
+    assert_eq!(faces, 6);
+
+    let mut pixels = [0u8; 4 * 4 * 4];
+    unsafe {
+        gl::BindTexture(gl::TEXTURE_CUBE_MAP, probe.texture());
+        for (index, face) in CUBE_FACES.iter().enumerate() {
+            gl::GetTexImage(face.gl_target(), 0, gl::RGBA, gl::UNSIGNED_BYTE, pixels.as_mut_ptr() as *mut _);
+            let expected = ((index + 1) as f32 / 10.0 * 255.0).round() as i32;
+            assert!((pixels[0] as i32 - expected).abs() <= 1, "face {} is {}", index, pixels[0]);
+        }
+        gl::BindTexture(gl::TEXTURE_CUBE_MAP, 0);
+    }
+
+    // with an interval of 3 the next two frames reuse the cubemap
+    let updates: Vec<bool> = (0..4).map(|_| probe.update(|_, _| ())).collect();
+    assert_eq!(updates, [false, false, true, false]);
+}
@@ -0,0 +1,82 @@
+//! Framebuffer attachments, completeness errors, resizing and blits on a headless context.
+#![cfg(unix)]
+
+mod common;
+
+use learn_opengl_rust::framebuffer::{ColorFormat, DepthFormat, Framebuffer, FramebufferDesc, Storage};
+use learn_opengl_rust::headless::HeadlessContext;
+
+fn context() -> Option<HeadlessContext> {
+    common::context(64, 64, "framebuffer tests")
+}
+
+#[test]
+fn attachments_and_resize() {
+    let _context = match context() { Some(context) => context, None => return };
+
+    let mut framebuffer = Framebuffer::new(FramebufferDesc {
+        width: 32,
+        height: 16,
+        colors: vec![ColorFormat::Rgba8, ColorFormat::Rgba16F, ColorFormat::Rgb10A2],
+        depth: Some((DepthFormat::Depth24Stencil8, Storage::Texture)),
+    }).expect("multiple render targets with a depth-stencil texture");
+    assert!(framebuffer.depth_texture().is_some());
+    assert_ne!(framebuffer.color_texture(0), framebuffer.color_texture(2));
+
+    framebuffer.resize(8, 4).expect("resize");
+    let mut width = 0;
+    unsafe {
+        gl::BindTexture(gl::TEXTURE_2D, framebuffer.color_texture(1));
+        gl::GetTexLevelParameteriv(gl::TEXTURE_2D, 0, gl::TEXTURE_WIDTH, &mut width);
+    }
+    assert_eq!((width, framebuffer.width(), framebuffer.height()), (8, 8, 4));
+    assert!(framebuffer.resize(0, 4).is_err());
+
+    // a depth only target, e.g. for shadow maps
+    let depth_only = Framebuffer::new(FramebufferDesc {
+        width: 16,
+        height: 16,
+        colors: vec![],
+        depth: Some((DepthFormat::Depth32F, Storage::Texture)),
+    }).expect("depth only framebuffer");
+    assert!(depth_only.depth_texture().is_some());
+}
+
+#[test]
+fn incomplete_framebuffers_are_reported() {
+    let _context = match context() { Some(context) => context, None => return };
+
+    let empty = Framebuffer::new(FramebufferDesc { width: 16, height: 16, colors: vec![], depth: None });
+    let message = empty.err().expect("a framebuffer without attachments is incomplete");
+    assert!(message.contains("no attachment"), "{}", message);
+
+    assert!(Framebuffer::new(FramebufferDesc::color_depth(0, 16)).is_err());
+}
+
+#[test]
+fn blit_copies_the_color() {
+    let context = match context() { Some(context) => context, None => return };
+
+    let source = Framebuffer::new(FramebufferDesc::color_depth(16, 16)).expect("source framebuffer");
+    source.bind();
+    unsafe {
+        gl::ClearColor(1.0, 0.0, 0.0, 1.0);
+        gl::Clear(gl::COLOR_BUFFER_BIT);
+    }
+
+    context.bind();
+    unsafe {
+        gl::ClearColor(0.0, 0.0, 1.0, 1.0);
+        gl::Clear(gl::COLOR_BUFFER_BIT);
+    }
+    // the lower left quarter of the 64x64 context
+    let mut target = 0;
+    unsafe { gl::GetIntegerv(gl::FRAMEBUFFER_BINDING, &mut target); }
+    source.blit_region(target as u32, [0, 0, 32, 32], gl::COLOR_BUFFER_BIT, gl::NEAREST);
+
+    let image = context.read_pixels();
+    // the image has the top row first
+    assert_eq!(image.get_pixel(5, 60).data, [255, 0, 0, 255]);
+    assert_eq!(image.get_pixel(40, 60).data, [0, 0, 255, 255]);
+    assert_eq!(image.get_pixel(5, 10).data, [0, 0, 255, 255]);
+}
@@ -0,0 +1,77 @@
+//! Switching scenes in the gallery, driven by synthetic key presses.
+
+use glfw::{Action, Key, Modifiers, WindowEvent};
+
+use learn_opengl_rust::gallery::Gallery;
+use learn_opengl_rust::input::{InputMap, InputState};
+use learn_opengl_rust::scene::{Control, Controller};
+use learn_opengl_rust::scenes::SCENES;
+
+// presses and releases a key within one frame
+fn tap(input: &mut InputState, key: Key) {
+    input.handle_event(&WindowEvent::Key(key, 0, Action::Press, Modifiers::empty()));
+    input.handle_event(&WindowEvent::Key(key, 0, Action::Release, Modifiers::empty()));
+    input.update();
+}
+
+fn idle(input: &mut InputState) {
+    input.update();
+}
+
+fn is_switch(control: &Control) -> bool {
+    matches!(control, Control::Switch(_))
+}
+
+#[test]
+fn next_and_previous_wrap_around() {
+    let mut input = InputState::new(InputMap::default_bindings());
+    let mut gallery = Gallery::new(SCENES, 0);
+
+    tap(&mut input, Key::PageUp);
+    assert!(is_switch(&gallery.control(&input)));
+    assert_eq!(gallery.current().name, SCENES[SCENES.len() - 1].name);
+
+    idle(&mut input);
+    assert!(matches!(gallery.control(&input), Control::Pass));
+
+    tap(&mut input, Key::PageDown);
+    assert!(is_switch(&gallery.control(&input)));
+    assert_eq!(gallery.current().name, SCENES[0].name);
+    assert!(gallery.title().contains(&format!("1/{}", SCENES.len())));
+}
+
+#[test]
+fn chapter_menu_jumps_to_the_first_scene_of_a_chapter() {
+    let mut input = InputState::new(InputMap::default_bindings());
+    let mut gallery = Gallery::new(SCENES, 0);
+    let chapters = gallery.chapters();
+    assert_eq!(chapters.first(), Some(&"1_getting_started"));
+
+    tap(&mut input, Key::M);
+    assert!(matches!(gallery.control(&input), Control::Capture));
+    assert_eq!(gallery.menu(), Some(0));
+
+    // the menu keeps the movement keys for itself
+    tap(&mut input, Key::S);
+    assert!(matches!(gallery.control(&input), Control::Capture));
+    assert_eq!(gallery.menu(), Some(1));
+    assert!(gallery.title().contains("2. Lighting"));
+
+    tap(&mut input, Key::Enter);
+    assert!(is_switch(&gallery.control(&input)));
+    assert_eq!(gallery.menu(), None);
+    assert_eq!(gallery.current().name, "colors");
+}
+
+#[test]
+fn escape_closes_the_menu_without_switching() {
+    let mut input = InputState::new(InputMap::default_bindings());
+    let mut gallery = Gallery::new(SCENES, 5);
+
+    tap(&mut input, Key::M);
+    gallery.control(&input);
+    tap(&mut input, Key::Escape);
+    assert!(matches!(gallery.control(&input), Control::Capture));
+    assert_eq!(gallery.menu(), None);
+    assert_eq!(gallery.current().name, SCENES[5].name);
+}
@@ -0,0 +1,72 @@
+//! sRGB textures are decoded when sampled, and both gamma outputs encode linear values the same way.
+#![cfg(unix)]
+
+mod common;
+
+use std::env;
+
+use learn_opengl_rust::framebuffer::{FullscreenQuad, FULLSCREEN_VERTEX_SHADER};
+use learn_opengl_rust::gamma::{GammaOutput, GammaPass, GAMMA_SHADER};
+use learn_opengl_rust::model::{self, ColorSpace};
+use learn_opengl_rust::scene;
+use learn_opengl_rust::shader::Shader;
+
+// stored as 188 in sRGB, the middle of the linear range
+const SRGB_HALF: u8 = 188;
+// linear value of an sRGB 128
+const LINEAR_HALF: f32 = 0.214;
+
+fn close(value: u8, expected: u8) -> bool {
+    (value as i32 - expected as i32).abs() <= 2
+}
+
+#[test]
+fn srgb_textures_sample_as_linear() {
+    let context = match common::context(4, 4, "gamma tests") { Some(context) => context, None => return };
+    unsafe { scene::reset_gl_state(); }
+
+    let path = env::temp_dir().join("learn_opengl_rust_srgb_half.png");
+    image::RgbImage::from_pixel(1, 1, image::Rgb([SRGB_HALF; 3])).save(&path).unwrap();
+    let path = path.to_str().unwrap();
+
+    // the gamma shader with a gamma of 1 copies what it samples
+    let shader = Shader::new(FULLSCREEN_VERTEX_SHADER, GAMMA_SHADER);
+    shader.use_program();
+    shader.set_int("screenTexture", 0);
+    shader.set_float("gamma", 1.0);
+    let quad = FullscreenQuad::new();
+
+    let mut sampled = Vec::new();
+    for &color_space in [ColorSpace::Linear, ColorSpace::Srgb].iter() {
+        let texture = unsafe { model::texture_from_file_with(path, color_space) };
+        context.bind();
+        quad.pass(&shader, &[texture]);
+        sampled.push(context.read_pixels().get_pixel(1, 1).data[0]);
+        unsafe { gl::DeleteTextures(1, &texture); }
+    }
+    assert!(close(sampled[0], SRGB_HALF), "linear texture sampled as {}", sampled[0]);
+    assert!(close(sampled[1], 128), "sRGB texture sampled as {}", sampled[1]);
+}
+
+#[test]
+fn both_outputs_encode_linear_values() {
+    let context = match common::context(4, 4, "gamma tests") { Some(context) => context, None => return };
+    unsafe { scene::reset_gl_state(); }
+
+    for &output in [GammaOutput::Shader, GammaOutput::SrgbFramebuffer].iter() {
+        let mut pass = GammaPass::new(output).unwrap();
+        context.bind();
+        pass.begin().unwrap();
+        unsafe {
+            gl::ClearColor(LINEAR_HALF, LINEAR_HALF, LINEAR_HALF, 1.0);
+            gl::Clear(gl::COLOR_BUFFER_BIT);
+        }
+        pass.end();
+
+        let pixel = context.read_pixels().get_pixel(1, 1).data;
+        assert!(close(pixel[0], 128), "{:?} encoded {} as {}", output, LINEAR_HALF, pixel[0]);
+        // nothing left for the next frame to encode twice
+        unsafe { assert_eq!(gl::IsEnabled(gl::FRAMEBUFFER_SRGB), gl::FALSE); }
+    }
+    unsafe { scene::reset_gl_state(); }
+}
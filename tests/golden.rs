@@ -0,0 +1,194 @@
+//! Renders every scene headless and compares the last frame against a stored reference image.
+//!
+//! References live in `tests/golden/reference/<scene>.png`. A missing reference is recorded
+//! from the current output, `UPDATE_GOLDEN=1 cargo test --test golden` records all of them again.
+//! With `GOLDEN_REQUIRED` set (CI) a missing reference fails instead, only `UPDATE_GOLDEN` records.
+//! On a mismatch the actual, expected and diff image are written to `target/golden/`.
+#![cfg(unix)]
+
+mod common;
+
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use image::{Rgba, RgbaImage};
+
+use learn_opengl_rust::headless::HeadlessContext;
+use learn_opengl_rust::scene;
+use learn_opengl_rust::scenes::{self, model_loading::backpack};
+
+// Constants
+const WIDTH: u32 = 320;
+const HEIGHT: u32 = 240;
+// every scene is rendered for the same frames, the last one is at time 0.5
+const FRAMES: u32 = 3;
+const DELTA_TIME: f32 = 0.25;
+
+// a channel may differ by this much before the pixel counts as mismatched
+const CHANNEL_TOLERANCE: u8 = 8;
+// fraction of mismatched pixels that is still accepted, driver rasterization differs slightly
+const MAX_MISMATCH_FRACTION: f64 = 0.005;
+// minimum mean structural similarity of the luma, catches blurry or shifted images
+const MIN_SSIM: f64 = 0.98;
+const SSIM_WINDOW: u32 = 8;
+
+fn reference_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/golden/reference")
+}
+
+fn output_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("target/golden")
+}
+
+// scenes depending on assets that are not checked in are skipped
+fn missing_assets(name: &str) -> Option<String> {
+    let model = Path::new(backpack::BACKPACK_DIRECTORY).join(backpack::BACKPACK_FILE);
+    match name {
+        "backpack" | "backpack_lighting" | "backpack_reflection" if !model.exists() => Some(format!("{} is missing", model.display())),
+        _ => None
+    }
+}
+
+struct Comparison {
+    mismatched: u64,
+    ssim: f64,
+    diff: RgbaImage,
+}
+
+impl Comparison {
+    fn mismatch_fraction(&self) -> f64 {
+        self.mismatched as f64 / (self.diff.width() * self.diff.height()) as f64
+    }
+
+    fn passed(&self) -> bool {
+        self.mismatch_fraction() <= MAX_MISMATCH_FRACTION && self.ssim >= MIN_SSIM
+    }
+}
+
+fn compare(actual: &RgbaImage, expected: &RgbaImage) -> Comparison {
+    let mut mismatched = 0;
+    // the diff shows the expected image dimmed in gray with mismatched pixels in red
+    let diff = RgbaImage::from_fn(actual.width(), actual.height(), |x, y| {
+        let (a, e) = (actual.get_pixel(x, y), expected.get_pixel(x, y));
+        let differs = a.data.iter().zip(e.data.iter()).any(|(&a, &e)| (a as i16 - e as i16).abs() > CHANNEL_TOLERANCE as i16);
+        if differs {
+            mismatched += 1;
+            Rgba([255, 0, 0, 255])
+        } else {
+            let gray = (luma(e) * 0.3) as u8;
+            Rgba([gray, gray, gray, 255])
+        }
+    });
+
+    Comparison { mismatched, ssim: mean_ssim(actual, expected), diff }
+}
+
+fn luma(pixel: &Rgba<u8>) -> f64 {
+    0.299 * pixel.data[0] as f64 + 0.587 * pixel.data[1] as f64 + 0.114 * pixel.data[2] as f64
+}
+
+// mean SSIM over non-overlapping windows of the luma channel
+fn mean_ssim(actual: &RgbaImage, expected: &RgbaImage) -> f64 {
+    const C1: f64 = (0.01 * 255.0) * (0.01 * 255.0);
+    const C2: f64 = (0.03 * 255.0) * (0.03 * 255.0);
+
+    let mut total = 0.0;
+    let mut windows = 0;
+    for wy in 0..actual.height() / SSIM_WINDOW {
+        for wx in 0..actual.width() / SSIM_WINDOW {
+            let mut samples = Vec::with_capacity((SSIM_WINDOW * SSIM_WINDOW) as usize);
+            for y in wy * SSIM_WINDOW..(wy + 1) * SSIM_WINDOW {
+                for x in wx * SSIM_WINDOW..(wx + 1) * SSIM_WINDOW {
+                    samples.push((luma(actual.get_pixel(x, y)), luma(expected.get_pixel(x, y))));
+                }
+            }
+
+            let n = samples.len() as f64;
+            let mean_a = samples.iter().map(|s| s.0).sum::<f64>() / n;
+            let mean_e = samples.iter().map(|s| s.1).sum::<f64>() / n;
+            let var_a = samples.iter().map(|s| (s.0 - mean_a).powi(2)).sum::<f64>() / n;
+            let var_e = samples.iter().map(|s| (s.1 - mean_e).powi(2)).sum::<f64>() / n;
+            let covariance = samples.iter().map(|s| (s.0 - mean_a) * (s.1 - mean_e)).sum::<f64>() / n;
+
+            total += ((2.0 * mean_a * mean_e + C1) * (2.0 * covariance + C2))
+                / ((mean_a * mean_a + mean_e * mean_e + C1) * (var_a + var_e + C2));
+            windows += 1;
+        }
+    }
+    total / windows as f64
+}
+
+fn save(image: &RgbaImage, path: &Path) {
+    fs::create_dir_all(path.parent().unwrap()).expect("Could not create the golden image directory");
+    image.save(path).unwrap_or_else(|e| panic!("Could not write {}. {}", path.display(), e));
+}
+
+// renders one scene, returns a description of the failure if there is one
+fn check_scene(context: &HeadlessContext, entry: &scenes::Entry, update: bool, required: bool) -> Result<(), String> {
+    let actual = unsafe {
+        // start from GL's defaults like the windowed gallery does, and drop errors of the previous scene
+        scene::reset_gl_state();
+        while gl::GetError() != gl::NO_ERROR {}
+
+        let mut scene = (entry.create)();
+        let image = context.render_scene(scene.as_mut(), FRAMES, DELTA_TIME);
+        let error = gl::GetError();
+        if error != gl::NO_ERROR {
+            return Err(format!("GL error 0x{:X} while rendering", error));
+        }
+        image
+    };
+
+    let reference = reference_dir().join(format!("{}.png", entry.name));
+    if !update && !reference.exists() && required {
+        return Err(format!("{} is missing, record it with UPDATE_GOLDEN=1", reference.display()));
+    }
+    if update || !reference.exists() {
+        save(&actual, &reference);
+        println!("{}: recorded {}", entry.name, reference.display());
+        return Ok(());
+    }
+
+    let expected = image::open(&reference)
+        .map_err(|e| format!("could not read {}. {}", reference.display(), e))?
+        .to_rgba();
+    if expected.dimensions() != actual.dimensions() {
+        return Err(format!("reference is {:?}, rendered {:?}", expected.dimensions(), actual.dimensions()));
+    }
+
+    let comparison = compare(&actual, &expected);
+    if comparison.passed() {
+        return Ok(());
+    }
+
+    let output = output_dir();
+    save(&actual, &output.join(format!("{}-actual.png", entry.name)));
+    save(&expected, &output.join(format!("{}-expected.png", entry.name)));
+    save(&comparison.diff, &output.join(format!("{}-diff.png", entry.name)));
+    Err(format!(
+        "{:.2}% pixels differ, SSIM {:.4}, images written to {}",
+        comparison.mismatch_fraction() * 100.0, comparison.ssim, output.display()
+    ))
+}
+
+#[test]
+fn every_scene_matches_its_reference() {
+    let required = env::var_os("GOLDEN_REQUIRED").is_some();
+    let context = match common::context(WIDTH, HEIGHT, "golden image tests") { Some(context) => context, None => return };
+    println!("Rendering golden images with {}", context.description());
+
+    let update = env::var_os("UPDATE_GOLDEN").is_some();
+    let mut failures = Vec::new();
+    for entry in scenes::SCENES {
+        if let Some(reason) = missing_assets(entry.name) {
+            println!("{}: skipped, {}", entry.name, reason);
+            continue;
+        }
+        if let Err(e) = check_scene(&context, entry, update, required) {
+            failures.push(format!("{}: {}", entry.name, e));
+        }
+    }
+
+    assert!(failures.is_empty(), "golden image mismatches:\n{}", failures.join("\n"));
+}
@@ -0,0 +1,89 @@
+//! Tone mapping curves on the CPU and the GPU, and automatic exposure adapting to a changing frame.
+#![cfg(unix)]
+
+mod common;
+
+use std::cell::Cell;
+use std::rc::Rc;
+
+use learn_opengl_rust::gamma::GAMMA;
+use learn_opengl_rust::hdr::{AutoExposure, ExposureSettings, ToneMapping, UNCHARTED2_WHITE};
+use learn_opengl_rust::postprocess::PostProcessed;
+use learn_opengl_rust::scene::{Frame, Scene};
+
+const OPERATORS: [ToneMapping; 4] = [ToneMapping::Reinhard, ToneMapping::Exposure, ToneMapping::AcesFilmic, ToneMapping::Uncharted2];
+
+// clears to a gray that can be changed between frames
+struct Clear(Rc<Cell<f32>>);
+
+impl Scene for Clear {
+    fn render(&mut self, _frame: &Frame) {
+        let value = self.0.get();
+        unsafe {
+            gl::ClearColor(value, value, value, 1.0);
+            gl::Clear(gl::COLOR_BUFFER_BIT | gl::DEPTH_BUFFER_BIT);
+        }
+    }
+}
+
+fn encoded(value: f32) -> i32 {
+    (value.powf(1.0 / GAMMA) * 255.0).round() as i32
+}
+
+#[test]
+fn curves_rise_from_black_towards_white() {
+    for &operator in OPERATORS.iter() {
+        assert!(operator.map(0.0).abs() < 1e-5, "{:?}", operator);
+        let mut last = 0.0;
+        for step in 1..200 {
+            let mapped = operator.map(step as f32 * 0.1);
+            assert!(mapped >= last && mapped <= 1.0 + 1e-5, "{:?} maps {} to {}", operator, step as f32 * 0.1, mapped);
+            last = mapped;
+        }
+        assert!(last > 0.9, "{:?} only reaches {}", operator, last);
+    }
+    assert!((ToneMapping::Uncharted2.map(UNCHARTED2_WHITE / 2.0) - 1.0).abs() < 1e-5);
+    // Reinhard never gets there
+    assert!(ToneMapping::Reinhard.map(100.0) < 1.0);
+}
+
+#[test]
+fn the_shader_follows_the_curves() {
+    let context = match common::context(32, 32, "HDR tests") { Some(context) => context, None => return };
+
+    for &value in [0.05, 0.5, 4.0].iter() {
+        let mut scene = PostProcessed::hdr(Box::new(Clear(Rc::new(Cell::new(value)))), &["tone_mapping"]);
+        for &operator in OPERATORS.iter() {
+            scene.chain.effect_mut("tone_mapping").unwrap().set_param("operator", operator.index()).unwrap();
+            let pixel = context.render_scene(&mut scene, 1, 0.0).get_pixel(16, 16).data;
+            let expected = encoded(operator.map(value));
+            assert!((pixel[0] as i32 - expected).abs() <= 2, "{:?} of {}: {:?}, expected {}", operator, value, pixel, expected);
+        }
+    }
+}
+
+#[test]
+fn exposure_adapts_to_the_frame() {
+    let context = match common::context(32, 32, "HDR tests") { Some(context) => context, None => return };
+
+    let gray = Rc::new(Cell::new(0.5));
+    let mut scene = PostProcessed::hdr(Box::new(Clear(gray.clone())), &["tone_mapping"]);
+    let auto_exposure = AutoExposure::new(ExposureSettings::default()).unwrap();
+    let effect = scene.chain.effect_mut("tone_mapping").unwrap();
+    effect.set_param("operator", ToneMapping::Reinhard.index()).unwrap();
+    effect.auto_exposure = Some(auto_exposure);
+    let mut render = |frames| context.render_scene(&mut scene, frames, 0.1).get_pixel(16, 16).data[0] as i32;
+
+    // the first frame jumps straight to its average, which ends up at the key
+    let key = encoded(ToneMapping::Reinhard.map(0.18));
+    assert!((render(1) - key).abs() <= 2);
+
+    // 8 times brighter, the eyes need a while
+    gray.set(4.0);
+    let dazzled = render(1);
+    assert!(dazzled > key + 40, "{} after the change, {} at the key", dazzled, key);
+    let adapting = render(5);
+    assert!(adapting < dazzled && adapting > key, "{} while adapting", adapting);
+    let adapted = render(60);
+    assert!((adapted - key).abs() <= 2, "{} after adapting", adapted);
+}
@@ -0,0 +1,115 @@
+//! Parsing of binding files and action states driven by synthetic events.
+
+use glfw::{Action, GamepadAxis, GamepadButton, Key, Modifiers, MouseButton, WindowEvent};
+
+use learn_opengl_rust::input::{Binding, InputMap, InputState};
+
+fn key(key: Key, action: Action) -> WindowEvent {
+    WindowEvent::Key(key, 0, action, Modifiers::empty())
+}
+
+fn state(config: &str) -> InputState {
+    InputState::new(InputMap::parse(config).unwrap())
+}
+
+#[test]
+fn parse_bindings_and_aliases() {
+    let map = InputMap::parse(
+        "# a comment\n\
+         \n\
+         jump = key:Space, button:A  # trailing comment\n\
+         forward = KEY:w, axis:LeftY-\n\
+         back = axis:AxisLeftY+\n\
+         fire = mouse:Left, key:Ctrl\n"
+    ).unwrap();
+
+    assert_eq!(map.actions["jump"], vec![Binding::Key(Key::Space), Binding::GamepadButton(GamepadButton::ButtonA)]);
+    assert_eq!(map.actions["forward"], vec![Binding::Key(Key::W), Binding::GamepadAxis(GamepadAxis::AxisLeftY, -1.0)]);
+    // glfw's variant names work as well as the short aliases
+    assert_eq!(map.actions["back"], vec![Binding::GamepadAxis(GamepadAxis::AxisLeftY, 1.0)]);
+    assert_eq!(map.actions["fire"], vec![Binding::Mouse(MouseButton::Button1), Binding::Key(Key::LeftControl)]);
+}
+
+#[test]
+fn bad_lines_name_the_line() {
+    let errors = [
+        ("jump key:Space", "line 1"),
+        ("\n = key:Space", "line 2"),
+        ("jump = Space", "missing a kind"),
+        ("jump = pedal:Left", "unknown binding kind"),
+        ("jump = key:NoSuchKey", "unknown key"),
+        ("move = axis:LeftZ-", "unknown axis"),
+    ];
+    for (source, message) in errors.iter() {
+        let error = InputMap::parse(source).expect_err(source);
+        assert!(error.contains(message), "{:?} gave {:?}", source, error);
+    }
+}
+
+#[test]
+fn pressed_held_and_released_edges() {
+    let mut input = state("jump = key:Space");
+
+    input.handle_event(&key(Key::Space, Action::Press));
+    input.update();
+    assert!(input.pressed("jump") && input.held("jump") && !input.released("jump"));
+
+    // key repeats and further frames keep it held, but it is pressed only once
+    input.handle_event(&key(Key::Space, Action::Repeat));
+    input.update();
+    assert!(!input.pressed("jump") && input.held("jump"));
+
+    input.handle_event(&key(Key::Space, Action::Release));
+    input.update();
+    assert!(!input.held("jump") && input.released("jump"));
+    input.update();
+    assert!(!input.released("jump"));
+
+    // unknown actions are simply never active
+    assert!(!input.held("missing"));
+}
+
+#[test]
+fn tap_within_one_frame_counts() {
+    let mut input = state("fire = mouse:Left");
+    input.handle_event(&WindowEvent::MouseButton(MouseButton::Button1, Action::Press, Modifiers::empty()));
+    input.handle_event(&WindowEvent::MouseButton(MouseButton::Button1, Action::Release, Modifiers::empty()));
+    input.update();
+    assert!(input.pressed("fire"));
+    input.update();
+    assert!(input.released("fire") && !input.held("fire"));
+}
+
+#[test]
+fn axis_dead_zone_and_direction() {
+    let mut input = state("forward = axis:LeftY-\nback = axis:LeftY+");
+
+    input.set_gamepad_axis(GamepadAxis::AxisLeftY, -0.2);
+    input.update();
+    assert_eq!(input.axis("forward"), 0.0);
+
+    input.set_gamepad_axis(GamepadAxis::AxisLeftY, -0.6);
+    input.update();
+    assert!((input.axis("forward") - 0.6).abs() < 1e-6);
+    assert_eq!(input.axis("back"), 0.0);
+    assert!((input.axis_pair("forward", "back") + 0.6).abs() < 1e-6);
+    assert!(input.pressed("forward"));
+}
+
+#[test]
+fn focus_loss_releases_everything() {
+    let mut input = state("jump = key:Space\nfire = button:A");
+    input.handle_event(&key(Key::Space, Action::Press));
+    input.set_gamepad_button(GamepadButton::ButtonA, true);
+    input.update();
+    assert!(input.held("jump") && input.held("fire"));
+
+    input.handle_event(&WindowEvent::Focus(false));
+    input.update();
+    assert!(input.released("jump") && input.released("fire"));
+
+    // the release that arrives later does not matter anymore
+    input.handle_event(&key(Key::Space, Action::Release));
+    input.update();
+    assert!(!input.held("jump") && !input.released("jump"));
+}
@@ -0,0 +1,108 @@
+//! Instanced drawing of a `Mesh`, each instance placed and colored by the instance buffer.
+#![cfg(unix)]
+
+mod common;
+
+use std::fs;
+use std::path::Path;
+
+use cgmath::{Matrix4, Vector2, Vector3, Vector4};
+
+use learn_opengl_rust::instancing::{Instance, InstanceBuffer};
+use learn_opengl_rust::mesh::{Mesh, Vertex};
+use learn_opengl_rust::scene::{Frame, Scene};
+use learn_opengl_rust::shader::Shader;
+
+const VERTEX_SHADER: &str = "#version 330 core
+layout (location = 0) in vec3 aPos;
+layout (location = 5) in mat4 aInstanceModel;
+layout (location = 9) in vec4 aInstanceColor;
+layout (location = 10) in vec4 aInstanceData;
+out vec4 color;
+void main() {
+    color = aInstanceColor * aInstanceData.x;
+    gl_Position = aInstanceModel * vec4(aPos, 1.0);
+}";
+
+const FRAGMENT_SHADER: &str = "#version 330 core
+in vec4 color;
+out vec4 FragColor;
+void main() {
+    FragColor = color;
+}";
+
+// the shader class loads files, so the sources are written next to the other test output
+fn shader() -> Shader {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("target/instancing_tests");
+    fs::create_dir_all(&dir).unwrap();
+    let (vertex, fragment) = (dir.join("instanced.vs"), dir.join("instanced.fs"));
+    fs::write(&vertex, VERTEX_SHADER).unwrap();
+    fs::write(&fragment, FRAGMENT_SHADER).unwrap();
+    Shader::new(vertex.to_str().unwrap(), fragment.to_str().unwrap())
+}
+
+// a square of a quarter of the screen around the origin
+fn square() -> Mesh {
+    let corner = |x: f32, y: f32| Vertex {
+        position: Vector3::new(x, y, 0.0),
+        tex_coords: Vector2::new(x, y),
+        ..Vertex::default()
+    };
+    let vertices = vec![corner(-0.25, -0.25), corner(0.25, -0.25), corner(0.25, 0.25), corner(-0.25, 0.25)];
+    Mesh::new(vertices, vec![0, 1, 2, 0, 2, 3], Vec::new())
+}
+
+struct Squares {
+    shader: Shader,
+    mesh: Mesh,
+    instances: InstanceBuffer,
+}
+
+impl Scene for Squares {
+    fn render(&mut self, _frame: &Frame) {
+        unsafe {
+            gl::ClearColor(0.0, 0.0, 0.0, 1.0);
+            gl::Clear(gl::COLOR_BUFFER_BIT);
+            self.shader.use_program();
+            self.mesh.DrawInstanced(&self.shader, self.instances.len());
+        }
+    }
+}
+
+fn instance(x: f32, color: [f32; 4], brightness: f32) -> Instance {
+    Instance {
+        model: Matrix4::from_translation(Vector3::new(x, 0.0, 0.0)),
+        color: Vector4::from(color),
+        data: Vector4::new(brightness, 0.0, 0.0, 0.0),
+    }
+}
+
+#[test]
+fn every_instance_has_its_own_attributes() {
+    let context = match common::context(32, 32, "instancing tests") { Some(context) => context, None => return };
+
+    let mesh = square();
+    let instances = InstanceBuffer::new(&[
+        instance(-0.5, [1.0, 0.0, 0.0, 1.0], 1.0),
+        instance(0.5, [0.0, 1.0, 0.0, 1.0], 1.0),
+    ]);
+    mesh.attach_instances(&instances);
+    let mut scene = Squares { shader: shader(), mesh, instances };
+
+    let image = context.render_scene(&mut scene, 1, 0.0);
+    assert_eq!(image.get_pixel(8, 16).data, [255, 0, 0, 255]);
+    assert_eq!(image.get_pixel(24, 16).data, [0, 255, 0, 255]);
+    // the square itself stays empty, no instance sits at the origin
+    assert_eq!(image.get_pixel(16, 16).data, [0, 0, 0, 255]);
+
+    // a third instance grows the buffer, the custom data dims it to half
+    scene.instances.update(&[
+        instance(-0.5, [1.0, 0.0, 0.0, 1.0], 1.0),
+        instance(0.5, [0.0, 1.0, 0.0, 1.0], 1.0),
+        instance(0.0, [0.0, 0.0, 1.0, 1.0], 0.5),
+    ]);
+    let image = context.render_scene(&mut scene, 1, 0.0);
+    let center = image.get_pixel(16, 16).data;
+    assert!(center[0] == 0 && (center[2] as i32 - 128).abs() <= 1, "{:?}", center);
+    assert_eq!(scene.instances.len(), 3);
+}
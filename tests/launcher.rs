@@ -0,0 +1,76 @@
+//! Discovery and matching of the examples offered by `main.rs`.
+
+use learn_opengl_rust::launcher::{self, Example, Match};
+
+const MANIFEST: &str = r#"
+[package]
+name = "learn_opengl_rust"
+
+[[example]]
+name = "hello_triangle"
+path = "examples/1_getting_started/1_hello_triangle.rs"
+
+[dependencies]
+name = "not an example"
+
+[[example]]
+name = "point_light"
+path = "examples/2_lighting/9_point_light.rs"
+
+[[example]]
+name = "spot_light"
+path = "examples/2_lighting/10_spot_light.rs"
+"#;
+
+fn example(name: &str, chapter: &str) -> Example {
+    Example { name: name.to_string(), chapter: chapter.to_string(), description: String::new() }
+}
+
+fn examples() -> Vec<Example> {
+    vec![
+        example("hello_triangle", "1_getting_started"),
+        example("hello_indexed", "1_getting_started"),
+        example("point_light", "2_lighting"),
+        example("spot_light", "2_lighting"),
+        example("lighting_maps", "2_lighting"),
+    ]
+}
+
+#[test]
+fn manifest_examples_keep_their_order_and_chapter() {
+    let examples = launcher::parse_manifest(MANIFEST);
+    let names: Vec<(&str, &str)> = examples.iter().map(|e| (e.name.as_str(), e.chapter.as_str())).collect();
+    assert_eq!(names, [
+        ("hello_triangle", "1_getting_started"),
+        ("point_light", "2_lighting"),
+        ("spot_light", "2_lighting"),
+    ]);
+    // descriptions come from the scene registry
+    assert_eq!(examples[0].description, "A single orange triangle.");
+}
+
+#[test]
+fn chapter_titles() {
+    assert_eq!(launcher::chapter_title("1_getting_started"), "1. Getting started");
+    assert_eq!(launcher::chapter_title("tools"), "Tools");
+    assert_eq!(launcher::chapter_title(""), "Other");
+}
+
+#[test]
+fn find_by_number_name_and_part() {
+    let examples = examples();
+    assert_eq!(launcher::find(&examples, "3"), Match::Found(&examples[2]));
+    assert_eq!(launcher::find(&examples, "0"), Match::NotFound);
+    assert_eq!(launcher::find(&examples, "6"), Match::NotFound);
+    assert_eq!(launcher::find(&examples, "Spot_Light"), Match::Found(&examples[3]));
+    assert_eq!(launcher::find(&examples, "maps"), Match::Fuzzy(&examples[4]));
+    assert_eq!(launcher::find(&examples, "hello"), Match::Ambiguous(vec![&examples[0], &examples[1]]));
+}
+
+#[test]
+fn find_forgives_typos() {
+    let examples = examples();
+    assert_eq!(launcher::find(&examples, "pooint_light"), Match::Fuzzy(&examples[2]));
+    assert_eq!(launcher::find(&examples, "helo_triangle"), Match::Fuzzy(&examples[0]));
+    assert_eq!(launcher::find(&examples, "shadow_mapping"), Match::NotFound);
+}
@@ -0,0 +1,112 @@
+//! Weighted blended order-independent transparency against sorted blending, with identity
+//! matrices so the quads are placed directly in normalized device coordinates.
+#![cfg(unix)]
+
+mod common;
+
+use cgmath::{Matrix4, SquareMatrix, Vector3, Vector4};
+
+use learn_opengl_rust::headless::HeadlessContext;
+use learn_opengl_rust::oit::OitPass;
+use learn_opengl_rust::render_queue::{Bucket, RenderQueue};
+use learn_opengl_rust::scene::{Frame, Scene};
+use learn_opengl_rust::scenes::shapes;
+use learn_opengl_rust::shader::Shader;
+
+const RED: [f32; 4] = [1.0, 0.0, 0.0, 0.5];
+const BLUE: [f32; 4] = [0.0, 0.0, 1.0, 0.5];
+
+struct Panes {
+    order_independent: bool,
+    colors: [[f32; 4]; 2],
+    sorted_shader: Shader,
+    oit_shader: Shader,
+    vao: shapes::VertexArray,
+    oit: OitPass,
+}
+
+impl Panes {
+    fn new(order_independent: bool, colors: [[f32; 4]; 2]) -> Self {
+        let shader = |fragment: &str| {
+            let shader = Shader::new("./src/shaders/4_advanced_opengl/blending.vs", fragment);
+            shader.use_program();
+            shader.set_mat4("projection", Matrix4::identity());
+            shader.set_mat4("view", Matrix4::identity());
+            shader
+        };
+        Panes {
+            order_independent,
+            colors,
+            sorted_shader: shader("./src/shaders/4_advanced_opengl/transparent_color.fs"),
+            oit_shader: shader("./src/shaders/4_advanced_opengl/oit_accumulate.fs"),
+            vao: unsafe { shapes::create_vao(&shapes::QUAD_VERTICES, &[3, 2]) },
+            oit: OitPass::new(1, 1).unwrap(),
+        }
+    }
+}
+
+impl Scene for Panes {
+    fn render(&mut self, _frame: &Frame) {
+        unsafe {
+            gl::Enable(gl::DEPTH_TEST);
+            gl::ClearColor(0.0, 0.0, 0.0, 1.0);
+            gl::Clear(gl::COLOR_BUFFER_BIT | gl::DEPTH_BUFFER_BIT);
+        }
+        let mut queue = RenderQueue::new();
+        let (sorted_shader, oit_shader, vao) = (&self.sorted_shader, &self.oit_shader, &self.vao);
+        let draw = move |shader: &'static str, color: [f32; 4], model: Matrix4<f32>| {
+            let shader = if shader == "oit" { oit_shader } else { sorted_shader };
+            move || unsafe {
+                shader.use_program();
+                shader.set_mat4("model", model);
+                shader.set_vector4v("color", Vector4::from(color));
+                gl::BindVertexArray(vao.id);
+                gl::DrawArrays(gl::TRIANGLES, 0, 6);
+            }
+        };
+
+        // the quad spans x from 0 to 1 and y from -0.5 to 0.5, scaled up it covers the screen
+        let screen = Matrix4::from_scale(2.0) * Matrix4::from_translation(Vector3::new(-0.5, 0.0, 0.0));
+        let (bucket, shader) = if self.order_independent { (Bucket::OrderIndependent, "oit") } else { (Bucket::Transparent, "sorted") };
+        for &color in self.colors.iter() {
+            // at the same distance, the push order decides for sorted blending
+            queue.push(bucket, Vector3::new(0.0, 0.0, 0.0), draw(shader, color, screen));
+        }
+        // an opaque quad in front of the left half hides them
+        let left = Matrix4::from_translation(Vector3::new(-1.0, 0.0, -0.5)) * Matrix4::from_nonuniform_scale(1.0, 2.0, 1.0);
+        queue.push(Bucket::Opaque, Vector3::new(0.0, 0.0, 0.0), draw("sorted", [0.0, 1.0, 0.0, 1.0], left));
+
+        let oit = if self.order_independent { Some(&mut self.oit) } else { None };
+        queue.flush_with(Vector3::new(0.0, 0.0, 1.0), oit).unwrap();
+    }
+}
+
+fn render(context: &HeadlessContext, order_independent: bool, colors: [[f32; 4]; 2]) -> ([u8; 4], [u8; 4]) {
+    let mut scene = Panes::new(order_independent, colors);
+    let image = context.render_scene(&mut scene, 1, 0.0);
+    (image.get_pixel(8, 16).data, image.get_pixel(24, 16).data)
+}
+
+// only the color, blending leaves different alphas behind
+fn close(actual: [u8; 4], expected: [u8; 3]) -> bool {
+    actual.iter().zip(expected.iter()).all(|(&a, &e)| (a as i32 - e as i32).abs() <= 2)
+}
+
+#[test]
+fn order_independent_blending_ignores_the_order() {
+    let context = match common::context(32, 32, "OIT tests") { Some(context) => context, None => return };
+
+    // sorted blending depends on which pane comes last
+    let (_, red_first) = render(&context, false, [RED, BLUE]);
+    let (_, blue_first) = render(&context, false, [BLUE, RED]);
+    assert!(close(red_first, [64, 0, 128]), "{:?}", red_first);
+    assert!(close(blue_first, [128, 0, 64]), "{:?}", blue_first);
+
+    // weighted blending averages both and covers 1 - 0.5 * 0.5 of the background, in any order
+    let (hidden, red_first) = render(&context, true, [RED, BLUE]);
+    let (_, blue_first) = render(&context, true, [BLUE, RED]);
+    assert!(close(red_first, [96, 0, 96]), "{:?}", red_first);
+    assert_eq!(red_first, blue_first);
+    // the opaque quad in front is not covered, the panes test against its depth
+    assert!(close(hidden, [0, 255, 0]), "{:?}", hidden);
+}
@@ -0,0 +1,105 @@
+//! Outlines around a translated cube, and the stencil and depth state they leave behind.
+#![cfg(unix)]
+
+mod common;
+
+use cgmath::{ortho, Deg, Matrix4, SquareMatrix, Vector3, Vector4};
+
+use learn_opengl_rust::headless::HeadlessContext;
+use learn_opengl_rust::mesh::Mesh;
+use learn_opengl_rust::outline::{Outline, OutlineStyle};
+use learn_opengl_rust::scene::{Frame, Scene};
+use learn_opengl_rust::scenes::shapes;
+
+// stencil state the scene had before, values the outline must not touch
+const STENCIL_REF: i32 = 3;
+const STENCIL_CLEAR: i32 = 1;
+const MARKED: i32 = 0x81;
+
+struct Outlined {
+    cube: Mesh,
+    outline: Outline,
+    // stencil func, ref, fail op, depth pass op and write mask after the outline, whether the depth and
+    // stencil tests are on, the stencil value inside the cube and left of it, and the stencil clear value
+    state_after: Vec<i32>,
+}
+
+impl Scene for Outlined {
+    fn render(&mut self, _frame: &Frame) {
+        // 4 by 4 units on 64 by 64 pixels, turned so its sides face the camera the cube covers x from
+        // pixel 28.7 to 51.3, extruded by a quarter unit it reaches 54.1
+        let projection = ortho(-2.0, 2.0, -2.0, 2.0, -10.0, 10.0);
+        let model = Matrix4::from_translation(Vector3::new(0.5, 0.0, 0.0)) * Matrix4::from_angle_y(Deg(45.0));
+        unsafe {
+            gl::ClearColor(0.0, 0.0, 0.0, 1.0);
+            gl::ClearStencil(STENCIL_CLEAR);
+            gl::Clear(gl::COLOR_BUFFER_BIT | gl::DEPTH_BUFFER_BIT | gl::STENCIL_BUFFER_BIT);
+            // a mark of the scene on the outline's bit, left of the cube
+            gl::Enable(gl::SCISSOR_TEST);
+            gl::Scissor(0, 0, 16, 64);
+            gl::ClearStencil(MARKED);
+            gl::Clear(gl::STENCIL_BUFFER_BIT);
+            gl::Disable(gl::SCISSOR_TEST);
+            gl::ClearStencil(STENCIL_CLEAR);
+            gl::Enable(gl::DEPTH_TEST);
+            gl::Enable(gl::STENCIL_TEST);
+            gl::StencilFunc(gl::LEQUAL, STENCIL_REF, 0x0F);
+            gl::StencilOp(gl::KEEP, gl::INCR, gl::DECR);
+            gl::StencilMask(0x0F);
+        }
+
+        self.outline.draw(&self.cube, model, Matrix4::identity(), projection).unwrap();
+
+        unsafe {
+            let get = |parameter: u32| {
+                let mut value = 0;
+                gl::GetIntegerv(parameter, &mut value);
+                value
+            };
+            let stencil = |x: i32| {
+                let mut value = 0u8;
+                gl::ReadPixels(x, 32, 1, 1, gl::STENCIL_INDEX, gl::UNSIGNED_BYTE, &mut value as *mut u8 as *mut _);
+                value as i32
+            };
+            self.state_after = vec![
+                get(gl::STENCIL_FUNC), get(gl::STENCIL_REF), get(gl::STENCIL_FAIL), get(gl::STENCIL_PASS_DEPTH_PASS),
+                get(gl::STENCIL_WRITEMASK), gl::IsEnabled(gl::DEPTH_TEST) as i32, gl::IsEnabled(gl::STENCIL_TEST) as i32,
+                stencil(40), stencil(8), get(gl::STENCIL_CLEAR_VALUE),
+            ];
+            gl::Disable(gl::DEPTH_TEST);
+            gl::Disable(gl::STENCIL_TEST);
+            gl::StencilFunc(gl::ALWAYS, 0, 0xFF);
+            gl::StencilOp(gl::KEEP, gl::KEEP, gl::KEEP);
+            gl::StencilMask(0xFF);
+            gl::ClearStencil(0);
+        }
+    }
+}
+
+fn outline(context: &HeadlessContext, outline: Outline) -> (Vec<[u8; 4]>, Vec<i32>) {
+    let mut scene = Outlined { cube: shapes::lit_cube_mesh(), outline, state_after: Vec::new() };
+    let image = context.render_scene(&mut scene, 1, 0.0);
+    // inside, two pixels right of the cube, and seven pixels right of it
+    let pixels = [40, 53, 58].iter().map(|&x| image.get_pixel(x, 32).data).collect();
+    (pixels, scene.state_after)
+}
+
+#[test]
+fn outlines_surround_translated_objects_and_restore_the_state() {
+    let context = match common::context(64, 64, "outline tests") { Some(context) => context, None => return };
+    let red = Vector4::new(1.0, 0.0, 0.0, 1.0);
+    let state = vec![
+        gl::LEQUAL as i32, STENCIL_REF, gl::KEEP as i32, gl::DECR as i32, 0x0F,
+        gl::TRUE as i32, gl::TRUE as i32, STENCIL_CLEAR, MARKED, STENCIL_CLEAR,
+    ];
+
+    let extruded = Outline::new(OutlineStyle::Extrude).unwrap().with_color(red).with_thickness(0.25);
+    let (pixels, state_after) = outline(&context, extruded);
+    assert_eq!(pixels, [[0, 0, 0, 255], [255, 0, 0, 255], [0, 0, 0, 255]]);
+    assert_eq!(state_after, state);
+
+    let dilated = Outline::new(OutlineStyle::Dilate).unwrap().with_color(red).with_thickness(4.0);
+    let (pixels, state_after) = outline(&context, dilated);
+    assert_eq!(pixels, [[0, 0, 0, 255], [255, 0, 0, 255], [0, 0, 0, 255]]);
+    assert_eq!(state_after, state);
+}
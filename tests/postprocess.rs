@@ -0,0 +1,93 @@
+//! The post-processing chain on a scene that only clears, so every pixel has a known color.
+#![cfg(unix)]
+
+mod common;
+
+use learn_opengl_rust::headless::HeadlessContext;
+use learn_opengl_rust::framebuffer::ColorFormat;
+use learn_opengl_rust::postprocess::{self, PostProcess, PostProcessed};
+use learn_opengl_rust::scene::{Frame, Scene};
+use learn_opengl_rust::scenes::advanced_opengl::face_culling::FaceCulling;
+
+struct Clear([f32; 3]);
+
+impl Scene for Clear {
+    fn render(&mut self, _frame: &Frame) {
+        unsafe {
+            gl::ClearColor(self.0[0], self.0[1], self.0[2], 1.0);
+            gl::Clear(gl::COLOR_BUFFER_BIT | gl::DEPTH_BUFFER_BIT);
+        }
+    }
+}
+
+// the center pixel after rendering the clear color through `effects`
+fn center(context: &HeadlessContext, color: [f32; 3], effects: &[&str]) -> [u8; 4] {
+    let mut scene = PostProcessed::new(Box::new(Clear(color)), effects);
+    let image = context.render_scene(&mut scene, 1, 0.0);
+    image.get_pixel(context.width / 2, context.height / 2).data
+}
+
+#[test]
+fn effects_run_in_order() {
+    let context = match common::context(32, 32, "post-processing tests") { Some(context) => context, None => return };
+
+    // without effects the frame is copied unchanged
+    assert_eq!(center(&context, [1.0, 0.0, 0.0], &[]), [255, 0, 0, 255]);
+    assert_eq!(center(&context, [1.0, 0.0, 0.0], &["inversion"]), [0, 255, 255, 255]);
+
+    // inversion runs before grayscale: cyan has the luma 1 - 0.2126
+    let gray = center(&context, [1.0, 0.0, 0.0], &["inversion", "grayscale"]);
+    assert_eq!(gray[0], gray[1]);
+    assert!((gray[0] as i32 - 201).abs() <= 1, "{:?}", gray);
+
+    // kernels summing up to 1 keep a flat color, edge detection sums up to 0
+    assert_eq!(center(&context, [0.0, 0.0, 1.0], &["blur", "sharpen"]), [0, 0, 255, 255]);
+    assert_eq!(center(&context, [0.0, 0.0, 1.0], &["edge_detection"]), [0, 0, 0, 255]);
+}
+
+#[test]
+fn parameters_are_uniforms() {
+    let context = match common::context(32, 32, "post-processing tests") { Some(context) => context, None => return };
+
+    let mut scene = PostProcessed::new(Box::new(Clear([1.0, 1.0, 1.0])), &["vignette"]);
+    let vignette = scene.chain.effect_mut("vignette").unwrap();
+    assert!(vignette.set_param("missing", 1.0).is_err());
+    vignette.set_param("radius", 0.0).unwrap();
+    vignette.set_param("strength", 1.0).unwrap();
+
+    let image = context.render_scene(&mut scene, 1, 0.0);
+    let (center, corner) = (image.get_pixel(16, 16).data, image.get_pixel(0, 0).data);
+    assert!(center[0] > 200, "{:?}", center);
+    assert!(corner[0] < 20, "{:?}", corner);
+}
+
+#[test]
+fn scenes_leaving_culling_on_still_show() {
+    let context = match common::context(32, 32, "post-processing tests") { Some(context) => context, None => return };
+
+    // face culling ends its frame with clockwise front faces, which used to cull the fullscreen quad
+    let mut scene = PostProcessed::new(Box::new(FaceCulling::new()), &["inversion"]);
+    let image = context.render_scene(&mut scene, 2, 0.25);
+    // the inverted clear color (0.2, 0.2, 0.4) in a corner
+    let corner = image.get_pixel(0, 0).data;
+    assert!(corner.iter().zip([204, 204, 153, 255].iter()).all(|(&a, &e)| (a as i32 - e).abs() <= 1), "{:?}", corner);
+}
+
+#[test]
+fn dropping_the_chain_frees_its_objects() {
+    let _context = match common::context(32, 32, "post-processing tests") { Some(context) => context, None => return };
+
+    // GL names are small integers, counting the live ones is enough here
+    let live = || unsafe {
+        let programs = (1..1000).filter(|&name| gl::IsProgram(name) == gl::TRUE).count();
+        let buffers = (1..1000).filter(|&name| gl::IsBuffer(name) == gl::TRUE).count();
+        (programs, buffers)
+    };
+    let before = live();
+    let chain = PostProcess::new(ColorFormat::Rgba8, postprocess::default_effects()).unwrap();
+    let created = live();
+    assert!(created.0 >= before.0 + chain.effects.len() && created.1 > before.1, "{:?} {:?}", before, created);
+
+    drop(chain);
+    assert_eq!(live(), before);
+}
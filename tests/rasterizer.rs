@@ -0,0 +1,121 @@
+//! The software rasterizer against hand-computed results of the GL rules.
+
+use cgmath::{perspective, Deg, Matrix4, Point3, SquareMatrix, Vector2, Vector3, Vector4};
+
+use learn_opengl_rust::mesh::Vertex;
+use learn_opengl_rust::rasterizer::{CullFace, FrontFace, Rasterizer};
+use learn_opengl_rust::utils;
+
+fn vertex(x: f32, y: f32, z: f32, u: f32, v: f32) -> Vertex {
+    Vertex {
+        position: Vector3::new(x, y, z),
+        tex_coords: Vector2::new(u, v),
+        ..Vertex::default()
+    }
+}
+
+// counter-clockwise triangle in the lower left half of the screen
+fn lower_left_triangle(z: f32) -> Vec<Vertex> {
+    vec![vertex(-1.0, -1.0, z, 0.0, 0.0), vertex(1.0, -1.0, z, 1.0, 0.0), vertex(-1.0, 1.0, z, 0.0, 1.0)]
+}
+
+fn covered(rasterizer: &Rasterizer) -> usize {
+    rasterizer.color.iter().filter(|color| color.x > 0.0).count()
+}
+
+const WHITE: Vector4<f32> = Vector4 { x: 1.0, y: 1.0, z: 1.0, w: 1.0 };
+
+#[test]
+fn two_triangles_sharing_an_edge_cover_every_pixel_once() {
+    let mut rasterizer = Rasterizer::new(16, 16);
+    let quad = vec![
+        vertex(-1.0, -1.0, 0.0, 0.0, 0.0), vertex(1.0, -1.0, 0.0, 1.0, 0.0),
+        vertex(1.0, 1.0, 0.0, 1.0, 1.0), vertex(-1.0, 1.0, 0.0, 0.0, 1.0),
+    ];
+
+    let mut fragments = 0;
+    rasterizer.draw(&quad, &[0, 1, 2, 0, 2, 3], Matrix4::identity(), |_| {
+        fragments += 1;
+        Some(WHITE)
+    });
+
+    assert_eq!(fragments, 16 * 16);
+    assert_eq!(covered(&rasterizer), 16 * 16);
+}
+
+#[test]
+fn culling_follows_front_face() {
+    let triangle = lower_left_triangle(0.0);
+    let clockwise = [0, 2, 1];
+
+    let mut rasterizer = Rasterizer::new(8, 8);
+    rasterizer.cull_face = Some(CullFace::Back);
+    rasterizer.draw(&triangle, &clockwise, Matrix4::identity(), |_| Some(WHITE));
+    assert_eq!(covered(&rasterizer), 0);
+
+    // with clockwise front faces the same triangle is visible and front facing
+    rasterizer.front_face = FrontFace::Cw;
+    rasterizer.draw(&triangle, &clockwise, Matrix4::identity(), |fragment| {
+        assert!(fragment.front_facing);
+        Some(WHITE)
+    });
+    assert!(covered(&rasterizer) > 0);
+}
+
+#[test]
+fn depth_test_keeps_the_nearer_triangle() {
+    let mut rasterizer = Rasterizer::new(8, 8);
+    rasterizer.depth_test = true;
+
+    let red = Vector4::new(1.0, 0.0, 0.0, 1.0);
+    let green = Vector4::new(0.0, 1.0, 0.0, 1.0);
+    rasterizer.draw(&lower_left_triangle(-0.5), &[0, 1, 2], Matrix4::identity(), |_| Some(green));
+    rasterizer.draw(&lower_left_triangle(0.5), &[0, 1, 2], Matrix4::identity(), |_| Some(red));
+
+    assert_eq!(rasterizer.pixel(1, 1), green);
+    assert!((rasterizer.depth_at(1, 1) - 0.25).abs() < 1e-6);
+}
+
+#[test]
+fn triangles_behind_the_near_plane_are_clipped() {
+    let mut rasterizer = Rasterizer::new(32, 32);
+    let projection = perspective(Deg(90.0), 1.0, 0.1, 100.0);
+    let camera = utils::camera_at(Point3::new(0.0, 0.0, 0.0));
+    // a floor reaching from behind the camera far into the scene
+    let floor = vec![
+        vertex(-1.0, -1.0, 10.0, 0.0, 0.0), vertex(1.0, -1.0, 10.0, 1.0, 0.0), vertex(0.0, -1.0, -50.0, 0.5, 1.0),
+    ];
+
+    rasterizer.draw(&floor, &[0, 1, 2], projection * camera.calculate_view(), |fragment| {
+        assert!(fragment.depth >= 0.0 && fragment.depth <= 1.0);
+        Some(WHITE)
+    });
+
+    // only the lower half of the screen shows the floor
+    assert!(covered(&rasterizer) > 0);
+    assert_eq!(rasterizer.pixel(16, 20), Vector4::new(0.0, 0.0, 0.0, 1.0));
+}
+
+#[test]
+fn attributes_are_interpolated_perspective_correct() {
+    let mut rasterizer = Rasterizer::new(64, 64);
+    let projection = perspective(Deg(90.0), 1.0, 0.1, 100.0);
+    // a quad tilted away from the camera, v runs from 0 at z = -1 to 1 at z = -3
+    let quad = vec![
+        vertex(-1.0, -1.0, -1.0, 0.0, 0.0), vertex(1.0, -1.0, -1.0, 1.0, 0.0),
+        vertex(1.0, 1.0, -3.0, 1.0, 1.0), vertex(-1.0, 1.0, -3.0, 0.0, 1.0),
+    ];
+
+    let mut samples = Vec::new();
+    rasterizer.draw(&quad, &[0, 1, 2, 0, 2, 3], projection, |fragment| {
+        samples.push((fragment.vertex.position, fragment.vertex.tex_coords));
+        Some(WHITE)
+    });
+
+    // v stays an affine function of the interpolated object space position
+    assert!(!samples.is_empty());
+    for (position, tex_coords) in samples {
+        assert!((tex_coords.y - (-1.0 - position.z) / 2.0).abs() < 1e-3);
+        assert!((tex_coords.x - (position.x + 1.0) / 2.0).abs() < 1e-3);
+    }
+}
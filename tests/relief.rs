@@ -0,0 +1,134 @@
+//! Tangents follow the texture coordinates, normals follow the heights, and parallax only moves what lies deeper.
+#![cfg(unix)]
+
+mod common;
+
+use std::fs;
+use std::path::Path;
+
+use cgmath::{perspective, Deg, InnerSpace, Matrix4, Point3, SquareMatrix, Vector2, Vector3};
+
+use learn_opengl_rust::mesh::{self, Texture, Vertex};
+use learn_opengl_rust::model::Model;
+use learn_opengl_rust::relief::{self, ParallaxMode, NORMAL_MAPPING_FRAGMENT_SHADER, NORMAL_MAPPING_VERTEX_SHADER};
+use learn_opengl_rust::scene;
+use learn_opengl_rust::scenes::shapes::{self, LIT_CUBE_VERTICES, LIT_PLANE_VERTICES};
+use learn_opengl_rust::shader::Shader;
+
+fn vertices(data: &[f32]) -> Vec<Vertex> {
+    let mut vertices: Vec<Vertex> = data.chunks(8).map(|v| Vertex {
+        position: Vector3::new(v[0], v[1], v[2]),
+        normal: Vector3::new(v[3], v[4], v[5]),
+        tex_coords: Vector2::new(v[6], v[7]),
+        ..Vertex::default()
+    }).collect();
+    let indices: Vec<u32> = (0..vertices.len() as u32).collect();
+    mesh::calculate_tangents(&mut vertices, &indices);
+    vertices
+}
+
+#[test]
+fn tangents_follow_the_texture_coordinates() {
+    // u grows along x and v towards -z on the floor
+    for vertex in vertices(&LIT_PLANE_VERTICES) {
+        assert!((vertex.tangent - Vector3::unit_x()).magnitude() < 1e-5, "{:?}", vertex.tangent);
+        assert!((vertex.bitangent + Vector3::unit_z()).magnitude() < 1e-5, "{:?}", vertex.bitangent);
+    }
+    // on the +x side of the cube u grows upwards
+    for vertex in vertices(&LIT_CUBE_VERTICES)[18..24].iter() {
+        assert!((vertex.tangent - Vector3::unit_y()).magnitude() < 1e-5, "{:?}", vertex.tangent);
+        assert!((vertex.bitangent + Vector3::unit_z()).magnitude() < 1e-5, "{:?}", vertex.bitangent);
+    }
+}
+
+#[test]
+fn normals_lean_away_from_the_slopes() {
+    let flat = relief::normals_from_heights(&[0.5; 16], 4, 4.0);
+    assert!(flat.chunks(3).all(|normal| normal == [128, 128, 255]));
+
+    // 2 by 2 tiles of 16 texels, rising over the first 4 texels of each
+    let heights = relief::tile_heights(32, 2, 0.25);
+    assert_eq!(heights.len(), 32 * 32);
+    assert_eq!(heights[8 * 32 + 8], 1.0);
+    assert!(heights[8 * 32] < 0.2);
+    let normals = relief::normals_from_heights(&heights, 32, 4.0);
+    let normal = |x: usize, y: usize| &normals[(y * 32 + x) * 3..(y * 32 + x) * 3 + 3];
+    // rising along x tilts towards -x, falling along y towards +y
+    assert!(normal(2, 8)[0] < 100 && normal(2, 8)[1] == 128, "{:?}", normal(2, 8));
+    assert!(normal(8, 14)[1] > 156 && normal(8, 14)[0] == 128, "{:?}", normal(8, 14));
+}
+
+#[test]
+fn parallax_only_shifts_deeper_surfaces() {
+    let context = match common::context(32, 32, "relief tests") { Some(context) => context, None => return };
+    unsafe { scene::reset_gl_state(); }
+
+    let shader = Shader::new(NORMAL_MAPPING_VERTEX_SHADER, NORMAL_MAPPING_FRAGMENT_SHADER);
+    shader.use_program();
+    shader.set_mat4("projection", perspective(Deg(45.0), 1.0, 0.1, 10.0));
+    shader.set_mat4("view", Matrix4::look_at(Point3::new(0.0, 1.0, 1.5), Point3::new(0.0, -0.5, 0.0), Vector3::unit_y()));
+    shader.set_mat4("model", Matrix4::identity());
+    shader.set_vector3v("cameraPosition", Vector3::new(0.0, 1.0, 1.5));
+    shader.set_vector3v("lightPosition", Vector3::new(0.0, 2.0, 0.0));
+    shader.set_vector3v("lightColor", Vector3::new(1.0, 1.0, 1.0));
+    shader.set_float("shininess", 32.0);
+    shader.set_float("heightScale", 0.1);
+    shader.set_bool("normalMapping", true);
+
+    // the tiles as colors, to see them move
+    let (pattern, pattern_normals) = unsafe { relief::relief_textures(&relief::tile_heights(64, 8, 0.1), 64, 1.0) };
+    let mut floor = shapes::lit_plane_mesh();
+    let mut render = |height: f32, parallax: ParallaxMode| {
+        let (height_map, normal_map) = unsafe { relief::relief_textures(&[height; 16], 4, 1.0) };
+        floor.textures = vec![
+            Texture { id: pattern, type_: "texture_diffuse".into(), path: String::new() },
+            Texture { id: height_map, type_: "texture_height".into(), path: String::new() },
+            Texture { id: normal_map, type_: "texture_normal".into(), path: String::new() },
+        ];
+        context.bind();
+        unsafe {
+            gl::ClearColor(0.0, 0.0, 0.0, 1.0);
+            gl::Clear(gl::COLOR_BUFFER_BIT);
+            shader.use_program();
+            parallax.apply(&shader);
+            floor.Draw(&shader);
+            shapes::delete_textures(&[height_map, normal_map]);
+        }
+        context.read_pixels()
+    };
+
+    let unshifted = render(1.0, ParallaxMode::Off);
+    for &parallax in [ParallaxMode::Simple, ParallaxMode::Steep, ParallaxMode::Occlusion].iter() {
+        // at the surface nothing moves
+        let surface = render(1.0, parallax);
+        assert!(unshifted.pixels().zip(surface.pixels()).all(|(a, b)| (a.data[0] as i32 - b.data[0] as i32).abs() <= 1), "{:?} moved the surface", parallax);
+        // all of it deeper, the tiles move
+        let deep = render(0.0, parallax);
+        let moved = unshifted.pixels().zip(deep.pixels()).filter(|(a, b)| (a.data[0] as i32 - b.data[0] as i32).abs() > 8).count();
+        assert!(moved > 32, "{:?} moved {} pixels", parallax, moved);
+    }
+    unsafe {
+        shapes::delete_textures(&[pattern, pattern_normals]);
+        scene::reset_gl_state();
+    }
+}
+
+#[test]
+fn models_take_normal_maps_from_bump_not_from_the_shininess_map() {
+    let _context = match common::context(4, 4, "relief tests") { Some(context) => context, None => return };
+
+    // a triangle with a shininess and a normal map, the way Blender exports them
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("target/relief_tests");
+    fs::create_dir_all(&dir).unwrap();
+    for &file in ["diffuse.png", "shininess.png", "normal.png"].iter() {
+        image::RgbImage::from_pixel(1, 1, image::Rgb([128, 128, 255])).save(dir.join(file)).unwrap();
+    }
+    fs::write(dir.join("bumped.mtl"), "newmtl bumped\nmap_Kd diffuse.png\nmap_Ns shininess.png\nmap_Bump normal.png\n").unwrap();
+    fs::write(dir.join("bumped.obj"), "mtllib bumped.mtl\nv 0 0 0\nv 1 0 0\nv 0 1 0\nvt 0 0\nvt 1 0\nvt 0 1\nvn 0 0 1\nusemtl bumped\nf 1/1/1 2/2/1 3/3/1\n").unwrap();
+
+    let model = Model::new(dir.to_str().unwrap(), "bumped.obj");
+    let textures: Vec<(&str, &str)> = model.meshes[0].textures.iter()
+        .map(|texture| (texture.type_.as_str(), Path::new(&texture.path).file_name().unwrap().to_str().unwrap()))
+        .collect();
+    assert_eq!(textures, [("texture_diffuse", "diffuse.png"), ("texture_normal", "normal.png")]);
+}
@@ -0,0 +1,74 @@
+//! Draw order and per-bucket state of the render queue.
+
+#[cfg(unix)]
+mod common;
+
+use std::cell::RefCell;
+
+use cgmath::Vector3;
+
+use learn_opengl_rust::render_queue::{Bucket, RenderQueue};
+
+#[test]
+fn buckets_and_distances_decide_the_order() {
+    let mut queue = RenderQueue::new();
+    let camera = Vector3::new(0.0, 0.0, 10.0);
+    // pushed in the worst order: transparent first, near to far
+    queue.push(Bucket::Transparent, Vector3::new(0.0, 0.0, 5.0), || ());
+    queue.push(Bucket::Transparent, Vector3::new(0.0, 0.0, -5.0), || ());
+    queue.push(Bucket::AlphaTested, Vector3::new(0.0, 0.0, 0.0), || ());
+    queue.push(Bucket::Opaque, Vector3::new(0.0, 0.0, -5.0), || ());
+    queue.push(Bucket::Opaque, Vector3::new(0.0, 0.0, 5.0), || ());
+    // same distance as the first transparent draw, stays behind it
+    queue.push(Bucket::Transparent, Vector3::new(0.0, 5.0, 10.0), || ());
+
+    // opaque front to back, then alpha-tested, then transparent back to front
+    assert_eq!(queue.order(camera), [4, 3, 2, 1, 0, 5]);
+    assert_eq!(queue.len(), 6);
+}
+
+#[cfg(unix)]
+#[test]
+fn flush_sets_the_state_per_bucket_and_restores_it() {
+    let _context = match common::context(16, 16, "render queue tests") { Some(context) => context, None => return };
+
+    // blending, depth writes and the blend function seen by each draw
+    let seen = RefCell::new(Vec::new());
+    let record = |name: &'static str| {
+        let seen = &seen;
+        move || unsafe {
+            let mut depth_mask = 0;
+            let mut destination = 0;
+            gl::GetBooleanv(gl::DEPTH_WRITEMASK, &mut depth_mask);
+            gl::GetIntegerv(gl::BLEND_DST_RGB, &mut destination);
+            seen.borrow_mut().push((name, gl::IsEnabled(gl::BLEND) == gl::TRUE, depth_mask == gl::TRUE, destination as u32));
+        }
+    };
+
+    unsafe {
+        gl::Enable(gl::BLEND);
+        gl::BlendFunc(gl::ONE, gl::ONE);
+    }
+    let mut queue = RenderQueue::new();
+    queue.push(Bucket::Transparent, Vector3::new(0.0, 0.0, 0.0), record("window"));
+    queue.push(Bucket::Opaque, Vector3::new(0.0, 0.0, 0.0), record("cube"));
+    queue.flush(Vector3::new(0.0, 0.0, 3.0));
+
+    // opaque draws keep the blend function that was set before, it does not matter without blending
+    assert_eq!(*seen.borrow(), [
+        ("cube", false, true, gl::ONE),
+        ("window", true, false, gl::ONE_MINUS_SRC_ALPHA),
+    ]);
+    assert!(queue.is_empty());
+
+    // the state from before the flush is back
+    unsafe {
+        let (mut depth_mask, mut destination) = (0, 0);
+        gl::GetBooleanv(gl::DEPTH_WRITEMASK, &mut depth_mask);
+        gl::GetIntegerv(gl::BLEND_DST_RGB, &mut destination);
+        assert!(gl::IsEnabled(gl::BLEND) == gl::TRUE && depth_mask == gl::TRUE);
+        assert_eq!(destination as u32, gl::ONE);
+        gl::Disable(gl::BLEND);
+        gl::BlendFunc(gl::ONE, gl::ZERO);
+    }
+}
@@ -0,0 +1,77 @@
+//! The state cache applies only differences and its scopes restore what was there before.
+#![cfg(unix)]
+
+mod common;
+
+use learn_opengl_rust::framebuffer::{Framebuffer, FramebufferDesc};
+use learn_opengl_rust::render_state::{BlendState, CullState, RenderState, StateCache, StencilState};
+use learn_opengl_rust::scene;
+use learn_opengl_rust::scenes;
+
+#[test]
+fn scopes_apply_differences_and_restore() {
+    let _context = match common::context(16, 16, "render state tests") { Some(context) => context, None => return };
+    unsafe { scene::reset_gl_state(); }
+    // GL clamps the stencil reference to the bits of the bound stencil buffer
+    let target = Framebuffer::new(FramebufferDesc::color_depth(16, 16)).unwrap();
+    target.bind();
+
+    let mut cache = StateCache::new();
+    assert_eq!(*cache.current(), RenderState::default());
+
+    // depth test on, then nothing left to do
+    assert_eq!(cache.apply(&RenderState::opaque()), 1);
+    assert_eq!(cache.apply(&RenderState::opaque()), 0);
+    // blending on with new factors, depth writes off
+    assert_eq!(cache.apply(&RenderState::transparent()), 3);
+
+    let odd = RenderState {
+        blend: BlendState { equation: gl::FUNC_REVERSE_SUBTRACT, ..BlendState::func(gl::ONE, gl::ONE) },
+        cull: CullState::culling(gl::FRONT, gl::CW),
+        stencil: StencilState {
+            test: true,
+            func: gl::NOTEQUAL,
+            reference: 1,
+            value_mask: 0x0F,
+            fail: gl::KEEP,
+            depth_fail: gl::INCR,
+            pass: gl::REPLACE,
+            write_mask: 0x00,
+        },
+        color_mask: [true, false, true, false],
+        polygon_mode: gl::LINE,
+        ..RenderState::opaque()
+    };
+    {
+        let mut outer = cache.scope(&odd);
+        // what the cache sent is what GL has
+        assert_eq!(RenderState::query(), odd);
+        {
+            let _inner = outer.scope(&RenderState::default());
+            assert_eq!(RenderState::query(), RenderState::default());
+        }
+        assert_eq!(RenderState::query(), odd);
+    }
+    assert_eq!(RenderState::query(), RenderState::transparent());
+    assert_eq!(*cache.current(), RenderState::transparent());
+
+    // a change behind the cache's back is picked up again
+    unsafe { gl::FrontFace(gl::CW); }
+    cache.invalidate();
+    assert_eq!(cache.current().cull.front_face, gl::CW);
+    unsafe { scene::reset_gl_state(); }
+}
+
+#[test]
+fn advanced_opengl_scenes_leave_no_state_behind() {
+    let context = match common::context(16, 16, "render state tests") { Some(context) => context, None => return };
+
+    for &name in ["face_culling", "depth_testing", "blending", "stencil"].iter() {
+        unsafe { scene::reset_gl_state(); }
+        let mut scene = scenes::create(name).unwrap();
+        // the cache is built once, the second frame starts from what the first one left
+        context.render_scene(scene.as_mut(), 2, 0.0);
+        // face_culling draws its second cube with clockwise front faces
+        assert_eq!(RenderState::query(), RenderState::default(), "{}", name);
+    }
+}
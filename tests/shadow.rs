@@ -0,0 +1,176 @@
+//! The light's projection fits the scene bounds, cascades split the view and stay on the texel grid,
+//! and the depth passes store what the light sees.
+#![cfg(unix)]
+
+mod common;
+
+use cgmath::{Deg, Matrix4, Point3, SquareMatrix, Transform, Vector3};
+
+use learn_opengl_rust::scene;
+use learn_opengl_rust::scenes::shapes;
+use learn_opengl_rust::cascades::{cascade_matrix, split_distances, CascadeSettings, CascadedShadowMap, ViewFrustum};
+use learn_opengl_rust::cubemap::CubeFace;
+use learn_opengl_rust::mesh::Geometry;
+use learn_opengl_rust::shadow::{light_space_matrix, Bounds, PointShadowMap, ShadowMap};
+
+#[test]
+fn light_space_fits_the_bounds() {
+    let bounds = Bounds::new(Point3::new(-4.0, -0.5, -3.0), Point3::new(2.0, 2.0, 5.0));
+    // straight down needs another up vector
+    for &direction in [Vector3::new(2.0, -4.0, 1.0), Vector3::new(0.0, -1.0, 0.0), Vector3::new(-1.0, -0.2, 0.0)].iter() {
+        let matrix = light_space_matrix(direction, &bounds);
+        let projected: Vec<Point3<f32>> = bounds.corners().iter().map(|&corner| matrix.transform_point(corner)).collect();
+        let fitted = Bounds::around(&projected);
+        // inside the clip volume, and touching it on every side
+        for &(min, max) in [(fitted.min.x, fitted.max.x), (fitted.min.y, fitted.max.y), (fitted.min.z, fitted.max.z)].iter() {
+            assert!((min + 1.0).abs() < 1e-4 && (max - 1.0).abs() < 1e-4, "{:?} fitted to {:?}", direction, fitted);
+        }
+    }
+}
+
+#[test]
+fn depth_pass_stores_the_nearest_caster() {
+    let context = match common::context(64, 64, "shadow tests") { Some(context) => context, None => return };
+    unsafe { scene::reset_gl_state(); }
+    let (floor, cube) = (shapes::lit_plane_mesh(), shapes::lit_cube_mesh());
+
+    // from above, the depth range spans y from 1 down to -0.5
+    let bounds = Bounds::new(Point3::new(-2.0, -0.5, -2.0), Point3::new(2.0, 1.0, 2.0));
+    let mut shadow_map = ShadowMap::new(64).unwrap();
+    context.bind();
+    unsafe { gl::Viewport(3, 5, 32, 16); }
+    shadow_map.begin(Vector3::new(0.0, -1.0, 0.0), &bounds);
+    shadow_map.cast(&floor, Matrix4::identity());
+    // top at y = 0.5, a third of the way down
+    shadow_map.cast(&cube, Matrix4::from_translation(Vector3::new(0.0, 0.25, 0.0)) * Matrix4::from_scale(0.5));
+    shadow_map.end();
+
+    let mut viewport = [0; 4];
+    unsafe {
+        gl::GetIntegerv(gl::VIEWPORT, viewport.as_mut_ptr());
+        assert_eq!(gl::IsEnabled(gl::DEPTH_TEST), gl::FALSE);
+    }
+    assert_eq!(viewport, [3, 5, 32, 16]);
+
+    context.bind();
+    shadow_map.draw_debug();
+    let image = context.read_pixels();
+    let (center, edge) = (image.get_pixel(32, 32).data[0], image.get_pixel(2, 2).data[0]);
+    assert!((center as i32 - 85).abs() <= 2, "cube top stored as {}", center);
+    assert!(edge >= 253, "floor stored as {}", edge);
+    unsafe { scene::reset_gl_state(); }
+}
+
+#[test]
+fn point_shadows_store_the_linear_distance() {
+    let context = match common::context(16, 16, "shadow tests") { Some(context) => context, None => return };
+    unsafe { scene::reset_gl_state(); }
+    let cube = shapes::lit_cube_mesh();
+
+    let mut shadow_map = PointShadowMap::new(16, 10.0).unwrap();
+    context.bind();
+    unsafe { gl::Viewport(1, 2, 8, 4); }
+    // its near side is 2.5 away from the light, along -z
+    let casters: Vec<(&dyn Geometry, Matrix4<f32>)> = vec![(&cube, Matrix4::from_translation(Vector3::new(1.0, 1.0, -3.0)))];
+    shadow_map.render(Vector3::new(1.0, 1.0, 0.0), &casters);
+
+    let mut viewport = [0; 4];
+    unsafe {
+        gl::GetIntegerv(gl::VIEWPORT, viewport.as_mut_ptr());
+        assert_eq!(gl::IsEnabled(gl::DEPTH_TEST), gl::FALSE);
+    }
+    assert_eq!(viewport, [1, 2, 8, 4]);
+
+    let face = |face: CubeFace| {
+        let mut depths = vec![0.0f32; 16 * 16];
+        unsafe {
+            gl::BindTexture(gl::TEXTURE_CUBE_MAP, shadow_map.texture());
+            gl::GetTexImage(face.gl_target(), 0, gl::DEPTH_COMPONENT, gl::FLOAT, depths.as_mut_ptr() as *mut _);
+            gl::BindTexture(gl::TEXTURE_CUBE_MAP, 0);
+        }
+        depths
+    };
+    // the middle texels look almost straight at the cube, the opposite face sees nothing
+    let center = face(CubeFace::NegativeZ)[8 * 16 + 8];
+    assert!((center - 0.25).abs() < 0.005, "distance stored as {}", center);
+    assert!(face(CubeFace::PositiveZ).iter().all(|&depth| depth == 1.0));
+    unsafe { scene::reset_gl_state(); }
+}
+
+#[test]
+fn cascade_splits_blend_log_and_uniform() {
+    let uniform = split_distances(1.0, 16.0, 4, 0.0);
+    let logarithmic = split_distances(1.0, 16.0, 4, 1.0);
+    let practical = split_distances(1.0, 16.0, 4, 0.5);
+    for (split, expected) in uniform.iter().zip([1.0, 4.75, 8.5, 12.25, 16.0].iter()) {
+        assert!((split - expected).abs() < 1e-4, "{:?}", uniform);
+    }
+    for (split, expected) in logarithmic.iter().zip([1.0, 2.0, 4.0, 8.0, 16.0].iter()) {
+        assert!((split - expected).abs() < 1e-4, "{:?}", logarithmic);
+    }
+    for index in 0..5 {
+        assert!((practical[index] - (uniform[index] + logarithmic[index]) / 2.0).abs() < 1e-4, "{:?}", practical);
+    }
+}
+
+#[test]
+fn cascades_are_stable_as_the_camera_moves() {
+    let frustum = ViewFrustum { fovy: Deg(45.0), aspect: 4.0 / 3.0, near: 0.1, far: 50.0 };
+    let direction = Vector3::new(-1.0, -1.0, -0.6);
+    let view_from = |position: Point3<f32>, target: Point3<f32>| Matrix4::look_at(position, target, Vector3::unit_y());
+    let matrix = |view| cascade_matrix(direction, &frustum.corners(view, 2.0, 10.0), 1024);
+
+    let first = matrix(view_from(Point3::new(0.0, 2.0, 5.0), Point3::new(0.0, 2.0, 0.0)));
+    // a fraction of a texel further, any world point moves on the map by whole texels
+    let moved = matrix(view_from(Point3::new(0.013, 2.0, 5.007), Point3::new(0.013, 2.0, 0.007)));
+    for &point in [Point3::new(0.0, 0.0, 0.0), Point3::new(3.0, -0.5, -4.0), Point3::new(-2.5, 1.0, 1.5)].iter() {
+        let shift = (moved.transform_point(point) - first.transform_point(point)) * 512.0;
+        assert!((shift.x - shift.x.round()).abs() < 0.02 && (shift.y - shift.y.round()).abs() < 0.02, "{:?} moved {:?} texels", point, shift);
+    }
+    // turned, the map covers the same size
+    let turned = matrix(view_from(Point3::new(0.0, 2.0, 5.0), Point3::new(3.0, 1.0, 0.0)));
+    assert!((first.x.x.hypot(first.y.x).hypot(first.z.x) - turned.x.x.hypot(turned.y.x).hypot(turned.z.x)).abs() < 1e-6);
+}
+
+#[test]
+fn cascades_store_the_nearest_caster() {
+    let context = match common::context(16, 16, "shadow tests") { Some(context) => context, None => return };
+    unsafe { scene::reset_gl_state(); }
+    let cube = shapes::lit_cube_mesh();
+
+    let settings = CascadeSettings { count: 2, ..CascadeSettings::default() };
+    let mut cascades = CascadedShadowMap::new(64, settings).unwrap();
+    assert!(CascadedShadowMap::new(64, CascadeSettings { count: 5, ..settings }).is_err());
+    context.bind();
+    unsafe { gl::Viewport(1, 2, 8, 4); }
+    let view = Matrix4::look_at(Point3::new(0.0, 2.0, 5.0), Point3::new(0.0, 0.0, 0.0), Vector3::unit_y());
+    let frustum = ViewFrustum { fovy: Deg(45.0), aspect: 1.0, near: 0.1, far: 20.0 };
+    // its top at y = 0.5, in front of the camera
+    let casters: Vec<(&dyn Geometry, Matrix4<f32>)> = vec![(&cube, Matrix4::from_translation(Vector3::new(0.0, 0.0, 2.0)))];
+    cascades.render(view, &frustum, Vector3::new(0.0, -1.0, 0.0), &casters);
+
+    let mut viewport = [0; 4];
+    unsafe {
+        gl::GetIntegerv(gl::VIEWPORT, viewport.as_mut_ptr());
+        assert_eq!(gl::IsEnabled(gl::DEPTH_TEST), gl::FALSE);
+        assert_eq!(gl::IsEnabled(gl::DEPTH_CLAMP), gl::FALSE);
+    }
+    assert_eq!(viewport, [1, 2, 8, 4]);
+    assert_eq!(cascades.splits().len(), 3);
+    assert_eq!(cascades.matrices().len(), 2);
+
+    let mut depths = vec![0.0f32; 64 * 64 * 4];
+    unsafe {
+        gl::BindTexture(gl::TEXTURE_2D_ARRAY, cascades.texture());
+        gl::GetTexImage(gl::TEXTURE_2D_ARRAY, 0, gl::DEPTH_COMPONENT, gl::FLOAT, depths.as_mut_ptr() as *mut _);
+        gl::BindTexture(gl::TEXTURE_2D_ARRAY, 0);
+    }
+    // each layer holds the top of the cube where its matrix puts it
+    for (layer, matrix) in cascades.matrices().iter().enumerate() {
+        let top = matrix.transform_point(Point3::new(0.0, 0.5, 2.0)) * 0.5 + Vector3::new(0.5, 0.5, 0.5);
+        let (x, y) = ((top.x * 64.0) as usize, (top.y * 64.0) as usize);
+        let stored = depths[layer * 64 * 64 + y * 64 + x];
+        assert!((stored - top.z).abs() < 0.01, "layer {} stored {} instead of {}", layer, stored, top.z);
+    }
+    unsafe { scene::reset_gl_state(); }
+}